@@ -0,0 +1,41 @@
+#!/usr/bin/env python3
+"""Reference objective function for `mahf::problems::evaluate::ExternalProcess`.
+
+Reads one JSON request per line from stdin, i.e. `{"solutions": [[x_1, ..., x_n], ...]}`,
+and writes one JSON response per line to stdout, i.e. `{"objectives": [f_1, ...]}`,
+where `f_i` is the sphere function of the `i`-th solution.
+
+For testing failure handling, the process exits after answering `--crash-after N` requests,
+or stops responding after answering `--hang-after N` requests.
+"""
+
+import argparse
+import json
+import sys
+import time
+
+
+def main():
+    parser = argparse.ArgumentParser()
+    parser.add_argument("--crash-after", type=int, default=None)
+    parser.add_argument("--hang-after", type=int, default=None)
+    args = parser.parse_args()
+
+    for answered, line in enumerate(sys.stdin):
+        if args.crash_after is not None and answered >= args.crash_after:
+            sys.exit(1)
+        if args.hang_after is not None and answered >= args.hang_after:
+            time.sleep(3600)
+
+        try:
+            request = json.loads(line)
+            objectives = [sum(x * x for x in solution) for solution in request["solutions"]]
+            response = {"objectives": objectives}
+        except (ValueError, KeyError, TypeError) as error:
+            response = {"error": str(error)}
+
+        print(json.dumps(response), flush=True)
+
+
+if __name__ == "__main__":
+    main()
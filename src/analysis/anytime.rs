@@ -0,0 +1,665 @@
+//! Fixed-target and fixed-budget analysis of the anytime performance of configurations.
+//!
+//! Every run is represented by a [`Trajectory`] of the best objective value over the number
+//! of evaluations, read from its [`Log`].
+//! Based on the trajectories, this module computes
+//! - the expected running time ([`expected_running_time`]) to reach a target value,
+//! - the empirical cumulative distribution function ([`Ecdf`]) of the running times over a set
+//!   of targets, and its normalized area as aggregate, and
+//! - the best objective value at fixed budgets ([`fixed_budget`]),
+//!
+//! similar to [COCO] and [IOHprofiler].
+//! The aggregated curves can be exported to CSV using the tables of [`Trajectories`].
+//!
+//! Note that the trajectories only contain the logged steps, so the accuracy of running
+//! times depends on the frequency of logging.
+//! Both the [`Evaluations`] and the best objective value need to be logged at the same steps,
+//! ideally whenever the best objective value improves.
+//!
+//! Targets are absolute objective values.
+//! When aggregating over problems with different optimal values, the trajectories should
+//! therefore be converted to the precision `f - f*` using [`Trajectories::set_optimum`],
+//! such that the same targets are meaningful for all problems.
+//!
+//! [`Log`]: crate::logging::Log
+//! [`Evaluations`]: crate::state::common::Evaluations
+//! [COCO]: https://github.com/numbbo/coco
+//! [IOHprofiler]: https://iohprofiler.github.io/
+//!
+//! # Examples
+//!
+//! Exporting the ECDF of all configurations of an [`Experiment`] over ten targets,
+//! given the `optimum` of every problem:
+//!
+//! ```no_run
+//! use mahf::{
+//!     analysis::anytime::{log_space, Trajectories},
+//!     ExecResult,
+//! };
+//!
+//! # fn example() -> ExecResult<()> {
+//! let mut trajectories =
+//!     Trajectories::from_manifest("data/experiment", "Evaluations", "BestObjectiveValue")?;
+//! # let optimum = |_problem: &str| 0.;
+//! // Aggregate over the precision of all problems.
+//! for problem in trajectories.problems().to_vec() {
+//!     trajectories.set_optimum(&problem, optimum(&problem));
+//! }
+//! let targets = log_space(1e-8, 1e2, 10);
+//! let budgets = log_space(1., 1e5, 50);
+//! let table = trajectories.ecdf_table(&targets, &budgets, false)?;
+//! std::fs::write("ecdf.csv", table.to_csv())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Experiment`]: crate::experiments::Experiment
+
+use std::{collections::HashMap, path::Path};
+
+use eyre::ensure;
+use serde::Serialize;
+
+use super::{
+    results::{folder_runs, manifest_runs, LoggedRun, RunLog},
+    statistics::Summary,
+    tables::{number, Table},
+};
+use crate::ExecResult;
+
+/// The best objective value over the number of evaluations of a single run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Trajectory {
+    /// Pairs of evaluations and the best objective value so far, ordered by evaluations.
+    points: Vec<(f64, f64)>,
+    budget: f64,
+}
+
+impl Trajectory {
+    /// Creates a trajectory from pairs of evaluations and objective values.
+    ///
+    /// The objective values are replaced by the best value so far, and the budget is the
+    /// greatest number of evaluations.
+    pub fn new(mut points: Vec<(f64, f64)>) -> Self {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut best = f64::INFINITY;
+        for (_, value) in &mut points {
+            best = best.min(*value);
+            *value = best;
+        }
+        let budget = points.last().map_or(0., |(evaluations, _)| *evaluations);
+        Self { points, budget }
+    }
+
+    /// Reads the trajectory from the entries `evaluations` and `objective` of the `log`.
+    ///
+    /// The budget is the last logged number of `evaluations`, even if the `objective` was not
+    /// logged at the same step.
+    pub fn from_log(log: &RunLog, evaluations: &str, objective: &str) -> Self {
+        let trajectory = Self::new(log.pairs(evaluations, objective));
+        match log.last(evaluations) {
+            Some(budget) if budget > trajectory.budget => trajectory.with_budget(budget),
+            _ => trajectory,
+        }
+    }
+
+    /// Converts the objective values to the precision `f - optimum`, where `optimum` is the
+    /// optimal objective value of the problem.
+    pub fn precision(mut self, optimum: f64) -> Self {
+        for (_, value) in &mut self.points {
+            *value -= optimum;
+        }
+        self
+    }
+
+    /// Sets the number of evaluations used by the run, if it differs from the last logged
+    /// number of evaluations.
+    pub fn with_budget(mut self, budget: f64) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    /// Returns the number of evaluations used by the run.
+    pub fn budget(&self) -> f64 {
+        self.budget
+    }
+
+    /// Returns the number of evaluations at which the best objective value first reached
+    /// `target`, i.e. was less than or equal to `target`.
+    pub fn running_time(&self, target: f64) -> Option<f64> {
+        self.points
+            .iter()
+            .find(|(_, value)| *value <= target)
+            .map(|(evaluations, _)| *evaluations)
+    }
+
+    /// Returns the best objective value reached within `budget` evaluations.
+    pub fn best_at(&self, budget: f64) -> Option<f64> {
+        self.points
+            .iter()
+            .take_while(|(evaluations, _)| *evaluations <= budget)
+            .last()
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Computes the expected running time (ERT) of the `trajectories` to reach `target`.
+///
+/// The ERT is the sum of the running times of successful runs and the budgets of unsuccessful
+/// runs, divided by the number of successful runs.
+/// It is infinite if no run reached the target.
+pub fn expected_running_time(trajectories: &[Trajectory], target: f64) -> f64 {
+    let mut evaluations = 0.;
+    let mut successes = 0;
+    for trajectory in trajectories {
+        match trajectory.running_time(target) {
+            Some(running_time) => {
+                evaluations += running_time;
+                successes += 1;
+            }
+            None => evaluations += trajectory.budget(),
+        }
+    }
+    if successes == 0 {
+        f64::INFINITY
+    } else {
+        evaluations / successes as f64
+    }
+}
+
+/// The empirical cumulative distribution function of the running times over a set of targets,
+/// i.e. the fraction of (run, target) pairs which were reached within each budget.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Ecdf {
+    pub budgets: Vec<f64>,
+    pub values: Vec<f64>,
+}
+
+impl Ecdf {
+    /// Computes the ECDF of the `trajectories` for all `targets` at all `budgets`.
+    pub fn new(trajectories: &[Trajectory], targets: &[f64], budgets: &[f64]) -> Self {
+        let running_times: Vec<_> = trajectories
+            .iter()
+            .flat_map(|trajectory| targets.iter().map(|t| trajectory.running_time(*t)))
+            .collect();
+        let total = running_times.len() as f64;
+
+        let values = budgets
+            .iter()
+            .map(|budget| {
+                let reached = running_times
+                    .iter()
+                    .filter(|rt| rt.is_some_and(|rt| rt <= *budget))
+                    .count();
+                if total > 0. {
+                    reached as f64 / total
+                } else {
+                    0.
+                }
+            })
+            .collect();
+
+        Self {
+            budgets: budgets.to_vec(),
+            values,
+        }
+    }
+
+    /// Returns the normalized area under the ECDF, which lies in `[0, 1]`.
+    ///
+    /// Every budget is weighted equally, i.e. for budgets from [`log_space`], this is the area
+    /// under the ECDF in log-scale, and for budgets from [`lin_space`] in linear scale.
+    pub fn area(&self) -> f64 {
+        if self.values.is_empty() {
+            0.
+        } else {
+            self.values.iter().sum::<f64>() / self.values.len() as f64
+        }
+    }
+}
+
+/// Returns the descriptive statistics of the best objective values of the `trajectories`
+/// at every budget of `budgets`.
+///
+/// Runs which did not log any value within a budget are ignored for that budget.
+pub fn fixed_budget(trajectories: &[Trajectory], budgets: &[f64]) -> Vec<Summary> {
+    budgets
+        .iter()
+        .map(|budget| {
+            let values: Vec<_> = trajectories
+                .iter()
+                .filter_map(|trajectory| trajectory.best_at(*budget))
+                .collect();
+            Summary::new(&values)
+        })
+        .collect()
+}
+
+/// Returns `n` values spaced evenly between `min` and `max` (inclusive).
+pub fn lin_space(min: f64, max: f64, n: usize) -> Vec<f64> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![min],
+        _ => (0..n)
+            .map(|i| min + (max - min) * i as f64 / (n - 1) as f64)
+            .collect(),
+    }
+}
+
+/// Returns `n` values spaced evenly on a log scale between `min` and `max` (inclusive),
+/// which must be positive.
+pub fn log_space(min: f64, max: f64, n: usize) -> Vec<f64> {
+    lin_space(min.log10(), max.log10(), n)
+        .into_iter()
+        .map(|exponent| 10f64.powf(exponent))
+        .collect()
+}
+
+/// The [`Trajectory`] of every run, grouped by configuration and problem.
+///
+/// Configurations and problems keep the order in which they were first inserted.
+#[derive(Clone, Debug, Default)]
+pub struct Trajectories {
+    configurations: Vec<String>,
+    problems: Vec<String>,
+    trajectories: HashMap<(usize, usize), Vec<Trajectory>>,
+    optima: HashMap<String, f64>,
+}
+
+impl Trajectories {
+    /// Creates an empty collection of trajectories.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the `trajectory` of a run of `configuration` on `problem`.
+    pub fn insert(&mut self, configuration: &str, problem: &str, trajectory: Trajectory) {
+        fn index(names: &mut Vec<String>, name: &str) -> usize {
+            names.iter().position(|n| n == name).unwrap_or_else(|| {
+                names.push(name.to_owned());
+                names.len() - 1
+            })
+        }
+
+        let c = index(&mut self.configurations, configuration);
+        let p = index(&mut self.problems, problem);
+        self.trajectories
+            .entry((c, p))
+            .or_default()
+            .push(trajectory);
+    }
+
+    /// Loads the trajectories of an [`Experiment`] from its [`Manifest`] in `folder`,
+    /// using the log entries `evaluations` and `objective`.
+    ///
+    /// Failed runs are ignored.
+    ///
+    /// [`Experiment`]: crate::experiments::Experiment
+    /// [`Manifest`]: crate::experiments::Manifest
+    pub fn from_manifest(
+        folder: impl AsRef<Path>,
+        evaluations: &str,
+        objective: &str,
+    ) -> ExecResult<Self> {
+        Self::from_runs(manifest_runs(folder.as_ref())?, evaluations, objective)
+    }
+
+    /// Loads the trajectories of [`par_experiment`]s, where every configuration is given by its
+    /// name and the folder of its experiment, using the log entries `evaluations` and `objective`.
+    ///
+    /// [`par_experiment`]: crate::experiments::par_experiment
+    pub fn from_folders<S, F>(
+        folders: impl IntoIterator<Item = (S, F)>,
+        evaluations: &str,
+        objective: &str,
+    ) -> ExecResult<Self>
+    where
+        S: AsRef<str>,
+        F: AsRef<Path>,
+    {
+        Self::from_runs(folder_runs(folders)?, evaluations, objective)
+    }
+
+    /// Sets the `optimum` of `problem`, such that all its trajectories are converted to the
+    /// precision `f - optimum`, see [`Trajectory::precision`].
+    ///
+    /// The optimum is applied when retrieving the trajectories, so it also applies to
+    /// trajectories inserted later, and setting it again replaces the previous optimum.
+    ///
+    /// This is required to aggregate over problems with different optimal values, because
+    /// targets are absolute.
+    pub fn set_optimum(&mut self, problem: &str, optimum: f64) {
+        self.optima.insert(problem.to_owned(), optimum);
+    }
+
+    fn from_runs(runs: Vec<LoggedRun>, evaluations: &str, objective: &str) -> ExecResult<Self> {
+        let mut trajectories = Self::new();
+        for run in runs {
+            let trajectory = Trajectory::from_log(&run.read()?, evaluations, objective);
+            ensure!(
+                !trajectory.points().is_empty(),
+                "{evaluations} and {objective} are never logged together in {}",
+                run.path.display()
+            );
+            trajectories.insert(&run.configuration, &run.problem, trajectory);
+        }
+        Ok(trajectories)
+    }
+
+    pub fn configurations(&self) -> &[String] {
+        &self.configurations
+    }
+
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    /// Returns the trajectories of `configuration` on `problem`, converted to the precision
+    /// if the optimum of `problem` was set.
+    pub fn get(&self, configuration: &str, problem: &str) -> Vec<Trajectory> {
+        let c = self.configurations.iter().position(|n| n == configuration);
+        let p = self.problems.iter().position(|n| n == problem);
+        let trajectories = c
+            .zip(p)
+            .and_then(|key| self.trajectories.get(&key))
+            .map_or(&[][..], Vec::as_slice);
+        match self.optima.get(problem) {
+            Some(&optimum) => trajectories
+                .iter()
+                .map(|trajectory| trajectory.clone().precision(optimum))
+                .collect(),
+            None => trajectories.to_vec(),
+        }
+    }
+
+    /// Returns the trajectories of `configuration` on all problems.
+    fn all(&self, configuration: &str) -> Vec<Trajectory> {
+        self.problems
+            .iter()
+            .flat_map(|problem| self.get(configuration, problem))
+            .collect()
+    }
+
+    fn ensure_nonempty(&self) -> ExecResult<()> {
+        ensure!(
+            !self.trajectories.is_empty(),
+            "there are no trajectories to analyze"
+        );
+        Ok(())
+    }
+
+    /// Creates a table of the [`expected_running_time`] of every configuration on every problem
+    /// for every target of `targets`, together with the number of successful runs.
+    pub fn ert_table(&self, targets: &[f64]) -> ExecResult<Table> {
+        self.ensure_nonempty()?;
+        let mut table = Table::new([
+            "Problem",
+            "Configuration",
+            "Target",
+            "ERT",
+            "Successes",
+            "Runs",
+        ]);
+        for problem in &self.problems {
+            for configuration in &self.configurations {
+                let trajectories = self.get(configuration, problem);
+                for target in targets {
+                    let successes = trajectories
+                        .iter()
+                        .filter(|t| t.running_time(*target).is_some())
+                        .count();
+                    table.push_row([
+                        problem.clone(),
+                        configuration.clone(),
+                        number(*target),
+                        number(expected_running_time(&trajectories, *target)),
+                        successes.to_string(),
+                        trajectories.len().to_string(),
+                    ]);
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// Creates a table of the [`Ecdf`] of every configuration for all `targets` at all `budgets`,
+    /// with one row per point of the curve.
+    ///
+    /// If `per_problem` is `true`, the ECDF is computed for every problem separately,
+    /// and aggregated over all problems otherwise, in which case the problem is `all`.
+    /// As the `targets` are the same for all problems, aggregating is only meaningful if the
+    /// values are precisions, see [`set_optimum`].
+    ///
+    /// [`set_optimum`]: Trajectories::set_optimum
+    pub fn ecdf_table(
+        &self,
+        targets: &[f64],
+        budgets: &[f64],
+        per_problem: bool,
+    ) -> ExecResult<Table> {
+        self.ensure_nonempty()?;
+        let mut table = Table::new(["Problem", "Configuration", "Evaluations", "ECDF"]);
+        for (problem, configuration, ecdf) in self.ecdfs(targets, budgets, per_problem) {
+            for (budget, value) in ecdf.budgets.iter().zip(&ecdf.values) {
+                table.push_row([
+                    problem.clone(),
+                    configuration.clone(),
+                    number(*budget),
+                    format!("{value:.4}"),
+                ]);
+            }
+        }
+        Ok(table)
+    }
+
+    /// Creates a table of the normalized area under the [`Ecdf`] of every configuration
+    /// for all `targets` at all `budgets`, see [`Ecdf::area`].
+    ///
+    /// If `per_problem` is `true`, the ECDF is computed for every problem separately,
+    /// and aggregated over all problems otherwise, in which case the problem is `all`.
+    /// As for [`ecdf_table`], aggregating is only meaningful if the values are precisions.
+    ///
+    /// [`ecdf_table`]: Trajectories::ecdf_table
+    pub fn auc_table(
+        &self,
+        targets: &[f64],
+        budgets: &[f64],
+        per_problem: bool,
+    ) -> ExecResult<Table> {
+        self.ensure_nonempty()?;
+        let mut table = Table::new(["Problem", "Configuration", "AUC"]);
+        for (problem, configuration, ecdf) in self.ecdfs(targets, budgets, per_problem) {
+            table.push_row([problem, configuration, format!("{:.4}", ecdf.area())]);
+        }
+        Ok(table)
+    }
+
+    fn ecdfs(
+        &self,
+        targets: &[f64],
+        budgets: &[f64],
+        per_problem: bool,
+    ) -> Vec<(String, String, Ecdf)> {
+        let mut ecdfs = Vec::new();
+        if per_problem {
+            for problem in &self.problems {
+                for configuration in &self.configurations {
+                    let trajectories = self.get(configuration, problem);
+                    let ecdf = Ecdf::new(&trajectories, targets, budgets);
+                    ecdfs.push((problem.clone(), configuration.clone(), ecdf));
+                }
+            }
+        } else {
+            for configuration in &self.configurations {
+                let ecdf = Ecdf::new(&self.all(configuration), targets, budgets);
+                ecdfs.push(("all".to_owned(), configuration.clone(), ecdf));
+            }
+        }
+        ecdfs
+    }
+
+    /// Creates a table of the best objective values of every configuration on every problem
+    /// at all `budgets`, see [`fixed_budget`].
+    pub fn fixed_budget_table(&self, budgets: &[f64]) -> ExecResult<Table> {
+        self.ensure_nonempty()?;
+        let mut table = Table::new([
+            "Problem",
+            "Configuration",
+            "Evaluations",
+            "Runs",
+            "Mean",
+            "Median",
+            "Lower quartile",
+            "Upper quartile",
+        ]);
+        for problem in &self.problems {
+            for configuration in &self.configurations {
+                let summaries = fixed_budget(&self.get(configuration, problem), budgets);
+                for (budget, summary) in budgets.iter().zip(summaries) {
+                    table.push_row([
+                        problem.clone(),
+                        configuration.clone(),
+                        number(*budget),
+                        summary.n.to_string(),
+                        number(summary.mean),
+                        number(summary.median),
+                        number(summary.lower_quartile),
+                        number(summary.upper_quartile),
+                    ]);
+                }
+            }
+        }
+        Ok(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+
+    use super::*;
+
+    fn trajectories() -> Vec<Trajectory> {
+        vec![
+            Trajectory::new(vec![(10., 5.), (20., 1.), (30., 2.), (40., 0.5)]),
+            Trajectory::new(vec![(10., 3.), (50., 0.8)]).with_budget(100.),
+        ]
+    }
+
+    #[test]
+    fn trajectory_tracks_best_value() {
+        let trajectory = &trajectories()[0];
+        assert_eq!(trajectory.points()[2], (30., 1.));
+        assert_eq!(trajectory.budget(), 40.);
+        assert_eq!(trajectory.running_time(1.), Some(20.));
+        assert_eq!(trajectory.running_time(0.1), None);
+        assert_eq!(trajectory.best_at(35.), Some(1.));
+        assert_eq!(trajectory.best_at(5.), None);
+    }
+
+    #[test]
+    fn trajectory_budget_is_read_from_last_evaluations() {
+        let log: RunLog = serde_json::from_value(serde_json::json!({
+            "names": ["Evaluations", "BestObjectiveValue"],
+            "entries": [
+                { "0": 10, "1": 5.0 },
+                { "0": 20, "1": 1.0 },
+                { "0": 60 },
+            ],
+        }))
+        .unwrap();
+        let trajectory = Trajectory::from_log(&log, "Evaluations", "BestObjectiveValue");
+        assert_eq!(trajectory.points(), [(10., 5.), (20., 1.)]);
+        assert_eq!(trajectory.budget(), 60.);
+    }
+
+    #[test]
+    fn optima_make_targets_comparable() {
+        let mut all = Trajectories::new();
+        all.insert("A", "Sphere", Trajectory::new(vec![(10., 1.), (20., 0.)]));
+        all.insert(
+            "A",
+            "Shifted",
+            Trajectory::new(vec![(10., 101.), (20., 100.)]),
+        );
+        all.set_optimum("Shifted", 100.);
+
+        assert_eq!(all.get("A", "Shifted")[0].points(), [(10., 1.), (20., 0.)]);
+        let table = all.auc_table(&[0.], &[20.], false).unwrap();
+        assert_eq!(table.rows()[0][2], "1.0000");
+    }
+
+    #[test]
+    fn optima_are_applied_once_to_all_trajectories() {
+        let mut all = Trajectories::new();
+        all.set_optimum("Shifted", 50.);
+        all.insert("A", "Shifted", Trajectory::new(vec![(10., 101.)]));
+        all.set_optimum("Shifted", 100.);
+        all.set_optimum("Shifted", 100.);
+        all.insert("B", "Shifted", Trajectory::new(vec![(10., 102.)]));
+
+        assert_eq!(all.get("A", "Shifted")[0].points(), [(10., 1.)]);
+        assert_eq!(all.get("B", "Shifted")[0].points(), [(10., 2.)]);
+    }
+
+    #[test]
+    fn ert_counts_budgets_of_unsuccessful_runs() {
+        let trajectories = trajectories();
+        // Both runs reach 1 after 20 and 50 evaluations.
+        assert_float_eq!(expected_running_time(&trajectories, 1.), 35., abs <= 1e-12);
+        // Only the first run reaches 0.5 after 40 evaluations, the second uses 100.
+        assert_float_eq!(
+            expected_running_time(&trajectories, 0.5),
+            140.,
+            abs <= 1e-12
+        );
+        assert_eq!(expected_running_time(&trajectories, 0.1), f64::INFINITY);
+    }
+
+    #[test]
+    fn ecdf_and_area() {
+        let ecdf = Ecdf::new(&trajectories(), &[1., 0.5], &[10., 20., 40., 50.]);
+        assert_eq!(ecdf.values, [0., 0.25, 0.5, 0.75]);
+        assert_float_eq!(ecdf.area(), 0.375, abs <= 1e-12);
+    }
+
+    #[test]
+    fn fixed_budget_summarizes_reached_values() {
+        let summaries = fixed_budget(&trajectories(), &[5., 10., 50.]);
+        assert_eq!(summaries[0].n, 0);
+        assert_eq!((summaries[1].min, summaries[1].max), (3., 5.));
+        assert_eq!((summaries[2].min, summaries[2].max), (0.5, 0.8));
+    }
+
+    #[test]
+    fn spaces() {
+        assert_eq!(lin_space(0., 1., 3), [0., 0.5, 1.]);
+        let log = log_space(1., 100., 3);
+        assert_float_eq!(log[1], 10., abs <= 1e-12);
+        assert_float_eq!(log[2], 100., abs <= 1e-9);
+    }
+
+    #[test]
+    fn tables_aggregate_over_problems() {
+        let mut all = Trajectories::new();
+        for trajectory in trajectories() {
+            all.insert("A", "Sphere", trajectory.clone());
+            all.insert("A", "Rastrigin", trajectory);
+        }
+
+        let table = all.ecdf_table(&[1.], &[20., 50.], false).unwrap();
+        assert_eq!(
+            table.to_csv(),
+            "Problem,Configuration,Evaluations,ECDF\nall,A,20.00,0.5000\nall,A,50.00,1.0000\n"
+        );
+
+        let table = all.ert_table(&[0.5]).unwrap();
+        assert_eq!(table.rows().len(), 2);
+        assert_eq!(table.rows()[0][3], "140.0");
+        assert_eq!(table.rows()[0][4], "1");
+    }
+}
@@ -0,0 +1,58 @@
+//! Statistical analysis of experiment results.
+//!
+//! This module reads the [`Log`]s written by [`Experiment`] or [`par_experiment`] back into
+//! [`RunLog`]s, and collects one scalar result per run (e.g. the final best objective value)
+//! into [`Results`], grouped by configuration and problem.
+//!
+//! The results can then be compared using
+//! - descriptive statistics ([`Summary`]),
+//! - pairwise tests ([`mann_whitney_u`], [`wilcoxon_signed_rank`]) with Holm correction ([`holm`]),
+//! - the [`Friedman`] test with Nemenyi post-hoc ranks over multiple problems, and
+//! - the Vargha–Delaney effect size ([`vargha_delaney_a12`]).
+//!
+//! [`Table`] collects these into tables, which can be exported to Markdown, LaTeX, and CSV.
+//!
+//! All statistics assume minimization, i.e. lower values are better.
+//!
+//! Beyond final results, the [`anytime`] module analyzes the best objective value over the
+//! number of evaluations, e.g. using expected running times and ECDFs.
+//!
+//! [`Log`]: crate::logging::Log
+//! [`Experiment`]: crate::experiments::Experiment
+//! [`par_experiment`]: crate::experiments::par_experiment
+//!
+//! # Examples
+//!
+//! Comparing the final best objective values of all configurations of an [`Experiment`]
+//! with the configuration `GA`:
+//!
+//! ```no_run
+//! use mahf::{
+//!     analysis::{PairwiseTest, Results, Table},
+//!     ExecResult,
+//! };
+//!
+//! # fn example() -> ExecResult<()> {
+//! let results = Results::from_manifest("data/experiment", |log| log.last("BestObjectiveValue"))?;
+//!
+//! println!("{}", Table::summary(&results).to_markdown());
+//! println!(
+//!     "{}",
+//!     Table::comparison(&results, "GA", PairwiseTest::WilcoxonSignedRank, 0.05)?.to_markdown()
+//! );
+//! std::fs::write("ranks.tex", Table::ranking(&results, 0.05)?.to_latex())?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod anytime;
+pub mod results;
+pub mod statistics;
+pub mod tables;
+
+pub use results::{Results, RunLog};
+pub use statistics::{
+    holm, mann_whitney_u, vargha_delaney_a12, wilcoxon_signed_rank, EffectSize, Friedman, Summary,
+    TestResult,
+};
+pub use tables::{PairwiseTest, Table};
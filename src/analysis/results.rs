@@ -0,0 +1,446 @@
+//! Loading of logged runs and collection of results per configuration and problem.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use ciborium::Value;
+use eyre::{ensure, eyre, WrapErr};
+use serde::Deserialize;
+
+use super::statistics::{Friedman, Summary};
+use crate::{
+    configuration::tree::short_type_name,
+    experiments::{Manifest, RunStatus},
+    ExecResult,
+};
+
+/// A [`Log`] read back from a file written by [`Log::to_cbor`] or [`Log::to_json`].
+///
+/// Entries are looked up by name, which is either the full entry name or the entry name
+/// without module paths, e.g. both `mahf::state::common::Evaluations` and `Evaluations`
+/// refer to the logged [`Evaluations`].
+///
+/// Only numeric values (including newtypes like [`SingleObjective`]) can be read as `f64`.
+///
+/// [`Log`]: crate::logging::Log
+/// [`Log::to_cbor`]: crate::logging::Log::to_cbor
+/// [`Log::to_json`]: crate::logging::Log::to_json
+/// [`Evaluations`]: crate::state::common::Evaluations
+/// [`SingleObjective`]: crate::problems::SingleObjective
+#[derive(Clone, Debug, Deserialize)]
+pub struct RunLog {
+    names: Vec<String>,
+    entries: Vec<HashMap<usize, Value>>,
+}
+
+impl RunLog {
+    /// Reads a log written by [`Log::to_cbor`].
+    ///
+    /// [`Log::to_cbor`]: crate::logging::Log::to_cbor
+    pub fn read_cbor(path: impl AsRef<Path>) -> ExecResult<Self> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).wrap_err_with(|| format!("failed to open {}", path.display()))?;
+        ciborium::de::from_reader(BufReader::new(file))
+            .wrap_err_with(|| format!("failed to read cbor log {}", path.display()))
+    }
+
+    /// Reads a log written by [`Log::to_json`].
+    ///
+    /// [`Log::to_json`]: crate::logging::Log::to_json
+    pub fn read_json(path: impl AsRef<Path>) -> ExecResult<Self> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).wrap_err_with(|| format!("failed to open {}", path.display()))?;
+        serde_json::from_reader(BufReader::new(file))
+            .wrap_err_with(|| format!("failed to read json log {}", path.display()))
+    }
+
+    /// Returns the names of all logged entries.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the number of logged steps.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no steps were logged.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn key(&self, name: &str) -> Option<usize> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .or_else(|| self.names.iter().position(|n| short_type_name(n) == name))
+    }
+
+    /// Returns the raw value of the entry `name` at `step`, if it was logged.
+    pub fn get(&self, step: usize, name: &str) -> Option<&Value> {
+        let key = self.key(name)?;
+        self.entries.get(step)?.get(&key)
+    }
+
+    /// Returns the numeric values of the entry `name` together with the index of their step.
+    ///
+    /// Steps where the entry was not logged or is not numeric are omitted.
+    pub fn series(&self, name: &str) -> Vec<(usize, f64)> {
+        let Some(key) = self.key(name) else {
+            return Vec::new();
+        };
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(step, entries)| Some((step, as_f64(entries.get(&key)?)?)))
+            .collect()
+    }
+
+    /// Returns the numeric values of the entries `x` and `y` for all steps where both were logged.
+    ///
+    /// This is useful e.g. for pairing [`Evaluations`] with the best objective value.
+    ///
+    /// [`Evaluations`]: crate::state::common::Evaluations
+    pub fn pairs(&self, x: &str, y: &str) -> Vec<(f64, f64)> {
+        let (Some(x), Some(y)) = (self.key(x), self.key(y)) else {
+            return Vec::new();
+        };
+        self.entries
+            .iter()
+            .filter_map(|entries| Some((as_f64(entries.get(&x)?)?, as_f64(entries.get(&y)?)?)))
+            .collect()
+    }
+
+    /// Returns the last numeric value of the entry `name`.
+    pub fn last(&self, name: &str) -> Option<f64> {
+        let key = self.key(name)?;
+        self.entries
+            .iter()
+            .rev()
+            .find_map(|entries| as_f64(entries.get(&key)?))
+    }
+}
+
+/// Converts a numeric [`Value`] into a `f64`.
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(i) => Some(i128::from(*i) as f64),
+        Value::Float(f) => Some(*f),
+        Value::Tag(_, value) => as_f64(value),
+        _ => None,
+    }
+}
+
+/// The location of the log of a single run.
+pub(crate) struct LoggedRun {
+    pub configuration: String,
+    pub problem: String,
+    pub run: u64,
+    pub path: PathBuf,
+}
+
+impl LoggedRun {
+    pub fn read(&self) -> ExecResult<RunLog> {
+        RunLog::read_cbor(&self.path)
+    }
+}
+
+/// Returns the logs of all runs of the [`Experiment`] in `folder` which did not fail.
+///
+/// [`Experiment`]: crate::experiments::Experiment
+pub(crate) fn manifest_runs(folder: &Path) -> ExecResult<Vec<LoggedRun>> {
+    let manifest = Manifest::read(folder)?;
+    Ok(manifest
+        .runs
+        .into_iter()
+        .filter(|record| record.status != RunStatus::Failed)
+        .map(|record| LoggedRun {
+            path: folder.join(&record.log),
+            configuration: record.configuration,
+            problem: record.problem,
+            run: record.run,
+        })
+        .collect())
+}
+
+/// Returns the logs of all runs of [`par_experiment`]s, given by the name of the configuration
+/// and the folder of its experiment.
+///
+/// The logs are named `{problem}_{run}.cbor`.
+///
+/// [`par_experiment`]: crate::experiments::par_experiment
+pub(crate) fn folder_runs<S, F>(
+    folders: impl IntoIterator<Item = (S, F)>,
+) -> ExecResult<Vec<LoggedRun>>
+where
+    S: AsRef<str>,
+    F: AsRef<Path>,
+{
+    let mut runs = Vec::new();
+    for (configuration, folder) in folders {
+        let folder = folder.as_ref();
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(folder)
+            .wrap_err_with(|| format!("failed to read {}", folder.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "cbor") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        for path in paths {
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            let (problem, run) = stem
+                .rsplit_once('_')
+                .and_then(|(problem, run)| Some((problem.to_owned(), run.parse::<u64>().ok()?)))
+                .ok_or_else(|| eyre!("unexpected log file name {}", path.display()))?;
+            runs.push(LoggedRun {
+                configuration: configuration.as_ref().to_owned(),
+                problem,
+                run,
+                path,
+            });
+        }
+    }
+    Ok(runs)
+}
+
+/// One scalar result (e.g. the final best objective value) per run, grouped by configuration
+/// and problem.
+///
+/// Configurations and problems keep the order in which they were first inserted,
+/// and values are ordered by run, which allows pairing runs with the same seed.
+///
+/// # Examples
+///
+/// Loading the final best objective values of an [`Experiment`]:
+///
+/// ```no_run
+/// # use mahf::{analysis::Results, ExecResult};
+/// # fn example() -> ExecResult<()> {
+/// let results = Results::from_manifest("data/experiment", |log| log.last("BestObjectiveValue"))?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Experiment`]: crate::experiments::Experiment
+#[derive(Clone, Debug, Default)]
+pub struct Results {
+    configurations: Vec<String>,
+    problems: Vec<String>,
+    values: HashMap<(usize, usize), BTreeMap<u64, f64>>,
+}
+
+impl Results {
+    /// Creates empty results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the `value` of `run` of `configuration` on `problem`.
+    ///
+    /// An existing value of the same run is replaced.
+    pub fn insert(&mut self, configuration: &str, problem: &str, run: u64, value: f64) {
+        fn index(names: &mut Vec<String>, name: &str) -> usize {
+            names.iter().position(|n| n == name).unwrap_or_else(|| {
+                names.push(name.to_owned());
+                names.len() - 1
+            })
+        }
+
+        let c = index(&mut self.configurations, configuration);
+        let p = index(&mut self.problems, problem);
+        self.values.entry((c, p)).or_default().insert(run, value);
+    }
+
+    /// Loads the results of an [`Experiment`] from its [`Manifest`] in `folder`.
+    ///
+    /// The `metric` extracts the result from the log of every run.
+    /// Failed runs are ignored.
+    ///
+    /// [`Experiment`]: crate::experiments::Experiment
+    pub fn from_manifest(
+        folder: impl AsRef<Path>,
+        metric: impl Fn(&RunLog) -> Option<f64>,
+    ) -> ExecResult<Self> {
+        Self::from_runs(manifest_runs(folder.as_ref())?, metric)
+    }
+
+    /// Loads the results of [`par_experiment`]s, where every configuration is given by its
+    /// name and the folder of its experiment.
+    ///
+    /// The `metric` extracts the result from the log of every run.
+    ///
+    /// [`par_experiment`]: crate::experiments::par_experiment
+    pub fn from_folders<S, F>(
+        folders: impl IntoIterator<Item = (S, F)>,
+        metric: impl Fn(&RunLog) -> Option<f64>,
+    ) -> ExecResult<Self>
+    where
+        S: AsRef<str>,
+        F: AsRef<Path>,
+    {
+        Self::from_runs(folder_runs(folders)?, metric)
+    }
+
+    fn from_runs(
+        runs: Vec<LoggedRun>,
+        metric: impl Fn(&RunLog) -> Option<f64>,
+    ) -> ExecResult<Self> {
+        let mut results = Self::new();
+        for run in runs {
+            let value = metric(&run.read()?)
+                .ok_or_else(|| eyre!("the metric is missing in {}", run.path.display()))?;
+            results.insert(&run.configuration, &run.problem, run.run, value);
+        }
+        Ok(results)
+    }
+
+    /// Returns the names of all configurations.
+    pub fn configurations(&self) -> &[String] {
+        &self.configurations
+    }
+
+    /// Returns the names of all problems.
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    fn cell(&self, configuration: &str, problem: &str) -> Option<&BTreeMap<u64, f64>> {
+        let c = self
+            .configurations
+            .iter()
+            .position(|n| n == configuration)?;
+        let p = self.problems.iter().position(|n| n == problem)?;
+        self.values.get(&(c, p))
+    }
+
+    /// Returns the values of `configuration` on `problem`, ordered by run.
+    pub fn values(&self, configuration: &str, problem: &str) -> Vec<f64> {
+        self.cell(configuration, problem)
+            .map(|values| values.values().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the values of the configurations `a` and `b` on `problem` for all runs
+    /// that exist for both, e.g. for paired tests.
+    pub fn paired(&self, a: &str, b: &str, problem: &str) -> (Vec<f64>, Vec<f64>) {
+        let (Some(a), Some(b)) = (self.cell(a, problem), self.cell(b, problem)) else {
+            return (Vec::new(), Vec::new());
+        };
+        a.iter()
+            .filter_map(|(run, x)| Some((*x, *b.get(run)?)))
+            .unzip()
+    }
+
+    /// Returns the descriptive statistics of `configuration` on `problem`.
+    pub fn summary(&self, configuration: &str, problem: &str) -> Summary {
+        Summary::new(&self.values(configuration, problem))
+    }
+
+    /// Performs the [`Friedman`] test with the configurations as treatments and the problems as
+    /// blocks, using the median value of every configuration on every problem.
+    pub fn friedman(&self) -> ExecResult<Friedman> {
+        self.ensure_complete()?;
+        let blocks: Vec<_> = self
+            .problems
+            .iter()
+            .map(|problem| {
+                self.configurations
+                    .iter()
+                    .map(|configuration| self.summary(configuration, problem).median)
+                    .collect()
+            })
+            .collect();
+        Friedman::new(&blocks)
+    }
+
+    /// Ensures that every configuration has at least one value on every problem.
+    pub(crate) fn ensure_complete(&self) -> ExecResult<()> {
+        for configuration in &self.configurations {
+            for problem in &self.problems {
+                ensure!(
+                    self.cell(configuration, problem)
+                        .is_some_and(|values| !values.is_empty()),
+                    "no results of {configuration} on {problem}"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::type_name;
+
+    use super::*;
+    use crate::{
+        logging::{
+            log::{Entry, Step},
+            Log,
+        },
+        problems::SingleObjective,
+        state::common::Evaluations,
+    };
+
+    #[test]
+    fn run_log_reads_cbor() {
+        let mut log = Log::new();
+        for (evaluations, best) in [(10u32, Some(3.5)), (20, None), (30, Some(1.25))] {
+            let mut step = Step::default();
+            step.push(Entry {
+                name: type_name::<Evaluations>(),
+                value: Box::new(evaluations),
+            });
+            if let Some(best) = best {
+                let best: SingleObjective = best.try_into().unwrap();
+                step.push(Entry {
+                    name: "BestObjectiveValue",
+                    value: Box::new(best),
+                });
+            }
+            log.push(step);
+        }
+
+        let path = std::env::temp_dir().join(format!("mahf-run-log-{}.cbor", std::process::id()));
+        log.to_cbor(&path).unwrap();
+        let log = RunLog::read_cbor(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.last("BestObjectiveValue"), Some(1.25));
+        assert_eq!(log.series("Evaluations"), [(0, 10.), (1, 20.), (2, 30.)]);
+        assert_eq!(
+            log.pairs("Evaluations", "BestObjectiveValue"),
+            [(10., 3.5), (30., 1.25)]
+        );
+        assert_eq!(log.last("Missing"), None);
+    }
+
+    #[test]
+    fn results_pair_runs() {
+        let mut results = Results::new();
+        results.insert("A", "Sphere", 1, 1.);
+        results.insert("A", "Sphere", 0, 0.);
+        results.insert("B", "Sphere", 1, 3.);
+        results.insert("B", "Sphere", 2, 4.);
+
+        assert_eq!(results.values("A", "Sphere"), [0., 1.]);
+        assert_eq!(results.paired("A", "B", "Sphere"), (vec![1.], vec![3.]));
+        assert!(results.ensure_complete().is_ok());
+        results.insert("C", "Rastrigin", 0, 0.);
+        assert!(results.ensure_complete().is_err());
+    }
+}
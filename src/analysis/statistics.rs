@@ -0,0 +1,468 @@
+//! Descriptive statistics, hypothesis tests, and effect sizes.
+//!
+//! All tests are two-sided.
+//! Rank-based statistics assign lower ranks to lower values, i.e. they assume minimization.
+
+use std::fmt;
+
+use eyre::{ensure, ContextCompat};
+use serde::Serialize;
+use statrs::{
+    distribution::{ChiSquared, ContinuousCDF, Normal},
+    statistics::{Data, OrderStatistics, RankTieBreaker, Statistics},
+};
+
+use crate::ExecResult;
+
+/// Descriptive statistics of a sample.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub n: usize,
+    pub mean: f64,
+    /// The sample standard deviation.
+    pub std_dev: f64,
+    pub min: f64,
+    pub lower_quartile: f64,
+    pub median: f64,
+    pub upper_quartile: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    /// Computes the descriptive statistics of `values`.
+    ///
+    /// All statistics except `n` are `NaN` if `values` is empty.
+    pub fn new(values: &[f64]) -> Self {
+        let mut data = Data::new(values.to_vec());
+        Self {
+            n: values.len(),
+            mean: values.mean(),
+            std_dev: values.std_dev(),
+            min: values.min(),
+            lower_quartile: data.lower_quartile(),
+            median: data.median(),
+            upper_quartile: data.upper_quartile(),
+            max: values.max(),
+        }
+    }
+}
+
+/// Returns the ranks of `values`, starting at `1` for the lowest value.
+///
+/// Tied values receive the average of their ranks.
+pub fn ranks(values: &[f64]) -> Vec<f64> {
+    Data::new(values.to_vec()).ranks(RankTieBreaker::Average)
+}
+
+/// Returns the sum of `t³ - t` over the sizes `t` of all groups of tied `values`.
+fn tie_correction(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+        .chunk_by(|a, b| a == b)
+        .map(|group| {
+            let t = group.len() as f64;
+            t.powi(3) - t
+        })
+        .sum()
+}
+
+/// The result of a hypothesis test.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct TestResult {
+    /// The test statistic.
+    pub statistic: f64,
+    /// The two-sided p-value.
+    pub p_value: f64,
+}
+
+/// Returns the two-sided p-value of an integer-valued `statistic` with the distribution given
+/// by the `counts` of all its values.
+fn exact_p_value(counts: &[f64], statistic: f64) -> f64 {
+    let total: f64 = counts.iter().sum();
+    let statistic = statistic.round() as usize;
+    let lower: f64 = counts[..=statistic].iter().sum();
+    let upper: f64 = counts[statistic..].iter().sum();
+    (2. * lower.min(upper) / total).min(1.)
+}
+
+/// Returns the two-sided p-value of `statistic` using a normal approximation with continuity
+/// correction.
+fn normal_p_value(statistic: f64, mean: f64, variance: f64) -> f64 {
+    if variance <= 0. {
+        return 1.;
+    }
+    let z = ((statistic - mean).abs() - 0.5).max(0.) / variance.sqrt();
+    let normal = Normal::new(0., 1.).unwrap();
+    (2. * normal.sf(z)).min(1.)
+}
+
+/// Performs the Mann–Whitney U test (also known as Wilcoxon rank-sum test) on the independent
+/// samples `a` and `b`.
+///
+/// The statistic is the U of `a`, i.e. the number of pairs where the value of `a` is greater
+/// than the value of `b`, with ties counting half.
+///
+/// The exact distribution is used if both samples contain less than 50 values and there are
+/// no ties, and a normal approximation otherwise.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> ExecResult<TestResult> {
+    ensure!(
+        !a.is_empty() && !b.is_empty(),
+        "the Mann–Whitney U test requires non-empty samples"
+    );
+
+    let (m, n) = (a.len(), b.len());
+    let combined: Vec<_> = a.iter().chain(b).copied().collect();
+    let rank_sum: f64 = ranks(&combined)[..m].iter().sum();
+    let u = rank_sum - (m * (m + 1)) as f64 / 2.;
+
+    let ties = tie_correction(&combined);
+    let p_value = if ties == 0. && m < 50 && n < 50 {
+        // counts[j][u] is the number of orderings of `i` values of `a` and `j` values of `b`
+        // with statistic `u`, which is computed incrementally for `i = 0..=m`.
+        let delta = |len| {
+            let mut counts = vec![0.; len];
+            counts[0] = 1.;
+            counts
+        };
+        let mut counts = vec![delta(m * n + 1); n + 1];
+        for _ in 1..=m {
+            let previous = std::mem::replace(&mut counts, vec![delta(m * n + 1)]);
+            for j in 1..=n {
+                // The greatest value is either from `a` and greater than all `j` values of `b`,
+                // or it is from `b`.
+                let row = (0..=m * n)
+                    .map(|u| {
+                        let from_a = if u >= j { previous[j][u - j] } else { 0. };
+                        from_a + counts[j - 1][u]
+                    })
+                    .collect();
+                counts.push(row);
+            }
+        }
+        exact_p_value(&counts[n], u)
+    } else {
+        let (m, n) = (m as f64, n as f64);
+        let variance = m * n / 12. * ((m + n + 1.) - ties / ((m + n) * (m + n - 1.)));
+        normal_p_value(u, m * n / 2., variance)
+    };
+
+    Ok(TestResult {
+        statistic: u,
+        p_value,
+    })
+}
+
+/// Performs the Wilcoxon signed-rank test on the paired samples `a` and `b`.
+///
+/// The statistic is the sum of the ranks of the positive differences `a - b`.
+/// Zero differences are discarded.
+///
+/// The exact distribution is used if there are less than 50 non-zero differences and no ties,
+/// and a normal approximation otherwise.
+pub fn wilcoxon_signed_rank(a: &[f64], b: &[f64]) -> ExecResult<TestResult> {
+    ensure!(
+        a.len() == b.len(),
+        "the Wilcoxon signed-rank test requires paired samples of equal size"
+    );
+
+    let differences: Vec<_> = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| x - y)
+        .filter(|d| *d != 0.)
+        .collect();
+    let n = differences.len();
+    if n == 0 {
+        return Ok(TestResult {
+            statistic: 0.,
+            p_value: 1.,
+        });
+    }
+
+    let magnitudes: Vec<_> = differences.iter().map(|d| d.abs()).collect();
+    let w: f64 = ranks(&magnitudes)
+        .iter()
+        .zip(&differences)
+        .filter(|(_, d)| **d > 0.)
+        .map(|(r, _)| r)
+        .sum();
+
+    let ties = tie_correction(&magnitudes);
+    let p_value = if ties == 0. && n < 50 {
+        // Distribution of the sum of a random subset of the ranks 1..=n.
+        let mut counts = vec![0.; n * (n + 1) / 2 + 1];
+        counts[0] = 1.;
+        for rank in 1..=n {
+            for sum in (rank..counts.len()).rev() {
+                counts[sum] += counts[sum - rank];
+            }
+        }
+        exact_p_value(&counts, w)
+    } else {
+        let n = n as f64;
+        let variance = n * (n + 1.) * (2. * n + 1.) / 24. - ties / 48.;
+        normal_p_value(w, n * (n + 1.) / 4., variance)
+    };
+
+    Ok(TestResult {
+        statistic: w,
+        p_value,
+    })
+}
+
+/// Adjusts the `p_values` of multiple comparisons using the Holm–Bonferroni method.
+///
+/// The adjusted p-values are returned in the order of `p_values`.
+pub fn holm(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    let mut order: Vec<_> = (0..m).collect();
+    order.sort_by(|&i, &j| p_values[i].total_cmp(&p_values[j]));
+
+    let mut adjusted = vec![0.; m];
+    let mut running_max: f64 = 0.;
+    for (k, &i) in order.iter().enumerate() {
+        running_max = running_max.max(((m - k) as f64 * p_values[i]).min(1.));
+        adjusted[i] = running_max;
+    }
+    adjusted
+}
+
+/// Computes the Vargha–Delaney A12 effect size of the samples `a` and `b`.
+///
+/// A12 is the probability that a value of `a` is greater than a value of `b`, with ties
+/// counting half.
+/// For minimization, values below `0.5` therefore indicate that `a` is better than `b`.
+pub fn vargha_delaney_a12(a: &[f64], b: &[f64]) -> f64 {
+    let mut wins = 0.;
+    for x in a {
+        for y in b {
+            if x > y {
+                wins += 1.;
+            } else if x == y {
+                wins += 0.5;
+            }
+        }
+    }
+    wins / (a.len() * b.len()) as f64
+}
+
+/// The magnitude of an effect size, following Vargha and Delaney.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectSize {
+    Negligible,
+    Small,
+    Medium,
+    Large,
+}
+
+impl EffectSize {
+    /// Classifies the Vargha–Delaney `a12` effect size.
+    ///
+    /// The thresholds are `0.56`, `0.64`, and `0.71` for small, medium, and large effects,
+    /// respectively (and symmetrically below `0.5`).
+    pub fn from_a12(a12: f64) -> Self {
+        let deviation = (a12 - 0.5).abs();
+        if deviation < 0.06 {
+            Self::Negligible
+        } else if deviation < 0.14 {
+            Self::Small
+        } else if deviation < 0.21 {
+            Self::Medium
+        } else {
+            Self::Large
+        }
+    }
+}
+
+impl fmt::Display for EffectSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Negligible => "negligible",
+            Self::Small => "small",
+            Self::Medium => "medium",
+            Self::Large => "large",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The critical values of the Nemenyi test for `k = 2..=10` treatments,
+/// i.e. the studentized range statistic divided by `√2`, from Demšar (2006).
+const NEMENYI_Q_05: [f64; 9] = [
+    1.960, 2.343, 2.569, 2.728, 2.850, 2.949, 3.031, 3.102, 3.164,
+];
+const NEMENYI_Q_10: [f64; 9] = [
+    1.645, 2.052, 2.291, 2.459, 2.589, 2.693, 2.780, 2.855, 2.920,
+];
+
+/// The result of the Friedman test, including the average ranks for the Nemenyi post-hoc test.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Friedman {
+    /// The average rank of every treatment (e.g. configuration) over all blocks (e.g. problems).
+    pub ranks: Vec<f64>,
+    /// The number of blocks.
+    pub blocks: usize,
+    /// The chi-squared statistic, corrected for ties.
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+impl Friedman {
+    /// Performs the Friedman test, where every block of `blocks` contains the value of every
+    /// treatment, e.g. the median result of every configuration on one problem.
+    pub fn new(blocks: &[Vec<f64>]) -> ExecResult<Self> {
+        let n = blocks.len();
+        let k = blocks.first().map_or(0, Vec::len);
+        ensure!(
+            n > 0 && k > 1,
+            "the Friedman test requires at least one block and two treatments"
+        );
+        ensure!(
+            blocks.iter().all(|block| block.len() == k),
+            "all blocks must contain a value of every treatment"
+        );
+
+        let mut rank_sums = vec![0.; k];
+        let mut ties = 0.;
+        for block in blocks {
+            for (sum, rank) in rank_sums.iter_mut().zip(ranks(block)) {
+                *sum += rank;
+            }
+            ties += tie_correction(block);
+        }
+
+        let (nf, kf) = (n as f64, k as f64);
+        let squares: f64 = rank_sums.iter().map(|s| s * s).sum();
+        let chi2 = 12. / (nf * kf * (kf + 1.)) * squares - 3. * nf * (kf + 1.);
+        let denominator = 1. - ties / (nf * (kf.powi(3) - kf));
+
+        let (statistic, p_value) = if denominator > 0. {
+            let statistic = chi2 / denominator;
+            let distribution = ChiSquared::new(kf - 1.).unwrap();
+            (statistic, distribution.sf(statistic))
+        } else {
+            (0., 1.)
+        };
+
+        Ok(Self {
+            ranks: rank_sums.iter().map(|s| s / nf).collect(),
+            blocks: n,
+            statistic,
+            p_value,
+        })
+    }
+
+    /// Returns the critical difference of the average ranks of the Nemenyi post-hoc test
+    /// at significance level `alpha`.
+    ///
+    /// Only `alpha` of `0.05` and `0.10` and up to ten treatments are supported.
+    pub fn critical_difference(&self, alpha: f64) -> ExecResult<f64> {
+        let table = if alpha == 0.05 {
+            &NEMENYI_Q_05
+        } else if alpha == 0.10 {
+            &NEMENYI_Q_10
+        } else {
+            eyre::bail!("the Nemenyi test only supports alpha of 0.05 or 0.10")
+        };
+
+        let k = self.ranks.len();
+        let q = table
+            .get(k.wrapping_sub(2))
+            .wrap_err("the Nemenyi test supports up to ten treatments")?;
+        Ok(q * ((k * (k + 1)) as f64 / (6. * self.blocks as f64)).sqrt())
+    }
+
+    /// Returns whether the treatments `i` and `j` differ significantly according to the
+    /// Nemenyi post-hoc test at significance level `alpha`.
+    pub fn nemenyi(&self, i: usize, j: usize, alpha: f64) -> ExecResult<bool> {
+        let cd = self.critical_difference(alpha)?;
+        Ok((self.ranks[i] - self.ranks[j]).abs() > cd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+
+    use super::*;
+
+    #[test]
+    fn ranks_average_ties() {
+        assert_eq!(ranks(&[3., 1., 4., 1., 5.]), [3., 1.5, 4., 1.5, 5.]);
+    }
+
+    #[test]
+    fn summary_computes_quartiles() {
+        let summary = Summary::new(&[1., 2., 3., 4., 5.]);
+        assert_eq!(summary.n, 5);
+        assert_float_eq!(summary.mean, 3., abs <= 1e-12);
+        assert_float_eq!(summary.median, 3., abs <= 1e-12);
+        assert_float_eq!(summary.std_dev, 2.5f64.sqrt(), abs <= 1e-12);
+        assert_eq!((summary.min, summary.max), (1., 5.));
+    }
+
+    #[test]
+    fn mann_whitney_u_exact() {
+        let result = mann_whitney_u(&[1., 2., 3.], &[4., 5., 6.]).unwrap();
+        assert_eq!(result.statistic, 0.);
+        assert_float_eq!(result.p_value, 0.1, abs <= 1e-12);
+
+        let result = mann_whitney_u(&[1., 5., 3.], &[4., 2., 6.]).unwrap();
+        assert_eq!(result.statistic, 3.);
+        assert_float_eq!(result.p_value, 0.7, abs <= 1e-12);
+    }
+
+    #[test]
+    fn mann_whitney_u_with_ties() {
+        let result = mann_whitney_u(&[1., 1., 2., 3.], &[2., 4., 4., 5.]).unwrap();
+        assert_eq!(result.statistic, 1.5);
+        assert!(result.p_value > 0.05 && result.p_value < 0.1);
+    }
+
+    #[test]
+    fn wilcoxon_signed_rank_exact() {
+        let a = [2., 3., 4., 5., 6.];
+        let b = [1., 1., 1., 1., 1.];
+        let result = wilcoxon_signed_rank(&a, &b).unwrap();
+        assert_eq!(result.statistic, 15.);
+        assert_float_eq!(result.p_value, 0.0625, abs <= 1e-12);
+
+        let result = wilcoxon_signed_rank(&a, &a).unwrap();
+        assert_eq!(result.p_value, 1.);
+    }
+
+    #[test]
+    fn holm_adjusts_in_order() {
+        let adjusted = holm(&[0.01, 0.04, 0.03, 0.005]);
+        let expected = [0.03, 0.06, 0.06, 0.02];
+        for (a, e) in adjusted.iter().zip(expected) {
+            assert_float_eq!(*a, e, abs <= 1e-12);
+        }
+    }
+
+    #[test]
+    fn a12_and_magnitude() {
+        assert_eq!(vargha_delaney_a12(&[1., 2., 3.], &[0., 0., 0.]), 1.);
+        assert_eq!(vargha_delaney_a12(&[1., 2., 3.], &[2., 2., 2.]), 0.5);
+        assert_eq!(EffectSize::from_a12(0.5), EffectSize::Negligible);
+        assert_eq!(EffectSize::from_a12(0.4), EffectSize::Small);
+        assert_eq!(EffectSize::from_a12(0.7), EffectSize::Medium);
+        assert_eq!(EffectSize::from_a12(0.), EffectSize::Large);
+    }
+
+    #[test]
+    fn friedman_with_nemenyi() {
+        let blocks = vec![vec![1., 2., 3.]; 4];
+        let friedman = Friedman::new(&blocks).unwrap();
+        assert_eq!(friedman.ranks, [1., 2., 3.]);
+        assert_float_eq!(friedman.statistic, 8., abs <= 1e-12);
+        assert_float_eq!(friedman.p_value, (-4f64).exp(), abs <= 1e-12);
+
+        let cd = friedman.critical_difference(0.05).unwrap();
+        assert_float_eq!(cd, 2.343 * 0.5f64.sqrt(), abs <= 1e-12);
+        assert!(friedman.nemenyi(0, 2, 0.05).unwrap());
+        assert!(!friedman.nemenyi(0, 1, 0.05).unwrap());
+    }
+}
@@ -0,0 +1,450 @@
+//! Tables of results, which can be exported to Markdown, LaTeX, and CSV.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    results::Results,
+    statistics::{self, EffectSize, TestResult},
+};
+use crate::ExecResult;
+
+/// A table of formatted values with an optional caption.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Table {
+    caption: Option<String>,
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Creates an empty table with the given column names.
+    pub fn new<S: Into<String>>(header: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            caption: None,
+            header: header.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Sets the caption of the table.
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Appends a row to the table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values differs from the number of columns.
+    pub fn push_row<S: Into<String>>(&mut self, row: impl IntoIterator<Item = S>) {
+        let row: Vec<_> = row.into_iter().map(Into::into).collect();
+        assert_eq!(
+            row.len(),
+            self.header.len(),
+            "the row must contain a value for every column"
+        );
+        self.rows.push(row);
+    }
+
+    pub fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    pub fn header(&self) -> &[String] {
+        &self.header
+    }
+
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    /// Returns whether all values of the column `index` are numbers.
+    fn is_numeric(&self, index: usize) -> bool {
+        !self.rows.is_empty()
+            && self
+                .rows
+                .iter()
+                .all(|row| row[index].parse::<f64>().is_ok())
+    }
+
+    /// Renders the table as GitHub-flavored Markdown, with the caption as a paragraph above.
+    pub fn to_markdown(&self) -> String {
+        fn line(out: &mut String, cells: impl IntoIterator<Item = String>) {
+            out.push('|');
+            for cell in cells {
+                let _ = write!(out, " {} |", cell.replace('|', "\\|"));
+            }
+            out.push('\n');
+        }
+
+        let mut out = String::new();
+        if let Some(caption) = &self.caption {
+            let _ = writeln!(out, "{caption}\n");
+        }
+        line(&mut out, self.header.iter().cloned());
+        line(
+            &mut out,
+            (0..self.header.len()).map(|i| {
+                if self.is_numeric(i) {
+                    "---:".to_owned()
+                } else {
+                    "---".to_owned()
+                }
+            }),
+        );
+        for row in &self.rows {
+            line(&mut out, row.iter().cloned());
+        }
+        out
+    }
+
+    /// Renders the table as a LaTeX `table` environment.
+    ///
+    /// The table uses the rules of the `booktabs` package.
+    pub fn to_latex(&self) -> String {
+        fn escape(value: &str) -> String {
+            let mut escaped = String::new();
+            for c in value.chars() {
+                match c {
+                    '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                        escaped.push('\\');
+                        escaped.push(c);
+                    }
+                    '~' => escaped.push_str("\\textasciitilde{}"),
+                    '^' => escaped.push_str("\\textasciicircum{}"),
+                    '\\' => escaped.push_str("\\textbackslash{}"),
+                    _ => escaped.push(c),
+                }
+            }
+            escaped
+        }
+
+        fn line(out: &mut String, cells: &[String]) {
+            let cells: Vec<_> = cells.iter().map(|cell| escape(cell)).collect();
+            let _ = writeln!(out, "{} \\\\", cells.join(" & "));
+        }
+
+        let alignment: String = (0..self.header.len())
+            .map(|i| if self.is_numeric(i) { 'r' } else { 'l' })
+            .collect();
+
+        let mut out = String::from("\\begin{table}\n\\centering\n");
+        if let Some(caption) = &self.caption {
+            let _ = writeln!(out, "\\caption{{{}}}", escape(caption));
+        }
+        let _ = writeln!(out, "\\begin{{tabular}}{{{alignment}}}\n\\toprule");
+        line(&mut out, &self.header);
+        out.push_str("\\midrule\n");
+        for row in &self.rows {
+            line(&mut out, row);
+        }
+        out.push_str("\\bottomrule\n\\end{tabular}\n\\end{table}\n");
+        out
+    }
+
+    /// Renders the table as CSV, with the header as first line.
+    ///
+    /// The caption is omitted.
+    pub fn to_csv(&self) -> String {
+        fn line(out: &mut String, cells: &[String]) {
+            let cells: Vec<_> = cells
+                .iter()
+                .map(|cell| {
+                    if cell.contains([',', '"', '\n']) {
+                        format!("\"{}\"", cell.replace('"', "\"\""))
+                    } else {
+                        cell.clone()
+                    }
+                })
+                .collect();
+            let _ = writeln!(out, "{}", cells.join(","));
+        }
+
+        let mut out = String::new();
+        line(&mut out, &self.header);
+        for row in &self.rows {
+            line(&mut out, row);
+        }
+        out
+    }
+
+    /// Creates a table of the descriptive statistics of every configuration on every problem.
+    pub fn summary(results: &Results) -> Self {
+        let mut table = Self::new([
+            "Problem",
+            "Configuration",
+            "Runs",
+            "Mean",
+            "Std",
+            "Min",
+            "Median",
+            "Max",
+        ]);
+        for problem in results.problems() {
+            for configuration in results.configurations() {
+                let summary = results.summary(configuration, problem);
+                table.push_row([
+                    problem.clone(),
+                    configuration.clone(),
+                    summary.n.to_string(),
+                    number(summary.mean),
+                    number(summary.std_dev),
+                    number(summary.min),
+                    number(summary.median),
+                    number(summary.max),
+                ]);
+            }
+        }
+        table
+    }
+
+    /// Creates a table comparing every configuration with the `baseline` configuration
+    /// on every problem.
+    ///
+    /// The p-values of the `test` are adjusted for all comparisons of the table using
+    /// [`holm`], and the last column indicates whether the configuration is significantly
+    /// better (`+`) or worse (`-`) than the baseline at significance level `alpha`, or not
+    /// significantly different (`=`), assuming minimization.
+    ///
+    /// [`holm`]: statistics::holm
+    pub fn comparison(
+        results: &Results,
+        baseline: &str,
+        test: PairwiseTest,
+        alpha: f64,
+    ) -> ExecResult<Self> {
+        eyre::ensure!(
+            results.configurations().iter().any(|c| c == baseline),
+            "the baseline {baseline} has no results"
+        );
+        results.ensure_complete()?;
+
+        struct Row<'a> {
+            problem: &'a str,
+            configuration: &'a str,
+            median: f64,
+            result: TestResult,
+            a12: f64,
+        }
+
+        let mut comparisons = Vec::new();
+        for problem in results.problems() {
+            for configuration in results.configurations() {
+                if configuration == baseline {
+                    continue;
+                }
+                let (a, b) = match test {
+                    PairwiseTest::MannWhitneyU => (
+                        results.values(configuration, problem),
+                        results.values(baseline, problem),
+                    ),
+                    PairwiseTest::WilcoxonSignedRank => {
+                        results.paired(configuration, baseline, problem)
+                    }
+                };
+                comparisons.push(Row {
+                    problem,
+                    configuration,
+                    median: results.summary(configuration, problem).median,
+                    result: test.test(&a, &b)?,
+                    a12: statistics::vargha_delaney_a12(&a, &b),
+                });
+            }
+        }
+
+        let p_values: Vec<_> = comparisons.iter().map(|row| row.result.p_value).collect();
+        let adjusted = statistics::holm(&p_values);
+
+        let mut table = Self::new([
+            "Problem",
+            "Configuration",
+            "Median",
+            "Baseline median",
+            "p",
+            "Holm p",
+            "A12",
+            "Effect",
+            "Result",
+        ])
+        .with_caption(format!(
+            "Comparison with {baseline} using the {test} test (alpha = {alpha})."
+        ));
+        for (row, p) in comparisons.iter().zip(adjusted) {
+            let outcome = if p >= alpha {
+                "="
+            } else if row.a12 < 0.5 {
+                "+"
+            } else {
+                "-"
+            };
+            table.push_row([
+                row.problem.to_owned(),
+                row.configuration.to_owned(),
+                number(row.median),
+                number(results.summary(baseline, row.problem).median),
+                number(row.result.p_value),
+                number(p),
+                format!("{:.3}", row.a12),
+                EffectSize::from_a12(row.a12).to_string(),
+                outcome.to_owned(),
+            ]);
+        }
+        Ok(table)
+    }
+
+    /// Creates a table of the average ranks of the configurations over all problems
+    /// according to their median values, see [`Results::friedman`].
+    ///
+    /// The caption contains the result of the Friedman test and the critical difference of
+    /// the Nemenyi post-hoc test at significance level `alpha`, and the last column indicates
+    /// whether the configuration differs significantly from the best-ranked configuration.
+    pub fn ranking(results: &Results, alpha: f64) -> ExecResult<Self> {
+        let friedman = results.friedman()?;
+        let cd = friedman.critical_difference(alpha)?;
+        let best = friedman.ranks.iter().copied().fold(f64::INFINITY, f64::min);
+
+        let mut table = Self::new(["Configuration", "Average rank", "Different from best"])
+            .with_caption(format!(
+                "Friedman chi2 = {}, p = {}, Nemenyi CD = {} (alpha = {alpha}).",
+                number(friedman.statistic),
+                number(friedman.p_value),
+                number(cd),
+            ));
+        for (configuration, rank) in results.configurations().iter().zip(&friedman.ranks) {
+            let different = if rank - best > cd { "yes" } else { "no" };
+            table.push_row([
+                configuration.clone(),
+                format!("{rank:.3}"),
+                different.to_owned(),
+            ]);
+        }
+        Ok(table)
+    }
+}
+
+/// A pairwise statistical test, see [`Table::comparison`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PairwiseTest {
+    /// The [`mann_whitney_u`] test for independent runs.
+    ///
+    /// [`mann_whitney_u`]: statistics::mann_whitney_u
+    MannWhitneyU,
+    /// The [`wilcoxon_signed_rank`] test for runs paired by their run number, i.e. their seed.
+    ///
+    /// [`wilcoxon_signed_rank`]: statistics::wilcoxon_signed_rank
+    WilcoxonSignedRank,
+}
+
+impl PairwiseTest {
+    /// Performs the test on the samples `a` and `b`.
+    pub fn test(&self, a: &[f64], b: &[f64]) -> ExecResult<TestResult> {
+        match self {
+            Self::MannWhitneyU => statistics::mann_whitney_u(a, b),
+            Self::WilcoxonSignedRank => statistics::wilcoxon_signed_rank(a, b),
+        }
+    }
+}
+
+impl std::fmt::Display for PairwiseTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::MannWhitneyU => "Mann-Whitney U",
+            Self::WilcoxonSignedRank => "Wilcoxon signed-rank",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Formats `value` with four significant digits, using scientific notation for very small
+/// or large values.
+pub(crate) fn number(value: f64) -> String {
+    if value == 0. || !value.is_finite() {
+        return value.to_string();
+    }
+    let magnitude = value.abs();
+    if (1e-3..1e4).contains(&magnitude) {
+        let decimals = (3 - magnitude.log10().floor() as i32).max(0) as usize;
+        format!("{value:.decimals$}")
+    } else {
+        format!("{value:.3e}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> Results {
+        let mut results = Results::new();
+        for run in 0..10 {
+            let x = run as f64;
+            results.insert("A", "Sphere", run, x);
+            results.insert("B", "Sphere", run, x + 100.);
+            results.insert("C", "Sphere", run, x + 0.5);
+            results.insert("A", "Rastrigin", run, x);
+            results.insert("B", "Rastrigin", run, x + 100.);
+            results.insert("C", "Rastrigin", run, x + 200.);
+        }
+        results
+    }
+
+    #[test]
+    fn number_uses_significant_digits() {
+        assert_eq!(number(0.), "0");
+        assert_eq!(number(1.23456), "1.235");
+        assert_eq!(number(123.456), "123.5");
+        assert_eq!(number(0.012345), "0.01235");
+        assert_eq!(number(1.5e-7), "1.500e-7");
+    }
+
+    #[test]
+    fn table_exports() {
+        let mut table = Table::new(["Name", "Value"]).with_caption("A_1");
+        table.push_row(["a|b", "1.5"]);
+        table.push_row(["c,\"d\"", "2"]);
+
+        assert_eq!(
+            table.to_markdown(),
+            "A_1\n\n| Name | Value |\n| --- | ---: |\n| a\\|b | 1.5 |\n| c,\"d\" | 2 |\n"
+        );
+        assert_eq!(table.to_csv(), "Name,Value\na|b,1.5\n\"c,\"\"d\"\"\",2\n");
+        let latex = table.to_latex();
+        assert!(latex.contains("\\caption{A\\_1}"));
+        assert!(latex.contains("\\begin{tabular}{lr}"));
+        assert!(latex.contains("Name & Value \\\\"));
+    }
+
+    #[test]
+    fn comparison_marks_significant_differences() {
+        let table =
+            Table::comparison(&results(), "A", PairwiseTest::WilcoxonSignedRank, 0.05).unwrap();
+        let outcomes: Vec<_> = table
+            .rows()
+            .iter()
+            .map(|row| (row[0].as_str(), row[1].as_str(), row[8].as_str()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("Sphere", "B", "-"),
+                ("Sphere", "C", "-"),
+                ("Rastrigin", "B", "-"),
+                ("Rastrigin", "C", "-"),
+            ]
+        );
+
+        let table = Table::comparison(&results(), "A", PairwiseTest::MannWhitneyU, 0.05).unwrap();
+        assert_eq!(table.rows()[1][8], "=");
+        assert_eq!(table.rows()[1][7], "negligible");
+    }
+
+    #[test]
+    fn ranking_uses_medians() {
+        let table = Table::ranking(&results(), 0.05).unwrap();
+        let ranks: Vec<_> = table.rows().iter().map(|row| row[1].as_str()).collect();
+        assert_eq!(ranks, ["1.000", "2.500", "2.500"]);
+    }
+}
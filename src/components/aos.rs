@@ -0,0 +1,836 @@
+//! Adaptive operator selection (AOS).
+//!
+//! Adaptive operator selection chooses one of several operators (e.g. mutation or crossover
+//! components) in every execution, based on the credit the operators received for their
+//! previous applications.
+//!
+//! # Credit assignment
+//!
+//! The credit (reward) of an operator is retrieved using a *reward lens*, i.e. any [`Lens`]
+//! with `Target = f64`, e.g. the [`ImprovementCreditLens`] or the success lenses of
+//! [`mapping::adaptive`].
+//!
+//! As the effect of an operator is usually only known after evaluation, the reward for the
+//! operator applied in the previous execution is retrieved at the beginning of the next execution.
+//!
+//! [`mapping::adaptive`]: crate::components::mapping::adaptive
+//!
+//! # References
+//!
+//! \[1\] Dirk Thierens. 2005.
+//! An adaptive pursuit strategy for allocating operator probabilities.
+//! In Proceedings of the 7th Annual Conference on Genetic and Evolutionary Computation (GECCO '05), 1539–1546.
+//! DOI:<https://doi.org/10.1145/1068009.1068251>
+//!
+//! \[2\] Peter Auer, Nicolò Cesa-Bianchi, and Paul Fischer. 2002.
+//! Finite-time Analysis of the Multiarmed Bandit Problem.
+//! Machine Learning 47, 2 (May 2002), 235–256.
+//! DOI:<https://doi.org/10.1023/A:1013689704352>
+//!
+//! \[3\] Álvaro Fialho, Luis Da Costa, Marc Schoenauer, and Michèle Sebag. 2010.
+//! Analyzing bandit-based adaptive operator selection mechanisms.
+//! Annals of Mathematics and Artificial Intelligence 60, 1 (2010), 25–64.
+//! DOI:<https://doi.org/10.1007/s10472-010-9213-y>
+
+use std::collections::VecDeque;
+
+use better_any::{Tid, TidAble};
+use derivative::Derivative;
+use derive_more::{Deref, DerefMut};
+use dyn_clone::DynClone;
+use erased_serde::Serialize as DynSerialize;
+use eyre::{ensure, WrapErr};
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::Serialize;
+
+use crate::{
+    component::{AnyComponent, ExecResult},
+    components::{measures::improvement::Improvement, Component},
+    configuration::tree::{self, Edge, EdgeMut},
+    identifier::{Global, Identifier, PhantomId},
+    lens::{AnyLens, Lens, LensMap},
+    logging::extractor::{EntryExtractor, EntryName},
+    state::{random::Random, StateReq},
+    utils::SerializablePhantom,
+    CustomState, Problem, State,
+};
+
+/// Statistics about the operators used by an [`OperatorSelectionStrategy`].
+///
+/// Not all strategies use all statistics.
+#[derive(Clone, Default, Serialize)]
+pub struct OperatorStatistics {
+    /// Estimated quality of each operator.
+    pub qualities: Vec<f64>,
+    /// Probability of selecting each operator.
+    pub probabilities: Vec<f64>,
+    /// Number of times each operator was selected.
+    pub counts: Vec<u32>,
+    /// Number of times each operator was rewarded.
+    ///
+    /// This differs from `counts` by the `pending` operator, which was selected but not rewarded yet.
+    pub rewards: Vec<u32>,
+    /// Recently applied operators and their rewards, newest last.
+    pub history: VecDeque<(usize, f64)>,
+    /// The operator selected in the previous execution, which has not received a reward yet.
+    pub pending: Option<usize>,
+}
+
+impl OperatorStatistics {
+    /// Creates new statistics for `n` operators with uniform probabilities.
+    pub fn new(n: usize) -> Self {
+        Self {
+            qualities: vec![0.; n],
+            probabilities: vec![1. / n as f64; n],
+            counts: vec![0; n],
+            rewards: vec![0; n],
+            history: VecDeque::new(),
+            pending: None,
+        }
+    }
+
+    /// Returns the number of operators.
+    pub fn len(&self) -> usize {
+        self.qualities.len()
+    }
+
+    /// Returns `true` if there are no operators.
+    pub fn is_empty(&self) -> bool {
+        self.qualities.is_empty()
+    }
+
+    /// Updates the quality estimate of `operator` with `reward` using the learning rate `alpha`.
+    pub fn update_quality(&mut self, operator: usize, reward: f64, alpha: f64) {
+        let q = &mut self.qualities[operator];
+        *q += alpha * (reward - *q);
+    }
+
+    /// Selects an operator proportional to the current probabilities.
+    pub fn sample(&self, rng: &mut Random) -> ExecResult<usize> {
+        let wheel =
+            WeightedIndex::new(&self.probabilities).wrap_err("invalid operator probabilities")?;
+        Ok(wheel.sample(rng))
+    }
+}
+
+/// The [`OperatorStatistics`] of the [`AdaptiveOperatorSelection`] with identifier `I`.
+#[derive(Clone, Deref, DerefMut, Serialize, Tid)]
+#[serde(transparent)]
+pub struct OperatorSelection<I: Identifier + 'static>(
+    #[deref]
+    #[deref_mut]
+    OperatorStatistics,
+    #[serde(skip)] PhantomId<I>,
+);
+
+impl<I: Identifier> OperatorSelection<I> {
+    /// Creates the state for `n` operators.
+    pub fn new(n: usize) -> Self {
+        Self(OperatorStatistics::new(n), PhantomId::default())
+    }
+}
+
+impl<I: Identifier> CustomState<'_> for OperatorSelection<I> {}
+
+/// Trait for representing a strategy to select operators based on their received credit.
+pub trait OperatorSelectionStrategy: DynClone + DynSerialize + Send + Sync {
+    /// Checks if the strategy can be used with `n` operators.
+    #[allow(unused_variables)]
+    fn validate(&self, n: usize) -> ExecResult<()> {
+        Ok(())
+    }
+
+    /// Updates the `stats` with the `reward` received by `operator`.
+    ///
+    /// The `reward` is already included in `stats.rewards`.
+    fn reward(&self, stats: &mut OperatorStatistics, operator: usize, reward: f64);
+
+    /// Selects the next operator to apply.
+    fn select(&self, stats: &mut OperatorStatistics, rng: &mut Random) -> ExecResult<usize>;
+}
+
+dyn_clone::clone_trait_object!(OperatorSelectionStrategy);
+erased_serde::serialize_trait_object!(OperatorSelectionStrategy);
+
+/// Probability matching (PM) operator selection.
+///
+/// The quality `q_i` of the rewarded operator is updated using the learning rate `alpha`,
+/// and the selection probabilities are set to
+/// `p_i = p_min + (1 - n * p_min) * q_i / sum(q)`.
+///
+/// Rewards must be non-negative, and negative rewards are treated as `0`.
+#[derive(Clone, Serialize)]
+pub struct ProbabilityMatching {
+    /// Minimal selection probability of each operator.
+    pub p_min: f64,
+    /// Learning rate of the quality estimates.
+    pub alpha: f64,
+}
+
+impl ProbabilityMatching {
+    pub fn from_params(p_min: f64, alpha: f64) -> ExecResult<Self> {
+        ensure!(p_min >= 0., "`p_min` must be non-negative");
+        ensure!((0.0..=1.0).contains(&alpha), "`alpha` must be in [0, 1]");
+        Ok(Self { p_min, alpha })
+    }
+
+    pub fn new(p_min: f64, alpha: f64) -> ExecResult<Box<dyn OperatorSelectionStrategy>> {
+        Ok(Box::new(Self::from_params(p_min, alpha)?))
+    }
+}
+
+impl OperatorSelectionStrategy for ProbabilityMatching {
+    fn validate(&self, n: usize) -> ExecResult<()> {
+        ensure!(
+            self.p_min * n as f64 <= 1.,
+            "`p_min` times the number of operators must not exceed 1"
+        );
+        Ok(())
+    }
+
+    fn reward(&self, stats: &mut OperatorStatistics, operator: usize, reward: f64) {
+        stats.update_quality(operator, reward.max(0.), self.alpha);
+
+        let n = stats.len() as f64;
+        let total: f64 = stats.qualities.iter().sum();
+        for (p, q) in stats.probabilities.iter_mut().zip(&stats.qualities) {
+            *p = if total > 0. {
+                self.p_min + (1. - n * self.p_min) * q / total
+            } else {
+                1. / n
+            };
+        }
+    }
+
+    fn select(&self, stats: &mut OperatorStatistics, rng: &mut Random) -> ExecResult<usize> {
+        stats.sample(rng)
+    }
+}
+
+/// Adaptive pursuit (AP) operator selection.
+///
+/// The quality `q_i` of the rewarded operator is updated using the learning rate `alpha`.
+/// Afterwards, the probability of the operator with the best quality is increased towards
+/// `p_max = 1 - (n - 1) * p_min`, and all other probabilities are decreased towards `p_min`,
+/// using the learning rate `beta`.
+///
+/// Rewards must be non-negative, and negative rewards are treated as `0`.
+#[derive(Clone, Serialize)]
+pub struct AdaptivePursuit {
+    /// Minimal selection probability of each operator.
+    pub p_min: f64,
+    /// Learning rate of the quality estimates.
+    pub alpha: f64,
+    /// Learning rate of the probabilities.
+    pub beta: f64,
+}
+
+impl AdaptivePursuit {
+    pub fn from_params(p_min: f64, alpha: f64, beta: f64) -> ExecResult<Self> {
+        ensure!(p_min >= 0., "`p_min` must be non-negative");
+        ensure!((0.0..=1.0).contains(&alpha), "`alpha` must be in [0, 1]");
+        ensure!((0.0..=1.0).contains(&beta), "`beta` must be in [0, 1]");
+        Ok(Self { p_min, alpha, beta })
+    }
+
+    pub fn new(
+        p_min: f64,
+        alpha: f64,
+        beta: f64,
+    ) -> ExecResult<Box<dyn OperatorSelectionStrategy>> {
+        Ok(Box::new(Self::from_params(p_min, alpha, beta)?))
+    }
+}
+
+impl OperatorSelectionStrategy for AdaptivePursuit {
+    fn validate(&self, n: usize) -> ExecResult<()> {
+        ensure!(
+            self.p_min * n as f64 <= 1.,
+            "`p_min` times the number of operators must not exceed 1"
+        );
+        Ok(())
+    }
+
+    fn reward(&self, stats: &mut OperatorStatistics, operator: usize, reward: f64) {
+        stats.update_quality(operator, reward.max(0.), self.alpha);
+
+        let n = stats.len() as f64;
+        let p_max = 1. - (n - 1.) * self.p_min;
+        let best = argmax(&stats.qualities);
+        for (i, p) in stats.probabilities.iter_mut().enumerate() {
+            let target = if i == best { p_max } else { self.p_min };
+            *p += self.beta * (target - *p);
+        }
+    }
+
+    fn select(&self, stats: &mut OperatorStatistics, rng: &mut Random) -> ExecResult<usize> {
+        stats.sample(rng)
+    }
+}
+
+/// Upper confidence bound (UCB1) operator selection.
+///
+/// Every operator is applied once, afterwards the operator maximizing
+/// `q_i + c * sqrt(2 * ln(n) / n_i)` is selected, where `q_i` is the mean reward of the operator,
+/// `n_i` the number of times it was rewarded, and `n` the total number of rewards.
+///
+/// As the selection is deterministic, the probability of the selected operator is set to `1`,
+/// and all others to `0`.
+#[derive(Clone, Serialize)]
+pub struct Ucb1 {
+    /// Scaling factor of the exploration term.
+    pub c: f64,
+}
+
+impl Ucb1 {
+    pub fn from_params(c: f64) -> ExecResult<Self> {
+        ensure!(c >= 0., "`c` must be non-negative");
+        Ok(Self { c })
+    }
+
+    pub fn new(c: f64) -> ExecResult<Box<dyn OperatorSelectionStrategy>> {
+        Ok(Box::new(Self::from_params(c)?))
+    }
+}
+
+impl OperatorSelectionStrategy for Ucb1 {
+    fn reward(&self, stats: &mut OperatorStatistics, operator: usize, reward: f64) {
+        let n = stats.rewards[operator].max(1) as f64;
+        stats.update_quality(operator, reward, 1. / n);
+    }
+
+    fn select(&self, stats: &mut OperatorStatistics, _rng: &mut Random) -> ExecResult<usize> {
+        let operator = ucb(&stats.qualities, &stats.rewards, self.c);
+        set_greedy(&mut stats.probabilities, operator);
+        Ok(operator)
+    }
+}
+
+/// Sliding window upper confidence bound (SW-UCB) operator selection.
+///
+/// Similar to [`Ucb1`], but the mean rewards and counts are only calculated from the last
+/// `window` applications, which allows to track non-stationary rewards.
+///
+/// As the selection is deterministic, the probability of the selected operator is set to `1`,
+/// and all others to `0`.
+#[derive(Clone, Serialize)]
+pub struct SlidingWindowUcb {
+    /// Scaling factor of the exploration term.
+    pub c: f64,
+    /// Size of the sliding window.
+    pub window: usize,
+}
+
+impl SlidingWindowUcb {
+    pub fn from_params(c: f64, window: usize) -> ExecResult<Self> {
+        ensure!(c >= 0., "`c` must be non-negative");
+        ensure!(window > 0, "`window` must be greater than 0");
+        Ok(Self { c, window })
+    }
+
+    pub fn new(c: f64, window: usize) -> ExecResult<Box<dyn OperatorSelectionStrategy>> {
+        Ok(Box::new(Self::from_params(c, window)?))
+    }
+}
+
+impl OperatorSelectionStrategy for SlidingWindowUcb {
+    fn reward(&self, stats: &mut OperatorStatistics, operator: usize, reward: f64) {
+        stats.history.push_back((operator, reward));
+        while stats.history.len() > self.window {
+            stats.history.pop_front();
+        }
+
+        let n = stats.len();
+        let mut sums = vec![0.; n];
+        let mut counts = vec![0u32; n];
+        for &(operator, reward) in &stats.history {
+            sums[operator] += reward;
+            counts[operator] += 1;
+        }
+        for ((q, sum), count) in stats.qualities.iter_mut().zip(sums).zip(counts) {
+            *q = if count > 0 { sum / count as f64 } else { 0. };
+        }
+    }
+
+    fn select(&self, stats: &mut OperatorStatistics, _rng: &mut Random) -> ExecResult<usize> {
+        let mut counts = vec![0u32; stats.len()];
+        for &(operator, _) in &stats.history {
+            counts[operator] += 1;
+        }
+        let operator = ucb(&stats.qualities, &counts, self.c);
+        set_greedy(&mut stats.probabilities, operator);
+        Ok(operator)
+    }
+}
+
+/// Returns the index of the maximum value, preferring lower indices on ties.
+fn argmax(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, &v)| if v > values[best] { i } else { best })
+}
+
+/// Returns the operator with the highest upper confidence bound, or the first untried operator.
+fn ucb(qualities: &[f64], counts: &[u32], c: f64) -> usize {
+    if let Some(untried) = counts.iter().position(|&n| n == 0) {
+        return untried;
+    }
+    let total: u32 = counts.iter().sum();
+    let bounds: Vec<_> = qualities
+        .iter()
+        .zip(counts)
+        .map(|(q, &n)| q + c * (2. * (total as f64).ln() / n as f64).sqrt())
+        .collect();
+    argmax(&bounds)
+}
+
+/// Sets the probability of `operator` to `1`, and all others to `0`.
+fn set_greedy(probabilities: &mut [f64], operator: usize) {
+    for (i, p) in probabilities.iter_mut().enumerate() {
+        *p = if i == operator { 1. } else { 0. };
+    }
+}
+
+/// Selects and executes one of several `operators` in every execution using an [`OperatorSelectionStrategy`].
+///
+/// The reward of the operator applied in the previous execution is retrieved using
+/// the `reward_lens` before the next operator is selected.
+/// See the [module documentation] for more information.
+///
+/// [module documentation]: crate::components::aos
+///
+/// # State
+///
+/// The statistics of the operators are stored in [`OperatorSelection<I>`], and can be logged
+/// using e.g. the [`OperatorProbabilitiesLens`].
+///
+/// # Call propagation
+///
+/// Calling any of the `{init, require}` methods calls the specific method on all operators.
+///
+/// # Examples
+///
+/// Selecting between two mutation operators using adaptive pursuit, rewarding operators
+/// by the improvement of the solutions they mutated:
+///
+/// ```
+/// use mahf::{
+///     components::{
+///         aos::{AdaptiveOperatorSelection, AdaptivePursuit, ImprovementCreditLens},
+///         measures::improvement::FitnessImprovement,
+///         mutation,
+///     },
+/// #    problems::LimitedVectorProblem, Component, ExecResult, SingleObjectiveProblem,
+/// };
+///
+/// # fn example<P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>>() -> ExecResult<Box<dyn Component<P>>> {
+/// # Ok(
+/// AdaptiveOperatorSelection::new(
+///     [
+///         mutation::NormalMutation::new_dev(0.1),
+///         mutation::UniformMutation::new_bound(0.5),
+///     ],
+///     AdaptivePursuit::new(0.1, 0.3, 0.3)?,
+///     ImprovementCreditLens::<FitnessImprovement>::new(false),
+/// )?
+/// # )
+/// # }
+/// ```
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct AdaptiveOperatorSelection<P: Problem, R: AnyLens, I: Identifier = Global> {
+    operators: Vec<Box<dyn Component<P>>>,
+    strategy: Box<dyn OperatorSelectionStrategy>,
+    reward_lens: R,
+    id: PhantomId<I>,
+}
+
+impl<P, R, I> AdaptiveOperatorSelection<P, R, I>
+where
+    P: Problem,
+    R: Lens<P, Target = f64>,
+    I: Identifier,
+{
+    pub fn from_params(
+        operators: impl IntoIterator<Item = Box<dyn Component<P>>>,
+        strategy: Box<dyn OperatorSelectionStrategy>,
+        reward_lens: R,
+    ) -> ExecResult<Self> {
+        let operators: Vec<_> = operators.into_iter().collect();
+        ensure!(!operators.is_empty(), "at least one operator is required");
+        strategy.validate(operators.len())?;
+        Ok(Self {
+            operators,
+            strategy,
+            reward_lens,
+            id: PhantomId::default(),
+        })
+    }
+
+    pub fn new_with_id(
+        operators: impl IntoIterator<Item = Box<dyn Component<P>>>,
+        strategy: Box<dyn OperatorSelectionStrategy>,
+        reward_lens: R,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(
+            operators,
+            strategy,
+            reward_lens,
+        )?))
+    }
+}
+
+impl<P, R> AdaptiveOperatorSelection<P, R, Global>
+where
+    P: Problem,
+    R: Lens<P, Target = f64>,
+{
+    pub fn new(
+        operators: impl IntoIterator<Item = Box<dyn Component<P>>>,
+        strategy: Box<dyn OperatorSelectionStrategy>,
+        reward_lens: R,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Self::new_with_id(operators, strategy, reward_lens)
+    }
+}
+
+impl<P, R, I> Component<P> for AdaptiveOperatorSelection<P, R, I>
+where
+    P: Problem,
+    R: Lens<P, Target = f64>,
+    I: Identifier,
+{
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(OperatorSelection::<I>::new(self.operators.len()));
+        tree::init_children(self.children(), problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        tree::require_children(self.children(), problem, state_req)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let pending = state.try_borrow_mut::<OperatorSelection<I>>()?.pending;
+        if let Some(operator) = pending {
+            let reward = self
+                .reward_lens
+                .get(problem, state)
+                .wrap_err("failed to retrieve operator reward")?;
+            let mut stats = state.borrow_mut::<OperatorSelection<I>>();
+            stats.rewards[operator] += 1;
+            self.strategy.reward(&mut stats, operator, reward);
+        }
+
+        let operator = {
+            let mut stats = state.borrow_mut::<OperatorSelection<I>>();
+            let operator = self.strategy.select(&mut stats, &mut state.random_mut())?;
+            stats.counts[operator] += 1;
+            stats.pending = Some(operator);
+            operator
+        };
+
+        self.operators[operator].execute(problem, state)
+    }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        self.operators
+            .iter()
+            .enumerate()
+            .map(|(i, operator)| {
+                Edge::component(
+                    format!("AdaptiveOperatorSelection.operators[{i}]"),
+                    operator.as_ref(),
+                )
+            })
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        self.operators
+            .iter_mut()
+            .enumerate()
+            .map(|(i, operator)| {
+                EdgeMut::component(
+                    format!("AdaptiveOperatorSelection.operators[{i}]"),
+                    operator,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Lens for the selection probabilities of the operators of the [`AdaptiveOperatorSelection`]
+/// with identifier `I`.
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Default(bound = ""), Clone(bound = ""))]
+pub struct OperatorProbabilitiesLens<I>(SerializablePhantom<I>);
+
+impl<I: Identifier> OperatorProbabilitiesLens<I> {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self(SerializablePhantom::default())
+    }
+
+    /// Constructs the lens for logging.
+    pub fn entry<P: Problem>() -> Box<dyn EntryExtractor<P>> {
+        Box::<Self>::default()
+    }
+}
+
+impl<I: Identifier> AnyLens for OperatorProbabilitiesLens<I> {
+    type Target = Vec<f64>;
+}
+
+impl<I> EntryName for OperatorProbabilitiesLens<I> {
+    fn entry_name() -> &'static str {
+        "Operator probabilities"
+    }
+}
+
+impl<I: Identifier> LensMap for OperatorProbabilitiesLens<I> {
+    type Source = OperatorSelection<I>;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        source.probabilities.clone()
+    }
+}
+
+/// Lens for the number of times the operators of the [`AdaptiveOperatorSelection`]
+/// with identifier `I` were selected.
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Default(bound = ""), Clone(bound = ""))]
+pub struct OperatorCountsLens<I>(SerializablePhantom<I>);
+
+impl<I: Identifier> OperatorCountsLens<I> {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self(SerializablePhantom::default())
+    }
+
+    /// Constructs the lens for logging.
+    pub fn entry<P: Problem>() -> Box<dyn EntryExtractor<P>> {
+        Box::<Self>::default()
+    }
+}
+
+impl<I: Identifier> AnyLens for OperatorCountsLens<I> {
+    type Target = Vec<u32>;
+}
+
+impl<I> EntryName for OperatorCountsLens<I> {
+    fn entry_name() -> &'static str {
+        "Operator counts"
+    }
+}
+
+impl<I: Identifier> LensMap for OperatorCountsLens<I> {
+    type Source = OperatorSelection<I>;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        source.counts.clone()
+    }
+}
+
+/// Lens for assigning credit to an operator using the [`Improvement`] measured by `I`.
+///
+/// Only positive improvements are considered.
+/// The credit is either the mean (`extreme = false`) or the maximum (`extreme = true`)
+/// of the positive improvements, and `0` if there are none.
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct ImprovementCreditLens<I> {
+    /// Whether to use the maximum instead of the mean improvement.
+    pub extreme: bool,
+    marker: SerializablePhantom<I>,
+}
+
+impl<I> ImprovementCreditLens<I> {
+    /// Constructs the lens.
+    pub fn new(extreme: bool) -> Self {
+        Self {
+            extreme,
+            marker: SerializablePhantom::default(),
+        }
+    }
+}
+
+impl<I: AnyComponent + 'static> AnyLens for ImprovementCreditLens<I> {
+    type Target = f64;
+}
+
+impl<I> EntryName for ImprovementCreditLens<I> {
+    fn entry_name() -> &'static str {
+        "Improvement credit"
+    }
+}
+
+impl<I: AnyComponent + 'static> LensMap for ImprovementCreditLens<I> {
+    type Source = Improvement<I>;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        let positive: Vec<_> = source
+            .total_improvement
+            .iter()
+            .copied()
+            .filter(|&i| i > 0.)
+            .collect();
+
+        if positive.is_empty() {
+            0.
+        } else if self.extreme {
+            positive.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+        } else {
+            positive.iter().sum::<f64>() / positive.len() as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{lens::ValueOf, testing::*};
+
+    /// Rewards `operator` like [`AdaptiveOperatorSelection`] does.
+    fn reward(
+        strategy: &dyn OperatorSelectionStrategy,
+        stats: &mut OperatorStatistics,
+        operator: usize,
+        reward: f64,
+    ) {
+        stats.rewards[operator] += 1;
+        strategy.reward(stats, operator, reward);
+    }
+
+    #[test]
+    fn probability_matching_ignores_negative_rewards() {
+        let strategy = ProbabilityMatching::from_params(0.1, 0.5).unwrap();
+        let mut stats = OperatorStatistics::new(2);
+        strategy.reward(&mut stats, 0, 1.);
+        strategy.reward(&mut stats, 1, -10.);
+
+        assert_eq!(stats.qualities, [0.5, 0.]);
+        assert_eq!(stats.probabilities, [0.9, 0.1]);
+        assert!(stats.sample(&mut Random::new(0)).is_ok());
+    }
+
+    #[test]
+    fn adaptive_pursuit_ignores_negative_rewards() {
+        let strategy = AdaptivePursuit::from_params(0.1, 0.5, 0.5).unwrap();
+        let mut stats = OperatorStatistics::new(2);
+        strategy.reward(&mut stats, 0, -1.);
+        strategy.reward(&mut stats, 1, 1.);
+
+        assert_eq!(stats.qualities, [0., 0.5]);
+        assert!(stats.probabilities.iter().all(|&p| p >= 0.1));
+        assert!(stats.probabilities[1] > stats.probabilities[0]);
+        assert!(stats.sample(&mut Random::new(0)).is_ok());
+    }
+
+    #[test]
+    fn ucb1_tries_every_operator_before_exploiting() {
+        let strategy = Ucb1::from_params(0.).unwrap();
+        let mut stats = OperatorStatistics::new(3);
+        let rng = &mut Random::new(0);
+
+        for (expected, r) in [(0, 0.2), (1, 0.8), (2, 0.5)] {
+            assert_eq!(strategy.select(&mut stats, rng).unwrap(), expected);
+            reward(&strategy, &mut stats, expected, r);
+        }
+        assert_eq!(strategy.select(&mut stats, rng).unwrap(), 1);
+        assert_eq!(stats.probabilities, [0., 1., 0.]);
+    }
+
+    #[test]
+    fn ucb1_averages_rewards_ignoring_pending_selections() {
+        let strategy = Ucb1::from_params(1.).unwrap();
+        let mut stats = OperatorStatistics::new(2);
+        // Selections which were not rewarded yet must not affect the mean reward.
+        stats.counts = vec![5, 5];
+
+        reward(&strategy, &mut stats, 0, 1.);
+        reward(&strategy, &mut stats, 0, 0.);
+        reward(&strategy, &mut stats, 0, 0.5);
+
+        assert_floats_eq(&[0.5, 0.], &stats.qualities);
+    }
+
+    #[test_case(0., 0; "exploitation")]
+    #[test_case(1., 1; "exploration")]
+    fn ucb1_balances_exploration(c: f64, expected: usize) {
+        let strategy = Ucb1::from_params(c).unwrap();
+        let mut stats = OperatorStatistics::new(2);
+        for _ in 0..10 {
+            reward(&strategy, &mut stats, 0, 1.);
+        }
+        reward(&strategy, &mut stats, 1, 0.9);
+
+        let operator = strategy.select(&mut stats, &mut Random::new(0)).unwrap();
+        assert_eq!(operator, expected);
+    }
+
+    #[test]
+    fn sliding_window_ucb_forgets_old_rewards() {
+        let strategy = SlidingWindowUcb::from_params(0., 2).unwrap();
+        let mut stats = OperatorStatistics::new(2);
+        reward(&strategy, &mut stats, 0, 1.);
+        reward(&strategy, &mut stats, 1, 0.5);
+        assert_floats_eq(&[1., 0.5], &stats.qualities);
+        assert_eq!(strategy.select(&mut stats, &mut Random::new(0)).unwrap(), 0);
+
+        reward(&strategy, &mut stats, 1, 0.3);
+        assert_floats_eq(&[0., 0.4], &stats.qualities);
+        assert_eq!(stats.history, [(1, 0.5), (1, 0.3)]);
+        // Operator 0 dropped out of the window and is tried again.
+        assert_eq!(strategy.select(&mut stats, &mut Random::new(0)).unwrap(), 0);
+    }
+
+    #[derive(Deref, Tid)]
+    struct Reward(f64);
+
+    impl CustomState<'_> for Reward {}
+
+    /// Sets the [`Reward`] of the next execution.
+    #[derive(Clone, Serialize)]
+    struct SetReward(f64);
+
+    impl<P: Problem> Component<P> for SetReward {
+        fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+            state.insert(Reward(self.0));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn execute_rewards_the_previous_operator() {
+        let problem = IntegerTestProblem(vec![0..10; 2]);
+        let mut state = integer_test_state(Vec::new());
+        let aos = AdaptiveOperatorSelection::new(
+            [
+                Box::new(SetReward(0.)) as Box<dyn Component<_>>,
+                Box::new(SetReward(1.)),
+            ],
+            Ucb1::new(0.).unwrap(),
+            ValueOf::<Reward>::new(),
+        )
+        .unwrap();
+        aos.init(&problem, &mut state).unwrap();
+
+        for _ in 0..5 {
+            aos.execute(&problem, &mut state).unwrap();
+        }
+
+        let stats = state.borrow::<OperatorSelection<Global>>();
+        assert_eq!(stats.counts, [1, 4]);
+        // The last selection is still waiting for its reward.
+        assert_eq!(stats.rewards, [1, 3]);
+        assert_eq!(stats.pending, Some(1));
+        assert_floats_eq(&[0., 1.], &stats.qualities);
+    }
+}
@@ -6,6 +6,7 @@
 //! Differential evolution outside the box. Information Sciences 581, (December 2021), 587–604.
 //! DOI:<https://doi.org/10/grsff3>
 
+use eyre::ensure;
 use itertools::izip;
 use rand::distributions::Distribution;
 use rand_distr::Normal;
@@ -15,7 +16,7 @@ use crate::{
     component::{AnyComponent, ExecResult},
     components::Component,
     population::AsSolutionsMut,
-    problems::LimitedVectorProblem,
+    problems::{mixed, LimitedVectorProblem, MixedVectorProblem},
     state::{random::Random, State},
     Problem,
 };
@@ -76,6 +77,57 @@ where
     }
 }
 
+/// Clamps the values of a [`MixedVectorProblem`] to the domain of their [`MixedVariable`],
+/// converting values of the wrong type, e.g. rounding real values of integer variables.
+///
+/// See [`MixedVariable::clamp`].
+///
+/// # Errors
+///
+/// Returns an `Err` on initialization if the domain of any [`MixedVariable`] is empty.
+///
+/// [`MixedVariable`]: crate::problems::MixedVariable
+/// [`MixedVariable::clamp`]: crate::problems::MixedVariable::clamp
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MixedSaturation;
+
+impl MixedSaturation {
+    pub fn from_params() -> Self {
+        Self
+    }
+
+    pub fn new<P>() -> Box<dyn Component<P>>
+    where
+        P: MixedVectorProblem,
+    {
+        Box::new(Self::from_params())
+    }
+}
+
+impl<P> BoundaryConstraint<P> for MixedSaturation
+where
+    P: MixedVectorProblem,
+{
+    fn constrain(&self, solution: &mut P::Encoding, problem: &P, _rng: &mut Random) {
+        for (x, variable) in izip!(solution, problem.variables()) {
+            *x = variable.clamp(*x);
+        }
+    }
+}
+
+impl<P> Component<P> for MixedSaturation
+where
+    P: MixedVectorProblem,
+{
+    fn init(&self, problem: &P, _state: &mut State<P>) -> ExecResult<()> {
+        mixed::validate_variables(&problem.variables())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        boundary_constraint(self, problem, state)
+    }
+}
+
 /// Reflects values outside the domain off the opposite domain boundary inwards,
 /// as if the boundaries are connected and the domain forms a ring.
 #[derive(Clone, Serialize, Deserialize)]
@@ -123,6 +175,111 @@ where
     }
 }
 
+/// Ensures that no dimension of the integer domain of the `problem` is empty.
+fn ensure_nonempty_domain<P>(problem: &P) -> ExecResult<()>
+where
+    P: LimitedVectorProblem<Element = i64>,
+{
+    ensure!(
+        problem.domain().iter().all(|range| range.start < range.end),
+        "the domain must not be empty in any dimension"
+    );
+    Ok(())
+}
+
+/// Clamps the values of an integer vector to the domain boundaries.
+///
+/// As the domain `start..end` excludes `end`, values are clamped to `[start, end - 1]`.
+///
+/// # Errors
+///
+/// Returns an `Err` on initialization if the domain is empty in any dimension.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IntegerSaturation;
+
+impl IntegerSaturation {
+    pub fn from_params() -> Self {
+        Self
+    }
+
+    pub fn new<P>() -> Box<dyn Component<P>>
+    where
+        P: LimitedVectorProblem<Element = i64>,
+    {
+        Box::new(Self::from_params())
+    }
+}
+
+impl<P> BoundaryConstraint<P> for IntegerSaturation
+where
+    P: LimitedVectorProblem<Element = i64>,
+{
+    fn constrain(&self, solution: &mut P::Encoding, problem: &P, _rng: &mut Random) {
+        for (x, range) in izip!(solution, problem.domain()) {
+            *x = (*x).clamp(range.start, range.end - 1);
+        }
+    }
+}
+
+impl<P> Component<P> for IntegerSaturation
+where
+    P: LimitedVectorProblem<Element = i64>,
+{
+    fn init(&self, problem: &P, _state: &mut State<P>) -> ExecResult<()> {
+        ensure_nonempty_domain(problem)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        boundary_constraint(self, problem, state)
+    }
+}
+
+/// Wraps the values of an integer vector outside the domain around to the opposite boundary,
+/// as if the boundaries are connected and the domain forms a ring.
+///
+/// # Errors
+///
+/// Returns an `Err` on initialization if the domain is empty in any dimension.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IntegerToroidal;
+
+impl IntegerToroidal {
+    pub fn from_params() -> Self {
+        Self
+    }
+
+    pub fn new<P>() -> Box<dyn Component<P>>
+    where
+        P: LimitedVectorProblem<Element = i64>,
+    {
+        Box::new(Self::from_params())
+    }
+}
+
+impl<P> BoundaryConstraint<P> for IntegerToroidal
+where
+    P: LimitedVectorProblem<Element = i64>,
+{
+    fn constrain(&self, solution: &mut P::Encoding, problem: &P, _rng: &mut Random) {
+        for (x, range) in izip!(solution, problem.domain()) {
+            *x = range.start + (*x - range.start).rem_euclid(range.end - range.start);
+        }
+    }
+}
+
+impl<P> Component<P> for IntegerToroidal
+where
+    P: LimitedVectorProblem<Element = i64>,
+{
+    fn init(&self, problem: &P, _state: &mut State<P>) -> ExecResult<()> {
+        ensure_nonempty_domain(problem)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        boundary_constraint(self, problem, state)
+    }
+}
+
 /// The amount exceeding the boundary is reflected inwards at the same boundary.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Mirror;
@@ -230,3 +387,31 @@ where
         boundary_constraint(self, problem, state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn integer_boundaries_repair_values() {
+        let problem = IntegerTestProblem(vec![0..5; 4]);
+        let mut rng = Random::new(0);
+
+        let mut solution = vec![-3, 0, 4, 7];
+        IntegerSaturation.constrain(&mut solution, &problem, &mut rng);
+        assert_eq!(solution, [0, 0, 4, 4]);
+
+        let mut solution = vec![-3, 0, 4, 7];
+        IntegerToroidal.constrain(&mut solution, &problem, &mut rng);
+        assert_eq!(solution, [2, 0, 4, 2]);
+    }
+
+    #[test]
+    fn integer_boundaries_reject_empty_domains() {
+        let problem = IntegerTestProblem(vec![0..5, 3..3]);
+        let mut state = integer_test_state(vec![vec![0, 3]]);
+        assert!(IntegerSaturation::new().init(&problem, &mut state).is_err());
+        assert!(IntegerToroidal::new().init(&problem, &mut state).is_err());
+    }
+}
@@ -1,14 +1,21 @@
 //! Meta-components for specifying control flow.
 
 use derivative::Derivative;
-use serde::Serialize;
+use eyre::{ensure, ContextCompat};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     component::ExecResult,
     components::Component,
     conditions::Condition,
+    configuration::{
+        tree::{self, Edge, EdgeMut},
+        validation,
+    },
     problems::Problem,
-    state::{common, State, StateReq},
+    state::{common, random::Random, State, StateReq},
+    Individual,
 };
 
 /// A block of components executed sequentially.
@@ -67,17 +74,11 @@ impl<P: Problem> Block<P> {
 
 impl<P: Problem> Component<P> for Block<P> {
     fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
-        for component in &self.0 {
-            component.init(problem, state)?;
-        }
-        Ok(())
+        tree::init_children(self.children(), problem, state)
     }
 
     fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
-        for component in &self.0 {
-            component.require(problem, state_req)?;
-        }
-        Ok(())
+        tree::require_children(self.children(), problem, state_req)
     }
 
     fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
@@ -86,6 +87,22 @@ impl<P: Problem> Component<P> for Block<P> {
         }
         Ok(())
     }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, component)| Edge::component(format!("Block[{i}]"), component.as_ref()))
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        self.0
+            .iter_mut()
+            .enumerate()
+            .map(|(i, component)| EdgeMut::component(format!("Block[{i}]"), component))
+            .collect()
+    }
 }
 
 impl<I: IntoIterator<Item = Box<dyn Component<P>>>, P: Problem> From<I> for Box<dyn Component<P>> {
@@ -181,17 +198,12 @@ impl<P: Problem> Loop<P> {
 impl<P: Problem> Component<P> for Loop<P> {
     fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
         state.insert(common::Iterations(0));
-
-        self.condition.init(problem, state)?;
-        self.body.init(problem, state)?;
-
-        Ok(())
+        tree::init_children(self.children(), problem, state)
     }
 
     fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
-        self.condition.require(problem, state_req)?;
-        self.body.require(problem, state_req)?;
-        Ok(())
+        state_req.claim::<Self, common::Iterations>();
+        tree::require_children(self.children(), problem, state_req)
     }
 
     fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
@@ -202,6 +214,20 @@ impl<P: Problem> Component<P> for Loop<P> {
         }
         Ok(())
     }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        vec![
+            Edge::condition("Loop.while", self.condition.as_ref()),
+            Edge::component("Loop.do", self.body.as_ref()),
+        ]
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        vec![
+            EdgeMut::condition("Loop.while", &mut self.condition),
+            EdgeMut::component("Loop.do", &mut self.body),
+        ]
+    }
 }
 
 /// Executes the `if` or `else` branch depending on the `condition`.
@@ -332,21 +358,11 @@ impl<P: Problem> Branch<P> {
 
 impl<P: Problem> Component<P> for Branch<P> {
     fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
-        self.condition.init(problem, state)?;
-        self.if_body.init(problem, state)?;
-        if let Some(else_body) = &self.else_body {
-            else_body.init(problem, state)?;
-        }
-        Ok(())
+        tree::init_children(self.children(), problem, state)
     }
 
     fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
-        self.condition.require(problem, state_req)?;
-        self.if_body.require(problem, state_req)?;
-        if let Some(else_body) = &self.else_body {
-            else_body.require(problem, state_req)?;
-        }
-        Ok(())
+        tree::require_children(self.children(), problem, state_req)
     }
 
     fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
@@ -357,6 +373,28 @@ impl<P: Problem> Component<P> for Branch<P> {
         }
         Ok(())
     }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        let mut children = vec![
+            Edge::condition("Branch.condition", self.condition.as_ref()),
+            Edge::component("Branch.if_body", self.if_body.as_ref()),
+        ];
+        if let Some(else_body) = &self.else_body {
+            children.push(Edge::component("Branch.else_body", else_body.as_ref()));
+        }
+        children
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        let mut children = vec![
+            EdgeMut::condition("Branch.condition", &mut self.condition),
+            EdgeMut::component("Branch.if_body", &mut self.if_body),
+        ];
+        if let Some(else_body) = &mut self.else_body {
+            children.push(EdgeMut::component("Branch.else_body", else_body));
+        }
+        children
+    }
 }
 
 /// Executes the `body` in a new scope, where shadowing custom state is possible.
@@ -377,7 +415,9 @@ impl<P: Problem> Component<P> for Branch<P> {
 ///
 /// # Call propagation
 ///
-/// Calling any of the `{init, require}` methods on a scope **doesn't do anything**.
+/// Calling any of the `{init, require}` methods on a scope **doesn't do anything**,
+/// except during [`Configuration::validate`], where the body is validated on a simulated
+/// inner state.
 ///
 /// On calling the `execute` method, the `state` is first initialized using
 /// the provided `state_init` function.
@@ -388,6 +428,7 @@ impl<P: Problem> Component<P> for Branch<P> {
 /// the newly created child state.
 ///
 /// [`Configuration`]: crate::Configuration
+/// [`Configuration::validate`]: crate::Configuration::validate
 ///
 /// # Examples
 ///
@@ -447,6 +488,12 @@ impl<P: Problem> Scope<P> {
 }
 
 impl<P: Problem> Component<P> for Scope<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        // The body is otherwise only initialized at runtime, so it is validated on a
+        // simulated inner state.
+        validation::validate_isolated(state, problem, self.children(), self.state_init)
+    }
+
     fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
         let inner = state.with_inner_state(|state| {
             (self.state_init)(state)?;
@@ -458,4 +505,338 @@ impl<P: Problem> Component<P> for Scope<P> {
         (self.states_merge)(state, inner)?;
         Ok(())
     }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        vec![Edge::component("Scope.do", self.body.as_ref())]
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        vec![EdgeMut::component("Scope.do", &mut self.body)]
+    }
+}
+
+/// Specifies how the current population is passed to the branches of a [`ParallelBlock`].
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BranchInput {
+    /// Every branch operates on a clone of the whole population.
+    Cloned,
+    /// The population is split into one contiguous part of (almost) equal size per branch.
+    Partitioned,
+    /// Every individual is passed separately to branch `i % n`, where `i` is its index and
+    /// `n` the number of branches.
+    ///
+    /// This is most useful with a single branch, e.g. for independent local searches.
+    PerIndividual,
+}
+
+/// Executes several branches concurrently, each on its own [`State`].
+///
+/// The current population is passed to the branches as specified by the [`BranchInput`].
+/// Each branch is executed on a new [`State`], which is initialized with the provided
+/// `state_init` function, a child [`Random`] generator, and the input population.
+/// Note that the branch states are **not** nested within the parent state, so all state
+/// required by the branches (e.g. an evaluator) has to be inserted using `state_init`.
+///
+/// After all branches finished, their resulting populations are concatenated in the order of
+/// the branches (or individuals) and replace the current population.
+/// The results are therefore independent of scheduling, and reproducible through the
+/// child [`Random`] generators, which are created in the same order.
+///
+/// Evaluations performed by a branch-local [`PopulationEvaluator`] are added to the
+/// [`Evaluations`] of the parent state.
+///
+/// Execution is parallelized using [`rayon`].
+///
+/// [`Random`]: crate::Random
+/// [`PopulationEvaluator`]: crate::components::evaluation::PopulationEvaluator
+/// [`Evaluations`]: common::Evaluations
+///
+/// # Call propagation
+///
+/// Calling any of the `{init, require}` methods on a parallel block **doesn't do anything**,
+/// except during [`Configuration::validate`], where the branches are validated on a simulated
+/// inner state.
+///
+/// On calling the `execute` method, the `{init, require, execute}` methods are called in order
+/// on each branch, similar to a [`Scope`].
+///
+/// [`Configuration::validate`]: crate::Configuration::validate
+///
+/// # Examples
+///
+/// Applying some `local_search` to every individual in parallel:
+///
+/// ```
+/// # use mahf::{Component, ExecResult, SingleObjectiveProblem, problems::ObjectiveFunction};
+/// use mahf::{
+///     components::control_flow::{BranchInput, ParallelBlock},
+///     problems::Sequential,
+/// };
+///
+/// # fn example<P: SingleObjectiveProblem + ObjectiveFunction + Sync>(local_search: Box<dyn Component<P>>) -> ExecResult<Box<dyn Component<P>>> {
+/// ParallelBlock::new(
+///     BranchInput::PerIndividual,
+///     |state| {
+///         state.insert_evaluator(Sequential::new());
+///         Ok(())
+///     },
+///     [local_search],
+/// )
+/// # }
+/// ```
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct ParallelBlock<P: Problem> {
+    input: BranchInput,
+    #[serde(skip)]
+    state_init: fn(&mut State<P>) -> ExecResult<()>,
+    branches: Vec<Box<dyn Component<P>>>,
+}
+
+impl<P: Problem + Sync> ParallelBlock<P> {
+    pub fn from_params(
+        input: BranchInput,
+        state_init: fn(&mut State<P>) -> ExecResult<()>,
+        branches: impl IntoIterator<Item = Box<dyn Component<P>>>,
+    ) -> ExecResult<Self> {
+        let branches: Vec<_> = branches.into_iter().collect();
+        ensure!(!branches.is_empty(), "at least one branch is required");
+        Ok(Self {
+            input,
+            state_init,
+            branches,
+        })
+    }
+
+    /// Creates a new `ParallelBlock` from a collection of `branches`.
+    pub fn new(
+        input: BranchInput,
+        state_init: fn(&mut State<P>) -> ExecResult<()>,
+        branches: impl IntoIterator<Item = Box<dyn Component<P>>>,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(input, state_init, branches)?))
+    }
+
+    /// Splits the `population` into the inputs of the branches, and returns the index of the
+    /// branch for each input.
+    fn split(&self, population: Vec<Individual<P>>) -> Vec<(usize, Vec<Individual<P>>)> {
+        let n = self.branches.len();
+        match self.input {
+            BranchInput::Cloned => (0..n).map(|i| (i, population.clone())).collect(),
+            BranchInput::Partitioned => {
+                let (base, rest) = (population.len() / n, population.len() % n);
+                let mut population = population.into_iter();
+                (0..n)
+                    .map(|i| {
+                        let size = base + usize::from(i < rest);
+                        (i, population.by_ref().take(size).collect())
+                    })
+                    .collect()
+            }
+            BranchInput::PerIndividual => population
+                .into_iter()
+                .enumerate()
+                .map(|(i, individual)| (i % n, vec![individual]))
+                .collect(),
+        }
+    }
+
+    /// Executes the branch with `index` on the `population`, and returns the resulting
+    /// population and the number of evaluations performed by the branch.
+    fn execute_branch(
+        &self,
+        problem: &P,
+        index: usize,
+        population: Vec<Individual<P>>,
+        rng: Random,
+    ) -> ExecResult<(Vec<Individual<P>>, u32)> {
+        let branch = &self.branches[index];
+
+        let mut state = State::new();
+        (self.state_init)(&mut state)?;
+        state.insert(rng);
+        state.insert(common::Populations::<P>::new());
+        state.populations_mut().push(population);
+
+        branch.init(problem, &mut state)?;
+        branch.require(problem, &state.requirements())?;
+        branch.execute(problem, &mut state)?;
+
+        let population = state
+            .populations_mut()
+            .try_pop()
+            .wrap_err("the branch removed its population")?;
+        let evaluations = state
+            .try_get_value::<common::Evaluations>()
+            .unwrap_or_default();
+        Ok((population, evaluations))
+    }
+}
+
+impl<P: Problem + Sync> Component<P> for ParallelBlock<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        // The branches are otherwise only initialized at runtime, so they are validated on a
+        // simulated inner state.
+        validation::validate_isolated(state, problem, self.children(), |state| {
+            (self.state_init)(state)?;
+            state.insert(common::Populations::<P>::new());
+            Ok(())
+        })
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let population = state.populations_mut().pop();
+        let inputs = self.split(population);
+        let rngs: Vec<_> = state
+            .random_mut()
+            .iter_children()
+            .take(inputs.len())
+            .collect();
+
+        let results: Vec<_> = inputs
+            .into_par_iter()
+            .zip(rngs)
+            .map(|((index, population), rng)| self.execute_branch(problem, index, population, rng))
+            .collect::<ExecResult<_>>()?;
+        let (populations, evaluations): (Vec<_>, Vec<u32>) = results.into_iter().unzip();
+
+        let evaluations: u32 = evaluations.into_iter().sum();
+        if evaluations > 0 && state.contains::<common::Evaluations>() {
+            *state.borrow_value_mut::<common::Evaluations>() += evaluations;
+        }
+        state.populations_mut().push(populations.concat());
+        Ok(())
+    }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        self.branches
+            .iter()
+            .enumerate()
+            .map(|(i, branch)| Edge::component(format!("ParallelBlock[{i}]"), branch.as_ref()))
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        self.branches
+            .iter_mut()
+            .enumerate()
+            .map(|(i, branch)| EdgeMut::component(format!("ParallelBlock[{i}]"), branch))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{
+        components::{evaluation::PopulationEvaluator, mutation::CreepMutation},
+        problems::Sequential,
+        testing::*,
+        Configuration,
+    };
+
+    /// Tags all individuals by setting their second element to the given value.
+    #[derive(Clone, Serialize)]
+    struct Tag(i64);
+
+    impl<P: Problem<Encoding = Vec<i64>>> Component<P> for Tag {
+        fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+            for individual in state.populations_mut().current_mut() {
+                individual.solution_mut()[1] = self.0;
+            }
+            Ok(())
+        }
+    }
+
+    fn problem() -> IntegerTestProblem {
+        IntegerTestProblem(vec![0..10; 2])
+    }
+
+    fn state(n: i64) -> State<'static, IntegerTestProblem> {
+        integer_test_state((0..n).map(|i| vec![i, 0]).collect())
+    }
+
+    fn solutions(state: &State<IntegerTestProblem>) -> Vec<Vec<i64>> {
+        state
+            .populations()
+            .current()
+            .iter()
+            .map(|i| i.solution().clone())
+            .collect()
+    }
+
+    fn insert_evaluator(state: &mut State<IntegerTestProblem>) -> ExecResult<()> {
+        state.insert_evaluator(Sequential::new());
+        Ok(())
+    }
+
+    #[test_case(BranchInput::Cloned, &[[0, 1], [1, 1], [2, 1], [0, 2], [1, 2], [2, 2]]; "cloned")]
+    #[test_case(BranchInput::Partitioned, &[[0, 1], [1, 1], [2, 2]]; "partitioned")]
+    #[test_case(BranchInput::PerIndividual, &[[0, 1], [1, 2], [2, 1]]; "per individual")]
+    fn parallel_block_splits_and_merges_population(input: BranchInput, expected: &[[i64; 2]]) {
+        let problem = problem();
+        let mut state = state(3);
+        let block = ParallelBlock::from_params(
+            input,
+            |_| Ok(()),
+            [Box::new(Tag(1)) as Box<dyn Component<_>>, Box::new(Tag(2))],
+        )
+        .unwrap();
+        block.init(&problem, &mut state).unwrap();
+        block.execute(&problem, &mut state).unwrap();
+
+        assert_eq!(solutions(&state), expected);
+        assert_eq!(state.populations().len(), 1);
+    }
+
+    #[test]
+    fn parallel_block_adds_branch_evaluations() {
+        let problem = problem();
+        let mut state = state(5);
+        state.insert(common::Evaluations(3));
+        let block = ParallelBlock::from_params(
+            BranchInput::Cloned,
+            insert_evaluator,
+            [PopulationEvaluator::new(), PopulationEvaluator::new()],
+        )
+        .unwrap();
+        block.init(&problem, &mut state).unwrap();
+        block.execute(&problem, &mut state).unwrap();
+
+        assert_eq!(state.get_value::<common::Evaluations>(), 13);
+        assert!(state
+            .populations()
+            .current()
+            .iter()
+            .all(|i| i.is_evaluated()));
+    }
+
+    #[test]
+    fn parallel_block_is_reproducible() {
+        let problem = problem();
+        let run = |seed| {
+            let mut state = state(6);
+            state.insert(Random::new(seed));
+            let block = ParallelBlock::from_params(
+                BranchInput::PerIndividual,
+                insert_evaluator,
+                [Configuration::builder()
+                    .do_(CreepMutation::new(3, 1.))
+                    .do_(PopulationEvaluator::new())
+                    .build_component()],
+            )
+            .unwrap();
+            block.init(&problem, &mut state).unwrap();
+            for _ in 0..3 {
+                block.execute(&problem, &mut state).unwrap();
+            }
+            solutions(&state)
+        };
+
+        assert_eq!(run(0), run(0));
+        assert_ne!(run(0), run(1));
+    }
 }
@@ -124,15 +124,16 @@ where
     fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
         let population = state.populations_mut().try_pop();
         if let Some(mut population) = population {
+            let mut evaluations = 0;
             state.holding::<common::Evaluator<P, I>>(
                 |evaluator: &mut common::Evaluator<P, I>, state| {
-                    evaluator
-                        .as_inner_mut()
-                        .evaluate(problem, state, &mut population);
+                    let inner = evaluator.as_inner_mut();
+                    inner.evaluate(problem, state, &mut population);
+                    evaluations = inner.counted_evaluations(population.len());
                     Ok(())
                 },
             )?;
-            *state.borrow_value_mut::<common::Evaluations>() += population.len() as u32;
+            *state.borrow_value_mut::<common::Evaluations>() += evaluations as u32;
             state.populations_mut().push(population);
         }
         Ok(())
@@ -0,0 +1,278 @@
+//! Components for grammatical evolution (GE) on variable-length integer genomes.
+//!
+//! Besides the GE-specific operators in this module, genomes can be created using
+//! [`RandomSpread`], recombined using [`CutAndSplice`], and mutated using the integer mutations,
+//! e.g. [`CreepMutation`].
+//! Tournament selection and replacement work as usual.
+//!
+//! [`RandomSpread`]: crate::components::initialization::RandomSpread
+//! [`CutAndSplice`]: crate::components::recombination::CutAndSplice
+//! [`CreepMutation`]: crate::components::mutation::CreepMutation
+//!
+//! # Examples
+//!
+//! A basic GE:
+//!
+//! ```
+//! use mahf::{
+//!     components::{ge, initialization, recombination, replacement, selection},
+//!     conditions::LessThanN,
+//!     problems::GrammarProblem,
+//!     Configuration, ExecResult, SingleObjectiveProblem,
+//! };
+//!
+//! # fn example<P: SingleObjectiveProblem + GrammarProblem>() -> ExecResult<Configuration<P>> {
+//! let codon_mutation = ge::CodonMutation::new(0.01)?;
+//! let duplication = ge::Duplication::new(0.05)?;
+//! let pruning = ge::Pruning::new(0.05)?;
+//!
+//! # Ok(
+//! Configuration::builder()
+//!     .do_(initialization::RandomSpread::new(200))
+//!     .evaluate()
+//!     .update_best_individual()
+//!     .while_(LessThanN::iterations(100), |builder| {
+//!         builder
+//!             .do_(selection::Tournament::new(200, 3))
+//!             .do_(recombination::CutAndSplice::new(0.9, true))
+//!             .do_(codon_mutation)
+//!             .do_(duplication)
+//!             .do_(pruning)
+//!             .evaluate()
+//!             .update_best_individual()
+//!             .do_(replacement::Generational::new(200))
+//!     })
+//!     .build()
+//! # )
+//! # }
+//! ```
+//!
+//! # References
+//!
+//! \[1\] Michael O'Neill and Conor Ryan. 2001.
+//! Grammatical Evolution.
+//! IEEE Transactions on Evolutionary Computation 5, 4 (2001), 349–358.
+//! DOI:<https://doi.org/10.1109/4235.942529>
+
+use eyre::ensure;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::ExecResult, components::Component, population::AsSolutionsMut,
+    problems::GrammarProblem, State,
+};
+
+/// Replaces each codon with a value uniformly sampled from [`GrammarProblem::codons`]
+/// with probability `rm`.
+///
+/// # Errors
+///
+/// Returns an `Err` on initialization if [`GrammarProblem::codons`] is empty.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CodonMutation {
+    /// Mutation rate, i.e. the probability of mutating a codon.
+    pub rm: f64,
+}
+
+impl CodonMutation {
+    pub fn from_params(rm: f64) -> ExecResult<Self> {
+        ensure!((0.0..=1.0).contains(&rm), "`rm` must be in [0, 1]");
+        Ok(Self { rm })
+    }
+
+    pub fn new<P: GrammarProblem>(rm: f64) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(rm)?))
+    }
+}
+
+impl<P: GrammarProblem> Component<P> for CodonMutation {
+    fn init(&self, problem: &P, _state: &mut State<P>) -> ExecResult<()> {
+        ensure!(
+            !problem.codons().is_empty(),
+            "the range of codon values must not be empty"
+        );
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let codons = problem.codons();
+
+        for genome in populations.current_mut().as_solutions_mut() {
+            for codon in genome {
+                if rng.gen_bool(self.rm) {
+                    *codon = rng.gen_range(codons.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Duplicates a random sequence of codons of each genome with probability `rm`,
+/// inserting the copy before the last codon.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Duplication {
+    /// Mutation rate, i.e. the probability of mutating a genome.
+    pub rm: f64,
+}
+
+impl Duplication {
+    pub fn from_params(rm: f64) -> ExecResult<Self> {
+        ensure!((0.0..=1.0).contains(&rm), "`rm` must be in [0, 1]");
+        Ok(Self { rm })
+    }
+
+    pub fn new<P: GrammarProblem>(rm: f64) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(rm)?))
+    }
+}
+
+impl<P: GrammarProblem> Component<P> for Duplication {
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+
+        for genome in populations.current_mut().as_solutions_mut() {
+            if genome.is_empty() || !rng.gen_bool(self.rm) {
+                continue;
+            }
+            let start = rng.gen_range(0..genome.len());
+            let end = rng.gen_range(start + 1..=genome.len());
+            let copy = genome[start..end].to_vec();
+            let last = genome.len() - 1;
+            genome.splice(last..last, copy);
+        }
+        Ok(())
+    }
+}
+
+/// Removes the codons not used during mapping from each valid genome with probability `rm`.
+///
+/// Genomes which required wrapping are left unchanged.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Pruning {
+    /// Mutation rate, i.e. the probability of mutating a genome.
+    pub rm: f64,
+}
+
+impl Pruning {
+    pub fn from_params(rm: f64) -> ExecResult<Self> {
+        ensure!((0.0..=1.0).contains(&rm), "`rm` must be in [0, 1]");
+        Ok(Self { rm })
+    }
+
+    pub fn new<P: GrammarProblem>(rm: f64) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(rm)?))
+    }
+}
+
+impl<P: GrammarProblem> Component<P> for Pruning {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+
+        for genome in populations.current_mut().as_solutions_mut() {
+            if !rng.gen_bool(self.rm) {
+                continue;
+            }
+            if let Some(mapping) = problem.grammar().map(genome, problem.max_wraps()) {
+                genome.truncate(mapping.codons.max(1));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        problems::grammar::{Grammar, GrammaticalEvolution},
+        testing::test_state,
+    };
+
+    type GeProblem = GrammaticalEvolution<fn(&str) -> f64>;
+
+    fn problem() -> GeProblem {
+        let grammar = Grammar::parse(
+            "<expr> ::= <expr> <op> <expr> | <var>
+             <op>   ::= + | -
+             <var>  ::= x",
+        )
+        .unwrap();
+        let mut problem: GeProblem = GrammaticalEvolution::new(grammar, |_| 0.);
+        problem.codons = 0..10;
+        problem
+    }
+
+    fn mutate(
+        component: Box<dyn Component<GeProblem>>,
+        problem: &GeProblem,
+        genome: &[i64],
+    ) -> Vec<i64> {
+        let mut state = test_state(vec![genome.to_vec()]);
+        component.init(problem, &mut state).unwrap();
+        component.execute(problem, &mut state).unwrap();
+        let mut population = state.populations_mut().pop();
+        population.pop().unwrap().into_solution()
+    }
+
+    #[test]
+    fn rates_must_be_probabilities() {
+        for rm in [-0.1, 1.1, f64::NAN] {
+            assert!(CodonMutation::from_params(rm).is_err());
+            assert!(Duplication::from_params(rm).is_err());
+            assert!(Pruning::from_params(rm).is_err());
+        }
+    }
+
+    #[test]
+    fn codon_mutation_samples_from_codons() {
+        let problem = problem();
+        let genome = vec![100; 50];
+        let mutated = mutate(CodonMutation::new(1.).unwrap(), &problem, &genome);
+        assert!(mutated.iter().all(|codon| (0..10).contains(codon)));
+        let mutated = mutate(CodonMutation::new(0.).unwrap(), &problem, &genome);
+        assert_eq!(mutated, genome);
+    }
+
+    #[test]
+    fn codon_mutation_rejects_empty_codons() {
+        let mut problem = problem();
+        problem.codons = 5..5;
+        let mut state = test_state(vec![vec![1]]);
+        let component: Box<dyn Component<GeProblem>> = CodonMutation::new(1.).unwrap();
+        assert!(component.init(&problem, &mut state).is_err());
+    }
+
+    #[test]
+    fn duplication_inserts_copy_before_last_codon() {
+        let problem = problem();
+        let genome = [1, 2, 3, 4, 5];
+        for _ in 0..10 {
+            let mutated = mutate(Duplication::new(1.).unwrap(), &problem, &genome);
+            assert!(mutated.len() > genome.len());
+            assert_eq!(mutated[..4], genome[..4]);
+            assert_eq!(mutated.last(), Some(&5));
+            let copy = &mutated[4..mutated.len() - 1];
+            assert!(genome.windows(copy.len()).any(|window| window == copy));
+        }
+    }
+
+    #[test]
+    fn pruning_removes_unused_codons() {
+        let problem = problem();
+        // The first codon selects `<var>`, which needs no further codons.
+        let mutated = mutate(Pruning::new(1.).unwrap(), &problem, &[1, 7, 8, 9]);
+        assert_eq!(mutated, [1]);
+        // `<expr> <op> <expr>` with `x + x` uses four codons.
+        let mutated = mutate(Pruning::new(1.).unwrap(), &problem, &[0, 1, 0, 1, 5, 6]);
+        assert_eq!(mutated, [0, 1, 0, 1]);
+        // Invalid genomes are left unchanged.
+        let mutated = mutate(Pruning::new(1.).unwrap(), &problem, &[0, 0]);
+        assert_eq!(mutated, [0, 0]);
+    }
+}
@@ -0,0 +1,731 @@
+//! Estimation of distribution algorithms (EDAs).
+//!
+//! EDAs replace recombination and mutation with a probabilistic model, which is learned from
+//! the selected individuals and sampled to generate the next population.
+//!
+//! The models are stored as custom state:
+//! - [`UnivariateModel`]: independent marginal probabilities of bitstrings.
+//! - [`DependencyTree`]: tree-structured pairwise dependencies between bits.
+//! - [`GaussianModel`]: independent normal distributions of real-valued vectors.
+//!
+//! Each model has a sampling component, which inserts the model in [`Component::init`] and
+//! replaces the current population with samples, and one or more update components,
+//! which learn the model from the current population.
+//!
+//! See [`heuristics::eda`] for templates.
+//!
+//! [`heuristics::eda`]: crate::heuristics::eda
+//!
+//! # References
+//!
+//! \[1\] Heinz Mühlenbein and Gerhard Paaß. 1996.
+//! From recombination of genes to the estimation of distributions I. Binary parameters.
+//! In Parallel Problem Solving from Nature — PPSN IV, 178–187.
+//! DOI:<https://doi.org/10.1007/3-540-61723-X_982>
+//!
+//! \[2\] Shumeet Baluja. 1994.
+//! Population-Based Incremental Learning: A Method for Integrating Genetic Search Based Function
+//! Optimization and Competitive Learning. Technical Report CMU-CS-94-163.
+//!
+//! \[3\] Georges R. Harik, Fernando G. Lobo, and David E. Goldberg. 1999.
+//! The compact genetic algorithm.
+//! IEEE Transactions on Evolutionary Computation 3, 4 (1999), 287–297.
+//! DOI:<https://doi.org/10.1109/4235.797971>
+//!
+//! \[4\] Shumeet Baluja and Scott Davies. 1997.
+//! Using Optimal Dependency-Trees for Combinatorial Optimization: Learning the Structure of the
+//! Search Space. In Proceedings of the 14th International Conference on Machine Learning, 30–38.
+
+use better_any::{Tid, TidAble};
+use eyre::{ensure, WrapErr};
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::ExecResult,
+    components::Component,
+    population::{AsSolutions, IntoIndividuals},
+    problems::{LimitedVectorProblem, SingleObjectiveProblem, VectorProblem},
+    state::StateReq,
+    CustomState, State,
+};
+
+/// Independent marginal probabilities `p(x_i = true)` of bitstrings.
+#[derive(Clone, Debug, Tid)]
+pub struct UnivariateModel {
+    /// The probability of every bit being `true`.
+    pub probabilities: Vec<f64>,
+}
+
+impl UnivariateModel {
+    /// Creates the uniform distribution over bitstrings of length `dimension`.
+    pub fn uniform(dimension: usize) -> Self {
+        Self {
+            probabilities: vec![0.5; dimension],
+        }
+    }
+
+    /// Samples a bitstring.
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec<bool> {
+        self.probabilities
+            .iter()
+            .map(|&p| rng.gen_bool(p))
+            .collect()
+    }
+}
+
+impl CustomState<'_> for UnivariateModel {}
+
+/// Generates `population_size` bitstrings from the [`UnivariateModel`],
+/// replacing the current population.
+///
+/// The model is initialized with the uniform distribution.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UnivariateSampling {
+    /// Size of the population to be generated.
+    pub population_size: u32,
+}
+
+impl UnivariateSampling {
+    pub fn from_params(population_size: u32) -> Self {
+        Self { population_size }
+    }
+
+    pub fn new<P: VectorProblem<Element = bool>>(population_size: u32) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(population_size))
+    }
+}
+
+impl<P: VectorProblem<Element = bool>> Component<P> for UnivariateSampling {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(UnivariateModel::uniform(problem.dimension()));
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let model = state.borrow::<UnivariateModel>();
+        let mut rng = state.random_mut();
+        let population: Vec<_> = (0..self.population_size)
+            .map(|_| model.sample(&mut *rng))
+            .collect();
+        *state.populations_mut().current_mut() = population.into_individuals();
+        Ok(())
+    }
+}
+
+/// Moves the [`UnivariateModel`] towards the bit frequencies of the current population
+/// with the `learning_rate`, i.e. `p = (1 - learning_rate) * p + learning_rate * frequency`.
+///
+/// The probabilities are restricted to `[margin, 1 - margin]` to prevent premature convergence.
+///
+/// A learning rate of 1 corresponds to the univariate marginal distribution algorithm (UMDA) \[1\],
+/// and a smaller learning rate to population-based incremental learning (PBIL) \[2\].
+///
+/// # Errors
+///
+/// Returns an `Err` in `from_params` or `new` if `learning_rate` is not within `(0, 1]`
+/// or `margin` is not within `[0, 0.5)`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UnivariateUpdate {
+    /// The learning rate.
+    pub learning_rate: f64,
+    /// The minimal distance of the probabilities to 0 and 1.
+    pub margin: f64,
+}
+
+impl UnivariateUpdate {
+    pub fn from_params(learning_rate: f64, margin: f64) -> ExecResult<Self> {
+        ensure!(
+            learning_rate > 0. && learning_rate <= 1.,
+            "`learning_rate` must be within (0, 1], but was {learning_rate}"
+        );
+        ensure!(
+            (0. ..0.5).contains(&margin),
+            "`margin` must be within [0, 0.5), but was {margin}"
+        );
+        Ok(Self {
+            learning_rate,
+            margin,
+        })
+    }
+
+    pub fn new<P: VectorProblem<Element = bool>>(
+        learning_rate: f64,
+        margin: f64,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(learning_rate, margin)?))
+    }
+}
+
+impl<P: VectorProblem<Element = bool>> Component<P> for UnivariateUpdate {
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, UnivariateModel>()?;
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let populations = state.populations();
+        let mut model = state.borrow_mut::<UnivariateModel>();
+
+        let population = populations.current();
+        let solutions = population.as_solutions();
+        if solutions.is_empty() {
+            return Ok(());
+        }
+        let n = solutions.len() as f64;
+
+        for (i, p) in model.probabilities.iter_mut().enumerate() {
+            let frequency = solutions.iter().filter(|x| x[i]).count() as f64 / n;
+            *p = ((1. - self.learning_rate) * *p + self.learning_rate * frequency)
+                .clamp(self.margin, 1. - self.margin);
+        }
+        Ok(())
+    }
+}
+
+/// Updates the [`UnivariateModel`] by comparing the best and worst individual of the current
+/// population, as done by the compact genetic algorithm (cGA) \[3\].
+///
+/// The probability of every bit in which both individuals differ is shifted by
+/// `1 / virtual_population_size` towards the value of the best individual.
+///
+/// # Errors
+///
+/// Returns an `Err` in `from_params` or `new` if `virtual_population_size` is 0,
+/// and on execution if the current population contains less than two individuals.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CompactUpdate {
+    /// The simulated population size, which determines the step size.
+    pub virtual_population_size: u32,
+}
+
+impl CompactUpdate {
+    pub fn from_params(virtual_population_size: u32) -> ExecResult<Self> {
+        ensure!(
+            virtual_population_size > 0,
+            "`virtual_population_size` must be greater than 0"
+        );
+        Ok(Self {
+            virtual_population_size,
+        })
+    }
+
+    pub fn new<P>(virtual_population_size: u32) -> ExecResult<Box<dyn Component<P>>>
+    where
+        P: SingleObjectiveProblem + VectorProblem<Element = bool>,
+    {
+        Ok(Box::new(Self::from_params(virtual_population_size)?))
+    }
+}
+
+impl<P> Component<P> for CompactUpdate
+where
+    P: SingleObjectiveProblem + VectorProblem<Element = bool>,
+{
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, UnivariateModel>()?;
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let populations = state.populations();
+        let mut model = state.borrow_mut::<UnivariateModel>();
+
+        let population = populations.current();
+        ensure!(
+            population.len() >= 2,
+            "the compact update requires at least two individuals"
+        );
+        let winner = population.iter().min_by_key(|i| i.objective()).unwrap();
+        let loser = population.iter().max_by_key(|i| i.objective()).unwrap();
+        let step = 1. / self.virtual_population_size as f64;
+
+        for ((p, &w), &l) in model
+            .probabilities
+            .iter_mut()
+            .zip(winner.solution())
+            .zip(loser.solution())
+        {
+            if w != l {
+                *p = (*p + if w { step } else { -step }).clamp(0., 1.);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A tree of pairwise dependencies between the bits of bitstrings.
+///
+/// Every bit depends on at most one parent bit, i.e. the probability of a bit being `true`
+/// is conditioned on the value of its parent.
+#[derive(Clone, Debug, Tid)]
+pub struct DependencyTree {
+    parents: Vec<Option<usize>>,
+    order: Vec<usize>,
+    probabilities: Vec<[f64; 2]>,
+}
+
+impl DependencyTree {
+    /// Creates the uniform distribution over bitstrings of length `dimension`,
+    /// i.e. without dependencies.
+    pub fn independent(dimension: usize) -> Self {
+        Self {
+            parents: vec![None; dimension],
+            order: (0..dimension).collect(),
+            probabilities: vec![[0.5; 2]; dimension],
+        }
+    }
+
+    /// Returns the parent of every bit, where `None` marks roots.
+    pub fn parents(&self) -> &[Option<usize>] {
+        &self.parents
+    }
+
+    /// Returns the probability of bit `i` being `true` given the value of its parent.
+    ///
+    /// The value of the parent is ignored for roots.
+    pub fn probability(&self, i: usize, parent: bool) -> f64 {
+        self.probabilities[i][parent as usize]
+    }
+
+    /// Samples a bitstring by sampling parents before their children.
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec<bool> {
+        let mut x = vec![false; self.parents.len()];
+        for &i in &self.order {
+            let parent = self.parents[i].is_some_and(|j| x[j]);
+            x[i] = rng.gen_bool(self.probability(i, parent));
+        }
+        x
+    }
+
+    /// Learns the tree maximizing the likelihood of the `solutions` using the Chow–Liu algorithm,
+    /// i.e. the maximum spanning tree of the pairwise mutual information.
+    ///
+    /// All frequencies are estimated using the pseudo-count `prior`, which has to be positive
+    /// for the conditional probabilities to be defined for unobserved parent values.
+    pub fn learn(solutions: &[&Vec<bool>], dimension: usize, prior: f64) -> Self {
+        let n = solutions.len() as f64;
+
+        // Counts of `x_i = true` and of `x_i = a, x_j = b`.
+        let ones: Vec<f64> = (0..dimension)
+            .map(|i| solutions.iter().filter(|x| x[i]).count() as f64)
+            .collect();
+        let joint = |i: usize, j: usize| {
+            let mut counts = [[0.; 2]; 2];
+            for x in solutions {
+                counts[x[i] as usize][x[j] as usize] += 1.;
+            }
+            counts
+        };
+        let marginal = |i: usize, a: usize| {
+            let p = (ones[i] + prior) / (n + 2. * prior);
+            if a == 1 {
+                p
+            } else {
+                1. - p
+            }
+        };
+        let mutual_information = |i: usize, j: usize| {
+            let counts = joint(i, j);
+            let mut mi = 0.;
+            for (a, row) in counts.iter().enumerate() {
+                for (b, &count) in row.iter().enumerate() {
+                    let p = (count + prior) / (n + 4. * prior);
+                    if p > 0. {
+                        mi += p * (p / (marginal(i, a) * marginal(j, b))).ln();
+                    }
+                }
+            }
+            mi
+        };
+
+        // Prim's algorithm for the maximum spanning tree, rooted at the first bit.
+        let mut parents = vec![None; dimension];
+        let mut order = Vec::with_capacity(dimension);
+        let mut in_tree = vec![false; dimension];
+        let mut best: Vec<(f64, Option<usize>)> = vec![(f64::NEG_INFINITY, None); dimension];
+        if dimension > 0 {
+            best[0].0 = 0.;
+        }
+        for _ in 0..dimension {
+            let i = (0..dimension)
+                .filter(|&i| !in_tree[i])
+                .max_by(|&a, &b| best[a].0.total_cmp(&best[b].0))
+                .unwrap();
+            in_tree[i] = true;
+            parents[i] = best[i].1;
+            order.push(i);
+            for j in 0..dimension {
+                if !in_tree[j] {
+                    let mi = mutual_information(i, j);
+                    if mi > best[j].0 {
+                        best[j] = (mi, Some(i));
+                    }
+                }
+            }
+        }
+
+        let probabilities = (0..dimension)
+            .map(|i| match parents[i] {
+                None => [marginal(i, 1); 2],
+                Some(j) => {
+                    let counts = joint(j, i);
+                    [0, 1].map(|a| {
+                        (counts[a][1] + prior) / (counts[a][0] + counts[a][1] + 2. * prior)
+                    })
+                }
+            })
+            .collect();
+
+        Self {
+            parents,
+            order,
+            probabilities,
+        }
+    }
+}
+
+impl CustomState<'_> for DependencyTree {}
+
+/// Generates `population_size` bitstrings from the [`DependencyTree`],
+/// replacing the current population.
+///
+/// The model is initialized with the uniform distribution.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DependencyTreeSampling {
+    /// Size of the population to be generated.
+    pub population_size: u32,
+}
+
+impl DependencyTreeSampling {
+    pub fn from_params(population_size: u32) -> Self {
+        Self { population_size }
+    }
+
+    pub fn new<P: VectorProblem<Element = bool>>(population_size: u32) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(population_size))
+    }
+}
+
+impl<P: VectorProblem<Element = bool>> Component<P> for DependencyTreeSampling {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(DependencyTree::independent(problem.dimension()));
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let model = state.borrow::<DependencyTree>();
+        let mut rng = state.random_mut();
+        let population: Vec<_> = (0..self.population_size)
+            .map(|_| model.sample(&mut *rng))
+            .collect();
+        *state.populations_mut().current_mut() = population.into_individuals();
+        Ok(())
+    }
+}
+
+/// Learns the [`DependencyTree`] from the current population using the Chow–Liu algorithm \[4\],
+/// see [`DependencyTree::learn`].
+///
+/// # Errors
+///
+/// Returns an `Err` in `from_params` or `new` if `prior` is not positive.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ChowLiuUpdate {
+    /// The pseudo-count added to all frequencies.
+    pub prior: f64,
+}
+
+impl ChowLiuUpdate {
+    pub fn from_params(prior: f64) -> ExecResult<Self> {
+        ensure!(
+            prior > 0. && prior.is_finite(),
+            "`prior` must be positive, but was {prior}"
+        );
+        Ok(Self { prior })
+    }
+
+    pub fn new<P: VectorProblem<Element = bool>>(prior: f64) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(prior)?))
+    }
+}
+
+impl<P: VectorProblem<Element = bool>> Component<P> for ChowLiuUpdate {
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, DependencyTree>()?;
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let populations = state.populations();
+        let population = populations.current();
+        let solutions = population.as_solutions();
+        if !solutions.is_empty() {
+            *state.borrow_mut::<DependencyTree>() =
+                DependencyTree::learn(&solutions, problem.dimension(), self.prior);
+        }
+        Ok(())
+    }
+}
+
+/// Independent normal distributions `N(mean_i, std_dev_i)` of real-valued vectors.
+#[derive(Clone, Debug, Tid)]
+pub struct GaussianModel {
+    /// The mean of every dimension.
+    pub mean: Vec<f64>,
+    /// The standard deviation of every dimension.
+    pub std_dev: Vec<f64>,
+}
+
+impl GaussianModel {
+    /// Samples a vector.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if any standard deviation is not finite.
+    pub fn sample(&self, rng: &mut impl Rng) -> ExecResult<Vec<f64>> {
+        self.mean
+            .iter()
+            .zip(&self.std_dev)
+            .map(|(&mean, &std_dev)| {
+                let distribution = Normal::new(mean, std_dev)
+                    .wrap_err_with(|| format!("invalid standard deviation {std_dev}"))?;
+                Ok(distribution.sample(rng))
+            })
+            .collect()
+    }
+}
+
+impl CustomState<'_> for GaussianModel {}
+
+/// Generates `population_size` vectors from the [`GaussianModel`], clamped to the domain,
+/// replacing the current population.
+///
+/// The model is initialized with the center of the domain as mean and half its width
+/// as standard deviation.
+///
+/// # Errors
+///
+/// Returns an `Err` on execution if the [`GaussianModel`] can't be sampled,
+/// see [`GaussianModel::sample`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GaussianSampling {
+    /// Size of the population to be generated.
+    pub population_size: u32,
+}
+
+impl GaussianSampling {
+    pub fn from_params(population_size: u32) -> Self {
+        Self { population_size }
+    }
+
+    pub fn new<P: LimitedVectorProblem<Element = f64>>(
+        population_size: u32,
+    ) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(population_size))
+    }
+}
+
+impl<P: LimitedVectorProblem<Element = f64>> Component<P> for GaussianSampling {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let domain = problem.domain();
+        state.insert(GaussianModel {
+            mean: domain.iter().map(|d| (d.start + d.end) / 2.).collect(),
+            std_dev: domain.iter().map(|d| (d.end - d.start) / 2.).collect(),
+        });
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let model = state.borrow::<GaussianModel>();
+        let mut rng = state.random_mut();
+        let domain = problem.domain();
+
+        let population = (0..self.population_size)
+            .map(|_| {
+                let mut x = model.sample(&mut *rng)?;
+                for (x, d) in x.iter_mut().zip(&domain) {
+                    *x = x.clamp(d.start, d.end);
+                }
+                Ok(x)
+            })
+            .collect::<ExecResult<Vec<Vec<f64>>>>()?;
+        *state.populations_mut().current_mut() = population.into_individuals();
+        Ok(())
+    }
+}
+
+/// Moves the [`GaussianModel`] towards the mean and standard deviation of the current population
+/// with the `learning_rate`.
+///
+/// The standard deviation is kept above `min_std_dev` to prevent premature convergence.
+///
+/// A learning rate of 1 corresponds to the continuous UMDA.
+///
+/// # Errors
+///
+/// Returns an `Err` in `from_params` or `new` if `learning_rate` is not within `(0, 1]`
+/// or `min_std_dev` is negative.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GaussianUpdate {
+    /// The learning rate.
+    pub learning_rate: f64,
+    /// The minimal standard deviation.
+    pub min_std_dev: f64,
+}
+
+impl GaussianUpdate {
+    pub fn from_params(learning_rate: f64, min_std_dev: f64) -> ExecResult<Self> {
+        ensure!(
+            learning_rate > 0. && learning_rate <= 1.,
+            "`learning_rate` must be within (0, 1], but was {learning_rate}"
+        );
+        ensure!(
+            min_std_dev >= 0.,
+            "`min_std_dev` must not be negative, but was {min_std_dev}"
+        );
+        Ok(Self {
+            learning_rate,
+            min_std_dev,
+        })
+    }
+
+    pub fn new<P: LimitedVectorProblem<Element = f64>>(
+        learning_rate: f64,
+        min_std_dev: f64,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(learning_rate, min_std_dev)?))
+    }
+}
+
+impl<P: LimitedVectorProblem<Element = f64>> Component<P> for GaussianUpdate {
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, GaussianModel>()?;
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let populations = state.populations();
+        let mut model = state.borrow_mut::<GaussianModel>();
+
+        let population = populations.current();
+        let solutions = population.as_solutions();
+        if solutions.is_empty() {
+            return Ok(());
+        }
+        let n = solutions.len() as f64;
+        let lr = self.learning_rate;
+        let GaussianModel { mean, std_dev } = &mut *model;
+
+        for (i, (mean, std_dev)) in mean.iter_mut().zip(std_dev).enumerate() {
+            let m = solutions.iter().map(|x| x[i]).sum::<f64>() / n;
+            let s = (solutions.iter().map(|x| (x[i] - m).powi(2)).sum::<f64>() / n).sqrt();
+            *mean = (1. - lr) * *mean + lr * m;
+            *std_dev = ((1. - lr) * *std_dev + lr * s).max(self.min_std_dev);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+
+    use super::*;
+    use crate::{testing::*, Random};
+
+    fn evaluated_state(solutions: Vec<(Vec<bool>, f64)>) -> State<'static, BitstringTestProblem> {
+        let (solutions, objectives): (Vec<_>, Vec<_>) = solutions.into_iter().unzip();
+        let state = test_state(solutions);
+        for (individual, objective) in state
+            .populations_mut()
+            .current_mut()
+            .iter_mut()
+            .zip(objectives)
+        {
+            individual.set_objective(objective.try_into().unwrap());
+        }
+        state
+    }
+
+    #[test]
+    fn univariate_update_moves_towards_frequencies() {
+        let problem = BitstringTestProblem(2);
+        let mut state = evaluated_state(vec![(vec![true, false], 1.), (vec![true, true], 2.)]);
+        state.insert(UnivariateModel::uniform(2));
+        UnivariateUpdate::from_params(0.5, 0.1)
+            .unwrap()
+            .execute(&problem, &mut state)
+            .unwrap();
+        let model = state.borrow::<UnivariateModel>();
+        assert_float_eq!(model.probabilities[0], 0.75, abs <= 1e-12);
+        assert_float_eq!(model.probabilities[1], 0.5, abs <= 1e-12);
+    }
+
+    #[test]
+    fn compact_update_shifts_towards_winner() {
+        let problem = BitstringTestProblem(3);
+        let mut state = evaluated_state(vec![
+            (vec![true, false, true], 1.),
+            (vec![false, true, true], 2.),
+        ]);
+        state.insert(UnivariateModel::uniform(3));
+        let update = CompactUpdate::from_params(4).unwrap();
+        update.execute(&problem, &mut state).unwrap();
+        let model = state.borrow::<UnivariateModel>();
+        assert_eq!(model.probabilities, [0.75, 0.25, 0.5]);
+    }
+
+    #[test]
+    fn compact_update_requires_two_individuals() {
+        let problem = BitstringTestProblem(1);
+        let mut state = evaluated_state(vec![(vec![true], 1.)]);
+        state.insert(UnivariateModel::uniform(1));
+        let update = CompactUpdate::from_params(4).unwrap();
+        assert!(update.execute(&problem, &mut state).is_err());
+    }
+
+    #[test]
+    fn invalid_params_are_rejected() {
+        assert!(CompactUpdate::from_params(0).is_err());
+        assert!(ChowLiuUpdate::from_params(-1.).is_err());
+        assert!(ChowLiuUpdate::from_params(0.).is_err());
+        assert!(ChowLiuUpdate::from_params(f64::NAN).is_err());
+        assert!(UnivariateUpdate::from_params(0., 0.).is_err());
+        assert!(GaussianUpdate::from_params(1., -1.).is_err());
+    }
+
+    #[test]
+    fn gaussian_model_rejects_invalid_std_dev() {
+        let mut rng = Random::new(0);
+        let model = GaussianModel {
+            mean: vec![0., 1.],
+            std_dev: vec![1., 0.],
+        };
+        let x = model.sample(&mut rng).unwrap();
+        assert_eq!(x[1], 1.);
+
+        let model = GaussianModel {
+            mean: vec![0.],
+            std_dev: vec![f64::NAN],
+        };
+        assert!(model.sample(&mut rng).is_err());
+    }
+
+    #[test]
+    fn chow_liu_learns_dependent_bits() {
+        // The second bit always equals the first, the third is independent.
+        let solutions = [
+            vec![true, true, false],
+            vec![false, false, false],
+            vec![true, true, true],
+            vec![false, false, true],
+        ];
+        let refs: Vec<_> = solutions.iter().collect();
+        let tree = DependencyTree::learn(&refs, 3, 0.01);
+        assert_eq!(tree.parents()[1], Some(0));
+        assert!(tree.probability(1, true) > 0.99);
+        assert!(tree.probability(1, false) < 0.01);
+    }
+}
@@ -0,0 +1,13 @@
+//! Generate solutions from partial solutions or probabilistic models.
+//!
+//! - [`aco`]: Ant Colony Optimization (ACO) and ACO-like components.
+//! - [`eda`]: Estimation of distribution algorithms (EDAs).
+
+pub mod aco;
+pub mod eda;
+
+pub use aco::{AcoGeneration, AsPheromoneUpdate, MinMaxPheromoneUpdate, PheromoneMatrix};
+pub use eda::{
+    ChowLiuUpdate, CompactUpdate, DependencyTree, DependencyTreeSampling, GaussianModel,
+    GaussianSampling, GaussianUpdate, UnivariateModel, UnivariateSampling, UnivariateUpdate,
+};
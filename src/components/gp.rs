@@ -0,0 +1,660 @@
+//! Components for genetic programming (GP) on [`Tree`]s.
+//!
+//! All components respect the type constraints of the [`PrimitiveSet`], i.e. only subtrees
+//! returning the same [`ValueType`] are exchanged, and limit bloat by never producing trees
+//! deeper than [`TreeProblem::max_depth`].
+//! Offspring violating the depth limit are discarded and replaced by a copy of their parent
+//! after a fixed number of attempts.
+//! Additionally, [`ParsimonyTournament`] prefers smaller trees among equally fit ones.
+//!
+//! [`ValueType`]: crate::problems::tree::ValueType
+//!
+//! # Examples
+//!
+//! A basic GP for symbolic regression:
+//!
+//! ```
+//! use mahf::{
+//!     components::{gp, replacement},
+//!     conditions::LessThanN,
+//!     problems::symbolic_regression::SymbolicRegression,
+//!     Configuration, ExecResult,
+//! };
+//!
+//! # fn example() -> ExecResult<Configuration<SymbolicRegression>> {
+//! let crossover = gp::SubtreeCrossover::new(0.9, true)?;
+//! let subtree_mutation = gp::SubtreeMutation::new(0.1, 4)?;
+//! let point_mutation = gp::PointMutation::new(0.02)?;
+//!
+//! # Ok(
+//! Configuration::builder()
+//!     .do_(gp::RampedHalfAndHalf::new(500, 2, 6)?)
+//!     .evaluate()
+//!     .update_best_individual()
+//!     .while_(LessThanN::iterations(50), |builder| {
+//!         builder
+//!             .do_(gp::ParsimonyTournament::new(500, 7))
+//!             .do_(crossover)
+//!             .do_(subtree_mutation)
+//!             .do_(point_mutation)
+//!             .evaluate()
+//!             .update_best_individual()
+//!             .do_(replacement::Generational::new(500))
+//!     })
+//!     .build()
+//! # )
+//! # }
+//! ```
+//!
+//! # References
+//!
+//! \[1\] John R. Koza. 1992.
+//! Genetic Programming: On the Programming of Computers by Means of Natural Selection.
+//! MIT Press, Cambridge, MA, USA.
+//!
+//! \[2\] Sean Luke and Liviu Panait. 2002.
+//! Lexicographic Parsimony Pressure.
+//! In Proceedings of the 4th Annual Conference on Genetic and Evolutionary Computation (GECCO '02), 829–836.
+
+use eyre::ensure;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::ExecResult,
+    components::{
+        initialization::{initialization, Initialization},
+        selection::{selection, Selection},
+        Component,
+    },
+    population::{AsSolutionsMut, IntoIndividuals, IntoSolutions},
+    problems::{
+        tree::{PrimitiveSet, ValueType},
+        SingleObjectiveProblem, Tree, TreeProblem,
+    },
+    state::random::Random,
+    Individual, State,
+};
+
+/// The number of attempts to create offspring within the depth limit.
+const MAX_ATTEMPTS: usize = 10;
+
+/// Returns the output type of the node with `index`.
+fn output(set: &PrimitiveSet, tree: &Tree, index: usize) -> ValueType {
+    set.get(tree.nodes()[index].primitive).output
+}
+
+/// Generates trees using the ramped half-and-half method.
+///
+/// The population is split evenly between the depths `min_depth..=max_depth`, where half
+/// of the trees of every depth are generated using the full method, and the other half using
+/// the grow method (see [`PrimitiveSet::generate`]).
+/// The depth is additionally limited by [`TreeProblem::max_depth`].
+///
+/// # Errors
+///
+/// Returns an `Err` in [`Component::init`] if the [`PrimitiveSet`] is invalid,
+/// see [`PrimitiveSet::validate`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RampedHalfAndHalf {
+    /// Size of the population to be generated.
+    pub population_size: u32,
+    /// Minimal depth of the generated trees.
+    pub min_depth: usize,
+    /// Maximal depth of the generated trees.
+    pub max_depth: usize,
+}
+
+impl RampedHalfAndHalf {
+    pub fn from_params(
+        population_size: u32,
+        min_depth: usize,
+        max_depth: usize,
+    ) -> ExecResult<Self> {
+        ensure!(
+            min_depth <= max_depth,
+            "`min_depth` must not be greater than `max_depth`"
+        );
+        Ok(Self {
+            population_size,
+            min_depth,
+            max_depth,
+        })
+    }
+
+    pub fn new<P: TreeProblem>(
+        population_size: u32,
+        min_depth: usize,
+        max_depth: usize,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(
+            population_size,
+            min_depth,
+            max_depth,
+        )?))
+    }
+}
+
+impl<P: TreeProblem> Initialization<P> for RampedHalfAndHalf {
+    fn initialize(&self, problem: &P, rng: &mut Random) -> Vec<P::Encoding> {
+        let set = problem.primitives();
+        let levels = self.max_depth - self.min_depth + 1;
+        (0..self.population_size as usize)
+            .map(|k| {
+                let depth = (self.min_depth + (k / 2) % levels).min(problem.max_depth());
+                set.generate(set.root, depth, k % 2 == 0, rng)
+            })
+            .collect()
+    }
+}
+
+impl<P: TreeProblem> Component<P> for RampedHalfAndHalf {
+    fn init(&self, problem: &P, _state: &mut State<P>) -> ExecResult<()> {
+        problem.primitives().validate()
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        initialization(self, problem, state)
+    }
+}
+
+/// Applies a subtree crossover to two parent trees depending on crossover probability `pc`.
+///
+/// A random node is chosen in the first parent, and a random node with the same output type
+/// in the second parent, after which the subtrees rooted at both nodes are exchanged.
+/// Children exceeding the depth limit are replaced by their parent.
+///
+/// If `insert_both` is `false`, the second child is discarded.
+///
+/// # Errors
+///
+/// Returns an `Err` on construction if `pc` is not in `[0, 1]`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SubtreeCrossover {
+    /// Crossover probability.
+    pub pc: f64,
+    /// If `false`, the second child is discarded.
+    pub insert_both: bool,
+}
+
+impl SubtreeCrossover {
+    pub fn from_params(pc: f64, insert_both: bool) -> ExecResult<Self> {
+        ensure!((0.0..=1.0).contains(&pc), "`pc` must be in [0, 1]");
+        Ok(Self { pc, insert_both })
+    }
+
+    pub fn new<P: TreeProblem>(pc: f64, insert_both: bool) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(pc, insert_both)?))
+    }
+
+    /// Returns both children, or `None` if no valid crossover points were found.
+    fn crossover(
+        parent1: &Tree,
+        parent2: &Tree,
+        set: &PrimitiveSet,
+        max_depth: usize,
+        rng: &mut Random,
+    ) -> Option<[Tree; 2]> {
+        for _ in 0..MAX_ATTEMPTS {
+            let i = rng.gen_range(0..parent1.len());
+            let ty = output(set, parent1, i);
+            let candidates: Vec<_> = (0..parent2.len())
+                .filter(|&j| output(set, parent2, j) == ty)
+                .collect();
+            let Some(&j) = candidates.choose(rng) else {
+                continue;
+            };
+
+            let mut child1 = parent1.clone();
+            child1.replace_subtree(i, &parent2.nodes()[parent2.subtree(j)]);
+            let mut child2 = parent2.clone();
+            child2.replace_subtree(j, &parent1.nodes()[parent1.subtree(i)]);
+
+            if child1.depth() <= max_depth || child2.depth() <= max_depth {
+                let keep = |child: Tree, parent: &Tree| {
+                    if child.depth() <= max_depth {
+                        child
+                    } else {
+                        parent.clone()
+                    }
+                };
+                return Some([keep(child1, parent1), keep(child2, parent2)]);
+            }
+        }
+        None
+    }
+}
+
+impl<P: TreeProblem> Component<P> for SubtreeCrossover {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let set = problem.primitives();
+
+        let solutions = populations.pop().into_solutions();
+        let mut population = Vec::new();
+
+        for chunk in solutions.chunks(2) {
+            match chunk {
+                [parent1, parent2] => {
+                    let children = if rng.gen::<f64>() <= self.pc {
+                        Self::crossover(parent1, parent2, set, problem.max_depth(), &mut rng)
+                    } else {
+                        None
+                    };
+                    let [child1, child2] =
+                        children.unwrap_or_else(|| [parent1.clone(), parent2.clone()]);
+                    population.push(child1);
+                    if self.insert_both {
+                        population.push(child2);
+                    }
+                }
+                [remainder] => population.push(remainder.clone()),
+                _ => unreachable!(),
+            }
+        }
+
+        populations.push(population.into_individuals());
+        Ok(())
+    }
+}
+
+/// Replaces a random subtree of each tree with a newly grown subtree of at most `depth`
+/// with probability `rm`.
+///
+/// The new subtree returns the same type as the replaced one.
+/// Trees exceeding the depth limit are left unchanged.
+///
+/// # Errors
+///
+/// Returns an `Err` on construction if `rm` is not in `[0, 1]`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SubtreeMutation {
+    /// Mutation rate, i.e. the probability of mutating a tree.
+    pub rm: f64,
+    /// Maximal depth of the new subtrees.
+    pub depth: usize,
+}
+
+impl SubtreeMutation {
+    pub fn from_params(rm: f64, depth: usize) -> ExecResult<Self> {
+        ensure!((0.0..=1.0).contains(&rm), "`rm` must be in [0, 1]");
+        Ok(Self { rm, depth })
+    }
+
+    pub fn new<P: TreeProblem>(rm: f64, depth: usize) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(rm, depth)?))
+    }
+}
+
+impl<P: TreeProblem> Component<P> for SubtreeMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let set = problem.primitives();
+
+        for tree in populations.current_mut().as_solutions_mut() {
+            if !rng.gen_bool(self.rm) {
+                continue;
+            }
+            for _ in 0..MAX_ATTEMPTS {
+                let i = rng.gen_range(0..tree.len());
+                let subtree = set.generate(output(set, tree, i), self.depth, false, &mut *rng);
+                let mut mutated = tree.clone();
+                mutated.replace_subtree(i, subtree.nodes());
+                if mutated.depth() <= problem.max_depth() {
+                    *tree = mutated;
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replaces each node with probability `rm` with a different primitive of the same signature,
+/// i.e. with the same output and input types.
+///
+/// Ephemeral random constants may also be replaced by a re-sampled constant of the same
+/// primitive, while other nodes without a different primitive of the same signature are
+/// left unchanged.
+/// As the shape of the tree does not change, the depth limit is always respected.
+///
+/// # Errors
+///
+/// Returns an `Err` on construction if `rm` is not in `[0, 1]`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PointMutation {
+    /// Mutation rate, i.e. the probability of mutating a node.
+    pub rm: f64,
+}
+
+impl PointMutation {
+    pub fn from_params(rm: f64) -> ExecResult<Self> {
+        ensure!((0.0..=1.0).contains(&rm), "`rm` must be in [0, 1]");
+        Ok(Self { rm })
+    }
+
+    pub fn new<P: TreeProblem>(rm: f64) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(rm)?))
+    }
+}
+
+impl<P: TreeProblem> Component<P> for PointMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let set = problem.primitives();
+
+        // The other primitives with the same signature as each primitive,
+        // including the primitive itself only if it is a constant, which is re-sampled.
+        let alternatives: Vec<Vec<usize>> = set
+            .primitives()
+            .iter()
+            .enumerate()
+            .map(|(j, p)| {
+                (0..set.primitives().len())
+                    .filter(|&i| {
+                        let other = set.get(i);
+                        (i != j || p.constant.is_some())
+                            && other.output == p.output
+                            && other.inputs == p.inputs
+                    })
+                    .collect()
+            })
+            .collect();
+
+        for tree in populations.current_mut().as_solutions_mut() {
+            for node in tree.nodes_mut() {
+                if rng.gen_bool(self.rm) {
+                    if let Some(&index) = alternatives[node.primitive].choose(&mut *rng) {
+                        *node = set.node(index, &mut *rng);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replaces each tree with probability `rm` by one of its own subtrees returning the root type,
+/// which reduces the size of the tree.
+///
+/// # Errors
+///
+/// Returns an `Err` on construction if `rm` is not in `[0, 1]`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HoistMutation {
+    /// Mutation rate, i.e. the probability of mutating a tree.
+    pub rm: f64,
+}
+
+impl HoistMutation {
+    pub fn from_params(rm: f64) -> ExecResult<Self> {
+        ensure!((0.0..=1.0).contains(&rm), "`rm` must be in [0, 1]");
+        Ok(Self { rm })
+    }
+
+    pub fn new<P: TreeProblem>(rm: f64) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(rm)?))
+    }
+}
+
+impl<P: TreeProblem> Component<P> for HoistMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let set = problem.primitives();
+
+        for tree in populations.current_mut().as_solutions_mut() {
+            if !rng.gen_bool(self.rm) {
+                continue;
+            }
+            let candidates: Vec<_> = (1..tree.len())
+                .filter(|&i| output(set, tree, i) == set.root)
+                .collect();
+            if let Some(&i) = candidates.choose(&mut *rng) {
+                *tree = Tree::from_nodes(tree.nodes()[tree.subtree(i)].to_vec());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Selects `num_selected` individuals using tournament selection of `size`,
+/// where ties in the objective value are broken in favor of smaller trees.
+///
+/// Every tournament consists of `size` distinct individuals, but the same individual may win
+/// multiple tournaments.
+///
+/// This applies lexicographic parsimony pressure \[2\] to control bloat.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ParsimonyTournament {
+    /// Number of selected individuals.
+    pub num_selected: u32,
+    /// Tournament size.
+    pub size: u32,
+}
+
+impl ParsimonyTournament {
+    pub fn from_params(num_selected: u32, size: u32) -> Self {
+        Self { num_selected, size }
+    }
+
+    pub fn new<P>(num_selected: u32, size: u32) -> Box<dyn Component<P>>
+    where
+        P: SingleObjectiveProblem + TreeProblem,
+    {
+        Box::new(Self::from_params(num_selected, size))
+    }
+}
+
+impl<P> Selection<P> for ParsimonyTournament
+where
+    P: SingleObjectiveProblem + TreeProblem,
+{
+    fn select<'a>(
+        &self,
+        population: &'a [Individual<P>],
+        rng: &mut Random,
+    ) -> ExecResult<Vec<&'a Individual<P>>> {
+        ensure!(
+            population.len() >= self.size as usize,
+            "population size must be equal to or greater than the tournament size"
+        );
+        let selection = (0..self.num_selected)
+            .map(|_| {
+                population
+                    .choose_multiple(rng, self.size as usize)
+                    .min_by_key(|i| (*i.objective(), i.solution().len()))
+                    .unwrap()
+            })
+            .collect();
+        Ok(selection)
+    }
+}
+
+impl<P> Component<P> for ParsimonyTournament
+where
+    P: SingleObjectiveProblem + TreeProblem,
+{
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        selection(self, problem, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        problems::{
+            symbolic_regression::{Dataset, SymbolicRegression},
+            tree::Node,
+        },
+        state::common::Populations,
+        Random,
+    };
+
+    fn problem() -> SymbolicRegression {
+        let csv = "a,b,y\n0,1,1\n1,2,3\n";
+        SymbolicRegression::new(Dataset::from_csv(csv.as_bytes()).unwrap(), 4)
+    }
+
+    fn node(problem: &SymbolicRegression, name: &str) -> Node {
+        let set = problem.primitives();
+        let index = set
+            .primitives()
+            .iter()
+            .position(|p| p.name == name)
+            .unwrap();
+        set.node(index, &mut Random::new(0))
+    }
+
+    fn state(trees: Vec<Tree>) -> State<'static, SymbolicRegression> {
+        let mut state = State::new();
+        state.insert(Random::new(0));
+        state.insert(Populations::<SymbolicRegression>::new());
+        state.populations_mut().push(trees.into_individuals());
+        state
+    }
+
+    #[test]
+    fn point_mutation_changes_primitives() {
+        let problem = problem();
+        let tree = Tree::from_nodes(vec![node(&problem, "sin"), node(&problem, "a")]);
+        let mut state = state(vec![tree; 20]);
+        PointMutation::new(1.)
+            .unwrap()
+            .execute(&problem, &mut state)
+            .unwrap();
+
+        let set = problem.primitives();
+        for tree in state.populations_mut().current_mut().as_solutions_mut() {
+            assert_eq!(set.get(tree.nodes()[0].primitive).name, "cos");
+            assert_ne!(set.get(tree.nodes()[1].primitive).name, "a");
+        }
+    }
+
+    #[test]
+    fn parsimony_tournament_prefers_smaller_trees() {
+        let problem = problem();
+        let small = Tree::from_nodes(vec![node(&problem, "a")]);
+        let large = Tree::from_nodes(vec![node(&problem, "sin"), node(&problem, "a")]);
+        let mut population: Vec<Individual<SymbolicRegression>> =
+            vec![large, small].into_individuals();
+        for individual in &mut population {
+            individual.set_objective(1.0.try_into().unwrap());
+        }
+
+        let selected = ParsimonyTournament::from_params(5, 2)
+            .select(&population, &mut Random::new(0))
+            .unwrap();
+        assert!(selected.iter().all(|i| i.solution().len() == 1));
+    }
+
+    #[test]
+    fn rates_are_validated() {
+        assert!(SubtreeCrossover::from_params(1.5, true).is_err());
+        assert!(SubtreeMutation::from_params(-0.1, 2).is_err());
+        assert!(PointMutation::from_params(f64::NAN).is_err());
+        assert!(HoistMutation::from_params(2.).is_err());
+        assert!(PointMutation::from_params(0.5).is_ok());
+    }
+
+    fn ramped(
+        problem: &SymbolicRegression,
+        n: u32,
+        min_depth: usize,
+        max_depth: usize,
+    ) -> Vec<Tree> {
+        RampedHalfAndHalf::from_params(n, min_depth, max_depth)
+            .unwrap()
+            .initialize(problem, &mut Random::new(0))
+    }
+
+    fn trees(state: &State<SymbolicRegression>) -> Vec<Tree> {
+        state
+            .populations()
+            .current()
+            .iter()
+            .map(|i| i.solution().clone())
+            .collect()
+    }
+
+    #[test]
+    fn ramped_half_and_half_ramps_depths() {
+        let problem = problem();
+        let trees = ramped(&problem, 12, 1, 3);
+        assert_eq!(trees.len(), 12);
+        for (k, tree) in trees.iter().enumerate() {
+            let depth = 1 + (k / 2) % 3;
+            if k % 2 == 0 {
+                // Full method.
+                assert_eq!(tree.depth(), depth);
+            } else {
+                // Grow method.
+                assert!(tree.depth() <= depth);
+            }
+        }
+    }
+
+    #[test]
+    fn ramped_half_and_half_respects_depth_limit() {
+        let problem = problem();
+        let trees = ramped(&problem, 20, 2, 10);
+        assert!(trees.iter().all(|tree| tree.depth() <= problem.max_depth()));
+        assert!(trees.iter().any(|tree| tree.depth() == problem.max_depth()));
+    }
+
+    #[test]
+    fn subtree_crossover_respects_depth_limit() {
+        let problem = problem();
+        let initial = ramped(&problem, 40, 2, 4);
+        let mut state = state(initial.clone());
+        let crossover = SubtreeCrossover::from_params(1., true).unwrap();
+
+        for _ in 0..10 {
+            crossover.execute(&problem, &mut state).unwrap();
+            let trees = trees(&state);
+            assert_eq!(trees.len(), 40);
+            assert!(trees.iter().all(|tree| tree.depth() <= problem.max_depth()));
+        }
+        assert_ne!(trees(&state), initial);
+    }
+
+    #[test]
+    fn subtree_mutation_respects_depth_limit() {
+        let problem = problem();
+        let initial = ramped(&problem, 40, 2, 4);
+        let mut state = state(initial.clone());
+        let mutation = SubtreeMutation::from_params(1., 4).unwrap();
+
+        for _ in 0..10 {
+            mutation.execute(&problem, &mut state).unwrap();
+            assert!(trees(&state)
+                .iter()
+                .all(|tree| tree.depth() <= problem.max_depth()));
+        }
+        assert_ne!(trees(&state), initial);
+    }
+
+    #[test]
+    fn hoist_mutation_replaces_tree_by_subtree() {
+        let problem = problem();
+        let tree = Tree::from_nodes(vec![node(&problem, "sin"), node(&problem, "a")]);
+        let mut state = state(vec![tree.clone(); 5]);
+
+        HoistMutation::from_params(0.)
+            .unwrap()
+            .execute(&problem, &mut state)
+            .unwrap();
+        assert!(trees(&state).iter().all(|t| t == &tree));
+
+        HoistMutation::from_params(1.)
+            .unwrap()
+            .execute(&problem, &mut state)
+            .unwrap();
+        let hoisted = Tree::from_nodes(vec![node(&problem, "a")]);
+        assert!(trees(&state).iter().all(|t| t == &hoisted));
+    }
+}
@@ -1,6 +1,7 @@
 //! Common initialization components.
 
-use rand::distributions::uniform::SampleUniform;
+use eyre::ensure;
+use rand::{distributions::uniform::SampleUniform, seq::IteratorRandom, Rng};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -9,7 +10,10 @@ use crate::{
         initialization::{functional as f, initialization, Initialization},
         Component,
     },
-    problems::{LimitedVectorProblem, VectorProblem},
+    problems::{
+        mixed, LimitedVectorProblem, MixedVectorProblem, SubsetProblem, VariableLengthProblem,
+        VectorProblem,
+    },
     state::random::Random,
     Problem, State,
 };
@@ -165,3 +169,234 @@ where
         initialization(self, problem, state)
     }
 }
+
+/// Generates uniformly distributed solutions within the domain of every [`MixedVariable`].
+///
+/// # Errors
+///
+/// Returns an `Err` on initialization if the domain of any [`MixedVariable`] is empty.
+///
+/// [`MixedVariable`]: crate::problems::MixedVariable
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RandomMixed {
+    /// Size of the population to be generated.
+    pub population_size: u32,
+}
+
+impl RandomMixed {
+    pub fn from_params(population_size: u32) -> Self {
+        Self { population_size }
+    }
+
+    pub fn new<P>(population_size: u32) -> Box<dyn Component<P>>
+    where
+        P: MixedVectorProblem,
+    {
+        Box::new(Self::from_params(population_size))
+    }
+}
+
+impl<P> Initialization<P> for RandomMixed
+where
+    P: MixedVectorProblem,
+{
+    fn initialize(&self, problem: &P, rng: &mut Random) -> Vec<P::Encoding> {
+        let variables = problem.variables();
+        (0..self.population_size)
+            .map(|_| variables.iter().map(|v| v.sample(rng)).collect())
+            .collect()
+    }
+}
+
+impl<P> Component<P> for RandomMixed
+where
+    P: MixedVectorProblem,
+{
+    fn init(&self, problem: &P, _state: &mut State<P>) -> ExecResult<()> {
+        mixed::validate_variables(&problem.variables())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        initialization(self, problem, state)
+    }
+}
+
+/// Generates solutions with a length uniformly sampled from [`VariableLengthProblem::lengths`]
+/// and random elements.
+///
+/// # Errors
+///
+/// Returns an `Err` on initialization if [`VariableLengthProblem::lengths`] is empty.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RandomLength {
+    /// Size of the population to be generated.
+    pub population_size: u32,
+}
+
+impl RandomLength {
+    pub fn from_params(population_size: u32) -> Self {
+        Self { population_size }
+    }
+
+    pub fn new<P>(population_size: u32) -> Box<dyn Component<P>>
+    where
+        P: VariableLengthProblem,
+    {
+        Box::new(Self::from_params(population_size))
+    }
+}
+
+impl<P> Initialization<P> for RandomLength
+where
+    P: VariableLengthProblem,
+{
+    fn initialize(&self, problem: &P, rng: &mut Random) -> Vec<P::Encoding> {
+        (0..self.population_size)
+            .map(|_| {
+                let length = rng.gen_range(problem.lengths());
+                (0..length).map(|_| problem.random_element(rng)).collect()
+            })
+            .collect()
+    }
+}
+
+impl<P> Component<P> for RandomLength
+where
+    P: VariableLengthProblem,
+{
+    fn init(&self, problem: &P, _state: &mut State<P>) -> ExecResult<()> {
+        ensure!(
+            !problem.lengths().is_empty(),
+            "the range of solution lengths must not be empty"
+        );
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        initialization(self, problem, state)
+    }
+}
+
+/// Generates random subsets with a size uniformly sampled from [`SubsetProblem::sizes`].
+///
+/// # Errors
+///
+/// Returns an `Err` on initialization if [`SubsetProblem::sizes`] is empty or exceeds
+/// [`SubsetProblem::items`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RandomSubset {
+    /// Size of the population to be generated.
+    pub population_size: u32,
+}
+
+impl RandomSubset {
+    pub fn from_params(population_size: u32) -> Self {
+        Self { population_size }
+    }
+
+    pub fn new<P>(population_size: u32) -> Box<dyn Component<P>>
+    where
+        P: SubsetProblem,
+    {
+        Box::new(Self::from_params(population_size))
+    }
+}
+
+impl<P> Initialization<P> for RandomSubset
+where
+    P: SubsetProblem,
+{
+    fn initialize(&self, problem: &P, rng: &mut Random) -> Vec<P::Encoding> {
+        (0..self.population_size)
+            .map(|_| {
+                let size = rng.gen_range(problem.sizes());
+                (0..problem.items())
+                    .choose_multiple(rng, size)
+                    .into_iter()
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<P> Component<P> for RandomSubset
+where
+    P: SubsetProblem,
+{
+    fn init(&self, problem: &P, _state: &mut State<P>) -> ExecResult<()> {
+        let sizes = problem.sizes();
+        ensure!(
+            !sizes.is_empty(),
+            "the range of subset sizes must not be empty"
+        );
+        ensure!(
+            *sizes.end() <= problem.items(),
+            "the subset sizes must not exceed the number of items"
+        );
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        initialization(self, problem, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use super::*;
+    use crate::testing::*;
+
+    fn initialize<P: Problem>(
+        component: Box<dyn Component<P>>,
+        problem: &P,
+    ) -> ExecResult<Vec<P::Encoding>> {
+        let mut state = test_state(Vec::new());
+        state.populations_mut().pop();
+        component.init(problem, &mut state)?;
+        component.execute(problem, &mut state)?;
+        let population = state.populations_mut().pop();
+        Ok(population.into_iter().map(|i| i.into_solution()).collect())
+    }
+
+    #[test]
+    fn random_length_samples_lengths_and_elements() {
+        let problem = VariableLengthTestProblem(2..=4);
+        let solutions = initialize(RandomLength::new(50), &problem).unwrap();
+        assert_eq!(solutions.len(), 50);
+        assert!(solutions
+            .iter()
+            .all(|s| (2..=4).contains(&s.len()) && s.iter().all(|x| (0..10).contains(x))));
+        for length in 2..=4 {
+            assert!(solutions.iter().any(|s| s.len() == length));
+        }
+    }
+
+    #[test]
+    fn random_length_rejects_empty_lengths() {
+        let problem = VariableLengthTestProblem(RangeInclusive::new(3, 2));
+        assert!(initialize(RandomLength::new(1), &problem).is_err());
+    }
+
+    #[test]
+    fn random_subset_samples_sizes_and_items() {
+        let problem = SubsetTestProblem(5, 1..=3);
+        let subsets = initialize(RandomSubset::new(50), &problem).unwrap();
+        assert_eq!(subsets.len(), 50);
+        assert!(subsets
+            .iter()
+            .all(|s| (1..=3).contains(&s.len()) && s.iter().all(|&i| i < 5)));
+        for size in 1..=3 {
+            assert!(subsets.iter().any(|s| s.len() == size));
+        }
+    }
+
+    #[test]
+    fn random_subset_rejects_invalid_sizes() {
+        let problem = SubsetTestProblem(5, RangeInclusive::new(3, 2));
+        assert!(initialize(RandomSubset::new(1), &problem).is_err());
+        let problem = SubsetTestProblem(5, 0..=6);
+        assert!(initialize(RandomSubset::new(1), &problem).is_err());
+    }
+}
@@ -9,7 +9,10 @@ use crate::{
 pub mod common;
 pub mod functional;
 
-pub use common::{Empty, RandomBitstring, RandomPermutation, RandomSpread};
+pub use common::{
+    Empty, RandomBitstring, RandomLength, RandomMixed, RandomPermutation, RandomSpread,
+    RandomSubset,
+};
 
 use crate::population::IntoIndividuals;
 
@@ -0,0 +1,904 @@
+//! Island models with migration between sub-populations.
+//!
+//! An island model splits the population into several sub-populations (*islands*), which
+//! evolve independently, and periodically exchanges individuals between them (*migration*).
+//!
+//! The [`IslandModel`] and [`ParallelIslandModel`] execute a body on every island, where each
+//! island has its own nested [`State`], which is preserved across executions.
+//! The [`Migration`] component exchanges individuals between the islands according to
+//! some [`Topology`].
+//!
+//! # Population layout
+//!
+//! Outside the island model, the current population is the concatenation of all islands.
+//! The sizes of the islands are stored in [`IslandSizes`].
+//! This allows to use components like [`BestIndividualUpdate`] on the whole population.
+//!
+//! [`BestIndividualUpdate`]: crate::components::evaluation::BestIndividualUpdate
+//!
+//! # References
+//!
+//! \[1\] Dirk Sudholt. 2015.
+//! Parallel Evolutionary Algorithms.
+//! In Springer Handbook of Computational Intelligence, 929–959.
+//! DOI:<https://doi.org/10.1007/978-3-662-43505-2_46>
+
+use std::ops::Range;
+
+use better_any::{Tid, TidAble};
+use derivative::Derivative;
+use derive_more::{Deref, DerefMut};
+use eyre::{ensure, ContextCompat};
+use itertools::Itertools;
+use rand::{seq::index, Rng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::ExecResult,
+    components::Component,
+    configuration::{
+        tree::{Edge, EdgeMut},
+        validation,
+    },
+    problems::SingleObjectiveProblem,
+    state::{common, random::Random, StateReq},
+    CustomState, Individual, Problem, State,
+};
+
+/// The sizes of the islands of an [`IslandModel`] or [`ParallelIslandModel`].
+///
+/// The current population is the concatenation of all islands in order.
+#[derive(Clone, Default, Deref, DerefMut, Serialize, Tid)]
+pub struct IslandSizes(pub Vec<usize>);
+
+impl IslandSizes {
+    /// Returns the range of each island within the current population.
+    pub fn ranges(&self) -> Vec<Range<usize>> {
+        let mut start = 0;
+        self.iter()
+            .map(|&size| {
+                let range = start..start + size;
+                start += size;
+                range
+            })
+            .collect()
+    }
+}
+
+impl CustomState<'_> for IslandSizes {}
+
+/// The nested [`State`]s of the islands of an [`IslandModel`] or [`ParallelIslandModel`].
+#[derive(Deref, DerefMut, Tid)]
+pub struct IslandStates<'a, P: Problem + 'static>(Vec<State<'a, P>>);
+
+impl<P: Problem> Default for IslandStates<'_, P> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<'a, P: Problem> CustomState<'a> for IslandStates<'a, P> {}
+
+/// Splits a population of size `len` into `n` contiguous parts whose sizes differ by at most one.
+fn split_evenly(len: usize, n: usize) -> Vec<usize> {
+    let (base, rest) = (len / n, len % n);
+    (0..n).map(|i| base + usize::from(i < rest)).collect()
+}
+
+/// Returns the sizes of `n` islands for a population of size `len`.
+///
+/// The previous `sizes` are kept if they still add up to `len`, otherwise the population
+/// is split evenly.
+fn island_sizes(sizes: &[usize], len: usize, n: usize) -> ExecResult<Vec<usize>> {
+    if sizes.len() == n && sizes.iter().sum::<usize>() == len {
+        return Ok(sizes.to_vec());
+    }
+    ensure!(
+        len >= n,
+        "the population is smaller than the number of islands"
+    );
+    Ok(split_evenly(len, n))
+}
+
+/// Splits the `population` into contiguous parts of `sizes`.
+fn split_by_sizes<P: Problem>(
+    population: Vec<Individual<P>>,
+    sizes: &[usize],
+) -> Vec<Vec<Individual<P>>> {
+    let mut population = population.into_iter();
+    sizes
+        .iter()
+        .map(|&size| population.by_ref().take(size).collect())
+        .collect()
+}
+
+/// Returns the evaluations counted in the top-most registry of the `state`.
+fn local_evaluations<P: Problem>(state: &State<P>) -> u32 {
+    if state.contains_at_top::<common::Evaluations>() {
+        state.get_value::<common::Evaluations>()
+    } else {
+        0
+    }
+}
+
+/// Initializes a new island with its own `rng` and `body`.
+fn init_island<P: Problem>(
+    body: &dyn Component<P>,
+    problem: &P,
+    island: &mut State<P>,
+    rng: Random,
+) -> ExecResult<()> {
+    island.insert(rng);
+    island.insert(common::Populations::<P>::new());
+    body.init(problem, island)?;
+    body.require(problem, &island.requirements())?;
+    Ok(())
+}
+
+/// Executes the `body` on an island with the given `population`, and returns the resulting
+/// population and the number of evaluations performed locally.
+fn execute_island<P: Problem>(
+    body: &dyn Component<P>,
+    problem: &P,
+    island: &mut State<P>,
+    population: Vec<Individual<P>>,
+) -> ExecResult<(Vec<Individual<P>>, u32)> {
+    let evaluations = local_evaluations(island);
+    island.populations_mut().push(population);
+    body.execute(problem, island)?;
+    let population = island
+        .populations_mut()
+        .try_pop()
+        .wrap_err("the island body removed the island population")?;
+    Ok((population, local_evaluations(island) - evaluations))
+}
+
+/// Adds the locally performed `evaluations` of the islands to the [`Evaluations`] of the `state`.
+///
+/// [`Evaluations`]: common::Evaluations
+fn count_evaluations<P: Problem>(state: &mut State<P>, evaluations: u32) {
+    if evaluations > 0 && state.contains::<common::Evaluations>() {
+        *state.borrow_value_mut::<common::Evaluations>() += evaluations;
+    }
+}
+
+/// Runs the `body` on `islands` sub-populations sequentially, each in its own nested [`State`].
+///
+/// On the first execution, the current population is split into `islands` contiguous
+/// sub-populations of (almost) equal size.
+/// The islands keep their sizes across executions unless the size of the current population
+/// changed in between, e.g. by components outside of the island model, in which case the
+/// population is split evenly again.
+/// Every island gets its own child [`Random`] generator, and the `body` is initialized once
+/// for each island, such that its state (e.g. an island-local [`BestIndividual`]) is preserved
+/// across executions.
+/// Note that an inner [`Loop`] does not reset its [`Iterations`] between executions.
+///
+/// As the island states are nested within the parent state, the `body` has access to all state
+/// of the parent, e.g. the evaluator.
+/// Evaluations performed by an island-local [`PopulationEvaluator`] are also added to the
+/// [`Evaluations`] of the parent.
+///
+/// As the `body` is only initialized on the first execution, it is validated on a simulated
+/// island state during [`Configuration::validate`].
+///
+/// See the [module documentation] for more information.
+///
+/// [`Configuration::validate`]: crate::Configuration::validate
+/// [`BestIndividual`]: common::BestIndividual
+/// [`Iterations`]: common::Iterations
+/// [`Evaluations`]: common::Evaluations
+/// [`Loop`]: crate::components::Loop
+/// [`PopulationEvaluator`]: crate::components::evaluation::PopulationEvaluator
+/// [module documentation]: crate::components::islands
+///
+/// # Examples
+///
+/// Running some `generation` on each of four islands, migrating the best two individuals
+/// of each island along a ring every ten iterations:
+///
+/// ```
+/// # use mahf::{Component, Configuration, ExecResult, SingleObjectiveProblem};
+/// use mahf::{
+///     components::islands::{
+///         EmigrantPolicy, ImmigrantPolicy, IslandModel, Migration, Topology,
+///     },
+///     conditions::LessThanN,
+/// };
+///
+/// # fn example<P: SingleObjectiveProblem>(generation: Box<dyn Component<P>>) -> ExecResult<Box<dyn Component<P>>> {
+/// let islands = IslandModel::new(4, generation)?;
+/// let migration = Migration::new(
+///     Topology::Ring,
+///     10,
+///     2,
+///     EmigrantPolicy::Best,
+///     ImmigrantPolicy::Worst,
+/// )?;
+///
+/// # Ok(
+/// Configuration::builder()
+///     .while_(LessThanN::iterations(100), |builder| {
+///         builder
+///             .do_(islands)
+///             .update_best_individual()
+///             .do_(migration)
+///     })
+///     .build_component()
+/// # )
+/// # }
+/// ```
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct IslandModel<P: Problem> {
+    /// The number of islands.
+    pub islands: usize,
+    body: Box<dyn Component<P>>,
+}
+
+impl<P: Problem> IslandModel<P> {
+    pub fn from_params(islands: usize, body: Box<dyn Component<P>>) -> ExecResult<Self> {
+        ensure!(islands > 0, "`islands` must be greater than 0");
+        Ok(Self { islands, body })
+    }
+
+    pub fn new(islands: usize, body: Box<dyn Component<P>>) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(islands, body)?))
+    }
+}
+
+impl<P: Problem> Component<P> for IslandModel<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(IslandSizes::default());
+        state.insert(IslandStates::<P>::default());
+        validation::validate_isolated(state, problem, self.children(), |island| {
+            island.insert(common::Populations::<P>::new());
+            Ok(())
+        })
+    }
+
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, common::Populations<P>>()?;
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let population = state.populations_mut().pop();
+
+        state.holding::<IslandStates<P>>(|islands, state| {
+            let sizes = island_sizes(
+                &state.borrow::<IslandSizes>(),
+                population.len(),
+                self.islands,
+            )?;
+            if islands.is_empty() {
+                let rngs: Vec<_> = state
+                    .random_mut()
+                    .iter_children()
+                    .take(self.islands)
+                    .collect();
+                for rng in rngs {
+                    let island = state.with_inner_state(|island| {
+                        init_island(self.body.as_ref(), problem, island, rng)
+                    })?;
+                    islands.push(island);
+                }
+            }
+
+            let subpopulations = split_by_sizes(population, &sizes);
+
+            let mut results = Vec::with_capacity(self.islands);
+            let mut evaluations = 0;
+            for (island, subpopulation) in islands.iter_mut().zip(subpopulations) {
+                let inner = std::mem::take(island);
+                *island = state.with_existing_inner_state(inner, |island| {
+                    let (population, n) =
+                        execute_island(self.body.as_ref(), problem, island, subpopulation)?;
+                    results.push(population);
+                    evaluations += n;
+                    Ok(())
+                })?;
+            }
+
+            count_evaluations(state, evaluations);
+            *state.borrow_mut::<IslandSizes>() =
+                IslandSizes(results.iter().map(Vec::len).collect());
+            state.populations_mut().push(results.concat());
+            Ok(())
+        })
+    }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        vec![Edge::component("IslandModel.body", self.body.as_ref())]
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        vec![EdgeMut::component("IslandModel.body", &mut self.body)]
+    }
+}
+
+/// Runs the `body` on `islands` sub-populations in parallel, each in its own [`State`].
+///
+/// This is the parallel version of the [`IslandModel`], and behaves the same,
+/// except that the island states are **not** nested within the parent state.
+/// Therefore, all state required by the `body` (e.g. an evaluator) has to be inserted
+/// into each island state using `state_init`.
+/// During [`Configuration::validate`], the `body` is validated on a simulated island state
+/// initialized using `state_init`.
+///
+/// [`Configuration::validate`]: crate::Configuration::validate
+///
+/// Execution is parallelized using [`rayon`], and reproducible through the child [`Random`]
+/// generator of each island.
+///
+/// # Examples
+///
+/// Inserting a [`Sequential`] evaluator into each island:
+///
+/// [`Sequential`]: crate::problems::Sequential
+///
+/// ```
+/// # use mahf::{Component, ExecResult, SingleObjectiveProblem, problems::ObjectiveFunction};
+/// use mahf::{components::islands::ParallelIslandModel, problems::Sequential};
+///
+/// # fn example<P: SingleObjectiveProblem + ObjectiveFunction + Sync>(body: Box<dyn Component<P>>) -> ExecResult<Box<dyn Component<P>>> {
+/// # Ok(
+/// ParallelIslandModel::new(
+///     4,
+///     |state| {
+///         state.insert_evaluator(Sequential::new());
+///         Ok(())
+///     },
+///     body,
+/// )?
+/// # )
+/// # }
+/// ```
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct ParallelIslandModel<P: Problem> {
+    /// The number of islands.
+    pub islands: usize,
+    #[serde(skip)]
+    state_init: fn(&mut State<P>) -> ExecResult<()>,
+    body: Box<dyn Component<P>>,
+}
+
+impl<P: Problem + Sync> ParallelIslandModel<P> {
+    pub fn from_params(
+        islands: usize,
+        state_init: fn(&mut State<P>) -> ExecResult<()>,
+        body: Box<dyn Component<P>>,
+    ) -> ExecResult<Self> {
+        ensure!(islands > 0, "`islands` must be greater than 0");
+        Ok(Self {
+            islands,
+            state_init,
+            body,
+        })
+    }
+
+    pub fn new(
+        islands: usize,
+        state_init: fn(&mut State<P>) -> ExecResult<()>,
+        body: Box<dyn Component<P>>,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(islands, state_init, body)?))
+    }
+}
+
+impl<P: Problem + Sync> Component<P> for ParallelIslandModel<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(IslandSizes::default());
+        state.insert(IslandStates::<P>::default());
+        validation::validate_isolated(state, problem, self.children(), |island| {
+            (self.state_init)(island)?;
+            island.insert(common::Populations::<P>::new());
+            Ok(())
+        })
+    }
+
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, common::Populations<P>>()?;
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let population = state.populations_mut().pop();
+
+        state.holding::<IslandStates<P>>(|islands, state| {
+            let sizes = island_sizes(
+                &state.borrow::<IslandSizes>(),
+                population.len(),
+                self.islands,
+            )?;
+            if islands.is_empty() {
+                let rngs: Vec<_> = state
+                    .random_mut()
+                    .iter_children()
+                    .take(self.islands)
+                    .collect();
+                for rng in rngs {
+                    let mut island = State::new();
+                    (self.state_init)(&mut island)?;
+                    init_island(self.body.as_ref(), problem, &mut island, rng)?;
+                    islands.push(island);
+                }
+            }
+
+            let subpopulations = split_by_sizes(population, &sizes);
+
+            let results: Vec<_> = islands
+                .par_iter_mut()
+                .zip(subpopulations)
+                .map(|(island, subpopulation)| {
+                    execute_island(self.body.as_ref(), problem, island, subpopulation)
+                })
+                .collect::<ExecResult<_>>()?;
+            let (results, evaluations): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+
+            count_evaluations(state, evaluations.into_iter().sum());
+            *state.borrow_mut::<IslandSizes>() =
+                IslandSizes(results.iter().map(Vec::len).collect());
+            state.populations_mut().push(results.concat());
+            Ok(())
+        })
+    }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        vec![Edge::component(
+            "ParallelIslandModel.body",
+            self.body.as_ref(),
+        )]
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        vec![EdgeMut::component(
+            "ParallelIslandModel.body",
+            &mut self.body,
+        )]
+    }
+}
+
+/// The topology defining which islands exchange individuals during [`Migration`].
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Topology {
+    /// Island `i` sends emigrants to island `i + 1`, and the last island to the first.
+    Ring,
+    /// Every island sends emigrants to every other island.
+    FullyConnected,
+    /// Every island sends emigrants to a uniformly random other island.
+    Random,
+}
+
+impl Topology {
+    /// Returns the destinations of the emigrants of each of `n` islands.
+    pub fn destinations(&self, n: usize, rng: &mut impl Rng) -> Vec<Vec<usize>> {
+        if n < 2 {
+            return vec![Vec::new(); n];
+        }
+        match self {
+            Topology::Ring => (0..n).map(|i| vec![(i + 1) % n]).collect(),
+            Topology::FullyConnected => (0..n)
+                .map(|i| (0..n).filter(|&j| j != i).collect())
+                .collect(),
+            Topology::Random => (0..n)
+                .map(|i| {
+                    let j = rng.gen_range(0..n - 1);
+                    vec![if j >= i { j + 1 } else { j }]
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The policy for selecting the emigrants of an island during [`Migration`].
+///
+/// Emigrants are copied, i.e. they remain on their source island.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EmigrantPolicy {
+    /// The best individuals emigrate.
+    Best,
+    /// Uniformly random individuals emigrate.
+    Random,
+}
+
+/// The policy for selecting the individuals replaced by immigrants during [`Migration`].
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ImmigrantPolicy {
+    /// Immigrants replace the worst individuals.
+    Worst,
+    /// Immigrants replace uniformly random individuals.
+    Random,
+}
+
+/// Returns the indices of the `n` individuals of the `island` with the lowest objective value,
+/// or with the highest objective value if `worst` is `true`.
+fn ranked_indices<P: SingleObjectiveProblem>(
+    island: &[Individual<P>],
+    n: usize,
+    worst: bool,
+) -> Vec<usize> {
+    let ranked = (0..island.len()).sorted_by_key(|&i| island[i].objective());
+    if worst {
+        ranked.rev().take(n).collect()
+    } else {
+        ranked.take(n).collect()
+    }
+}
+
+/// Exchanges individuals between the islands of an [`IslandModel`] or [`ParallelIslandModel`].
+///
+/// Every `interval` [`Iterations`], each island sends `migrants` emigrants, selected according
+/// to the [`EmigrantPolicy`], to the islands defined by the [`Topology`].
+/// The immigrants replace individuals on the destination island selected according to the
+/// [`ImmigrantPolicy`].
+///
+/// Emigrants are selected from all islands before any immigrants are inserted.
+///
+/// Note that the [`Iterations`] are incremented after the body of a [`Loop`], i.e. migration
+/// takes place in the iterations `interval - 1`, `2 * interval - 1`, and so on.
+///
+/// [`Iterations`]: common::Iterations
+/// [`Loop`]: crate::components::Loop
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Migration {
+    /// The migration topology.
+    pub topology: Topology,
+    /// The number of iterations between migrations.
+    pub interval: u32,
+    /// The number of emigrants each island sends to each destination.
+    pub migrants: usize,
+    /// The emigrant selection policy.
+    pub emigrants: EmigrantPolicy,
+    /// The immigrant replacement policy.
+    pub immigrants: ImmigrantPolicy,
+}
+
+impl Migration {
+    pub fn from_params(
+        topology: Topology,
+        interval: u32,
+        migrants: usize,
+        emigrants: EmigrantPolicy,
+        immigrants: ImmigrantPolicy,
+    ) -> ExecResult<Self> {
+        ensure!(interval > 0, "`interval` must be greater than 0");
+        Ok(Self {
+            topology,
+            interval,
+            migrants,
+            emigrants,
+            immigrants,
+        })
+    }
+
+    pub fn new<P: SingleObjectiveProblem>(
+        topology: Topology,
+        interval: u32,
+        migrants: usize,
+        emigrants: EmigrantPolicy,
+        immigrants: ImmigrantPolicy,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(
+            topology, interval, migrants, emigrants, immigrants,
+        )?))
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for Migration {
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, IslandSizes>()?;
+        state_req.require::<Self, common::Iterations>()?;
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let iterations = state.try_get_value::<common::Iterations>()?;
+        if (iterations + 1) % self.interval != 0 {
+            return Ok(());
+        }
+
+        let ranges = state.try_borrow::<IslandSizes>()?.ranges();
+        let mut populations = state.populations_mut();
+        let population = populations.current_mut();
+        ensure!(
+            ranges.last().map_or(0, |r| r.end) == population.len(),
+            "the population size does not match the island sizes"
+        );
+
+        let mut rng = state.random_mut();
+        let destinations = self.topology.destinations(ranges.len(), &mut *rng);
+
+        let mut incoming: Vec<Vec<Individual<P>>> = vec![Vec::new(); ranges.len()];
+        for (range, targets) in ranges.iter().zip(&destinations) {
+            let island = &population[range.clone()];
+            ensure!(
+                self.migrants <= island.len(),
+                "an island has fewer individuals than `migrants`"
+            );
+            for &target in targets {
+                let selected = match self.emigrants {
+                    EmigrantPolicy::Best => ranked_indices(island, self.migrants, false),
+                    EmigrantPolicy::Random => {
+                        index::sample(&mut *rng, island.len(), self.migrants).into_vec()
+                    }
+                };
+                incoming[target].extend(selected.into_iter().map(|i| island[i].clone()));
+            }
+        }
+
+        for (range, immigrants) in ranges.into_iter().zip(incoming) {
+            let island = &mut population[range];
+            ensure!(
+                immigrants.len() <= island.len(),
+                "an island receives more immigrants than it has individuals"
+            );
+            let replaced = match self.immigrants {
+                ImmigrantPolicy::Worst => ranked_indices(island, immigrants.len(), true),
+                ImmigrantPolicy::Random => {
+                    index::sample(&mut *rng, island.len(), immigrants.len()).into_vec()
+                }
+            };
+            for (i, immigrant) in replaced.into_iter().zip(immigrants) {
+                island[i] = immigrant;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{
+        components::{evaluation::PopulationEvaluator, mutation::CreepMutation},
+        problems::Sequential,
+        testing::*,
+        Configuration,
+    };
+
+    /// Removes the last individual of the current population.
+    #[derive(Clone, Serialize)]
+    struct DropLast;
+
+    impl<P: Problem> Component<P> for DropLast {
+        fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+            state.populations_mut().current_mut().pop();
+            Ok(())
+        }
+    }
+
+    fn problem() -> IntegerTestProblem {
+        IntegerTestProblem(vec![0..10; 2])
+    }
+
+    fn state(n: i64) -> State<'static, IntegerTestProblem> {
+        integer_test_state((0..n).map(|i| vec![i, 0]).collect())
+    }
+
+    fn first_elements(state: &State<IntegerTestProblem>) -> Vec<i64> {
+        state
+            .populations()
+            .current()
+            .iter()
+            .map(|i| i.solution()[0])
+            .collect()
+    }
+
+    fn mutate_and_evaluate() -> Box<dyn Component<IntegerTestProblem>> {
+        Configuration::builder()
+            .do_(CreepMutation::new(3, 1.))
+            .do_(PopulationEvaluator::new())
+            .build_component()
+    }
+
+    fn objectives(state: &State<SingleObjectiveTestProblem>) -> Vec<f64> {
+        state
+            .populations()
+            .current()
+            .iter()
+            .map(|i| i.objective().value())
+            .collect()
+    }
+
+    fn migration_state(
+        objectives: &[f64],
+        sizes: Vec<usize>,
+        iterations: u32,
+    ) -> State<'static, SingleObjectiveTestProblem> {
+        let mut state = State::new();
+        state.insert(Random::new(0));
+        state.insert(common::Populations::<SingleObjectiveTestProblem>::new());
+        state
+            .populations_mut()
+            .push(single_test_population(objectives));
+        state.insert(IslandSizes(sizes));
+        state.insert(common::Iterations(iterations));
+        state
+    }
+
+    #[test]
+    fn island_sizes_are_kept_or_split_evenly() {
+        assert_eq!(island_sizes(&[], 10, 3).unwrap(), [4, 3, 3]);
+        assert_eq!(island_sizes(&[5, 1, 4], 10, 3).unwrap(), [5, 1, 4]);
+        assert_eq!(island_sizes(&[5, 1, 4], 8, 3).unwrap(), [3, 3, 2]);
+        assert!(island_sizes(&[], 2, 3).is_err());
+    }
+
+    #[test]
+    fn split_by_sizes_keeps_order() {
+        let population = integer_test_state((0..5).map(|i| vec![i]).collect())
+            .populations_mut()
+            .pop();
+        let islands = split_by_sizes(population, &[2, 0, 3]);
+        let islands: Vec<Vec<i64>> = islands
+            .iter()
+            .map(|island| island.iter().map(|i| i.solution()[0]).collect())
+            .collect();
+        assert_eq!(islands, [vec![0, 1], vec![], vec![2, 3, 4]]);
+    }
+
+    #[test]
+    fn island_model_splits_and_merges_population() {
+        let problem = problem();
+        let mut state = state(10);
+        let islands = IslandModel::from_params(3, Box::new(DropLast)).unwrap();
+        islands.init(&problem, &mut state).unwrap();
+
+        islands.execute(&problem, &mut state).unwrap();
+        assert_eq!(state.borrow::<IslandSizes>().0, [3, 2, 2]);
+        assert_eq!(first_elements(&state), [0, 1, 2, 4, 5, 7, 8]);
+
+        // The population grows outside of the island model.
+        state
+            .populations_mut()
+            .current_mut()
+            .push(Individual::new_unevaluated(vec![9, 0]));
+        islands.execute(&problem, &mut state).unwrap();
+        assert_eq!(state.borrow::<IslandSizes>().0, [2, 2, 1]);
+        assert_eq!(first_elements(&state), [0, 1, 4, 5, 8]);
+    }
+
+    #[test]
+    fn island_model_counts_evaluations() {
+        let problem = problem();
+        let mut state = state(10);
+        state.insert(common::Evaluations(0));
+        state.insert_evaluator(Sequential::new());
+        let islands = IslandModel::from_params(3, PopulationEvaluator::new()).unwrap();
+        islands.init(&problem, &mut state).unwrap();
+
+        islands.execute(&problem, &mut state).unwrap();
+        islands.execute(&problem, &mut state).unwrap();
+        assert_eq!(state.get_value::<common::Evaluations>(), 20);
+        assert!(state
+            .populations()
+            .current()
+            .iter()
+            .all(|i| i.is_evaluated()));
+    }
+
+    #[test]
+    fn parallel_island_model_is_reproducible() {
+        let run = |seed| {
+            let problem = problem();
+            let mut state = state(10);
+            state.insert(Random::new(seed));
+            state.insert(common::Evaluations(0));
+            let islands = ParallelIslandModel::from_params(
+                3,
+                |state| {
+                    state.insert_evaluator(Sequential::new());
+                    Ok(())
+                },
+                mutate_and_evaluate(),
+            )
+            .unwrap();
+            islands.init(&problem, &mut state).unwrap();
+            for _ in 0..3 {
+                islands.execute(&problem, &mut state).unwrap();
+            }
+            assert_eq!(state.get_value::<common::Evaluations>(), 30);
+            let population = state.populations_mut().pop();
+            population
+        };
+
+        let solutions = |population: Vec<Individual<IntegerTestProblem>>| {
+            population
+                .into_iter()
+                .map(|i| i.into_solution())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(solutions(run(1)), solutions(run(1)));
+        assert_ne!(solutions(run(1)), solutions(run(2)));
+    }
+
+    #[test]
+    fn topologies_define_destinations() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        assert_eq!(
+            Topology::Ring.destinations(3, &mut rng),
+            [vec![1], vec![2], vec![0]]
+        );
+        assert_eq!(
+            Topology::FullyConnected.destinations(3, &mut rng),
+            [vec![1, 2], vec![0, 2], vec![0, 1]]
+        );
+        for _ in 0..20 {
+            let destinations = Topology::Random.destinations(4, &mut rng);
+            for (i, targets) in destinations.iter().enumerate() {
+                assert_eq!(targets.len(), 1);
+                assert!(targets[0] < 4 && targets[0] != i);
+            }
+        }
+        assert_eq!(
+            Topology::Ring.destinations(1, &mut rng),
+            [Vec::<usize>::new()]
+        );
+    }
+
+    #[test]
+    fn migration_replaces_worst_with_best_emigrants() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = migration_state(&[1., 5., 3., 4.], vec![2, 2], 1);
+        let migration = Migration::from_params(
+            Topology::Ring,
+            2,
+            1,
+            EmigrantPolicy::Best,
+            ImmigrantPolicy::Worst,
+        )
+        .unwrap();
+        migration.execute(&problem, &mut state).unwrap();
+        assert_eq!(objectives(&state), [1., 3., 3., 1.]);
+    }
+
+    #[test]
+    fn migration_with_random_policies_exchanges_islands() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = migration_state(&[1., 5., 3., 4.], vec![2, 2], 0);
+        let migration = Migration::from_params(
+            Topology::Ring,
+            1,
+            2,
+            EmigrantPolicy::Random,
+            ImmigrantPolicy::Random,
+        )
+        .unwrap();
+        migration.execute(&problem, &mut state).unwrap();
+
+        let objectives = objectives(&state);
+        let island = |range: Range<usize>| {
+            objectives[range]
+                .iter()
+                .copied()
+                .sorted_by(f64::total_cmp)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(island(0..2), [3., 4.]);
+        assert_eq!(island(2..4), [1., 5.]);
+    }
+
+    #[test]
+    fn migration_waits_for_interval() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = migration_state(&[1., 5., 3., 4.], vec![2, 2], 0);
+        let migration = Migration::from_params(
+            Topology::Ring,
+            2,
+            1,
+            EmigrantPolicy::Best,
+            ImmigrantPolicy::Worst,
+        )
+        .unwrap();
+        migration.execute(&problem, &mut state).unwrap();
+        assert_eq!(objectives(&state), [1., 5., 3., 4.]);
+    }
+}
@@ -0,0 +1,434 @@
+//! Closed-loop parameter control based on feedback from the search.
+//!
+//! Contrary to the open-loop schedules in [`common`], which only depend on some input like
+//! the progress of the run, the components in this module adapt a parameter based on
+//! the success of previous steps.
+//!
+//! The success is measured by a *success lens*, i.e. any [`Lens`] with `Target = f64` that
+//! returns the fraction of successful trials in the current step (in `[0, 1]`).
+//! Common success lenses are [`ImprovedLens`] and [`SuccessRateLens`].
+//!
+//! [`common`]: crate::components::mapping::common
+//!
+//! # References
+//!
+//! \[1\] Ingo Rechenberg. 1973.
+//! Evolutionsstrategie: Optimierung technischer Systeme nach Prinzipien der biologischen Evolution.
+//! Frommann-Holzboog, Stuttgart.
+//!
+//! \[2\] Hans-Georg Beyer and Hans-Paul Schwefel. 2002.
+//! Evolution strategies – A comprehensive introduction.
+//! Natural Computing 1, 1 (March 2002), 3–52.
+//! DOI:<https://doi.org/10/djvqhd>
+
+use std::marker::PhantomData;
+
+use better_any::{Tid, TidAble};
+use derivative::Derivative;
+use eyre::ensure;
+use serde::Serialize;
+
+use crate::{
+    component::{AnyComponent, ExecResult},
+    components::{
+        measures::improvement::Improvement, mutation::MutationStrength,
+        utils::improvement::StepsWithoutImprovement, Component,
+    },
+    lens::{AnyLens, Lens, LensMap, ValueLens, ValueOf},
+    logging::extractor::EntryName,
+    utils::SerializablePhantom,
+    CustomState, Problem, State,
+};
+
+/// The accumulated success of an adaptation rule `T` within the current adaptation window.
+#[derive(Tid)]
+pub struct SuccessHistory<T: AnyComponent + 'static> {
+    /// Sum of the success rates in the current window.
+    pub successes: f64,
+    /// Number of trials in the current window.
+    pub trials: u32,
+    marker: PhantomData<T>,
+}
+
+impl<T: AnyComponent> SuccessHistory<T> {
+    /// Creates a new, empty `SuccessHistory`.
+    pub fn new() -> Self {
+        Self {
+            successes: 0.,
+            trials: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Records a step with some success `rate`.
+    pub fn record(&mut self, rate: f64) {
+        self.successes += rate;
+        self.trials += 1;
+    }
+
+    /// Returns the mean success rate in the current window, or `None` if no trials were recorded.
+    pub fn rate(&self) -> Option<f64> {
+        (self.trials > 0).then(|| self.successes / self.trials as f64)
+    }
+
+    /// Resets the history, starting a new window.
+    pub fn reset(&mut self) {
+        self.successes = 0.;
+        self.trials = 0;
+    }
+}
+
+impl<T: AnyComponent> Default for SuccessHistory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: AnyComponent> CustomState<'_> for SuccessHistory<T> {}
+
+/// Adapts the `L::Target` multiplicatively depending on the success rate measured by the `success_lens`.
+///
+/// The success rate is averaged over a window of `window` executions.
+/// At the end of each window, the value is
+/// - multiplied with `increase` if the mean success rate is greater than the `target` rate,
+/// - multiplied with `decrease` if the mean success rate is less than the `target` rate, and
+/// - left unchanged otherwise.
+///
+/// The adapted value can be any [`ValueLens<P, f64>`], e.g. a [`MutationStrength`] or
+/// [`MutationRate`].
+///
+/// [`MutationRate`]: crate::components::mutation::MutationRate
+///
+/// # State
+///
+/// The success rates of the current window are stored in [`SuccessHistory<Self>`].
+///
+/// # Examples
+///
+/// Multiplying the mutation rate of [`BitFlipMutation`] with `1.5` if the [`BestIndividual`]
+/// improved in more than 1/5th of the last 10 iterations, and with `0.8` if it improved in less:
+///
+/// [`BitFlipMutation`]: crate::components::mutation::BitFlipMutation
+/// [`BestIndividual`]: crate::state::common::BestIndividual
+///
+/// ```
+/// use mahf::{
+///     components::{
+///         mapping::adaptive::{ImprovedLens, SuccessRule},
+///         mutation,
+///         utils::improvement::StepsWithoutImprovementUpdate,
+///     },
+///     lens::ValueOf,
+/// #    Component, ExecResult, SingleObjectiveProblem, problems::VectorProblem,
+/// };
+///
+/// # fn example<P: SingleObjectiveProblem + VectorProblem<Element = bool>>() -> ExecResult<Vec<Box<dyn Component<P>>>> {
+/// # Ok(vec![
+/// StepsWithoutImprovementUpdate::new(),
+/// SuccessRule::new(
+///     0.2,
+///     1.5,
+///     0.8,
+///     10,
+///     ImprovedLens::new(),
+///     ValueOf::<mutation::MutationRate<mutation::BitFlipMutation>>::new(),
+/// )?,
+/// # ])
+/// # }
+/// ```
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct SuccessRule<S: AnyLens, L: AnyLens> {
+    /// The target success rate.
+    pub target: f64,
+    /// Factor applied if the success rate is above the `target`.
+    pub increase: f64,
+    /// Factor applied if the success rate is below the `target`.
+    pub decrease: f64,
+    /// The number of executions after which the value is adapted.
+    pub window: u32,
+    pub success_lens: S,
+    pub lens: L,
+}
+
+impl<S: AnyLens, L: AnyLens> SuccessRule<S, L> {
+    pub fn from_params(
+        target: f64,
+        increase: f64,
+        decrease: f64,
+        window: u32,
+        success_lens: S,
+        lens: L,
+    ) -> ExecResult<Self> {
+        ensure!((0.0..=1.0).contains(&target), "`target` must be in [0, 1]");
+        ensure!(
+            increase > 0. && decrease > 0.,
+            "`increase` and `decrease` must be greater than 0"
+        );
+        ensure!(window > 0, "`window` must be greater than 0");
+        Ok(Self {
+            target,
+            increase,
+            decrease,
+            window,
+            success_lens,
+            lens,
+        })
+    }
+
+    pub fn new<P>(
+        target: f64,
+        increase: f64,
+        decrease: f64,
+        window: u32,
+        success_lens: S,
+        lens: L,
+    ) -> ExecResult<Box<dyn Component<P>>>
+    where
+        P: Problem,
+        S: Lens<P, Target = f64>,
+        L: ValueLens<P, f64>,
+    {
+        Ok(Box::new(Self::from_params(
+            target,
+            increase,
+            decrease,
+            window,
+            success_lens,
+            lens,
+        )?))
+    }
+}
+
+impl<S: AnyLens, T: AnyComponent> SuccessRule<S, ValueOf<MutationStrength<T>>> {
+    /// Creates the 1/5th success rule on the [`MutationStrength`] of `T`.
+    ///
+    /// Every `window` executions, the mutation strength is divided by `c` if more than 1/5th of
+    /// the mutations were successful, and multiplied with `c` if less than 1/5th were successful.
+    ///
+    /// Values for `c` within `[0.817, 1)` are recommended in the literature.
+    pub fn one_fifth<P>(c: f64, window: u32, success_lens: S) -> ExecResult<Box<dyn Component<P>>>
+    where
+        P: Problem,
+        S: Lens<P, Target = f64>,
+    {
+        ensure!(c > 0. && c < 1., "`c` must be in (0, 1)");
+        Self::new(
+            0.2,
+            1. / c,
+            c,
+            window,
+            success_lens,
+            ValueOf::<MutationStrength<T>>::new(),
+        )
+    }
+}
+
+impl<P, S, L> Component<P> for SuccessRule<S, L>
+where
+    P: Problem,
+    S: Lens<P, Target = f64>,
+    L: ValueLens<P, f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(SuccessHistory::<Self>::new());
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let rate = self.success_lens.get(problem, state)?;
+        ensure!(
+            (0.0..=1.0).contains(&rate),
+            "success rate must be in [0, 1], but was {rate}"
+        );
+
+        let mean = {
+            let mut history = state.try_borrow_mut::<SuccessHistory<Self>>()?;
+            history.record(rate);
+            if history.trials < self.window {
+                return Ok(());
+            }
+            let mean = history.rate().unwrap_or_default();
+            history.reset();
+            mean
+        };
+
+        let value = self.lens.get(problem, state)?;
+        let adapted = if mean > self.target {
+            value * self.increase
+        } else if mean < self.target {
+            value * self.decrease
+        } else {
+            value
+        };
+        self.lens.assign(adapted, problem, state)?;
+
+        Ok(())
+    }
+}
+
+/// Lens that evaluates to `1` if the [`BestIndividual`] improved in the last step, and to `0` otherwise.
+///
+/// Requires the [`StepsWithoutImprovement`] to be updated by the [`StepsWithoutImprovementUpdate`].
+///
+/// [`BestIndividual`]: crate::state::common::BestIndividual
+/// [`StepsWithoutImprovementUpdate`]: crate::components::utils::improvement::StepsWithoutImprovementUpdate
+#[derive(Default, Clone, Serialize)]
+pub struct ImprovedLens;
+
+impl ImprovedLens {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl AnyLens for ImprovedLens {
+    type Target = f64;
+}
+
+impl EntryName for ImprovedLens {
+    fn entry_name() -> &'static str {
+        "Improved"
+    }
+}
+
+impl LensMap for ImprovedLens {
+    type Source = StepsWithoutImprovement;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        if source.0 == 0 {
+            1.
+        } else {
+            0.
+        }
+    }
+}
+
+/// Lens for the fraction of solutions that were improved, as measured by the [`ImprovementMeasure`] `I`.
+///
+/// A solution counts as improved if its total improvement stored in [`Improvement<I>`] is positive.
+///
+/// [`ImprovementMeasure`]: crate::components::measures::improvement::ImprovementMeasure
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Default(bound = ""), Clone(bound = ""))]
+pub struct SuccessRateLens<I>(SerializablePhantom<I>);
+
+impl<I> SuccessRateLens<I> {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self(SerializablePhantom::default())
+    }
+}
+
+impl<I: AnyComponent + 'static> AnyLens for SuccessRateLens<I> {
+    type Target = f64;
+}
+
+impl<I> EntryName for SuccessRateLens<I> {
+    fn entry_name() -> &'static str {
+        "Success rate"
+    }
+}
+
+impl<I: AnyComponent + 'static> LensMap for SuccessRateLens<I> {
+    type Source = Improvement<I>;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        let improvements = &source.total_improvement;
+        if improvements.is_empty() {
+            0.
+        } else {
+            let successes = improvements.iter().filter(|&&i| i > 0.).count();
+            successes as f64 / improvements.len() as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+
+    use super::*;
+    use crate::{components::utils::Noop, testing::SingleObjectiveTestProblem};
+
+    type P = SingleObjectiveTestProblem;
+
+    fn state(strength: f64) -> State<'static, P> {
+        let mut state = State::new();
+        state.insert(StepsWithoutImprovement(0));
+        state.insert(MutationStrength::<Noop>::new(strength));
+        state
+    }
+
+    fn strength(state: &State<P>) -> f64 {
+        **state.borrow::<MutationStrength<Noop>>()
+    }
+
+    #[test]
+    fn one_fifth_rule_adapts_after_window() {
+        let problem = P::new();
+        let rule = SuccessRule::<ImprovedLens, ValueOf<MutationStrength<Noop>>>::one_fifth::<P>(
+            0.8,
+            2,
+            ImprovedLens::new(),
+        )
+        .unwrap();
+        let mut state = state(1.);
+        rule.init(&problem, &mut state).unwrap();
+
+        // Every step is successful, so the strength increases after the window.
+        rule.execute(&problem, &mut state).unwrap();
+        assert_float_eq!(strength(&state), 1., abs <= 1e-12);
+        rule.execute(&problem, &mut state).unwrap();
+        assert_float_eq!(strength(&state), 1.25, abs <= 1e-12);
+
+        // No step is successful, so the strength decreases after the window.
+        state.insert(StepsWithoutImprovement(3));
+        rule.execute(&problem, &mut state).unwrap();
+        rule.execute(&problem, &mut state).unwrap();
+        assert_float_eq!(strength(&state), 1., abs <= 1e-12);
+    }
+
+    #[test]
+    fn success_rule_uses_mean_rate_of_window() {
+        let problem = P::new();
+        let rule = SuccessRule::new(
+            0.5,
+            2.,
+            0.5,
+            2,
+            ImprovedLens::new(),
+            ValueOf::<MutationStrength<Noop>>::new(),
+        )
+        .unwrap();
+        let mut state = state(1.);
+        rule.init(&problem, &mut state).unwrap();
+
+        // One successful and one unsuccessful step meet the target exactly.
+        rule.execute(&problem, &mut state).unwrap();
+        state.insert(StepsWithoutImprovement(1));
+        rule.execute(&problem, &mut state).unwrap();
+        assert_float_eq!(strength(&state), 1., abs <= 1e-12);
+
+        rule.execute(&problem, &mut state).unwrap();
+        rule.execute(&problem, &mut state).unwrap();
+        assert_float_eq!(strength(&state), 0.5, abs <= 1e-12);
+    }
+
+    #[test]
+    fn success_rule_rejects_invalid_params() {
+        let lens = ValueOf::<MutationStrength<Noop>>::new();
+        assert!(SuccessRule::from_params(1.5, 2., 0.5, 2, ImprovedLens::new(), lens).is_err());
+        assert!(
+            SuccessRule::<ImprovedLens, ValueOf<MutationStrength<Noop>>>::one_fifth::<P>(
+                1.2,
+                2,
+                ImprovedLens::new()
+            )
+            .is_err()
+        );
+    }
+}
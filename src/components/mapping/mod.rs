@@ -12,6 +12,9 @@
 //! Note that a mapping only defines `f` (and maybe specifies bounds on `X` and `Y`), but
 //! the caller decides what `x` and `y` actually are.
 //!
+//! For closed-loop control of parameters based on the success of the search,
+//! see the [`adaptive`] module.
+//!
 //! # Example
 //!
 //! A specific example for this is adapting the [`InertiaWeight`] used by [`ParticleVelocitiesUpdate`].
@@ -52,6 +55,7 @@ use crate::{
     Problem, State,
 };
 
+pub mod adaptive;
 pub mod common;
 pub mod sa;
 
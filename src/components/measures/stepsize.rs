@@ -36,6 +36,8 @@ pub trait StepSizeMeasure<P: Problem>: AnyComponent {
 }
 
 /// A default implementation of [`Component::execute`] for types implementing [`StepSizeMeasure`].
+///
+/// The [`StepSize`] is left unchanged if the current or the archived population is empty.
 pub fn step_size_measure<P, T>(component: &T, problem: &P, state: &mut State<P>) -> ExecResult<()>
 where
     P: Problem,
@@ -47,9 +49,8 @@ where
     let previous_pop = archive.archived_population();
     let mut step_size = state.borrow_mut::<StepSize<T>>();
 
-    if current_pop.is_empty() || previous_pop.is_empty() {
-        step_size.update((vec![0.0], vec![0.0]));
-    } else {
+    // Without a previous population, there is no step to measure yet.
+    if !current_pop.is_empty() && !previous_pop.is_empty() {
         step_size.update(component.measure(
             problem,
             &previous_pop.as_solutions(),
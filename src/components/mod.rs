@@ -6,27 +6,36 @@
 
 use crate::{
     component::{AnyComponent, ExecResult},
+    configuration::tree::{Edge, EdgeMut},
     state::StateReq,
     Problem, State,
 };
 
+pub mod aos;
 pub mod archive;
 pub mod boundary;
 pub mod control_flow;
 pub mod evaluation;
+pub mod ge;
 pub mod generative;
+pub mod gp;
 pub mod initialization;
+pub mod islands;
 pub mod mapping;
 pub mod measures;
 pub mod misc;
 pub mod mutation;
+pub mod noise;
 pub mod recombination;
 pub mod replacement;
+pub mod restart;
 pub mod selection;
+pub mod steady_state;
+pub mod surrogate;
 pub mod swarm;
 pub mod utils;
 
-pub use control_flow::{Block, Branch, Loop, Scope};
+pub use control_flow::{Block, Branch, Loop, ParallelBlock, Scope};
 
 /// Trait to represent a *component*, a (small) functionality with a uniform interface.
 ///
@@ -135,6 +144,29 @@ pub trait Component<P: Problem>: AnyComponent {
 
     /// Executes the component, performing the actual logic.
     fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()>;
+
+    /// Returns the edges to the child components and conditions of the component, e.g. the body of a [`Loop`].
+    ///
+    /// Only components containing other components and conditions need to implement this method.
+    /// See the [`tree`] module for more details.
+    ///
+    /// [`tree`]: crate::configuration::tree
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        Vec::new()
+    }
+
+    /// Returns the edges to the mutable child components and conditions of the component.
+    ///
+    /// This is the mutable twin of [`children`](Self::children), and has to return the same
+    /// children with the same labels in the same order.
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        Vec::new()
+    }
+
+    /// Returns the full name of the type of the component.
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 erased_serde::serialize_trait_object!(<P: Problem> Component<P>);
@@ -5,7 +5,7 @@ use std::marker::PhantomData;
 use eyre::{ensure, WrapErr};
 use itertools::multizip;
 use rand::{
-    distributions::{Distribution, Uniform},
+    distributions::{uniform::SampleUniform, Distribution, Uniform},
     seq::{IteratorRandom, SliceRandom},
     Rng,
 };
@@ -20,7 +20,10 @@ use crate::{
     },
     identifier::{Global, Identifier},
     population::AsSolutionsMut,
-    problems::{LimitedVectorProblem, VectorProblem},
+    problems::{
+        mixed, LimitedVectorProblem, MixedValue, MixedVariable, MixedVectorProblem, SubsetProblem,
+        VariableLengthProblem, VectorProblem,
+    },
     State,
 };
 
@@ -268,6 +271,9 @@ where
 
 /// Applies a random uniform reset of a position in the solution with probability `rm`.
 ///
+/// The new value is sampled uniformly from the domain of the position, which makes this the
+/// uniform mutation for both real and integer vectors.
+///
 /// # Adapting parameters
 ///
 /// Adapting the `rm` is possible through modifying the respective state:
@@ -297,6 +303,14 @@ impl<I: Identifier> PartialRandomSpread<I> {
     {
         Box::new(Self::from_params(rm))
     }
+
+    /// Creates a new `PartialRandomSpread` for integer vectors.
+    pub fn new_integer_with_id<P>(rm: f64) -> Box<dyn Component<P>>
+    where
+        P: LimitedVectorProblem<Element = i64>,
+    {
+        Box::new(Self::from_params(rm))
+    }
 }
 
 impl PartialRandomSpread<Global> {
@@ -314,11 +328,20 @@ impl PartialRandomSpread<Global> {
     {
         Self::new(1.)
     }
+
+    /// Creates a new `PartialRandomSpread` for integer vectors.
+    pub fn new_integer<P>(rm: f64) -> Box<dyn Component<P>>
+    where
+        P: LimitedVectorProblem<Element = i64>,
+    {
+        Self::new_integer_with_id(rm)
+    }
 }
 
-impl<P, I> Component<P> for PartialRandomSpread<I>
+impl<P, D, I> Component<P> for PartialRandomSpread<I>
 where
-    P: LimitedVectorProblem<Element = f64>,
+    P: LimitedVectorProblem<Element = D>,
+    D: SampleUniform + Clone + PartialOrd + 'static,
     I: Identifier,
 {
     fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
@@ -343,6 +366,283 @@ where
     }
 }
 
+/// Mutates each dimension of an integer vector with a delta uniformly chosen from
+/// `[-step, step] \ {0}` depending on the mutation probability `rm`.
+///
+/// # Adapting parameters
+///
+/// Adapting the `step` and `rm` is possible through modifying the respective states:
+/// - `step`: [`MutationStrength<CreepMutation<I>>`], which is rounded to the nearest integer
+/// - `rm`: [`MutationRate<CreepMutation<I>>`]
+///
+/// # Errors
+///
+/// Returns an `Err` if the [`MutationStrength`] or [`MutationRate`] contain invalid values.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CreepMutation<I: Identifier = Global> {
+    /// Maximal absolute delta.
+    pub step: u32,
+    /// Mutation rate.
+    pub rm: f64,
+    phantom: PhantomData<I>,
+}
+
+impl<I: Identifier> CreepMutation<I> {
+    pub fn from_params(step: u32, rm: f64) -> Self {
+        Self {
+            step,
+            rm,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn new_with_id<P>(step: u32, rm: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = i64>,
+    {
+        Box::new(Self::from_params(step, rm))
+    }
+}
+
+impl CreepMutation<Global> {
+    pub fn new<P>(step: u32, rm: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = i64>,
+    {
+        Self::new_with_id(step, rm)
+    }
+}
+
+impl<P, I> Component<P> for CreepMutation<I>
+where
+    P: VectorProblem<Element = i64>,
+    I: Identifier,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(MutationStrength::<Self>::new(self.step as f64));
+        state.insert(MutationRate::<Self>::new(self.rm));
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+
+        let step = state.get_value::<MutationStrength<Self>>().round();
+        ensure!(step >= 1., "step must be at least 1");
+        let distr = Uniform::new_inclusive(1, step as i64);
+
+        let rm = state.borrow::<MutationRate<Self>>().value()?;
+
+        for solution in populations.current_mut().as_solutions_mut() {
+            for x in solution {
+                if rng.gen_bool(rm) {
+                    let sign = *[-1, 1].choose(&mut *rng).unwrap();
+                    *x += sign * distr.sample(&mut *rng);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mutates each dimension of an integer vector with a delta from a discretized normal
+/// distribution `round(N(0, std_dev))` depending on the mutation probability `rm`.
+///
+/// # Adapting parameters
+///
+/// Adapting the `std_dev` and `rm` is possible through modifying the respective states:
+/// - `std_dev`: [`MutationStrength<DiscreteGaussianMutation<I>>`]
+/// - `rm`: [`MutationRate<DiscreteGaussianMutation<I>>`]
+///
+/// # Errors
+///
+/// Returns an `Err` if the [`MutationStrength`] or [`MutationRate`] contain invalid values.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DiscreteGaussianMutation<I: Identifier = Global> {
+    /// Standard deviation of the normal distribution.
+    pub std_dev: f64,
+    /// Mutation rate.
+    pub rm: f64,
+    phantom: PhantomData<I>,
+}
+
+impl<I: Identifier> DiscreteGaussianMutation<I> {
+    pub fn from_params(std_dev: f64, rm: f64) -> Self {
+        Self {
+            std_dev,
+            rm,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn new_with_id<P>(std_dev: f64, rm: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = i64>,
+    {
+        Box::new(Self::from_params(std_dev, rm))
+    }
+}
+
+impl DiscreteGaussianMutation<Global> {
+    pub fn new<P>(std_dev: f64, rm: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = i64>,
+    {
+        Self::new_with_id(std_dev, rm)
+    }
+
+    /// Creates the `DiscreteGaussianMutation` with a mutation rate of 1.
+    pub fn new_dev<P>(std_dev: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = i64>,
+    {
+        Self::new(std_dev, 1.0)
+    }
+}
+
+impl<P, I> Component<P> for DiscreteGaussianMutation<I>
+where
+    P: VectorProblem<Element = i64>,
+    I: Identifier,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(MutationStrength::<Self>::new(self.std_dev));
+        state.insert(MutationRate::<Self>::new(self.rm));
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+
+        let distr = Normal::new(0., state.get_value::<MutationStrength<Self>>())
+            .wrap_err("invalid mutation strength")?;
+
+        let rm = state.borrow::<MutationRate<Self>>().value()?;
+
+        for solution in populations.current_mut().as_solutions_mut() {
+            for x in solution {
+                if rng.gen_bool(rm) {
+                    *x += distr.sample(&mut *rng).round() as i64;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mutates each variable of a [`MixedVectorProblem`] depending on the mutation probability
+/// `rm` and its [`MixedVariable`] type.
+///
+/// - Continuous variables are mutated with a delta from `N(0, std_dev · w)`, where `w` is the
+///   width of the domain of the variable.
+/// - Integer variables are mutated with a rounded delta from `N(0, std_dev · w)`, where a delta
+///   of zero is replaced by a delta of `±1`.
+/// - Categorical variables are set to a different, uniformly chosen category.
+///
+/// Note that mutated values may lie outside the domain, e.g. use [`MixedSaturation`]
+/// to repair them.
+///
+/// [`MixedSaturation`]: crate::components::boundary::MixedSaturation
+///
+/// # Adapting parameters
+///
+/// Adapting the `std_dev` and `rm` is possible through modifying the respective states:
+/// - `std_dev`: [`MutationStrength<MixedMutation<I>>`]
+/// - `rm`: [`MutationRate<MixedMutation<I>>`]
+///
+/// # Errors
+///
+/// Returns an `Err` if the [`MutationStrength`] or [`MutationRate`] contain invalid values,
+/// or on initialization if the domain of any [`MixedVariable`] is empty.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MixedMutation<I: Identifier = Global> {
+    /// Standard deviation of the normal distribution relative to the domain width.
+    pub std_dev: f64,
+    /// Mutation rate.
+    pub rm: f64,
+    phantom: PhantomData<I>,
+}
+
+impl<I: Identifier> MixedMutation<I> {
+    pub fn from_params(std_dev: f64, rm: f64) -> Self {
+        Self {
+            std_dev,
+            rm,
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn new_with_id<P>(std_dev: f64, rm: f64) -> Box<dyn Component<P>>
+    where
+        P: MixedVectorProblem,
+    {
+        Box::new(Self::from_params(std_dev, rm))
+    }
+}
+
+impl MixedMutation<Global> {
+    pub fn new<P>(std_dev: f64, rm: f64) -> Box<dyn Component<P>>
+    where
+        P: MixedVectorProblem,
+    {
+        Self::new_with_id(std_dev, rm)
+    }
+}
+
+impl<P, I> Component<P> for MixedMutation<I>
+where
+    P: MixedVectorProblem,
+    I: Identifier,
+{
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        mixed::validate_variables(&problem.variables())?;
+        state.insert(MutationStrength::<Self>::new(self.std_dev));
+        state.insert(MutationRate::<Self>::new(self.rm));
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+
+        let std_dev = state.get_value::<MutationStrength<Self>>();
+        let distr = Normal::new(0., std_dev).wrap_err("invalid mutation strength")?;
+        let rm = state.borrow::<MutationRate<Self>>().value()?;
+        let variables = problem.variables();
+
+        for solution in populations.current_mut().as_solutions_mut() {
+            for (x, variable) in solution.iter_mut().zip(&variables) {
+                if !rng.gen_bool(rm) {
+                    continue;
+                }
+                *x = match (variable, *x) {
+                    (MixedVariable::Continuous(range), MixedValue::Continuous(x)) => {
+                        let width = range.end - range.start;
+                        MixedValue::Continuous(x + width * distr.sample(&mut *rng))
+                    }
+                    (MixedVariable::Integer(range), MixedValue::Integer(x)) => {
+                        let width = (range.end() - range.start()) as f64;
+                        let delta = match (width * distr.sample(&mut *rng)).round() as i64 {
+                            0 => *[-1, 1].choose(&mut *rng).unwrap(),
+                            delta => delta,
+                        };
+                        MixedValue::Integer(x + delta)
+                    }
+                    (MixedVariable::Categorical(n), MixedValue::Categorical(x)) if *n > 1 => {
+                        // Choose uniformly from all categories except the current one.
+                        let other = rng.gen_range(0..n - 1);
+                        MixedValue::Categorical(if other >= x { other + 1 } else { other })
+                    }
+                    (_, x) => x,
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Applies a scramble mutation i.e. shuffling with probability `rm`.
 ///
 /// # Adapting parameters
@@ -686,3 +986,395 @@ where
         Ok(())
     }
 }
+
+/// Inserts a random element at a random position of each solution with probability `rm`.
+///
+/// Solutions already having the maximal length are left unchanged,
+/// see [`VariableLengthProblem::lengths`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GeneInsertionMutation {
+    /// Mutation rate, i.e. the probability of mutating a solution.
+    pub rm: f64,
+}
+
+impl GeneInsertionMutation {
+    pub fn from_params(rm: f64) -> Self {
+        Self { rm }
+    }
+
+    pub fn new<P: VariableLengthProblem>(rm: f64) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(rm))
+    }
+}
+
+impl<P: VariableLengthProblem> Component<P> for GeneInsertionMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let lengths = problem.lengths();
+
+        for solution in populations.current_mut().as_solutions_mut() {
+            if solution.len() < *lengths.end() && rng.gen_bool(self.rm) {
+                let index = rng.gen_range(0..=solution.len());
+                solution.insert(index, problem.random_element(&mut *rng));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Removes a random element of each solution with probability `rm`.
+///
+/// Solutions already having the minimal length are left unchanged,
+/// see [`VariableLengthProblem::lengths`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GeneDeletionMutation {
+    /// Mutation rate, i.e. the probability of mutating a solution.
+    pub rm: f64,
+}
+
+impl GeneDeletionMutation {
+    pub fn from_params(rm: f64) -> Self {
+        Self { rm }
+    }
+
+    pub fn new<P: VariableLengthProblem>(rm: f64) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(rm))
+    }
+}
+
+impl<P: VariableLengthProblem> Component<P> for GeneDeletionMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let lengths = problem.lengths();
+
+        for solution in populations.current_mut().as_solutions_mut() {
+            if !solution.is_empty() && solution.len() > *lengths.start() && rng.gen_bool(self.rm) {
+                let index = rng.gen_range(0..solution.len());
+                solution.remove(index);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Duplicates a random element of each solution with probability `rm`,
+/// inserting the copy next to the original.
+///
+/// Solutions already having the maximal length are left unchanged,
+/// see [`VariableLengthProblem::lengths`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GeneDuplicationMutation {
+    /// Mutation rate, i.e. the probability of mutating a solution.
+    pub rm: f64,
+}
+
+impl GeneDuplicationMutation {
+    pub fn from_params(rm: f64) -> Self {
+        Self { rm }
+    }
+
+    pub fn new<P: VariableLengthProblem>(rm: f64) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(rm))
+    }
+}
+
+impl<P: VariableLengthProblem> Component<P> for GeneDuplicationMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let lengths = problem.lengths();
+
+        for solution in populations.current_mut().as_solutions_mut() {
+            if !solution.is_empty() && solution.len() < *lengths.end() && rng.gen_bool(self.rm) {
+                let index = rng.gen_range(0..solution.len());
+                solution.insert(index + 1, solution[index].clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Adds a random item to each subset with probability `rm`.
+///
+/// Subsets already having the maximal size are left unchanged, see [`SubsetProblem::sizes`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AddItemMutation {
+    /// Mutation rate, i.e. the probability of mutating a subset.
+    pub rm: f64,
+}
+
+impl AddItemMutation {
+    pub fn from_params(rm: f64) -> Self {
+        Self { rm }
+    }
+
+    pub fn new<P: SubsetProblem>(rm: f64) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(rm))
+    }
+}
+
+impl<P: SubsetProblem> Component<P> for AddItemMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let sizes = problem.sizes();
+
+        for subset in populations.current_mut().as_solutions_mut() {
+            if subset.len() < *sizes.end() && rng.gen_bool(self.rm) {
+                let item = (0..problem.items())
+                    .filter(|item| !subset.contains(item))
+                    .choose(&mut *rng);
+                subset.extend(item);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Removes a random item from each subset with probability `rm`.
+///
+/// Subsets already having the minimal size are left unchanged, see [`SubsetProblem::sizes`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DropItemMutation {
+    /// Mutation rate, i.e. the probability of mutating a subset.
+    pub rm: f64,
+}
+
+impl DropItemMutation {
+    pub fn from_params(rm: f64) -> Self {
+        Self { rm }
+    }
+
+    pub fn new<P: SubsetProblem>(rm: f64) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(rm))
+    }
+}
+
+impl<P: SubsetProblem> Component<P> for DropItemMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+        let sizes = problem.sizes();
+
+        for subset in populations.current_mut().as_solutions_mut() {
+            if subset.len() > *sizes.start() && rng.gen_bool(self.rm) {
+                if let Some(item) = subset.iter().copied().choose(&mut *rng) {
+                    subset.remove(&item);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replaces a random item of each subset with a random item not in the subset
+/// with probability `rm`, which keeps the size of the subset.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SwapItemMutation {
+    /// Mutation rate, i.e. the probability of mutating a subset.
+    pub rm: f64,
+}
+
+impl SwapItemMutation {
+    pub fn from_params(rm: f64) -> Self {
+        Self { rm }
+    }
+
+    pub fn new<P: SubsetProblem>(rm: f64) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(rm))
+    }
+}
+
+impl<P: SubsetProblem> Component<P> for SwapItemMutation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let mut rng = state.random_mut();
+
+        for subset in populations.current_mut().as_solutions_mut() {
+            if !rng.gen_bool(self.rm) {
+                continue;
+            }
+            let removed = subset.iter().copied().choose(&mut *rng);
+            let added = (0..problem.items())
+                .filter(|item| !subset.contains(item))
+                .choose(&mut *rng);
+            if let (Some(removed), Some(added)) = (removed, added) {
+                subset.remove(&removed);
+                subset.insert(added);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::{population::AsSolutions, testing::*, Problem};
+
+    fn mutate(component: Box<dyn Component<IntegerTestProblem>>, solution: &[i64]) -> Vec<i64> {
+        let problem = IntegerTestProblem(vec![0..100; solution.len()]);
+        let mut state = integer_test_state(vec![solution.to_vec()]);
+        component.init(&problem, &mut state).unwrap();
+        component.execute(&problem, &mut state).unwrap();
+        let populations = state.populations();
+        populations.current().as_solutions()[0].clone()
+    }
+
+    #[test]
+    fn creep_mutation_changes_every_value_by_at_most_step() {
+        let solution = vec![50; 100];
+        let mutated = mutate(CreepMutation::new(2, 1.), &solution);
+        assert!(mutated.iter().all(|x| [-2, -1, 1, 2].contains(&(x - 50))));
+    }
+
+    #[test]
+    fn creep_mutation_keeps_values_without_mutation_rate() {
+        let solution = vec![50; 100];
+        assert_eq!(mutate(CreepMutation::new(2, 0.), &solution), solution);
+    }
+
+    #[test]
+    fn discrete_gaussian_mutation_adds_rounded_deltas() {
+        let solution = vec![50; 1000];
+        assert_eq!(
+            mutate(DiscreteGaussianMutation::new_dev(0.), &solution),
+            solution
+        );
+
+        let mutated = mutate(DiscreteGaussianMutation::new_dev(1.), &solution);
+        let deltas: Vec<_> = mutated.iter().map(|x| x - 50).collect();
+        assert!(deltas.iter().all(|delta| delta.abs() <= 6));
+        assert!(deltas.iter().any(|delta| *delta != 0));
+        let mean = deltas.iter().sum::<i64>() as f64 / deltas.len() as f64;
+        assert!(mean.abs() < 0.2);
+    }
+
+    #[test]
+    fn discrete_gaussian_mutation_rejects_invalid_std_dev() {
+        let problem = IntegerTestProblem(vec![0..100; 1]);
+        let mut state = integer_test_state(vec![vec![50]]);
+        let component: Box<dyn Component<IntegerTestProblem>> =
+            DiscreteGaussianMutation::new_dev(f64::NAN);
+        component.init(&problem, &mut state).unwrap();
+        assert!(component.execute(&problem, &mut state).is_err());
+    }
+
+    #[test]
+    fn integer_partial_random_spread_samples_from_domain() {
+        let mutated = mutate(PartialRandomSpread::new_integer(1.), &[200; 100]);
+        assert!(mutated.iter().all(|x| (0..100).contains(x)));
+    }
+
+    fn mutate_with<P: Problem>(
+        component: Box<dyn Component<P>>,
+        problem: &P,
+        solution: P::Encoding,
+    ) -> P::Encoding {
+        let mut state = test_state(vec![solution]);
+        component.init(problem, &mut state).unwrap();
+        component.execute(problem, &mut state).unwrap();
+        let population = state.populations_mut().pop();
+        population.into_iter().next().unwrap().into_solution()
+    }
+
+    /// Returns `true` if removing a single element of `longer` results in `shorter`.
+    fn differs_by_one_element(longer: &[i64], shorter: &[i64]) -> bool {
+        longer.len() == shorter.len() + 1
+            && (0..longer.len()).any(|i| [&longer[..i], &longer[i + 1..]].concat() == shorter)
+    }
+
+    #[test]
+    fn gene_insertion_mutation_inserts_random_element() {
+        let problem = VariableLengthTestProblem(1..=4);
+        let solution = vec![10, 10, 10];
+        let mutated = mutate_with(GeneInsertionMutation::new(1.), &problem, solution.clone());
+        assert!(differs_by_one_element(&mutated, &solution));
+        assert_eq!(mutated.iter().filter(|&&x| x < 10).count(), 1);
+
+        let solution = vec![10; 4];
+        let mutated = mutate_with(GeneInsertionMutation::new(1.), &problem, solution.clone());
+        assert_eq!(mutated, solution);
+    }
+
+    #[test]
+    fn gene_deletion_mutation_removes_element() {
+        let problem = VariableLengthTestProblem(2..=4);
+        let solution = vec![1, 2, 3];
+        let mutated = mutate_with(GeneDeletionMutation::new(1.), &problem, solution.clone());
+        assert!(differs_by_one_element(&solution, &mutated));
+
+        let solution = vec![1, 2];
+        let mutated = mutate_with(GeneDeletionMutation::new(1.), &problem, solution.clone());
+        assert_eq!(mutated, solution);
+    }
+
+    #[test]
+    fn gene_duplication_mutation_duplicates_element() {
+        let problem = VariableLengthTestProblem(1..=4);
+        let solution = vec![1, 2, 3];
+        let mutated = mutate_with(GeneDuplicationMutation::new(1.), &problem, solution.clone());
+        assert!(differs_by_one_element(&mutated, &solution));
+        assert!(mutated.windows(2).any(|w| w[0] == w[1]));
+
+        let solution = vec![1, 2, 3, 4];
+        let mutated = mutate_with(GeneDuplicationMutation::new(1.), &problem, solution.clone());
+        assert_eq!(mutated, solution);
+    }
+
+    #[test]
+    fn variable_length_mutations_keep_solutions_without_mutation_rate() {
+        let problem = VariableLengthTestProblem(1..=4);
+        let solution = vec![1, 2, 3];
+        for component in [
+            GeneInsertionMutation::new(0.),
+            GeneDeletionMutation::new(0.),
+            GeneDuplicationMutation::new(0.),
+        ] {
+            assert_eq!(mutate_with(component, &problem, solution.clone()), solution);
+        }
+    }
+
+    #[test]
+    fn add_item_mutation_adds_missing_item() {
+        let problem = SubsetTestProblem(5, 0..=3);
+        let subset = BTreeSet::from([0, 1]);
+        let mutated = mutate_with(AddItemMutation::new(1.), &problem, subset.clone());
+        assert_eq!(mutated.len(), 3);
+        assert!(mutated.is_superset(&subset) && mutated.iter().all(|&i| i < 5));
+
+        let subset = BTreeSet::from([0, 1, 2]);
+        let mutated = mutate_with(AddItemMutation::new(1.), &problem, subset.clone());
+        assert_eq!(mutated, subset);
+    }
+
+    #[test]
+    fn drop_item_mutation_removes_item() {
+        let problem = SubsetTestProblem(5, 2..=5);
+        let subset = BTreeSet::from([0, 1, 2]);
+        let mutated = mutate_with(DropItemMutation::new(1.), &problem, subset.clone());
+        assert_eq!(mutated.len(), 2);
+        assert!(mutated.is_subset(&subset));
+
+        let subset = BTreeSet::from([0, 1]);
+        let mutated = mutate_with(DropItemMutation::new(1.), &problem, subset.clone());
+        assert_eq!(mutated, subset);
+    }
+
+    #[test]
+    fn swap_item_mutation_keeps_size() {
+        let problem = SubsetTestProblem(5, 0..=5);
+        let subset = BTreeSet::from([0, 1]);
+        let mutated = mutate_with(SwapItemMutation::new(1.), &problem, subset.clone());
+        assert_eq!(mutated.len(), 2);
+        assert_eq!(mutated.intersection(&subset).count(), 1);
+
+        let subset = BTreeSet::from_iter(0..5);
+        let mutated = mutate_with(SwapItemMutation::new(1.), &problem, subset.clone());
+        assert_eq!(mutated, subset);
+    }
+}
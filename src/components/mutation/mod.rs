@@ -24,8 +24,10 @@ pub mod de;
 pub mod functional;
 
 pub use common::{
-    BitFlipMutation, InversionMutation, NormalMutation, PartialRandomBitstring,
-    PartialRandomSpread, ScrambleMutation, SwapMutation, TranslocationMutation, UniformMutation,
+    AddItemMutation, BitFlipMutation, CreepMutation, DiscreteGaussianMutation, DropItemMutation,
+    GeneDeletionMutation, GeneDuplicationMutation, GeneInsertionMutation, InversionMutation,
+    MixedMutation, NormalMutation, PartialRandomBitstring, PartialRandomSpread, ScrambleMutation,
+    SwapItemMutation, SwapMutation, TranslocationMutation, UniformMutation,
 };
 
 /// Trait for representing a component that mutates solutions.
@@ -0,0 +1,558 @@
+//! Components for optimizing noisy objective functions.
+//!
+//! These components use the repeated samples of the objective function stored in the
+//! [`SampleArchive`], e.g. by evaluating with a [`Resampling`] evaluator, to allocate additional
+//! samples where they matter most, track the best individual robustly, and compare individuals
+//! using confidence intervals instead of single (lucky) samples.
+//!
+//! [`Resampling`]: crate::problems::noise::Resampling
+//!
+//! # References
+//!
+//! \[1\] Chun-Hung Chen, Jianwu Lin, Enver Yücesan, and Stephen E. Chick. 2000.
+//! Simulation Budget Allocation for Further Enhancing the Efficiency of Ordinal Optimization.
+//! Discrete Event Dynamic Systems 10, 3 (2000), 251–270.
+//! DOI:<https://doi.org/10.1023/A:1008349927281>
+//!
+//! \[2\] Hans-Georg Beyer and Yaochu Jin. 2005.
+//! Evolutionary Optimization in Uncertain Environments—A Survey.
+//! IEEE Transactions on Evolutionary Computation 9, 3 (2005), 303–317.
+//! DOI:<https://doi.org/10.1109/TEVC.2005.846356>
+
+use eyre::ensure;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::ExecResult,
+    components::Component,
+    problems::{
+        noise::{SampleArchive, Samples},
+        ObjectiveFunction, SingleObjectiveProblem,
+    },
+    state::common,
+    Individual, State,
+};
+
+/// Evaluates the `solution` once more, recording the sample in the `archive`.
+fn sample<P>(problem: &P, archive: &mut SampleArchive<P>, solution: &P::Encoding) -> Samples
+where
+    P: SingleObjectiveProblem + ObjectiveFunction,
+{
+    archive.record(solution, problem.objective(solution).value())
+}
+
+/// Returns the pooled variance of all solutions in the `archive` with at least two samples.
+fn pooled_variance<P: SingleObjectiveProblem>(archive: &SampleArchive<P>) -> Option<f64> {
+    let (sum, weights) = archive
+        .iter()
+        .filter_map(|(_, samples)| samples.variance().map(|v| (v, samples.len() - 1)))
+        .fold((0., 0), |(sum, weights), (v, w)| {
+            (sum + v * w as f64, weights + w)
+        });
+    (weights > 0).then(|| sum / weights as f64)
+}
+
+/// The strategy of [`AllocateSamples`] for distributing additional samples.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum SampleAllocation {
+    /// Optimal computing budget allocation (OCBA).
+    ///
+    /// Every sample is allocated to the individual furthest below its optimal share of samples,
+    /// where the share of a non-best individual `i` is proportional to `(σ_i / δ_i)²`, with
+    /// `δ_i` the difference of its mean to the best mean, and the share of the best individual
+    /// `b` is `σ_b · sqrt(Σ N_i² / σ_i²)`.
+    Ocba,
+    /// Racing.
+    ///
+    /// Individuals are sampled round-robin as long as their confidence interval
+    /// `mean ± z · std_error` overlaps with that of the best individual.
+    Racing {
+        /// The z-score determining the width of the confidence intervals, e.g. `1.96`.
+        z: f64,
+    },
+}
+
+/// Allocates a `budget` of additional samples to the individuals of the current population.
+///
+/// First, every individual is sampled until it has at least two samples, which is necessary
+/// to estimate its variance.
+/// These initial samples count towards the `budget`, i.e. if the budget is exhausted first,
+/// the individuals are sampled round-robin in population order and some keep less than two
+/// samples.
+/// The remaining budget is distributed according to the [`SampleAllocation`].
+/// Afterwards, the objective value of every individual is set to the mean of its samples.
+///
+/// Note that samples are taken directly using [`ObjectiveFunction::objective`], i.e. the
+/// evaluator in the [`State`] is **not** used.
+///
+/// # State
+///
+/// The samples are stored in the [`SampleArchive`], which is inserted with its default
+/// capacity if not present, and all samples are added to the [`Evaluations`] if present.
+///
+/// [`Evaluations`]: common::Evaluations
+///
+/// # Examples
+///
+/// ```
+/// # use mahf::{Configuration, ExecResult, SingleObjectiveProblem, problems::ObjectiveFunction};
+/// use mahf::components::noise::{AllocateSamples, SampleAllocation};
+///
+/// # fn example<P: SingleObjectiveProblem + ObjectiveFunction>() -> ExecResult<Configuration<P>> {
+/// # Ok(
+/// Configuration::builder()
+///     .evaluate()
+///     .do_(AllocateSamples::new(50, SampleAllocation::Ocba)?)
+///     .build()
+/// # )
+/// # }
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AllocateSamples {
+    /// The number of additional samples per execution.
+    pub budget: u32,
+    /// The allocation strategy.
+    pub allocation: SampleAllocation,
+}
+
+impl AllocateSamples {
+    pub fn from_params(budget: u32, allocation: SampleAllocation) -> ExecResult<Self> {
+        if let SampleAllocation::Racing { z } = allocation {
+            ensure!(z > 0., "`z` must be greater than 0");
+        }
+        Ok(Self { budget, allocation })
+    }
+
+    pub fn new<P>(budget: u32, allocation: SampleAllocation) -> ExecResult<Box<dyn Component<P>>>
+    where
+        P: SingleObjectiveProblem + ObjectiveFunction,
+    {
+        Ok(Box::new(Self::from_params(budget, allocation)?))
+    }
+
+    /// Returns the index of the individual to sample next according to OCBA.
+    fn ocba(samples: &[Samples]) -> usize {
+        let best = (0..samples.len())
+            .min_by(|&i, &j| samples[i].mean().total_cmp(&samples[j].mean()))
+            .unwrap();
+        let total = samples.iter().map(|s| s.len() as f64).sum::<f64>() + 1.;
+        // Small constant preventing divisions by zero for equal means or zero variances.
+        let eps = 1e-12;
+
+        let mut ratios: Vec<_> = samples
+            .iter()
+            .map(|s| {
+                let delta = (s.mean() - samples[best].mean()).abs().max(eps);
+                s.variance().unwrap_or(0.) / delta.powi(2)
+            })
+            .collect();
+        let sum_squares = ratios
+            .iter()
+            .zip(samples)
+            .enumerate()
+            .filter(|&(i, _)| i != best)
+            .map(|(_, (r, s))| r.powi(2) / s.variance().unwrap_or(0.).max(eps))
+            .sum::<f64>();
+        ratios[best] = samples[best].variance().unwrap_or(0.).sqrt() * sum_squares.sqrt();
+
+        let norm = ratios.iter().sum::<f64>();
+        if norm <= 0. || !norm.is_finite() {
+            // No meaningful allocation possible, so sample the least sampled individual.
+            return (0..samples.len())
+                .min_by_key(|&i| samples[i].len())
+                .unwrap();
+        }
+        (0..samples.len())
+            .max_by(|&i, &j| {
+                let deficit = |k: usize| ratios[k] / norm * total - samples[k].len() as f64;
+                deficit(i).total_cmp(&deficit(j))
+            })
+            .unwrap()
+    }
+
+    /// Returns the indices of individuals whose confidence interval overlaps with the best one.
+    fn racing(samples: &[Samples], z: f64) -> Vec<usize> {
+        let best = (0..samples.len())
+            .min_by(|&i, &j| samples[i].mean().total_cmp(&samples[j].mean()))
+            .unwrap();
+        let (_, best_upper) = samples[best].confidence_interval(z);
+        (0..samples.len())
+            .filter(|&i| i == best || samples[i].confidence_interval(z).0 < best_upper)
+            .collect()
+    }
+}
+
+impl<P> Component<P> for AllocateSamples
+where
+    P: SingleObjectiveProblem + ObjectiveFunction,
+{
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.contains::<SampleArchive<P>>() {
+            state.insert(SampleArchive::<P>::default());
+        }
+        let mut populations = state.populations_mut();
+        let population = populations.current_mut();
+        if population.is_empty() {
+            return Ok(());
+        }
+        let mut archive = state.borrow_mut::<SampleArchive<P>>();
+
+        let mut samples: Vec<_> = population
+            .iter()
+            .map(|individual| {
+                archive
+                    .get(individual.solution())
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut budget = self.budget;
+        for n in 1..=2 {
+            for (individual, samples) in population.iter().zip(&mut samples) {
+                if budget > 0 && samples.len() < n {
+                    *samples = sample(problem, &mut archive, individual.solution());
+                    budget -= 1;
+                }
+            }
+        }
+
+        match self.allocation {
+            SampleAllocation::Ocba => {
+                for _ in 0..budget {
+                    let i = Self::ocba(&samples);
+                    samples[i] = sample(problem, &mut archive, population[i].solution());
+                }
+                budget = 0;
+            }
+            SampleAllocation::Racing { z } => {
+                'race: while budget > 0 {
+                    let survivors = Self::racing(&samples, z);
+                    if survivors.len() <= 1 {
+                        break;
+                    }
+                    for i in survivors {
+                        if budget == 0 {
+                            break 'race;
+                        }
+                        samples[i] = sample(problem, &mut archive, population[i].solution());
+                        budget -= 1;
+                    }
+                }
+            }
+        }
+
+        for (individual, samples) in population.iter_mut().zip(&samples) {
+            if let Some(objective) = samples.objective() {
+                individual.set_objective(objective);
+            }
+        }
+        drop(archive);
+        drop(populations);
+
+        if state.contains::<common::Evaluations>() {
+            *state.borrow_value_mut::<common::Evaluations>() += self.budget - budget;
+        }
+        Ok(())
+    }
+}
+
+/// Updates the [`BestIndividual`] yet found based on the mean of its samples.
+///
+/// Unlike [`BestIndividualUpdate`], the objective value of the stored best individual is
+/// refreshed from the [`SampleArchive`] on every execution, so a single lucky sample does not
+/// keep it the best forever.
+/// Only individuals with at least `min_samples` samples are considered as candidates.
+///
+/// If no [`SampleArchive`] is present, this component behaves like [`BestIndividualUpdate`].
+///
+/// [`BestIndividual`]: common::BestIndividual
+/// [`BestIndividualUpdate`]: crate::components::evaluation::BestIndividualUpdate
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NoisyBestIndividualUpdate {
+    /// The minimal number of samples of a candidate.
+    pub min_samples: u32,
+}
+
+impl NoisyBestIndividualUpdate {
+    pub fn from_params(min_samples: u32) -> Self {
+        Self { min_samples }
+    }
+
+    pub fn new<P: SingleObjectiveProblem>(min_samples: u32) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(min_samples))
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for NoisyBestIndividualUpdate {
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(common::BestIndividual::<P>::default());
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let populations = state.populations();
+        let mut best = state.borrow_mut::<common::BestIndividual<P>>();
+
+        let Some(archive) = state.try_borrow::<SampleArchive<P>>().ok() else {
+            if let Some(candidate) = populations.current().iter().min_by_key(|i| *i.objective()) {
+                best.update(candidate);
+            }
+            return Ok(());
+        };
+
+        // Refresh the objective value of the best individual with its latest samples.
+        if let Some(best) = best.as_mut() {
+            if let Some(objective) = archive.get(best.solution()).and_then(Samples::objective) {
+                best.set_objective(objective);
+            }
+        }
+
+        let candidate = populations
+            .current()
+            .iter()
+            .filter_map(|individual| {
+                let samples = archive.get(individual.solution())?;
+                (samples.len() >= self.min_samples)
+                    .then(|| samples.objective())
+                    .flatten()
+                    .map(|objective| (individual, objective))
+            })
+            .min_by_key(|&(_, objective)| objective);
+
+        if let Some((individual, objective)) = candidate {
+            let improves = best
+                .as_ref()
+                .is_none_or(|best| objective < *best.objective());
+            if improves {
+                let mut individual = individual.clone();
+                individual.set_objective(objective);
+                **best = Some(individual);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keeps the `max_population_size` best individuals from parents and offspring, ranked by
+/// the upper bound `mean + z · std_error` of the confidence interval of their mean.
+///
+/// Ranking by the upper bound prefers individuals which are reliably good over individuals
+/// which are good due to few lucky samples.
+/// For individuals with less than two samples, the standard error is estimated using the
+/// pooled variance of all solutions in the [`SampleArchive`].
+/// Individuals not present in the archive are ranked by their objective value.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConfidenceMuPlusLambda {
+    /// Maximal allowed population size.
+    pub max_population_size: u32,
+    /// The z-score determining the width of the confidence intervals, e.g. `1.96`.
+    pub z: f64,
+}
+
+impl ConfidenceMuPlusLambda {
+    pub fn from_params(max_population_size: u32, z: f64) -> ExecResult<Self> {
+        ensure!(z >= 0., "`z` must not be negative");
+        Ok(Self {
+            max_population_size,
+            z,
+        })
+    }
+
+    pub fn new<P: SingleObjectiveProblem>(
+        max_population_size: u32,
+        z: f64,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Ok(Box::new(Self::from_params(max_population_size, z)?))
+    }
+
+    /// Returns the upper confidence bound of the `individual`'s mean.
+    fn upper_bound<P: SingleObjectiveProblem>(
+        &self,
+        individual: &Individual<P>,
+        archive: Option<&SampleArchive<P>>,
+        pooled_variance: Option<f64>,
+    ) -> f64 {
+        match archive.and_then(|archive| archive.get(individual.solution())) {
+            Some(samples) if !samples.is_empty() => {
+                let std_error = samples
+                    .std_error()
+                    .or_else(|| pooled_variance.map(|v| (v / samples.len() as f64).sqrt()))
+                    .unwrap_or(0.);
+                samples.mean() + self.z * std_error
+            }
+            _ => individual.objective().value(),
+        }
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for ConfidenceMuPlusLambda {
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let mut populations = state.populations_mut();
+        let offspring = populations.pop();
+        let mut population = populations.pop();
+        population.extend(offspring);
+
+        let archive = state.try_borrow::<SampleArchive<P>>().ok();
+        let pooled_variance = archive.as_deref().and_then(pooled_variance);
+        let mut ranked: Vec<_> = population
+            .into_iter()
+            .map(|i| (self.upper_bound(&i, archive.as_deref(), pooled_variance), i))
+            .collect();
+        ranked.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        ranked.truncate(self.max_population_size as usize);
+
+        populations.push(ranked.into_iter().map(|(_, i)| i).collect());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+    use crate::testing::*;
+
+    type P = IntegerTestProblem;
+
+    fn samples(values: &[f64]) -> Samples {
+        let mut samples = Samples::new();
+        for &value in values {
+            samples.push(value);
+        }
+        samples
+    }
+
+    fn archive(entries: &[(Vec<i64>, &[f64])]) -> SampleArchive<P> {
+        let mut archive = SampleArchive::default();
+        for (solution, values) in entries {
+            for &value in *values {
+                archive.record(solution, value);
+            }
+        }
+        archive
+    }
+
+    fn individual(solution: Vec<i64>, objective: f64) -> Individual<P> {
+        Individual::new(solution, objective.try_into().unwrap())
+    }
+
+    #[test]
+    fn initial_samples_are_limited_by_budget() {
+        let problem = IntegerTestProblem(vec![0..10; 2]);
+        let mut state = integer_test_state(vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+        state.insert(common::Evaluations(0));
+        let component = AllocateSamples::new(4, SampleAllocation::Ocba).unwrap();
+        component.execute(&problem, &mut state).unwrap();
+
+        assert_eq!(state.get_value::<common::Evaluations>(), 4);
+        let archive = state.borrow::<SampleArchive<IntegerTestProblem>>();
+        let counts: Vec<_> = [vec![1, 2], vec![3, 4], vec![5, 6]]
+            .iter()
+            .map(|solution| archive.get(solution).unwrap().len())
+            .collect();
+        assert_eq!(counts, [2, 1, 1]);
+    }
+
+    #[test]
+    fn remaining_budget_is_allocated() {
+        let problem = IntegerTestProblem(vec![0..10; 2]);
+        let mut state = integer_test_state(vec![vec![1, 2], vec![3, 4]]);
+        state.insert(common::Evaluations(0));
+        let component = AllocateSamples::new(10, SampleAllocation::Ocba).unwrap();
+        component.execute(&problem, &mut state).unwrap();
+
+        assert_eq!(state.get_value::<common::Evaluations>(), 10);
+        let archive = state.borrow::<SampleArchive<IntegerTestProblem>>();
+        assert_eq!(archive.iter().map(|(_, s)| s.len()).sum::<u32>(), 10);
+        let populations = state.populations();
+        assert_eq!(populations.current()[1].objective().value(), 7.);
+    }
+
+    #[test]
+    fn racing_keeps_overlapping_confidence_intervals() {
+        let samples = [
+            samples(&[1.0, 1.2]),
+            samples(&[1.1, 1.5]),
+            samples(&[10.0, 10.2]),
+            samples(&[5.0]),
+        ];
+        // The last individual has an infinitely wide confidence interval.
+        assert_eq!(AllocateSamples::racing(&samples, 1.96), [0, 1, 3]);
+    }
+
+    #[test]
+    fn racing_stops_when_best_is_separated() {
+        // The objective is deterministic, so the initial samples separate all individuals.
+        let problem = IntegerTestProblem(vec![0..10; 2]);
+        let mut state = integer_test_state(vec![vec![1, 2], vec![3, 4], vec![5, 6]]);
+        state.insert(common::Evaluations(0));
+        let component = AllocateSamples::new(20, SampleAllocation::Racing { z: 1.96 }).unwrap();
+        component.execute(&problem, &mut state).unwrap();
+
+        assert_eq!(state.get_value::<common::Evaluations>(), 6);
+    }
+
+    #[test]
+    fn noisy_best_individual_update_uses_sample_means() {
+        let problem = IntegerTestProblem(vec![0..10; 2]);
+        let (a, b) = (vec![1, 0], vec![2, 0]);
+        let mut state = integer_test_state(Vec::new());
+        let component = NoisyBestIndividualUpdate::from_params(2);
+        component.init(&problem, &mut state).unwrap();
+
+        // `a` has a lucky objective value, but too few samples.
+        state.insert(archive(&[(a.clone(), &[1.]), (b.clone(), &[3., 3.])]));
+        *state.populations_mut().current_mut() =
+            vec![individual(a.clone(), 0.), individual(b.clone(), 3.)];
+        component.execute(&problem, &mut state).unwrap();
+        assert_eq!(state.best_individual().unwrap().solution(), &b);
+        assert_eq!(state.best_objective_value().unwrap().value(), 3.);
+
+        // The best individual is refreshed with its new samples.
+        state.insert(archive(&[
+            (a.clone(), &[1., 2.]),
+            (b.clone(), &[3., 3., 9., 9.]),
+        ]));
+        state.populations_mut().current_mut().clear();
+        component.execute(&problem, &mut state).unwrap();
+        assert_eq!(state.best_objective_value().unwrap().value(), 6.);
+
+        *state.populations_mut().current_mut() = vec![individual(a.clone(), 0.)];
+        component.execute(&problem, &mut state).unwrap();
+        assert_eq!(state.best_individual().unwrap().solution(), &a);
+        assert_eq!(state.best_objective_value().unwrap().value(), 1.5);
+    }
+
+    #[test_case(0., &[4, 2]; "mean")]
+    #[test_case(2., &[3, 1]; "upper bound")]
+    fn confidence_mu_plus_lambda_ranks_by_upper_bound(z: f64, expected: &[i64]) {
+        let problem = IntegerTestProblem(vec![0..10; 2]);
+        let mut state = integer_test_state(Vec::new());
+        // Only `[3, 0]` is ranked by its objective value, and `[4, 0]` uses the pooled variance.
+        state.insert(archive(&[
+            (vec![1, 0], &[1., 1.2]),
+            (vec![2, 0], &[0., 2.]),
+            (vec![4, 0], &[0.9]),
+        ]));
+        *state.populations_mut().current_mut() =
+            vec![individual(vec![1, 0], 1.1), individual(vec![2, 0], 1.)];
+        state.populations_mut().push(vec![
+            individual(vec![3, 0], 1.05),
+            individual(vec![4, 0], 0.9),
+        ]);
+
+        let component = ConfidenceMuPlusLambda::from_params(2, z).unwrap();
+        component.execute(&problem, &mut state).unwrap();
+
+        let populations = state.populations();
+        let kept: Vec<_> = populations
+            .current()
+            .iter()
+            .map(|i| i.solution()[0])
+            .collect();
+        assert_eq!(kept, expected);
+        assert_eq!(populations.len(), 1);
+    }
+}
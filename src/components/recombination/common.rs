@@ -2,6 +2,7 @@
 
 use std::cmp::min;
 
+use eyre::ensure;
 use rand::{
     distributions::{Bernoulli, Uniform},
     seq::IteratorRandom,
@@ -15,7 +16,7 @@ use crate::{
         recombination::{functional as f, recombination, OptionalPair, Recombination},
         Component,
     },
-    problems::VectorProblem,
+    problems::{MixedValue, VectorProblem},
     state::random::Random,
     State,
 };
@@ -248,6 +249,196 @@ where
     }
 }
 
+/// Applies a simulated binary crossover (SBX) to two integer parent solutions depending on
+/// crossover probability `pc`, rounding the children to the nearest integers.
+///
+/// The distribution index `eta` controls the spread of the children around the parents,
+/// where larger values create children closer to their parents.
+///
+/// If `insert_both` is `false`, the second child is discarded.
+///
+/// # References
+///
+/// \[1\] Kalyanmoy Deb and Ram Bhushan Agrawal. 1995.
+/// Simulated Binary Crossover for Continuous Search Space.
+/// Complex Systems 9, 2 (1995), 115–148.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IntegerSimulatedBinaryCrossover {
+    /// Crossover probability.
+    pub pc: f64,
+    /// Distribution index.
+    pub eta: f64,
+    /// If `false`, the second child is discarded.
+    pub insert_both: bool,
+}
+
+impl IntegerSimulatedBinaryCrossover {
+    pub fn from_params(pc: f64, eta: f64, insert_both: bool) -> ExecResult<Self> {
+        ensure!(eta >= 0., "`eta` must not be negative");
+        Ok(Self {
+            pc,
+            eta,
+            insert_both,
+        })
+    }
+
+    pub fn new<P>(pc: f64, eta: f64, insert_both: bool) -> ExecResult<Box<dyn Component<P>>>
+    where
+        P: VectorProblem<Element = i64>,
+    {
+        Ok(Box::new(Self::from_params(pc, eta, insert_both)?))
+    }
+
+    /// Creates a new `IntegerSimulatedBinaryCrossover` which inserts both children.
+    pub fn new_insert_both<P>(pc: f64, eta: f64) -> ExecResult<Box<dyn Component<P>>>
+    where
+        P: VectorProblem<Element = i64>,
+    {
+        Self::new(pc, eta, true)
+    }
+}
+
+impl<P> Recombination<P> for IntegerSimulatedBinaryCrossover
+where
+    P: VectorProblem<Element = i64>,
+{
+    fn recombine(
+        &self,
+        parent1: &P::Encoding,
+        parent2: &P::Encoding,
+        rng: &mut Random,
+    ) -> OptionalPair<P::Encoding> {
+        if rng.gen::<f64>() <= self.pc {
+            let dim = min(parent1.len(), parent2.len());
+            let exponent = 1. / (self.eta + 1.);
+            let betas: Vec<_> = rng
+                .sample_iter(Uniform::from(0.0..1.0))
+                .take(dim)
+                .map(|u: f64| {
+                    if u <= 0.5 {
+                        (2. * u).powf(exponent)
+                    } else {
+                        (1. / (2. * (1. - u))).powf(exponent)
+                    }
+                })
+                .collect();
+            let to_f64 = |x: &[i64]| x[..dim].iter().map(|&x| x as f64).collect::<Vec<_>>();
+            let children =
+                f::simulated_binary_crossover(&to_f64(parent1), &to_f64(parent2), &betas)
+                    .map(|child| child.into_iter().map(|x| x.round() as i64).collect());
+            OptionalPair::from_pair(children, self.insert_both)
+        } else {
+            OptionalPair::None
+        }
+    }
+}
+
+impl<P> Component<P> for IntegerSimulatedBinaryCrossover
+where
+    P: VectorProblem<Element = i64>,
+{
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        recombination(self, problem, state)
+    }
+}
+
+/// Applies a crossover to two parent solutions of a [`MixedVectorProblem`] depending on
+/// crossover probability `pc`, where every variable is recombined according to its type.
+///
+/// - Continuous variables are recombined using an arithmetic crossover.
+/// - Integer variables are recombined using an arithmetic crossover, rounded to the nearest
+///   integer.
+/// - Categorical variables are swapped with a probability of 0.5, i.e. a uniform crossover.
+///
+/// If `insert_both` is `false`, the second child is discarded.
+///
+/// [`MixedVectorProblem`]: crate::problems::MixedVectorProblem
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MixedCrossover {
+    /// Crossover probability.
+    pub pc: f64,
+    /// If `false`, the second child is discarded.
+    pub insert_both: bool,
+}
+
+impl MixedCrossover {
+    pub fn from_params(pc: f64, insert_both: bool) -> Self {
+        Self { pc, insert_both }
+    }
+
+    pub fn new<P>(pc: f64, insert_both: bool) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = MixedValue>,
+    {
+        Box::new(Self::from_params(pc, insert_both))
+    }
+
+    /// Creates a new `MixedCrossover` which inserts only the first child.
+    pub fn new_insert_single<P>(pc: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = MixedValue>,
+    {
+        Self::new(pc, false)
+    }
+
+    /// Creates a new `MixedCrossover` which inserts both children.
+    pub fn new_insert_both<P>(pc: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = MixedValue>,
+    {
+        Self::new(pc, true)
+    }
+}
+
+impl<P> Recombination<P> for MixedCrossover
+where
+    P: VectorProblem<Element = MixedValue>,
+{
+    fn recombine(
+        &self,
+        parent1: &P::Encoding,
+        parent2: &P::Encoding,
+        rng: &mut Random,
+    ) -> OptionalPair<P::Encoding> {
+        if rng.gen::<f64>() > self.pc {
+            return OptionalPair::None;
+        }
+
+        let mut child1 = parent1.clone();
+        let mut child2 = parent2.clone();
+        for (x1, x2) in child1.iter_mut().zip(child2.iter_mut()) {
+            let alpha = rng.gen_range(0.0..=1.0);
+            let blend =
+                |a: f64, b: f64| (alpha * a + (1. - alpha) * b, (1. - alpha) * a + alpha * b);
+            (*x1, *x2) = match (*x1, *x2) {
+                (MixedValue::Continuous(a), MixedValue::Continuous(b)) => {
+                    let (a, b) = blend(a, b);
+                    (MixedValue::Continuous(a), MixedValue::Continuous(b))
+                }
+                (MixedValue::Integer(a), MixedValue::Integer(b)) => {
+                    let (a, b) = blend(a as f64, b as f64);
+                    (
+                        MixedValue::Integer(a.round() as i64),
+                        MixedValue::Integer(b.round() as i64),
+                    )
+                }
+                (a, b) if rng.gen_bool(0.5) => (b, a),
+                (a, b) => (a, b),
+            };
+        }
+        OptionalPair::from_pair([child1, child2], self.insert_both)
+    }
+}
+
+impl<P> Component<P> for MixedCrossover
+where
+    P: VectorProblem<Element = MixedValue>,
+{
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        recombination(self, problem, state)
+    }
+}
+
 /// Applies a cycle crossover to two parent solutions depending on crossover probability `pc`.
 ///
 /// Usually exclusive to combinatorial problems.
@@ -320,3 +511,186 @@ where
         recombination(self, problem, state)
     }
 }
+
+/// Applies a cut-and-splice crossover to two parents of possibly different length depending on
+/// crossover probability `pc`, as used by messy GAs.
+///
+/// Both parents are cut at independently chosen points, and the tails are exchanged,
+/// which changes the length of the children.
+/// The points are chosen such that both children have at least one element, i.e. parents
+/// with less than two elements are not recombined.
+/// Note that the lengths of the children are not restricted otherwise.
+///
+/// If `insert_both` is `false`, the second child is discarded.
+///
+/// # References
+///
+/// \[1\] David E. Goldberg, Kalyanmoy Deb, and Bradley Korb. 1990.
+/// Messy Genetic Algorithms Revisited: Studies in Mixed Size and Scale.
+/// Complex Systems 4, 4 (1990), 415–444.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CutAndSplice {
+    /// Crossover probability.
+    pub pc: f64,
+    /// If `false`, the second child is discarded.
+    pub insert_both: bool,
+}
+
+impl CutAndSplice {
+    pub fn from_params(pc: f64, insert_both: bool) -> Self {
+        Self { pc, insert_both }
+    }
+
+    pub fn new<P, D>(pc: f64, insert_both: bool) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = D>,
+        D: Clone,
+    {
+        Box::new(Self::from_params(pc, insert_both))
+    }
+
+    /// Creates a new `CutAndSplice` which inserts only the first child.
+    pub fn new_insert_single<P, D>(pc: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = D>,
+        D: Clone,
+    {
+        Self::new(pc, false)
+    }
+
+    /// Creates a new `CutAndSplice` which inserts both children.
+    pub fn new_insert_both<P, D>(pc: f64) -> Box<dyn Component<P>>
+    where
+        P: VectorProblem<Element = D>,
+        D: Clone,
+    {
+        Self::new(pc, true)
+    }
+}
+
+impl<P, D> Recombination<P> for CutAndSplice
+where
+    P: VectorProblem<Element = D>,
+    D: Clone,
+{
+    fn recombine(
+        &self,
+        parent1: &P::Encoding,
+        parent2: &P::Encoding,
+        rng: &mut Random,
+    ) -> OptionalPair<P::Encoding> {
+        if parent1.len() >= 2 && parent2.len() >= 2 && rng.gen::<f64>() <= self.pc {
+            let point1 = rng.gen_range(1..parent1.len());
+            let point2 = rng.gen_range(1..parent2.len());
+            let children = f::cut_and_splice(parent1, parent2, point1, point2);
+            OptionalPair::from_pair(children, self.insert_both)
+        } else {
+            OptionalPair::None
+        }
+    }
+}
+
+impl<P, D> Component<P> for CutAndSplice
+where
+    P: VectorProblem<Element = D>,
+    D: Clone,
+{
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        recombination(self, problem, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::IntegerTestProblem;
+
+    fn recombine(
+        crossover: &IntegerSimulatedBinaryCrossover,
+        parent1: &[i64],
+        parent2: &[i64],
+    ) -> OptionalPair<Vec<i64>> {
+        Recombination::<IntegerTestProblem>::recombine(
+            crossover,
+            &parent1.to_vec(),
+            &parent2.to_vec(),
+            &mut Random::new(0),
+        )
+    }
+
+    #[test]
+    fn integer_sbx_keeps_identical_parents() {
+        let crossover = IntegerSimulatedBinaryCrossover::from_params(1., 2., true).unwrap();
+        let parent = [3, -7, 12, 0];
+        let OptionalPair::Both(children) = recombine(&crossover, &parent, &parent) else {
+            panic!("expected two children");
+        };
+        assert_eq!(children, [parent.to_vec(), parent.to_vec()]);
+    }
+
+    #[test]
+    fn integer_sbx_children_are_symmetric_around_parents() {
+        let crossover = IntegerSimulatedBinaryCrossover::from_params(1., 2., true).unwrap();
+        let parent1 = vec![0; 100];
+        let parent2 = vec![10; 100];
+        let OptionalPair::Both([child1, child2]) = recombine(&crossover, &parent1, &parent2) else {
+            panic!("expected two children");
+        };
+        // The children are symmetric around the mean of the parents, up to rounding.
+        for (c1, c2) in child1.iter().zip(&child2) {
+            assert!((c1 + c2 - 10).abs() <= 1, "{c1} + {c2}");
+        }
+        assert!(child1.iter().any(|&x| x != 0 && x != 10));
+    }
+
+    #[test]
+    fn integer_sbx_respects_crossover_probability_and_insert_both() {
+        let crossover = IntegerSimulatedBinaryCrossover::from_params(0., 2., true).unwrap();
+        assert!(matches!(
+            recombine(&crossover, &[0, 0], &[1, 1]),
+            OptionalPair::None
+        ));
+
+        let crossover = IntegerSimulatedBinaryCrossover::from_params(1., 2., false).unwrap();
+        assert!(matches!(
+            recombine(&crossover, &[0, 0], &[1, 1]),
+            OptionalPair::Single(_)
+        ));
+
+        assert!(IntegerSimulatedBinaryCrossover::from_params(1., -1., true).is_err());
+    }
+
+    #[test]
+    fn cut_and_splice_exchanges_tails() {
+        let parent1 = vec![0; 5];
+        let parent2 = vec![1; 8];
+        let mut rng = Random::new(0);
+        for _ in 0..20 {
+            let OptionalPair::Both([child1, child2]) =
+                Recombination::<IntegerTestProblem>::recombine(
+                    &CutAndSplice::from_params(1., true),
+                    &parent1,
+                    &parent2,
+                    &mut rng,
+                )
+            else {
+                panic!("expected two children");
+            };
+            assert_eq!(child1.len() + child2.len(), 13);
+            assert!(child1[0] == 0 && child2[0] == 1);
+            assert!(child1.contains(&1) && child2.contains(&0));
+            assert!(child1.is_sorted() && child2.iter().rev().is_sorted());
+        }
+    }
+
+    #[test]
+    fn cut_and_splice_keeps_short_parents() {
+        let recombined = Recombination::<IntegerTestProblem>::recombine(
+            &CutAndSplice::from_params(1., true),
+            &vec![0],
+            &vec![1, 1],
+            &mut Random::new(0),
+        );
+        assert!(matches!(recombined, OptionalPair::None));
+    }
+}
@@ -54,6 +54,19 @@ where
     [child1, child2]
 }
 
+/// Applies a cut-and-splice crossover to two parents of possibly different length, cutting
+/// them at `point1` and `point2` respectively and exchanging the tails.
+#[contracts::requires(point1 <= parent1.len())]
+#[contracts::requires(point2 <= parent2.len())]
+pub fn cut_and_splice<D>(parent1: &[D], parent2: &[D], point1: usize, point2: usize) -> [Vec<D>; 2]
+where
+    D: Clone,
+{
+    let child1 = [&parent1[..point1], &parent2[point2..]].concat();
+    let child2 = [&parent2[..point2], &parent1[point1..]].concat();
+    [child1, child2]
+}
+
 /// Applies an arithmetic crossover to two parents using the `alphas` to interpolate between the elements.
 #[contracts::requires(alphas.len() >= parent1.len())]
 #[contracts::requires(alphas.len() >= parent2.len())]
@@ -69,6 +82,25 @@ pub fn arithmetic_crossover(parent1: &[f64], parent2: &[f64], alphas: &[f64]) ->
     [child1, child2]
 }
 
+/// Applies a simulated binary crossover (SBX) to two parents using the spread factors `betas`.
+#[contracts::requires(betas.len() >= parent1.len())]
+#[contracts::requires(betas.len() >= parent2.len())]
+pub fn simulated_binary_crossover(
+    parent1: &[f64],
+    parent2: &[f64],
+    betas: &[f64],
+) -> [Vec<f64>; 2] {
+    let mut child1 = parent1.to_owned();
+    let mut child2 = parent2.to_owned();
+
+    for (i, (&p1, &p2, &beta)) in multizip((parent1, parent2, betas)).enumerate() {
+        child1[i] = 0.5 * ((1. + beta) * p1 + (1. - beta) * p2);
+        child2[i] = 0.5 * ((1. - beta) * p1 + (1. + beta) * p2);
+    }
+
+    [child1, child2]
+}
+
 /// Applies a cycle crossover to two parents.
 #[contracts::requires(parent1.len() == parent2.len())]
 #[contracts::requires(valid_permutation(parent1))]
@@ -78,15 +110,13 @@ pub fn cycle_crossover<D: Clone + Ord + PartialEq>(parent1: &[D], parent2: &[D])
     let mut child2 = Vec::new();
 
     let mut cycles = vec![-1; parent1.len()];
-    let mut cycle_number = 1;
     let cycle_start: Vec<_> = (0..cycles.len()).collect();
 
-    for mut pos in cycle_start {
+    for (cycle_number, mut pos) in (1..).zip(cycle_start) {
         while cycles[pos] < 0 {
             cycles[pos] = cycle_number;
             pos = parent1.iter().position(|r| r == &parent2[pos]).unwrap();
         }
-        cycle_number += 1;
     }
 
     for (p1, p2, n) in multizip((parent1, parent2, cycles)) {
@@ -141,6 +171,17 @@ mod tests {
         uniform_crossover(parent1, parent2, mask)
     }
 
+    #[test_case(&[0, 0, 0], &[1, 1, 1, 1, 1], 1, 3 => [vec![0, 1, 1], vec![1, 1, 1, 0, 0]]; "when different lengths")]
+    #[test_case(&[0, 0, 0], &[1, 1], 3, 0 => [vec![0, 0, 0, 1, 1], vec![]]; "when cut at ends")]
+    fn cut_and_splice_returns_correct_children(
+        parent1: &[usize],
+        parent2: &[usize],
+        point1: usize,
+        point2: usize,
+    ) -> [Vec<usize>; 2] {
+        cut_and_splice(parent1, parent2, point1, point2)
+    }
+
     #[test_case(&[0., 0., 0., 0., 0.], &[1., 1., 1., 1., 1.], &[0.5, 0.5, 0.5, 0.5, 0.5] => using assert_array_floats_eq([vec![0.5, 0.5, 0.5, 0.5, 0.5], vec![0.5, 0.5, 0.5, 0.5, 0.5]]); "when alpha uniform")]
     #[test_case(&[0., 0., 0., 0., 0.], &[1., 1., 1., 1., 1.], &[0.3, 0.3, 0.3, 0.3, 0.3] => using assert_array_floats_eq([vec![0.7, 0.7, 0.7, 0.7, 0.7], vec![0.3, 0.3, 0.3, 0.3, 0.3]]); "when alpha not uniform")]
     #[test_case(&[0., 0., 0., 0., 0.], &[1., 1., 1., 1., 1.], &[0.5, 0.3, 0.7, 0.1, 0.9] => using assert_array_floats_eq([vec![0.5, 0.7, 0.3, 0.9, 0.1], vec![0.5, 0.3, 0.7, 0.1, 0.9]]); "when alpha different")]
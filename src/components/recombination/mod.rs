@@ -11,7 +11,10 @@ pub mod common;
 pub mod de;
 pub mod functional;
 
-pub use common::{ArithmeticCrossover, CycleCrossover, NPointCrossover, UniformCrossover};
+pub use common::{
+    ArithmeticCrossover, CutAndSplice, CycleCrossover, IntegerSimulatedBinaryCrossover,
+    MixedCrossover, NPointCrossover, UniformCrossover,
+};
 
 /// Represents either no, one, or two elements.
 pub enum OptionalPair<T> {
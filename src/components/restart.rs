@@ -0,0 +1,654 @@
+//! Restart strategies, re-initializing the search when it stagnated or converged.
+//!
+//! The [`Restart`] component executes a [`Regime`] until a trigger condition fires, after which
+//! it re-initializes the search with the next regime of a [`RestartSchedule`].
+//! Regimes may differ between restarts, e.g. [`IncreasingPopulation`] doubles the population size
+//! with every restart (IPOP) \[1\], and [`Alternating`] cycles through a fixed list of regimes,
+//! e.g. alternating between large and small populations (BIPOP) \[2\].
+//!
+//! # References
+//!
+//! \[1\] Anne Auger and Nikolaus Hansen. 2005.
+//! A Restart CMA Evolution Strategy With Increasing Population Size.
+//! In 2005 IEEE Congress on Evolutionary Computation, 1769–1776.
+//! DOI:<https://doi.org/10.1109/CEC.2005.1554902>
+//!
+//! \[2\] Nikolaus Hansen. 2009.
+//! Benchmarking a BI-Population CMA-ES on the BBOB-2009 Function Testbed.
+//! In Proceedings of the 11th Annual Conference Companion on Genetic and Evolutionary Computation
+//! Conference: Late Breaking Papers, 2389–2396.
+//! DOI:<https://doi.org/10.1145/1570256.1570333>
+
+use better_any::{Tid, TidAble};
+use derivative::Derivative;
+use derive_more::{Deref, DerefMut};
+use dyn_clone::DynClone;
+use erased_serde::Serialize as DynSerialize;
+use eyre::ensure;
+use serde::Serialize;
+
+use crate::{
+    component::ExecResult,
+    components::Component,
+    conditions::Condition,
+    configuration::tree::{self, Edge, EdgeMut},
+    problems::SingleObjectiveProblem,
+    state::{common, StateReq},
+    CustomState, Problem, State,
+};
+
+/// The number of restarts performed by a [`Restart`].
+#[derive(Clone, Default, Deref, DerefMut, Serialize, Tid)]
+pub struct Restarts(pub u32);
+
+impl CustomState<'_> for Restarts {}
+
+/// A sub-configuration executed between two restarts.
+///
+/// The `start` is executed once after (re-)initialization, e.g. to initialize and evaluate
+/// a new population, and the `step` is executed once per iteration, similar to the body of
+/// a [`Loop`].
+///
+/// [`Loop`]: crate::components::Loop
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct Regime<P: Problem> {
+    /// Executed once at the beginning of the regime.
+    pub start: Box<dyn Component<P>>,
+    /// Executed once per iteration.
+    pub step: Box<dyn Component<P>>,
+}
+
+impl<P: Problem> Regime<P> {
+    /// Creates a new `Regime` from the `start` and `step` components.
+    pub fn new(
+        start: impl Into<Box<dyn Component<P>>>,
+        step: impl Into<Box<dyn Component<P>>>,
+    ) -> Self {
+        Self {
+            start: start.into(),
+            step: step.into(),
+        }
+    }
+}
+
+/// Trait for representing the schedule of [`Regime`]s of a [`Restart`].
+pub trait RestartSchedule<P: Problem>: DynClone + DynSerialize + Send + Sync {
+    /// Returns the regime executed after the `restart`-th restart, starting with 0.
+    fn regime(&self, restart: u32) -> Regime<P>;
+
+    /// Returns the regimes stored in the schedule, which are the children of the [`Restart`]
+    /// in the component tree.
+    ///
+    /// Defaults to no regimes, which is appropriate for schedules creating their regimes
+    /// on demand, e.g. [`IncreasingPopulation`].
+    fn regimes(&self) -> Vec<&Regime<P>> {
+        Vec::new()
+    }
+
+    /// Returns the regimes stored in the schedule mutably, in the same order as [`regimes`].
+    ///
+    /// [`regimes`]: RestartSchedule::regimes
+    fn regimes_mut(&mut self) -> Vec<&mut Regime<P>> {
+        Vec::new()
+    }
+}
+
+dyn_clone::clone_trait_object!(<P: Problem> RestartSchedule<P>);
+erased_serde::serialize_trait_object!(<P: Problem> RestartSchedule<P>);
+
+/// Cycles through a list of [`Regime`]s with every restart.
+///
+/// A single regime restarts the same sub-configuration over and over.
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct Alternating<P: Problem> {
+    regimes: Vec<Regime<P>>,
+}
+
+impl<P: Problem> Alternating<P> {
+    pub fn from_params(regimes: Vec<Regime<P>>) -> ExecResult<Self> {
+        ensure!(!regimes.is_empty(), "at least one regime is required");
+        Ok(Self { regimes })
+    }
+
+    pub fn new(regimes: Vec<Regime<P>>) -> ExecResult<Box<dyn RestartSchedule<P>>> {
+        Ok(Box::new(Self::from_params(regimes)?))
+    }
+}
+
+impl<P: Problem> RestartSchedule<P> for Alternating<P> {
+    fn regime(&self, restart: u32) -> Regime<P> {
+        self.regimes[restart as usize % self.regimes.len()].clone()
+    }
+
+    fn regimes(&self) -> Vec<&Regime<P>> {
+        self.regimes.iter().collect()
+    }
+
+    fn regimes_mut(&mut self) -> Vec<&mut Regime<P>> {
+        self.regimes.iter_mut().collect()
+    }
+}
+
+/// Increases the population size by a constant `factor` with every restart (IPOP) \[1\].
+///
+/// The [`Regime`] for a population size is created by the `regime` function.
+///
+/// See the [module documentation](self) for references.
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct IncreasingPopulation<P: Problem> {
+    /// The population size of the first regime.
+    pub population_size: u32,
+    /// The factor the population size is multiplied with on every restart.
+    pub factor: f64,
+    #[serde(skip)]
+    regime: fn(u32) -> Regime<P>,
+}
+
+impl<P: Problem> IncreasingPopulation<P> {
+    pub fn from_params(
+        population_size: u32,
+        factor: f64,
+        regime: fn(u32) -> Regime<P>,
+    ) -> ExecResult<Self> {
+        ensure!(
+            population_size > 0,
+            "population_size must be greater than 0"
+        );
+        ensure!(factor >= 1., "factor must be at least 1");
+        Ok(Self {
+            population_size,
+            factor,
+            regime,
+        })
+    }
+
+    pub fn new(
+        population_size: u32,
+        factor: f64,
+        regime: fn(u32) -> Regime<P>,
+    ) -> ExecResult<Box<dyn RestartSchedule<P>>> {
+        Ok(Box::new(Self::from_params(
+            population_size,
+            factor,
+            regime,
+        )?))
+    }
+
+    /// Returns the population size after the `restart`-th restart.
+    pub fn population_size(&self, restart: u32) -> u32 {
+        (self.population_size as f64 * self.factor.powf(restart as f64)).round() as u32
+    }
+}
+
+impl<P: Problem> RestartSchedule<P> for IncreasingPopulation<P> {
+    fn regime(&self, restart: u32) -> Regime<P> {
+        (self.regime)(self.population_size(restart))
+    }
+}
+
+/// The state preserved across restarts.
+struct Preserved<P: SingleObjectiveProblem + 'static> {
+    iterations: Option<u32>,
+    evaluations: Option<u32>,
+    best: Option<common::BestIndividual<P>>,
+}
+
+impl<P: SingleObjectiveProblem> Preserved<P> {
+    fn save(state: &mut State<P>) -> Self {
+        Self {
+            iterations: state.try_get_value::<common::Iterations>().ok(),
+            evaluations: state.try_get_value::<common::Evaluations>().ok(),
+            best: state.remove::<common::BestIndividual<P>>().ok(),
+        }
+    }
+
+    fn restore(self, state: &mut State<P>) {
+        if let Some(iterations) = self.iterations {
+            state.insert(common::Iterations(iterations));
+        }
+        if let Some(evaluations) = self.evaluations {
+            state.insert(common::Evaluations(evaluations));
+        }
+        if let Some(best) = self.best {
+            state.insert(best);
+        }
+    }
+}
+
+/// Executes [`Regime`]s while the `condition` is `true`, restarting with the next regime of the
+/// `schedule` whenever the `trigger` evaluates to `true`.
+///
+/// This is equivalent to:
+/// ```no_run
+/// # fn condition() -> bool { true }
+/// # fn trigger() -> bool { true }
+/// # fn schedule(_restart: u32) -> (fn(), fn()) { unimplemented!() }
+/// let mut restart = 0;
+/// 'restarts: while condition() {
+///     let (start, step) = schedule(restart);
+///     start();
+///     loop {
+///         if !condition() {
+///             break 'restarts;
+///         }
+///         if trigger() {
+///             break;
+///         }
+///         step();
+///     }
+///     restart += 1;
+/// }
+/// ```
+///
+/// # Call propagation
+///
+/// Calling any of the `{init, require}` methods on a restart calls the specific method once on
+/// the `condition`, the `trigger`, and the components of every regime stored in the `schedule`,
+/// or of the first regime if the schedule creates its regimes on demand.
+///
+/// # Component tree
+///
+/// The children of a restart are the `condition`, the `trigger`, and the components of the
+/// regimes stored in the `schedule` (see [`RestartSchedule::regimes`]), labeled `Restart.start[i]`
+/// and `Restart.step[i]` for the `i`-th regime.
+/// Regimes created on demand, e.g. by [`IncreasingPopulation`], are not part of the tree, and
+/// issues found while validating them are reported at the path of the restart itself.
+///
+/// # State
+///
+/// On every restart, the current population is discarded, and the components of the new regime
+/// and the `trigger` are (re-)initialized.
+/// The [`Iterations`], [`Evaluations`], and [`BestIndividual`] are preserved, as well as
+/// the [`Log`], which is not touched by initialization.
+/// This means that the `condition` and the log refer to the global progress over all restarts.
+///
+/// Note that this also holds for the `trigger`, e.g. [`Stagnation`] considers the overall best
+/// individual, i.e. fires if a regime can't improve it.
+/// To detect the convergence of the current regime, use conditions on the population,
+/// e.g. [`ObjectiveStdDevBelow`].
+///
+/// This component inserts and updates the [`Iterations`] like a [`Loop`], and the number of
+/// [`Restarts`].
+///
+/// [`Iterations`]: common::Iterations
+/// [`Evaluations`]: common::Evaluations
+/// [`BestIndividual`]: common::BestIndividual
+/// [`Log`]: crate::logging::Log
+/// [`Stagnation`]: crate::conditions::Stagnation
+/// [`ObjectiveStdDevBelow`]: crate::conditions::ObjectiveStdDevBelow
+/// [`Loop`]: crate::components::Loop
+///
+/// # Examples
+///
+/// A genetic algorithm restarting with doubled population size whenever the population
+/// converged (IPOP):
+///
+/// ```
+/// use mahf::{
+///     components::{
+///         initialization, mutation,
+///         restart::{IncreasingPopulation, Regime, Restart},
+///         replacement, selection,
+///     },
+///     conditions::{LessThanN, ObjectiveStdDevBelow},
+///     problems::LimitedVectorProblem,
+///     Configuration, ExecResult, SingleObjectiveProblem,
+/// };
+///
+/// fn regime<P>(population_size: u32) -> Regime<P>
+/// where
+///     P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+/// {
+///     Regime::new(
+///         Configuration::builder()
+///             .do_(initialization::RandomSpread::new(population_size))
+///             .evaluate()
+///             .update_best_individual()
+///             .build_component(),
+///         Configuration::builder()
+///             .do_(selection::Tournament::new(population_size, 3))
+///             .do_(mutation::NormalMutation::new_dev(0.1))
+///             .evaluate()
+///             .update_best_individual()
+///             .do_(replacement::MuPlusLambda::new(population_size))
+///             .build_component(),
+///     )
+/// }
+///
+/// # fn example<P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>>() -> ExecResult<Configuration<P>> {
+/// # Ok(
+/// Configuration::builder()
+///     .do_(Restart::new(
+///         LessThanN::evaluations(100_000),
+///         ObjectiveStdDevBelow::new(1e-8),
+///         IncreasingPopulation::new(20, 2., regime::<P>)?,
+///     ))
+///     .build()
+/// # )
+/// # }
+/// ```
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct Restart<P: Problem> {
+    condition: Box<dyn Condition<P>>,
+    trigger: Box<dyn Condition<P>>,
+    schedule: Box<dyn RestartSchedule<P>>,
+}
+
+impl<P: SingleObjectiveProblem> Restart<P> {
+    pub fn from_params(
+        condition: Box<dyn Condition<P>>,
+        trigger: Box<dyn Condition<P>>,
+        schedule: Box<dyn RestartSchedule<P>>,
+    ) -> Self {
+        Self {
+            condition,
+            trigger,
+            schedule,
+        }
+    }
+
+    pub fn new(
+        condition: Box<dyn Condition<P>>,
+        trigger: Box<dyn Condition<P>>,
+        schedule: Box<dyn RestartSchedule<P>>,
+    ) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(condition, trigger, schedule))
+    }
+
+    /// Re-initializes the `state` for the `regime`, preserving the global progress.
+    fn start(&self, problem: &P, state: &mut State<P>, regime: &Regime<P>) -> ExecResult<()> {
+        let preserved = Preserved::save(state);
+        regime.start.init(problem, state)?;
+        regime.step.init(problem, state)?;
+        self.trigger.init(problem, state)?;
+        preserved.restore(state);
+
+        let requirements = state.requirements();
+        regime.start.require(problem, &requirements)?;
+        regime.step.require(problem, &requirements)?;
+
+        regime.start.execute(problem, state)
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for Restart<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(common::Iterations(0));
+        state.insert(Restarts(0));
+
+        tree::init_children(self.children(), problem, state)?;
+        if self.schedule.regimes().is_empty() {
+            let regime = self.schedule.regime(0);
+            regime.start.init(problem, state)?;
+            regime.step.init(problem, state)?;
+        }
+
+        Ok(())
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.claim::<Self, common::Iterations>();
+        tree::require_children(self.children(), problem, state_req)?;
+        if self.schedule.regimes().is_empty() {
+            let regime = self.schedule.regime(0);
+            regime.start.require(problem, state_req)?;
+            regime.step.require(problem, state_req)?;
+        }
+        Ok(())
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let depth = state.populations().len();
+        self.condition.init(problem, state)?;
+
+        'restarts: while self.condition.evaluate(problem, state)? {
+            while state.populations().len() > depth {
+                state.populations_mut().pop();
+            }
+
+            let regime = self.schedule.regime(state.get_value::<Restarts>());
+            self.start(problem, state, &regime)?;
+
+            loop {
+                if !self.condition.evaluate(problem, state)? {
+                    break 'restarts;
+                }
+                if self.trigger.evaluate(problem, state)? {
+                    break;
+                }
+                regime.step.execute(problem, state)?;
+                *state.try_borrow_value_mut::<common::Iterations>()? += 1;
+            }
+
+            *state.borrow_value_mut::<Restarts>() += 1;
+        }
+        Ok(())
+    }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        let mut children = vec![
+            Edge::condition("Restart.condition", self.condition.as_ref()),
+            Edge::condition("Restart.trigger", self.trigger.as_ref()),
+        ];
+        for (i, regime) in self.schedule.regimes().into_iter().enumerate() {
+            children.push(Edge::component(
+                format!("Restart.start[{i}]"),
+                regime.start.as_ref(),
+            ));
+            children.push(Edge::component(
+                format!("Restart.step[{i}]"),
+                regime.step.as_ref(),
+            ));
+        }
+        children
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        let mut children = vec![
+            EdgeMut::condition("Restart.condition", &mut self.condition),
+            EdgeMut::condition("Restart.trigger", &mut self.trigger),
+        ];
+        for (i, regime) in self.schedule.regimes_mut().into_iter().enumerate() {
+            children.push(EdgeMut::component(
+                format!("Restart.start[{i}]"),
+                &mut regime.start,
+            ));
+            children.push(EdgeMut::component(
+                format!("Restart.step[{i}]"),
+                &mut regime.step,
+            ));
+        }
+        children
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::{measures::diversity::DimensionWiseDiversity, utils::Noop},
+        conditions::{DiversityBelow, LessThanN},
+        testing::*,
+        Configuration,
+    };
+
+    type P = SingleObjectiveTestProblem;
+
+    /// The values recorded by [`Record`].
+    #[derive(Default, Deref, DerefMut, Tid)]
+    struct Recorded(Vec<f64>);
+
+    impl CustomState<'_> for Recorded {}
+
+    /// Records the population size of its regime and the best objective value on execution.
+    ///
+    /// Clears the best individual on initialization, like `BestIndividualUpdate`.
+    #[derive(Clone, Serialize)]
+    struct Record(u32);
+
+    impl Record {
+        fn new(population_size: u32) -> Box<dyn Component<P>> {
+            Box::new(Self(population_size))
+        }
+    }
+
+    impl Component<P> for Record {
+        fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+            state.insert(common::BestIndividual::<P>::default());
+            Ok(())
+        }
+
+        fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+            let best = state.best_objective_value().map_or(f64::NAN, |o| o.value());
+            state.borrow_mut::<Recorded>().extend([self.0 as f64, best]);
+            Ok(())
+        }
+    }
+
+    /// The number of evaluations of [`AfterSteps`] since its initialization.
+    #[derive(Default, Deref, DerefMut, Tid)]
+    struct Steps(u32);
+
+    impl CustomState<'_> for Steps {}
+
+    /// Evaluates to `true` after `n` evaluations since its initialization.
+    #[derive(Clone, Serialize)]
+    struct AfterSteps(u32);
+
+    impl Condition<P> for AfterSteps {
+        fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+            state.insert(Steps(0));
+            Ok(())
+        }
+
+        fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+            let mut steps = state.borrow_mut::<Steps>();
+            **steps += 1;
+            Ok(**steps > self.0)
+        }
+    }
+
+    fn run(schedule: Box<dyn RestartSchedule<P>>) -> State<'static, P> {
+        let problem = P::new();
+        let mut state = test_state::<P>(Vec::new());
+        state.insert(Recorded::default());
+
+        let restart =
+            Restart::from_params(LessThanN::iterations(6), Box::new(AfterSteps(2)), schedule);
+        restart.init(&problem, &mut state).unwrap();
+        // The best individual found before the restart is executed.
+        state
+            .borrow_mut::<common::BestIndividual<P>>()
+            .update(&single_test_individual(5.));
+        restart.execute(&problem, &mut state).unwrap();
+        state
+    }
+
+    #[test]
+    fn stored_regimes_are_children_matching_validation_paths() {
+        let invalid = Configuration::<P>::builder()
+            .if_(
+                DiversityBelow::<DimensionWiseDiversity>::new(0.1),
+                |builder| builder,
+            )
+            .build_component();
+        let config = Configuration::new(Restart::new(
+            LessThanN::iterations(10),
+            LessThanN::iterations(5),
+            Alternating::new(vec![
+                Regime::new(Noop::new(), Noop::new()),
+                Regime::new(Noop::new(), invalid),
+            ])
+            .unwrap(),
+        ));
+
+        let mut paths = Vec::new();
+        config.walk(|path, _| paths.push(path.clone()));
+        let labels: Vec<_> = paths
+            .iter()
+            .filter(|path| path.segments().len() == 1)
+            .map(|path| path.segments()[0].as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "Restart.condition",
+                "Restart.trigger",
+                "Restart.start[0]",
+                "Restart.step[0]",
+                "Restart.start[1]",
+                "Restart.step[1]",
+            ]
+        );
+
+        let report = config.validate(&P::new());
+        assert_eq!(report.issues().len(), 1, "{report}");
+        let path = report.issues()[0].path();
+        assert_eq!(path.segments()[0], "Restart.step[1]");
+        assert!(paths.contains(path));
+    }
+
+    #[test]
+    fn generated_regimes_are_not_children() {
+        let config = Configuration::new(Restart::new(
+            LessThanN::iterations(10),
+            LessThanN::iterations(5),
+            IncreasingPopulation::new(10, 2., |_| Regime::new(Noop::new(), Noop::new())).unwrap(),
+        ));
+        let mut paths = Vec::new();
+        config.walk(|path, _| paths.push(path.to_string()));
+        assert_eq!(paths, ["<root>", "Restart.condition", "Restart.trigger"]);
+        assert!(config.validate(&P::new()).is_valid());
+    }
+
+    #[test]
+    fn restarts_are_counted_and_preserve_progress() {
+        let schedule = Alternating::new(vec![Regime::new(Record::new(1), Noop::new())]).unwrap();
+        let state = run(schedule);
+
+        // Every regime performs two steps, so the third regime is cut off by the condition.
+        assert_eq!(state.get_value::<Restarts>(), 2);
+        assert_eq!(state.get_value::<common::Iterations>(), 6);
+        // The best individual survives the re-initialization of every regime.
+        assert_eq!(**state.borrow::<Recorded>(), [1., 5., 1., 5., 1., 5.]);
+        assert_eq!(state.best_objective_value().unwrap().value(), 5.);
+    }
+
+    #[test]
+    fn increasing_population_grows_with_every_restart() {
+        let schedule = IncreasingPopulation::from_params(10, 1.5, |n| {
+            Regime::new(Record::new(n), Noop::new())
+        })
+        .unwrap();
+        assert_eq!(
+            (0..4)
+                .map(|r| schedule.population_size(r))
+                .collect::<Vec<_>>(),
+            [10, 15, 23, 34]
+        );
+
+        let state = run(Box::new(schedule));
+        let sizes: Vec<_> = state
+            .borrow::<Recorded>()
+            .iter()
+            .step_by(2)
+            .copied()
+            .collect();
+        assert_eq!(sizes, [10., 15., 23.]);
+    }
+
+    #[test]
+    fn increasing_population_rejects_invalid_params() {
+        let regime = |_| Regime::<P>::new(Noop::new(), Noop::new());
+        assert!(IncreasingPopulation::from_params(0, 2., regime).is_err());
+        assert!(IncreasingPopulation::from_params(10, 0.5, regime).is_err());
+    }
+}
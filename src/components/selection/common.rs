@@ -369,6 +369,50 @@ impl<P: SingleObjectiveProblem> Component<P> for Tournament {
     }
 }
 
+/// Selects the `num_selected` best individuals without replacement.
+///
+/// # Errors
+///
+/// Returns an `Err` if there is not a sufficient amount of solutions to choose from.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Truncation {
+    /// Number of selected individuals.
+    pub num_selected: u32,
+}
+
+impl Truncation {
+    pub fn from_params(num_selected: u32) -> Self {
+        Self { num_selected }
+    }
+
+    pub fn new<P: SingleObjectiveProblem>(num_selected: u32) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(num_selected))
+    }
+}
+
+impl<P: SingleObjectiveProblem> Selection<P> for Truncation {
+    fn select<'a>(
+        &self,
+        population: &'a [Individual<P>],
+        _rng: &mut Random,
+    ) -> ExecResult<Vec<&'a Individual<P>>> {
+        ensure!(
+            population.len() >= self.num_selected as usize,
+            "population size must be equal to or greater than the number of selected individuals"
+        );
+        let mut selection: Vec<_> = population.iter().collect();
+        selection.sort_by_key(|i| i.objective());
+        selection.truncate(self.num_selected as usize);
+        Ok(selection)
+    }
+}
+
+impl<P: SingleObjectiveProblem> Component<P> for Truncation {
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        selection(self, problem, state)
+    }
+}
+
 /// Selects `num_selected` solutions with replacement using linear ranking.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct LinearRank {
@@ -15,7 +15,7 @@ pub mod iwo;
 
 pub use common::{
     All, CloneSingle, ExponentialRank, FullyRandom, LinearRank, None, RandomWithoutRepetition,
-    RouletteWheel, StochasticUniversalSampling, Tournament,
+    RouletteWheel, StochasticUniversalSampling, Tournament, Truncation,
 };
 
 /// Trait for representing a component that selects a subset of a population.
@@ -0,0 +1,557 @@
+//! Asynchronous steady-state evolution.
+//!
+//! In a synchronous (generational) loop, all offspring of a generation are evaluated before
+//! the next generation starts, so workers idle while waiting for the slowest evaluation.
+//! In an asynchronous steady-state loop, every worker evaluates individuals independently,
+//! and each evaluated individual is inserted into the population as soon as it completes,
+//! after which a new individual is generated for the now idle worker.
+//!
+//! # References
+//!
+//! \[1\] Eric O. Scott and Kenneth A. De Jong. 2015.
+//! Understanding Simple Asynchronous Evolutionary Algorithms.
+//! In Proceedings of the 2015 ACM Conference on Foundations of Genetic Algorithms XIII (FOGA '15), 85–98.
+//! DOI:<https://doi.org/10.1145/2725494.2725509>
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use derivative::Derivative;
+use eyre::{ensure, ContextCompat};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::ExecResult,
+    components::Component,
+    conditions::Condition,
+    configuration::tree::{self, Edge, EdgeMut},
+    problems::{Evaluate, ObjectiveFunction, Sequential, WorkerPool},
+    state::{common, StateReq},
+    Individual, Problem, State,
+};
+
+/// The order in which evaluated individuals are inserted by an [`AsyncSteadyState`] loop.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum InsertionOrder {
+    /// Individuals are inserted as soon as their evaluation completes.
+    ///
+    /// This maximizes the utilization of the workers, but the results depend on the
+    /// evaluation times, and are therefore not reproducible.
+    Completion,
+    /// Individuals are inserted in the order they were generated, waiting for evaluations
+    /// to complete if necessary.
+    ///
+    /// This makes the results reproducible, at the cost of some idle time of the workers.
+    Submission,
+}
+
+/// Asynchronous steady-state loop, evaluating individuals on a pool of `workers` threads.
+///
+/// The `generate` component is expected to push a population of (unevaluated) offspring
+/// onto the stack, which are submitted to the workers.
+/// New offspring are generated as long as fewer than `workers` individuals are being evaluated.
+///
+/// Every evaluated individual is pushed as a population of size one, after which the
+/// `insert` component is executed, which is expected to merge it into the parent population,
+/// e.g. using [`MuPlusLambda`].
+/// The [`InsertionOrder`] determines when an individual is inserted.
+///
+/// The loop runs while the `condition` is `true`, where each insertion counts as one iteration.
+/// Individuals still being evaluated when the loop terminates are discarded, but the loop
+/// waits for their evaluations to complete, such that they are counted.
+///
+/// Individuals are evaluated on a [`WorkerPool`], where every worker uses its own evaluator,
+/// i.e. the evaluator in the [`State`] is **not** used.
+/// [`new`] evaluates sequentially using [`ObjectiveFunction::objective`], while
+/// [`new_with_evaluator`] allows to create any [`Evaluate`] implementation per worker.
+/// Note that any state of these evaluators (e.g. a cache) is local to the worker.
+///
+/// [`new`]: AsyncSteadyState::new
+/// [`new_with_evaluator`]: AsyncSteadyState::new_with_evaluator
+///
+/// [`MuPlusLambda`]: crate::components::replacement::MuPlusLambda
+///
+/// # State
+///
+/// This component inserts and updates the current number of [`Iterations`], and updates the
+/// [`Evaluations`] for every inserted or discarded individual if present, as counted by the
+/// evaluator (see [`Evaluate::counted_evaluations`]).
+///
+/// [`Iterations`]: common::Iterations
+/// [`Evaluations`]: common::Evaluations
+///
+/// # Call propagation
+///
+/// Calling any of the `{init, require}` methods calls the specific method once on the
+/// `condition`, `generate`, and `insert` components.
+///
+/// # Examples
+///
+/// A steady-state GA with tournament selection on 8 workers:
+///
+/// ```
+/// # use mahf::{Component, ExecResult, SingleObjectiveProblem, problems::{ObjectiveFunction, LimitedVectorProblem}};
+/// use mahf::{
+///     components::{
+///         evaluation::BestIndividualUpdate,
+///         mutation, recombination, replacement, selection,
+///         steady_state::{AsyncSteadyState, InsertionOrder},
+///     },
+///     conditions::LessThanN,
+///     Configuration,
+/// };
+///
+/// # fn example<P>() -> ExecResult<Box<dyn Component<P>>>
+/// # where P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64> + ObjectiveFunction + Sync {
+/// # Ok(
+/// AsyncSteadyState::new(
+///     LessThanN::evaluations(10_000),
+///     Configuration::builder()
+///         .do_(selection::Tournament::new(2, 3))
+///         .do_(recombination::UniformCrossover::new_insert_single(1.))
+///         .do_(mutation::NormalMutation::new_dev(0.1))
+///         .build_component(),
+///     Configuration::builder()
+///         .do_(BestIndividualUpdate::new())
+///         .do_(replacement::MuPlusLambda::new(100))
+///         .build_component(),
+///     8,
+///     InsertionOrder::Submission,
+/// )?
+/// # )
+/// # }
+/// ```
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct AsyncSteadyState<P: Problem> {
+    #[serde(rename = "while")]
+    condition: Box<dyn Condition<P>>,
+    generate: Box<dyn Component<P>>,
+    insert: Box<dyn Component<P>>,
+    /// The number of worker threads.
+    pub workers: usize,
+    /// The order of insertion.
+    pub order: InsertionOrder,
+    /// Creates the evaluator of each worker.
+    #[serde(skip)]
+    evaluator: Arc<EvaluatorFactory<P>>,
+}
+
+/// Creates a new evaluator for a worker of an [`AsyncSteadyState`] loop.
+pub type EvaluatorFactory<P> = dyn Fn() -> Box<dyn Evaluate<Problem = P>> + Send + Sync;
+
+impl<P: Problem + Sync> AsyncSteadyState<P> {
+    pub fn from_params(
+        condition: Box<dyn Condition<P>>,
+        generate: Box<dyn Component<P>>,
+        insert: Box<dyn Component<P>>,
+        workers: usize,
+        order: InsertionOrder,
+        evaluator: Arc<EvaluatorFactory<P>>,
+    ) -> ExecResult<Self> {
+        ensure!(workers > 0, "`workers` must be greater than 0");
+        Ok(Self {
+            condition,
+            generate,
+            insert,
+            workers,
+            order,
+            evaluator,
+        })
+    }
+
+    /// Creates a new `AsyncSteadyState` loop, where every worker uses an evaluator
+    /// created by `evaluator`.
+    pub fn new_with_evaluator<F>(
+        condition: Box<dyn Condition<P>>,
+        generate: Box<dyn Component<P>>,
+        insert: Box<dyn Component<P>>,
+        workers: usize,
+        order: InsertionOrder,
+        evaluator: F,
+    ) -> ExecResult<Box<dyn Component<P>>>
+    where
+        F: Fn() -> Box<dyn Evaluate<Problem = P>> + Send + Sync + 'static,
+    {
+        Ok(Box::new(Self::from_params(
+            condition,
+            generate,
+            insert,
+            workers,
+            order,
+            Arc::new(evaluator),
+        )?))
+    }
+
+    /// Generates new offspring and submits them to the `pool` until `in_flight` is at least
+    /// the number of workers.
+    fn refill(
+        &self,
+        problem: &P,
+        state: &mut State<P>,
+        pool: &WorkerPool<P>,
+        next_id: &mut usize,
+        in_flight: &mut usize,
+    ) -> ExecResult<()> {
+        while *in_flight < self.workers {
+            self.generate.execute(problem, state)?;
+            let offspring = state.populations_mut().pop();
+            ensure!(!offspring.is_empty(), "`generate` produced no offspring");
+            for individual in offspring {
+                pool.submit(*next_id, individual);
+                *next_id += 1;
+                *in_flight += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts the evaluated `individual` into the population.
+    fn insert(
+        &self,
+        problem: &P,
+        state: &mut State<P>,
+        individual: Individual<P>,
+        evaluations: usize,
+    ) -> ExecResult<()> {
+        state.populations_mut().push(vec![individual]);
+        self.insert.execute(problem, state)?;
+        count_evaluations(state, evaluations);
+        *state.try_borrow_value_mut::<common::Iterations>()? += 1;
+        Ok(())
+    }
+}
+
+/// Adds `evaluations` to the [`Evaluations`] of the `state`, if present.
+///
+/// [`Evaluations`]: common::Evaluations
+fn count_evaluations<P: Problem>(state: &mut State<P>, evaluations: usize) {
+    if state.contains::<common::Evaluations>() {
+        *state.borrow_value_mut::<common::Evaluations>() += evaluations as u32;
+    }
+}
+
+impl<P: ObjectiveFunction + Sync> AsyncSteadyState<P> {
+    /// Creates a new `AsyncSteadyState` loop, where every worker evaluates individuals
+    /// sequentially using [`ObjectiveFunction::objective`].
+    pub fn new(
+        condition: Box<dyn Condition<P>>,
+        generate: Box<dyn Component<P>>,
+        insert: Box<dyn Component<P>>,
+        workers: usize,
+        order: InsertionOrder,
+    ) -> ExecResult<Box<dyn Component<P>>> {
+        Self::new_with_evaluator(condition, generate, insert, workers, order, || {
+            Box::new(Sequential::<P>::new())
+        })
+    }
+}
+
+impl<P: Problem + Sync> Component<P> for AsyncSteadyState<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(common::Iterations(0));
+        tree::init_children(self.children(), problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.claim::<Self, common::Iterations>();
+        tree::require_children(self.children(), problem, state_req)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        self.condition.init(problem, state)?;
+
+        std::thread::scope(|scope| {
+            let pool = WorkerPool::spawn(scope, problem, self.workers, &*self.evaluator);
+            let mut next_id = 0;
+            let mut in_flight = 0;
+
+            // Completed individuals waiting for insertion in `InsertionOrder::Submission`.
+            let mut completed = BTreeMap::new();
+            let mut next_insertion = 0;
+
+            while self.condition.evaluate(problem, state)? {
+                self.refill(problem, state, &pool, &mut next_id, &mut in_flight)?;
+
+                let (individual, evaluations) = match self.order {
+                    InsertionOrder::Completion => {
+                        let (_, individual, evaluations) =
+                            pool.receive().wrap_err("all workers stopped")?;
+                        (individual, evaluations)
+                    }
+                    InsertionOrder::Submission => {
+                        while !completed.contains_key(&next_insertion) {
+                            let (id, individual, evaluations) =
+                                pool.receive().wrap_err("all workers stopped")?;
+                            completed.insert(id, (individual, evaluations));
+                        }
+                        next_insertion += 1;
+                        completed.remove(&(next_insertion - 1)).unwrap()
+                    }
+                };
+                in_flight -= 1;
+
+                self.insert(problem, state, individual, evaluations)?;
+            }
+
+            // Wait for the discarded individuals to count their evaluations.
+            let mut discarded: usize = completed
+                .values()
+                .map(|&(_, evaluations)| evaluations)
+                .sum();
+            for _ in completed.len()..in_flight {
+                let (_, _, evaluations) = pool.receive().wrap_err("all workers stopped")?;
+                discarded += evaluations;
+            }
+            count_evaluations(state, discarded);
+
+            Ok(())
+        })
+    }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        vec![
+            Edge::condition("AsyncSteadyState.condition", self.condition.as_ref()),
+            Edge::component("AsyncSteadyState.generate", self.generate.as_ref()),
+            Edge::component("AsyncSteadyState.insert", self.insert.as_ref()),
+        ]
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        vec![
+            EdgeMut::condition("AsyncSteadyState.condition", &mut self.condition),
+            EdgeMut::component("AsyncSteadyState.generate", &mut self.generate),
+            EdgeMut::component("AsyncSteadyState.insert", &mut self.insert),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use test_case::test_case;
+
+    use super::*;
+    use crate::{conditions::LessThanN, SingleObjective};
+
+    /// Problem whose objective value is the solution, where later solutions are evaluated faster.
+    struct Countdown;
+
+    impl Problem for Countdown {
+        type Encoding = usize;
+        type Objective = SingleObjective;
+
+        fn name(&self) -> &str {
+            "Countdown"
+        }
+    }
+
+    impl ObjectiveFunction for Countdown {
+        fn objective(&self, solution: &Self::Encoding) -> Self::Objective {
+            thread::sleep(Duration::from_millis(4 - (*solution % 4) as u64));
+            (*solution as f64).try_into().unwrap()
+        }
+    }
+
+    /// Generates one individual with the next solution.
+    #[derive(Clone, Serialize)]
+    struct Generate {
+        #[serde(skip)]
+        next: Arc<AtomicUsize>,
+    }
+
+    impl Component<Countdown> for Generate {
+        fn execute(&self, _problem: &Countdown, state: &mut State<Countdown>) -> ExecResult<()> {
+            let solution = self.next.fetch_add(1, Ordering::SeqCst);
+            state
+                .populations_mut()
+                .push(vec![Individual::new_unevaluated(solution)]);
+            Ok(())
+        }
+    }
+
+    /// Records the solutions of inserted individuals.
+    #[derive(Clone, Serialize)]
+    struct Record {
+        #[serde(skip)]
+        inserted: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl Component<Countdown> for Record {
+        fn execute(&self, _problem: &Countdown, state: &mut State<Countdown>) -> ExecResult<()> {
+            let individual = state.populations_mut().pop().pop().unwrap();
+            assert_eq!(
+                individual.objective().value(),
+                *individual.solution() as f64
+            );
+            self.inserted.lock().unwrap().push(*individual.solution());
+            Ok(())
+        }
+    }
+
+    /// Evaluates sequentially, but counts every evaluation twice.
+    struct Double(Sequential<Countdown>);
+
+    impl Evaluate for Double {
+        type Problem = Countdown;
+
+        fn evaluate(
+            &mut self,
+            problem: &Self::Problem,
+            state: &mut State<Self::Problem>,
+            individuals: &mut [Individual<Self::Problem>],
+        ) {
+            self.0.evaluate(problem, state, individuals);
+        }
+
+        fn counted_evaluations(&self, n: usize) -> usize {
+            2 * n
+        }
+    }
+
+    fn run(component: Box<dyn Component<Countdown>>) -> State<'static, Countdown> {
+        let problem = Countdown;
+        let mut state = State::new();
+        state.insert(common::Populations::<Countdown>::new());
+        state.insert(common::Evaluations(0));
+        component.init(&problem, &mut state).unwrap();
+        component.execute(&problem, &mut state).unwrap();
+        state
+    }
+
+    #[test]
+    fn submission_order_is_reproducible() {
+        let inserted = Arc::new(Mutex::new(Vec::new()));
+        let component = AsyncSteadyState::new(
+            LessThanN::iterations(20),
+            Box::new(Generate {
+                next: Arc::default(),
+            }),
+            Box::new(Record {
+                inserted: inserted.clone(),
+            }),
+            4,
+            InsertionOrder::Submission,
+        )
+        .unwrap();
+        let state = run(component);
+
+        assert_eq!(*inserted.lock().unwrap(), (0..20).collect::<Vec<_>>());
+        assert_eq!(state.get_value::<common::Iterations>(), 20);
+        // The three individuals still in flight are discarded, but counted.
+        assert_eq!(state.get_value::<common::Evaluations>(), 23);
+    }
+
+    #[test]
+    fn evaluations_are_counted_by_the_evaluator() {
+        let inserted = Arc::new(Mutex::new(Vec::new()));
+        let component = AsyncSteadyState::new_with_evaluator(
+            LessThanN::evaluations(20),
+            Box::new(Generate {
+                next: Arc::default(),
+            }),
+            Box::new(Record {
+                inserted: inserted.clone(),
+            }),
+            3,
+            InsertionOrder::Submission,
+            || Box::new(Double(Sequential::new())),
+        )
+        .unwrap();
+        let state = run(component);
+
+        assert_eq!(*inserted.lock().unwrap(), (0..10).collect::<Vec<_>>());
+        assert_eq!(state.get_value::<common::Iterations>(), 10);
+        // The two individuals still in flight are discarded, but counted.
+        assert_eq!(state.get_value::<common::Evaluations>(), 24);
+    }
+
+    /// Evaluates sequentially, and counts the evaluated individuals.
+    struct Counting(Sequential<Countdown>, Arc<AtomicUsize>);
+
+    impl Evaluate for Counting {
+        type Problem = Countdown;
+
+        fn evaluate(
+            &mut self,
+            problem: &Self::Problem,
+            state: &mut State<Self::Problem>,
+            individuals: &mut [Individual<Self::Problem>],
+        ) {
+            self.1.fetch_add(individuals.len(), Ordering::SeqCst);
+            self.0.evaluate(problem, state, individuals);
+        }
+    }
+
+    #[test_case(InsertionOrder::Completion; "completion")]
+    #[test_case(InsertionOrder::Submission; "submission")]
+    fn every_evaluation_is_counted(order: InsertionOrder) {
+        let evaluated = Arc::new(AtomicUsize::new(0));
+        let counter = evaluated.clone();
+        let component = AsyncSteadyState::new_with_evaluator(
+            LessThanN::iterations(15),
+            Box::new(Generate {
+                next: Arc::default(),
+            }),
+            Box::new(Record {
+                inserted: Arc::default(),
+            }),
+            4,
+            order,
+            move || Box::new(Counting(Sequential::new(), counter.clone())),
+        )
+        .unwrap();
+        let state = run(component);
+
+        assert_eq!(state.get_value::<common::Iterations>(), 15);
+        assert_eq!(state.get_value::<common::Evaluations>(), 18);
+        assert_eq!(evaluated.load(Ordering::SeqCst), 18);
+    }
+
+    #[test]
+    fn completion_order_inserts_every_individual_once() {
+        let inserted = Arc::new(Mutex::new(Vec::new()));
+        let component = AsyncSteadyState::new(
+            LessThanN::iterations(20),
+            Box::new(Generate {
+                next: Arc::default(),
+            }),
+            Box::new(Record {
+                inserted: inserted.clone(),
+            }),
+            4,
+            InsertionOrder::Completion,
+        )
+        .unwrap();
+        run(component);
+
+        let mut inserted = inserted.lock().unwrap().clone();
+        inserted.sort_unstable();
+        inserted.dedup();
+        assert_eq!(inserted.len(), 20);
+    }
+
+    #[test]
+    fn zero_workers_are_rejected() {
+        assert!(AsyncSteadyState::new(
+            LessThanN::iterations(1),
+            Box::new(Generate {
+                next: Arc::default(),
+            }),
+            Box::new(Record {
+                inserted: Arc::default(),
+            }),
+            0,
+            InsertionOrder::Submission,
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,655 @@
+//! Surrogate-assisted evaluation.
+//!
+//! For costly objective functions, a cheap surrogate model can be used to decide which
+//! solutions are worth being evaluated with the real objective function.
+//!
+//! The surrogate subsystem consists of
+//! - a [`SurrogateArchive`] of evaluated solutions, updated by [`SurrogateArchiveUpdate`],
+//! - a [`SurrogateModel`] fitted to the archive by [`SurrogateFit`], and
+//! - the [`PreScreening`] component, which only keeps the most promising offspring for
+//!   evaluation with the real objective function.
+//!
+//! The accuracy of the surrogate can be logged using the [`SurrogateMaeLens`] and
+//! [`SurrogateRmseLens`].
+//!
+//! # Examples
+//!
+//! Generating 40 offspring per generation, of which only the 10 offspring with the highest
+//! expected improvement according to a Kriging model are evaluated:
+//!
+//! ```
+//! # use mahf::{Component, ExecResult, SingleObjectiveProblem, problems::LimitedVectorProblem};
+//! use mahf::{
+//!     components::{
+//!         mutation, replacement, selection,
+//!         surrogate::{
+//!             models::Kriging, PreScreening, ScreeningCriterion, SurrogateArchiveUpdate,
+//!             SurrogateFit,
+//!         },
+//!     },
+//!     conditions::LessThanN,
+//!     Configuration,
+//! };
+//!
+//! # fn example<P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>>(
+//! #     initialization: Box<dyn Component<P>>,
+//! # ) -> ExecResult<Box<dyn Component<P>>> {
+//! let fit = SurrogateFit::new(Kriging::new(1.0, 1e-6)?, 10);
+//! let screening = PreScreening::new(10, ScreeningCriterion::ExpectedImprovement)?;
+//!
+//! # Ok(
+//! Configuration::builder()
+//!     .do_(initialization)
+//!     .evaluate()
+//!     .update_best_individual()
+//!     .do_(SurrogateArchiveUpdate::new(Some(500)))
+//!     .while_(LessThanN::iterations(100), |builder| {
+//!         builder
+//!             .do_(selection::FullyRandom::new(40))
+//!             .do_(mutation::NormalMutation::new_dev(0.1))
+//!             .do_(fit)
+//!             .do_(screening)
+//!             .evaluate()
+//!             .update_best_individual()
+//!             .do_(SurrogateArchiveUpdate::new(Some(500)))
+//!             .do_(replacement::MuPlusLambda::new(10))
+//!     })
+//!     .build_component()
+//! # )
+//! # }
+//! ```
+//!
+//! # References
+//!
+//! \[1\] Yaochu Jin. 2011.
+//! Surrogate-assisted evolutionary computation: Recent advances and future challenges.
+//! Swarm and Evolutionary Computation 1, 2 (June 2011), 61–70.
+//! DOI:<https://doi.org/10.1016/j.swevo.2011.05.001>
+//!
+//! \[2\] Donald R. Jones, Matthias Schonlau, and William J. Welch. 1998.
+//! Efficient Global Optimization of Expensive Black-Box Functions.
+//! Journal of Global Optimization 13, 4 (December 1998), 455–492.
+//! DOI:<https://doi.org/10.1023/A:1008306431147>
+
+use std::collections::VecDeque;
+
+use better_any::{Tid, TidAble};
+use eyre::ensure;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{Continuous, ContinuousCDF, Normal};
+
+use crate::{
+    component::ExecResult,
+    components::Component,
+    lens::{AnyLens, LensMap},
+    logging::extractor::{EntryExtractor, EntryName},
+    problems::{SingleObjectiveProblem, VectorProblem},
+    state::StateReq,
+    CustomState, State,
+};
+
+pub mod models;
+
+pub use models::{Prediction, Predictor, SurrogateModel};
+
+/// An archive of evaluated solutions used to fit surrogate models.
+///
+/// If a `capacity` is set, the oldest solutions are removed first.
+#[derive(Clone, Default, Serialize, Tid)]
+pub struct SurrogateArchive {
+    /// The evaluated solutions and their objective values, oldest first.
+    pub entries: VecDeque<(Vec<f64>, f64)>,
+    /// The maximum number of entries.
+    pub capacity: Option<usize>,
+}
+
+impl SurrogateArchive {
+    /// Creates a new, empty archive with some `capacity`.
+    pub fn new(capacity: Option<usize>) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Adds the solution `x` with objective value `y`, unless `x` is already in the archive.
+    pub fn add(&mut self, x: Vec<f64>, y: f64) {
+        if self.entries.iter().any(|(xi, _)| xi == &x) {
+            return;
+        }
+        self.entries.push_back((x, y));
+        if let Some(capacity) = self.capacity {
+            while self.entries.len() > capacity {
+                self.entries.pop_front();
+            }
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the archive is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the best objective value in the archive.
+    pub fn best(&self) -> Option<f64> {
+        self.entries
+            .iter()
+            .map(|&(_, y)| y)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+}
+
+impl CustomState<'_> for SurrogateArchive {}
+
+/// The surrogate model fitted by [`SurrogateFit`].
+///
+/// Is `None` if there was not enough training data yet.
+#[derive(Default, Tid)]
+pub struct Surrogate(pub Option<Box<dyn Predictor>>);
+
+impl CustomState<'_> for Surrogate {}
+
+/// The predicted objective values of the solutions kept by [`PreScreening`].
+#[derive(Clone, Default, Serialize, Tid)]
+pub struct SurrogatePredictions(pub Vec<(Vec<f64>, f64)>);
+
+impl CustomState<'_> for SurrogatePredictions {}
+
+/// The absolute errors of the predictions of the surrogate for the solutions added in the last
+/// [`SurrogateArchiveUpdate`].
+#[derive(Clone, Default, Serialize, Tid)]
+pub struct SurrogateError(pub Vec<f64>);
+
+impl CustomState<'_> for SurrogateError {}
+
+/// Adds the evaluated individuals of the current population to the [`SurrogateArchive`].
+///
+/// If [`SurrogatePredictions`] exist for the added solutions, the errors of the predictions
+/// are stored in [`SurrogateError`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SurrogateArchiveUpdate {
+    /// The maximum number of entries in the archive.
+    pub capacity: Option<usize>,
+}
+
+impl SurrogateArchiveUpdate {
+    pub fn from_params(capacity: Option<usize>) -> Self {
+        Self { capacity }
+    }
+
+    pub fn new<P>(capacity: Option<usize>) -> Box<dyn Component<P>>
+    where
+        P: SingleObjectiveProblem + VectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(capacity))
+    }
+}
+
+impl<P> Component<P> for SurrogateArchiveUpdate
+where
+    P: SingleObjectiveProblem + VectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(SurrogateArchive::new(self.capacity));
+        state.insert(SurrogateError::default());
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let populations = state.populations();
+        let mut archive = state.try_borrow_mut::<SurrogateArchive>()?;
+        let predictions = state.try_borrow::<SurrogatePredictions>().ok();
+
+        let mut errors = Vec::new();
+        for individual in populations.current() {
+            let Some(objective) = individual.get_objective() else {
+                continue;
+            };
+            let (x, y) = (individual.solution(), objective.value());
+            if let Some(predictions) = &predictions {
+                if let Some((_, predicted)) = predictions.0.iter().find(|(xi, _)| xi == x) {
+                    errors.push((predicted - y).abs());
+                }
+            }
+            archive.add(x.clone(), y);
+        }
+
+        state.try_borrow_mut::<SurrogateError>()?.0 = errors;
+        Ok(())
+    }
+}
+
+/// Fits a [`SurrogateModel`] to the [`SurrogateArchive`], and stores it in [`Surrogate`].
+///
+/// The model is only fitted if the archive contains at least `min_samples` entries.
+/// If fitting fails, e.g. because of a singular system, a warning is logged and the previously
+/// fitted model is kept.
+#[derive(Clone, Serialize)]
+pub struct SurrogateFit {
+    pub model: Box<dyn SurrogateModel>,
+    /// The minimum number of archive entries required to fit the model.
+    pub min_samples: usize,
+}
+
+impl SurrogateFit {
+    pub fn from_params(model: Box<dyn SurrogateModel>, min_samples: usize) -> Self {
+        Self { model, min_samples }
+    }
+
+    pub fn new<P>(model: Box<dyn SurrogateModel>, min_samples: usize) -> Box<dyn Component<P>>
+    where
+        P: SingleObjectiveProblem + VectorProblem<Element = f64>,
+    {
+        Box::new(Self::from_params(model, min_samples))
+    }
+}
+
+impl<P> Component<P> for SurrogateFit
+where
+    P: SingleObjectiveProblem + VectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(Surrogate::default());
+        Ok(())
+    }
+
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, SurrogateArchive>()?;
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let archive = state.try_borrow::<SurrogateArchive>()?;
+        if archive.len() < self.min_samples.max(1) {
+            return Ok(());
+        }
+
+        let (xs, ys): (Vec<_>, Vec<_>) = archive.entries.iter().cloned().unzip();
+        match self.model.fit(&xs, &ys) {
+            Ok(predictor) => state.try_borrow_mut::<Surrogate>()?.0 = Some(predictor),
+            Err(error) => log::warn!("keeping the previous surrogate model: {error}"),
+        }
+        Ok(())
+    }
+}
+
+/// The criterion used by [`PreScreening`] to rank solutions.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScreeningCriterion {
+    /// Solutions with a lower predicted objective value are preferred.
+    Mean,
+    /// Solutions with a higher expected improvement over the best objective value in the
+    /// [`SurrogateArchive`] are preferred.
+    ///
+    /// Falls back to [`Mean`] if the model provides no uncertainty estimate.
+    ///
+    /// [`Mean`]: ScreeningCriterion::Mean
+    ExpectedImprovement,
+}
+
+/// Returns the expected improvement of the `prediction` over the `best` objective value,
+/// or `None` if the prediction provides no variance.
+pub fn expected_improvement(best: f64, prediction: Prediction) -> Option<f64> {
+    let std_dev = prediction.variance?.sqrt();
+    let improvement = best - prediction.mean;
+    if std_dev <= 0. {
+        return Some(improvement.max(0.));
+    }
+    let z = improvement / std_dev;
+    let normal = Normal::new(0., 1.).unwrap();
+    Some(improvement * normal.cdf(z) + std_dev * normal.pdf(z))
+}
+
+/// Keeps only the `k` most promising individuals of the current population according to
+/// the [`Surrogate`], ranked by some [`ScreeningCriterion`].
+///
+/// If no surrogate is fitted yet, all individuals are kept.
+///
+/// The predictions of the kept individuals are stored in [`SurrogatePredictions`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PreScreening {
+    /// The number of individuals to keep.
+    pub k: usize,
+    /// The ranking criterion.
+    pub criterion: ScreeningCriterion,
+}
+
+impl PreScreening {
+    pub fn from_params(k: usize, criterion: ScreeningCriterion) -> ExecResult<Self> {
+        ensure!(k > 0, "`k` must be greater than 0");
+        Ok(Self { k, criterion })
+    }
+
+    pub fn new<P>(k: usize, criterion: ScreeningCriterion) -> ExecResult<Box<dyn Component<P>>>
+    where
+        P: SingleObjectiveProblem + VectorProblem<Element = f64>,
+    {
+        Ok(Box::new(Self::from_params(k, criterion)?))
+    }
+}
+
+impl<P> Component<P> for PreScreening
+where
+    P: SingleObjectiveProblem + VectorProblem<Element = f64>,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(SurrogatePredictions::default());
+        Ok(())
+    }
+
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, Surrogate>()?;
+        state_req.require::<Self, SurrogateArchive>()?;
+        Ok(())
+    }
+
+    fn execute(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let surrogate = state.try_borrow::<Surrogate>()?;
+        let Some(predictor) = &surrogate.0 else {
+            state.try_borrow_mut::<SurrogatePredictions>()?.0.clear();
+            return Ok(());
+        };
+        let best = state.try_borrow::<SurrogateArchive>()?.best();
+
+        let mut populations = state.populations_mut();
+        let population = populations.pop();
+
+        let scored: Vec<_> = population
+            .into_iter()
+            .map(|individual| {
+                let prediction = predictor.predict(individual.solution());
+                let ei = match self.criterion {
+                    ScreeningCriterion::Mean => None,
+                    ScreeningCriterion::ExpectedImprovement => {
+                        best.and_then(|best| expected_improvement(best, prediction))
+                    }
+                };
+                // Lower scores are better.
+                let score = ei.map_or(prediction.mean, |ei| -ei);
+                (score, prediction.mean, individual)
+            })
+            .sorted_by(|a, b| a.0.total_cmp(&b.0))
+            .take(self.k)
+            .collect();
+
+        state.try_borrow_mut::<SurrogatePredictions>()?.0 = scored
+            .iter()
+            .map(|(_, mean, individual)| (individual.solution().clone(), *mean))
+            .collect();
+        populations.push(scored.into_iter().map(|(_, _, i)| i).collect());
+
+        Ok(())
+    }
+}
+
+/// Lens for the mean absolute error of the surrogate predictions stored in [`SurrogateError`].
+///
+/// Evaluates to `NaN` if no errors were recorded.
+#[derive(Default, Clone, Serialize)]
+pub struct SurrogateMaeLens;
+
+impl SurrogateMaeLens {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Constructs the lens for logging.
+    pub fn entry<P: SingleObjectiveProblem>() -> Box<dyn EntryExtractor<P>> {
+        Box::new(Self)
+    }
+}
+
+impl AnyLens for SurrogateMaeLens {
+    type Target = f64;
+}
+
+impl EntryName for SurrogateMaeLens {
+    fn entry_name() -> &'static str {
+        "Surrogate MAE"
+    }
+}
+
+impl LensMap for SurrogateMaeLens {
+    type Source = SurrogateError;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        source.0.iter().sum::<f64>() / source.0.len() as f64
+    }
+}
+
+/// Lens for the root mean squared error of the surrogate predictions stored in [`SurrogateError`].
+///
+/// Evaluates to `NaN` if no errors were recorded.
+#[derive(Default, Clone, Serialize)]
+pub struct SurrogateRmseLens;
+
+impl SurrogateRmseLens {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Constructs the lens for logging.
+    pub fn entry<P: SingleObjectiveProblem>() -> Box<dyn EntryExtractor<P>> {
+        Box::new(Self)
+    }
+}
+
+impl AnyLens for SurrogateRmseLens {
+    type Target = f64;
+}
+
+impl EntryName for SurrogateRmseLens {
+    fn entry_name() -> &'static str {
+        "Surrogate RMSE"
+    }
+}
+
+impl LensMap for SurrogateRmseLens {
+    type Source = SurrogateError;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        (source.0.iter().map(|e| e.powi(2)).sum::<f64>() / source.0.len() as f64).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+
+    use super::*;
+    use crate::{population::IntoSolutions, problems::ObjectiveFunction, testing::*, Individual};
+
+    /// Predicts the first element of a solution with the second element as variance.
+    #[derive(Clone, Serialize)]
+    struct FirstElement;
+
+    impl Predictor for FirstElement {
+        fn predict(&self, x: &[f64]) -> Prediction {
+            Prediction {
+                mean: x[0],
+                variance: Some(x[1]),
+            }
+        }
+    }
+
+    impl SurrogateModel for FirstElement {
+        fn fit(&self, _xs: &[Vec<f64>], _ys: &[f64]) -> ExecResult<Box<dyn Predictor>> {
+            Ok(Box::new(Self))
+        }
+    }
+
+    /// A model which always fails to fit.
+    #[derive(Clone, Serialize)]
+    struct Singular;
+
+    impl SurrogateModel for Singular {
+        fn fit(&self, _xs: &[Vec<f64>], _ys: &[f64]) -> ExecResult<Box<dyn Predictor>> {
+            Err(eyre::eyre!("the system is singular"))
+        }
+    }
+
+    fn evaluated(solutions: &[[f64; 2]]) -> Vec<Individual<RealTestProblem>> {
+        let problem = RealTestProblem(2);
+        solutions
+            .iter()
+            .map(|x| Individual::new(x.to_vec(), problem.objective(&x.to_vec())))
+            .collect()
+    }
+
+    fn state(solutions: &[[f64; 2]]) -> State<'static, RealTestProblem> {
+        let state = test_state(Vec::new());
+        *state.populations_mut().current_mut() = evaluated(solutions);
+        state
+    }
+
+    fn screen(
+        criterion: ScreeningCriterion,
+        k: usize,
+        archive: &[[f64; 2]],
+        solutions: &[[f64; 2]],
+    ) -> Vec<Vec<f64>> {
+        let problem = RealTestProblem(2);
+        let mut state = state(archive);
+        let update = SurrogateArchiveUpdate::from_params(None);
+        let fit = SurrogateFit::from_params(Box::new(FirstElement), 1);
+        let screening = PreScreening::from_params(k, criterion).unwrap();
+        for component in [&update as &dyn Component<_>, &fit, &screening] {
+            component.init(&problem, &mut state).unwrap();
+        }
+        update.execute(&problem, &mut state).unwrap();
+        fit.execute(&problem, &mut state).unwrap();
+
+        let population = solutions
+            .iter()
+            .map(|x| Individual::new_unevaluated(x.to_vec()))
+            .collect();
+        *state.populations_mut().current_mut() = population;
+        screening.execute(&problem, &mut state).unwrap();
+
+        let kept = state.populations_mut().pop().into_solutions();
+        let predictions = state.borrow::<SurrogatePredictions>();
+        assert_eq!(
+            predictions.0,
+            kept.iter().map(|x| (x.clone(), x[0])).collect::<Vec<_>>()
+        );
+        kept
+    }
+
+    #[test]
+    fn expected_improvement_of_predictions() {
+        let prediction = |mean, variance| Prediction { mean, variance };
+        assert_eq!(expected_improvement(1., prediction(0., None)), None);
+        assert_eq!(
+            expected_improvement(1., prediction(0.5, Some(0.))),
+            Some(0.5)
+        );
+        assert_eq!(expected_improvement(1., prediction(2., Some(0.))), Some(0.));
+        // For a standard normal prediction at the best value, the EI is the density at 0.
+        let ei = expected_improvement(0., prediction(0., Some(1.))).unwrap();
+        assert_float_eq!(ei, 1. / (2. * std::f64::consts::PI).sqrt(), abs <= 1e-12);
+    }
+
+    #[test]
+    fn archive_update_adds_new_evaluated_solutions() {
+        let problem = RealTestProblem(2);
+        let mut state = state(&[[1., 0.], [2., 0.], [1., 0.]]);
+        state
+            .populations_mut()
+            .current_mut()
+            .push(Individual::new_unevaluated(vec![3., 0.]));
+        let update = SurrogateArchiveUpdate::from_params(Some(2));
+        update.init(&problem, &mut state).unwrap();
+        update.execute(&problem, &mut state).unwrap();
+
+        let archive = state.borrow::<SurrogateArchive>();
+        assert_eq!(archive.entries, [(vec![1., 0.], 1.), (vec![2., 0.], 4.)]);
+        assert_eq!(archive.best(), Some(1.));
+    }
+
+    #[test]
+    fn archive_update_respects_capacity_and_records_errors() {
+        let problem = RealTestProblem(2);
+        let mut state = state(&[[1., 0.], [2., 0.], [3., 0.]]);
+        state.insert(SurrogatePredictions(vec![
+            (vec![2., 0.], 3.),
+            (vec![3., 0.], 10.),
+        ]));
+        let update = SurrogateArchiveUpdate::from_params(Some(2));
+        update.init(&problem, &mut state).unwrap();
+        update.execute(&problem, &mut state).unwrap();
+
+        let archive = state.borrow::<SurrogateArchive>();
+        assert_eq!(archive.entries, [(vec![2., 0.], 4.), (vec![3., 0.], 9.)]);
+        assert_eq!(state.borrow::<SurrogateError>().0, [1., 1.]);
+    }
+
+    #[test]
+    fn fit_keeps_previous_model_on_failure() {
+        let problem = RealTestProblem(2);
+        let mut state = state(&[[1., 0.]]);
+        let update = SurrogateArchiveUpdate::from_params(None);
+        let fit = SurrogateFit::from_params(Box::new(FirstElement), 2);
+        let failing = SurrogateFit::from_params(Box::new(Singular), 1);
+        update.init(&problem, &mut state).unwrap();
+        fit.init(&problem, &mut state).unwrap();
+        update.execute(&problem, &mut state).unwrap();
+
+        // Not enough samples yet.
+        fit.execute(&problem, &mut state).unwrap();
+        assert!(state.borrow::<Surrogate>().0.is_none());
+
+        *state.populations_mut().current_mut() = evaluated(&[[2., 0.]]);
+        update.execute(&problem, &mut state).unwrap();
+        fit.execute(&problem, &mut state).unwrap();
+        failing.execute(&problem, &mut state).unwrap();
+        let surrogate = state.borrow::<Surrogate>();
+        assert_eq!(surrogate.0.as_ref().unwrap().predict(&[5., 0.]).mean, 5.);
+    }
+
+    #[test]
+    fn pre_screening_keeps_lowest_predictions() {
+        let kept = screen(
+            ScreeningCriterion::Mean,
+            2,
+            &[[1., 0.]],
+            &[[3., 0.], [1., 0.], [2., 0.], [0., 0.]],
+        );
+        assert_eq!(kept, [vec![0., 0.], vec![1., 0.]]);
+    }
+
+    #[test]
+    fn pre_screening_prefers_expected_improvement() {
+        let solutions = [[0.5, 0.], [2., 25.]];
+        let kept = screen(ScreeningCriterion::Mean, 1, &[[1., 0.]], &solutions);
+        assert_eq!(kept, [vec![0.5, 0.]]);
+        // The uncertain prediction has a higher expected improvement over the best value 1.
+        let kept = screen(
+            ScreeningCriterion::ExpectedImprovement,
+            1,
+            &[[1., 0.]],
+            &solutions,
+        );
+        assert_eq!(kept, [vec![2., 25.]]);
+    }
+
+    #[test]
+    fn pre_screening_keeps_all_without_surrogate() {
+        let problem = RealTestProblem(2);
+        let mut state = state(&[[1., 0.], [2., 0.], [3., 0.]]);
+        state.insert(Surrogate::default());
+        state.insert(SurrogateArchive::default());
+        let screening = PreScreening::from_params(1, ScreeningCriterion::Mean).unwrap();
+        screening.init(&problem, &mut state).unwrap();
+        screening.execute(&problem, &mut state).unwrap();
+        assert_eq!(state.populations().current().len(), 3);
+        assert!(state.borrow::<SurrogatePredictions>().0.is_empty());
+    }
+}
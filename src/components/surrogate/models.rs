@@ -0,0 +1,471 @@
+//! Surrogate models approximating real-valued objective functions.
+//!
+//! A [`SurrogateModel`] describes how to fit a model to training data, and returns
+//! a [`Predictor`] for the fitted model.
+
+use dyn_clone::DynClone;
+use eyre::{ensure, ContextCompat};
+use serde::{Deserialize, Serialize};
+
+use crate::component::ExecResult;
+
+/// The prediction of a [`Predictor`] for some solution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Prediction {
+    /// The predicted objective value.
+    pub mean: f64,
+    /// The variance of the prediction, if the model provides an uncertainty estimate.
+    pub variance: Option<f64>,
+}
+
+/// A fitted surrogate model.
+pub trait Predictor: Send {
+    /// Predicts the objective value of `x`.
+    fn predict(&self, x: &[f64]) -> Prediction;
+}
+
+/// Trait for representing a surrogate model which can be fitted to evaluated solutions.
+pub trait SurrogateModel: DynClone + erased_serde::Serialize + Send + Sync {
+    /// Fits the model to the solutions `xs` with objective values `ys`.
+    fn fit(&self, xs: &[Vec<f64>], ys: &[f64]) -> ExecResult<Box<dyn Predictor>>;
+}
+
+dyn_clone::clone_trait_object!(SurrogateModel);
+erased_serde::serialize_trait_object!(SurrogateModel);
+
+/// Returns the squared euclidean distance between `a` and `b`.
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum()
+}
+
+/// Solves `a * x = b` using Gaussian elimination with partial pivoting.
+///
+/// Returns `None` if `a` is singular.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < f64::EPSILON {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            for (x, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= factor * p;
+            }
+            b[col + 1 + offset] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Computes the lower triangular Cholesky factor `l` of `a`, such that `l * l^T = a`.
+///
+/// Returns `None` if `a` is not positive definite.
+fn cholesky(a: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = a.len();
+    let mut l = vec![vec![0.; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let d = a[i][i] - sum;
+                if d <= 0. {
+                    return None;
+                }
+                l[i][j] = d.sqrt();
+            } else {
+                l[i][j] = (a[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Solves `l * l^T * x = b` for a lower triangular Cholesky factor `l`.
+fn solve_cholesky(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let n = b.len();
+    let mut y = vec![0.; n];
+    for i in 0..n {
+        let sum: f64 = (0..i).map(|k| l[i][k] * y[k]).sum();
+        y[i] = (b[i] - sum) / l[i][i];
+    }
+    let mut x = vec![0.; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|k| l[k][i] * x[k]).sum();
+        x[i] = (y[i] - sum) / l[i][i];
+    }
+    x
+}
+
+/// k-nearest neighbors regression.
+///
+/// The prediction is the mean objective value of the `k` nearest solutions,
+/// and its variance the variance of their objective values.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KNearestNeighbors {
+    /// The number of neighbors.
+    pub k: usize,
+}
+
+impl KNearestNeighbors {
+    pub fn from_params(k: usize) -> ExecResult<Self> {
+        ensure!(k > 0, "`k` must be greater than 0");
+        Ok(Self { k })
+    }
+
+    pub fn new(k: usize) -> ExecResult<Box<dyn SurrogateModel>> {
+        Ok(Box::new(Self::from_params(k)?))
+    }
+}
+
+struct KNearestNeighborsPredictor {
+    k: usize,
+    xs: Vec<Vec<f64>>,
+    ys: Vec<f64>,
+}
+
+impl Predictor for KNearestNeighborsPredictor {
+    fn predict(&self, x: &[f64]) -> Prediction {
+        let mut distances: Vec<_> = self
+            .xs
+            .iter()
+            .zip(&self.ys)
+            .map(|(xi, &y)| (squared_distance(x, xi), y))
+            .collect();
+        distances.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let neighbors: Vec<_> = distances.iter().take(self.k).map(|&(_, y)| y).collect();
+        let n = neighbors.len() as f64;
+        let mean = neighbors.iter().sum::<f64>() / n;
+        let variance = neighbors.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / n;
+        Prediction {
+            mean,
+            variance: Some(variance),
+        }
+    }
+}
+
+impl SurrogateModel for KNearestNeighbors {
+    fn fit(&self, xs: &[Vec<f64>], ys: &[f64]) -> ExecResult<Box<dyn Predictor>> {
+        ensure!(!xs.is_empty(), "no training data");
+        Ok(Box::new(KNearestNeighborsPredictor {
+            k: self.k,
+            xs: xs.to_vec(),
+            ys: ys.to_vec(),
+        }))
+    }
+}
+
+/// The radial basis function of a [`RadialBasisFunction`] model.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum RbfKernel {
+    /// `exp(-(epsilon * r)^2)`
+    Gaussian { epsilon: f64 },
+    /// `sqrt(1 + (epsilon * r)^2)`
+    Multiquadric { epsilon: f64 },
+    /// `r^3`
+    Cubic,
+}
+
+impl RbfKernel {
+    /// Evaluates the kernel for the distance `r`.
+    pub fn apply(&self, r: f64) -> f64 {
+        match *self {
+            RbfKernel::Gaussian { epsilon } => (-(epsilon * r).powi(2)).exp(),
+            RbfKernel::Multiquadric { epsilon } => (1. + (epsilon * r).powi(2)).sqrt(),
+            RbfKernel::Cubic => r.powi(3),
+        }
+    }
+
+    /// Returns `true` if the kernel is only conditionally positive definite, i.e. requires
+    /// a polynomial tail for the interpolation to be well-posed.
+    pub fn requires_polynomial_tail(&self) -> bool {
+        !matches!(self, RbfKernel::Gaussian { .. })
+    }
+}
+
+/// Returns the linear polynomial basis `[1, x_1, ..., x_d]` of `x`.
+fn linear_basis(x: &[f64]) -> impl Iterator<Item = f64> + '_ {
+    std::iter::once(1.).chain(x.iter().copied())
+}
+
+/// Radial basis function (RBF) interpolation.
+///
+/// The prediction is `sum_i w_i * phi(|x - x_i|)`, where the weights `w` are determined by
+/// solving `(Phi + regularization * I) * w = y`.
+///
+/// For the [`Multiquadric`] and [`Cubic`] kernels, a linear polynomial tail `c^T * [1, x]` is
+/// added to the prediction, where the weights are additionally constrained by `P^T * w = 0` for
+/// the polynomial basis `P` of the training data.
+/// Therefore, these kernels require at least `d + 1` affinely independent training solutions
+/// of dimension `d`.
+///
+/// This model provides no uncertainty estimate.
+///
+/// [`Multiquadric`]: RbfKernel::Multiquadric
+/// [`Cubic`]: RbfKernel::Cubic
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RadialBasisFunction {
+    /// The radial basis function.
+    pub kernel: RbfKernel,
+    /// Regularization added to the diagonal, which smooths the interpolation.
+    pub regularization: f64,
+}
+
+impl RadialBasisFunction {
+    pub fn from_params(kernel: RbfKernel, regularization: f64) -> ExecResult<Self> {
+        ensure!(
+            regularization >= 0.,
+            "`regularization` must be non-negative"
+        );
+        Ok(Self {
+            kernel,
+            regularization,
+        })
+    }
+
+    pub fn new(kernel: RbfKernel, regularization: f64) -> ExecResult<Box<dyn SurrogateModel>> {
+        Ok(Box::new(Self::from_params(kernel, regularization)?))
+    }
+}
+
+struct RadialBasisFunctionPredictor {
+    kernel: RbfKernel,
+    xs: Vec<Vec<f64>>,
+    weights: Vec<f64>,
+    /// The coefficients of the polynomial tail, which is empty if no tail is used.
+    tail: Vec<f64>,
+}
+
+impl Predictor for RadialBasisFunctionPredictor {
+    fn predict(&self, x: &[f64]) -> Prediction {
+        let mean = self
+            .xs
+            .iter()
+            .zip(&self.weights)
+            .map(|(xi, w)| w * self.kernel.apply(squared_distance(x, xi).sqrt()))
+            .sum::<f64>()
+            + self
+                .tail
+                .iter()
+                .zip(linear_basis(x))
+                .map(|(c, p)| c * p)
+                .sum::<f64>();
+        Prediction {
+            mean,
+            variance: None,
+        }
+    }
+}
+
+impl SurrogateModel for RadialBasisFunction {
+    fn fit(&self, xs: &[Vec<f64>], ys: &[f64]) -> ExecResult<Box<dyn Predictor>> {
+        ensure!(!xs.is_empty(), "no training data");
+        let n = xs.len();
+        let tail = if self.kernel.requires_polynomial_tail() {
+            xs[0].len() + 1
+        } else {
+            0
+        };
+        ensure!(
+            n >= tail,
+            "the polynomial tail requires at least {tail} training solutions"
+        );
+
+        // The system [[Phi, P], [P^T, 0]] * [w, c] = [y, 0] for the polynomial basis P.
+        let mut a = vec![vec![0.; n + tail]; n + tail];
+        for (i, xi) in xs.iter().enumerate() {
+            for (j, xj) in xs.iter().enumerate() {
+                let diagonal = if i == j { self.regularization } else { 0. };
+                a[i][j] = self.kernel.apply(squared_distance(xi, xj).sqrt()) + diagonal;
+            }
+            for (k, p) in linear_basis(xi).take(tail).enumerate() {
+                a[i][n + k] = p;
+                a[n + k][i] = p;
+            }
+        }
+        let b = ys
+            .iter()
+            .copied()
+            .chain(std::iter::repeat_n(0., tail))
+            .collect();
+
+        let mut weights = solve(a, b).wrap_err("the RBF system is singular")?;
+        let tail = weights.split_off(n);
+        Ok(Box::new(RadialBasisFunctionPredictor {
+            kernel: self.kernel,
+            xs: xs.to_vec(),
+            weights,
+            tail,
+        }))
+    }
+}
+
+/// Kriging, i.e. Gaussian process regression with a squared exponential kernel.
+///
+/// The kernel is `s^2 * exp(-|x - x'|^2 / (2 * length_scale^2))`, where the signal variance
+/// `s^2` is estimated as the variance of the training objective values,
+/// and a constant mean is assumed.
+///
+/// This model provides an uncertainty estimate, which is required for the expected improvement.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Kriging {
+    /// The length scale of the kernel.
+    pub length_scale: f64,
+    /// The noise variance relative to the signal variance, which is added to the diagonal.
+    pub noise: f64,
+}
+
+impl Kriging {
+    pub fn from_params(length_scale: f64, noise: f64) -> ExecResult<Self> {
+        ensure!(length_scale > 0., "`length_scale` must be greater than 0");
+        ensure!(noise >= 0., "`noise` must be non-negative");
+        Ok(Self {
+            length_scale,
+            noise,
+        })
+    }
+
+    pub fn new(length_scale: f64, noise: f64) -> ExecResult<Box<dyn SurrogateModel>> {
+        Ok(Box::new(Self::from_params(length_scale, noise)?))
+    }
+
+    fn correlation(&self, a: &[f64], b: &[f64]) -> f64 {
+        (-squared_distance(a, b) / (2. * self.length_scale.powi(2))).exp()
+    }
+}
+
+struct KrigingPredictor {
+    model: Kriging,
+    xs: Vec<Vec<f64>>,
+    mean: f64,
+    signal_variance: f64,
+    cholesky: Vec<Vec<f64>>,
+    alpha: Vec<f64>,
+}
+
+impl Predictor for KrigingPredictor {
+    fn predict(&self, x: &[f64]) -> Prediction {
+        let k: Vec<_> = self
+            .xs
+            .iter()
+            .map(|xi| self.model.correlation(x, xi))
+            .collect();
+        let mean = self.mean + k.iter().zip(&self.alpha).map(|(k, a)| k * a).sum::<f64>();
+        let v = solve_cholesky(&self.cholesky, &k);
+        let explained: f64 = k.iter().zip(&v).map(|(k, v)| k * v).sum();
+        let variance = (self.signal_variance * (1. - explained)).max(0.);
+        Prediction {
+            mean,
+            variance: Some(variance),
+        }
+    }
+}
+
+impl SurrogateModel for Kriging {
+    fn fit(&self, xs: &[Vec<f64>], ys: &[f64]) -> ExecResult<Box<dyn Predictor>> {
+        ensure!(!xs.is_empty(), "no training data");
+        let n = ys.len() as f64;
+        let mean = ys.iter().sum::<f64>() / n;
+        let signal_variance = (ys.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / n).max(1e-12);
+
+        // The correlation matrix with a small nugget for numerical stability.
+        let correlations: Vec<Vec<f64>> = xs
+            .iter()
+            .enumerate()
+            .map(|(i, xi)| {
+                xs.iter()
+                    .enumerate()
+                    .map(|(j, xj)| {
+                        let diagonal = if i == j { self.noise + 1e-10 } else { 0. };
+                        self.correlation(xi, xj) + diagonal
+                    })
+                    .collect()
+            })
+            .collect();
+        let cholesky =
+            cholesky(&correlations).wrap_err("the correlation matrix is not positive definite")?;
+        let centered: Vec<_> = ys.iter().map(|y| y - mean).collect();
+        let alpha = solve_cholesky(&cholesky, &centered);
+
+        Ok(Box::new(KrigingPredictor {
+            model: self.clone(),
+            xs: xs.to_vec(),
+            mean,
+            signal_variance,
+            cholesky,
+            alpha,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+    use test_case::test_case;
+
+    use super::*;
+
+    fn training_data() -> (Vec<Vec<f64>>, Vec<f64>) {
+        let xs: Vec<_> = (0..5)
+            .map(|i| vec![i as f64, 0.5 * (i * i) as f64])
+            .collect();
+        let ys = xs.iter().map(|x| x[0].powi(2) + x[1]).collect();
+        (xs, ys)
+    }
+
+    #[test_case(RadialBasisFunction::new(RbfKernel::Gaussian { epsilon: 1. }, 0.).unwrap(); "rbf gaussian")]
+    #[test_case(RadialBasisFunction::new(RbfKernel::Multiquadric { epsilon: 1. }, 0.).unwrap(); "rbf multiquadric")]
+    #[test_case(RadialBasisFunction::new(RbfKernel::Cubic, 0.).unwrap(); "rbf cubic")]
+    #[test_case(Kriging::new(1., 0.).unwrap(); "kriging")]
+    #[test_case(KNearestNeighbors::new(1).unwrap(); "1-nn")]
+    fn interpolating_models_reproduce_training_data(model: Box<dyn SurrogateModel>) {
+        let (xs, ys) = training_data();
+        let predictor = model.fit(&xs, &ys).unwrap();
+        for (x, y) in xs.iter().zip(&ys) {
+            assert_float_eq!(predictor.predict(x).mean, *y, abs <= 1e-4);
+        }
+    }
+
+    #[test]
+    fn kriging_variance_vanishes_at_training_data() {
+        let (xs, ys) = training_data();
+        let predictor = Kriging::new(1., 0.).unwrap().fit(&xs, &ys).unwrap();
+        assert_float_eq!(predictor.predict(&xs[2]).variance.unwrap(), 0., abs <= 1e-6);
+        assert!(predictor.predict(&[10., 10.]).variance.unwrap() > 1.);
+    }
+
+    #[test_case(RbfKernel::Multiquadric { epsilon: 1. }; "multiquadric")]
+    #[test_case(RbfKernel::Cubic; "cubic")]
+    fn rbf_polynomial_tail_reproduces_linear_functions(kernel: RbfKernel) {
+        let linear = |x: &[f64]| 2. * x[0] - x[1] + 3.;
+        let xs = vec![vec![0., 0.], vec![1., 0.], vec![0., 1.], vec![2., 3.]];
+        let ys: Vec<_> = xs.iter().map(|x| linear(x)).collect();
+        let predictor = RadialBasisFunction::new(kernel, 0.)
+            .unwrap()
+            .fit(&xs, &ys)
+            .unwrap();
+        for x in [[5., -2.], [-3., 7.]] {
+            assert_float_eq!(predictor.predict(&x).mean, linear(&x), abs <= 1e-8);
+        }
+    }
+
+    #[test]
+    fn rbf_polynomial_tail_requires_enough_samples() {
+        let model = RadialBasisFunction::new(RbfKernel::Cubic, 0.).unwrap();
+        assert!(model.fit(&[vec![0., 0.], vec![1., 1.]], &[0., 1.]).is_err());
+        let model = RadialBasisFunction::new(RbfKernel::Gaussian { epsilon: 1. }, 0.).unwrap();
+        assert!(model.fit(&[vec![0., 0.], vec![1., 1.]], &[0., 1.]).is_ok());
+    }
+}
@@ -359,11 +359,10 @@ where
         let candidate = state.populations().current().best_individual().cloned();
 
         match (&mut *best, candidate) {
-            (Some(current), Some(candidate)) => {
-                if candidate.objective() < current.objective() {
-                    *current = candidate;
-                }
+            (Some(current), Some(candidate)) if candidate.objective() < current.objective() => {
+                *current = candidate;
             }
+            (Some(_), Some(_)) => {}
             (current, Some(candidate)) => *current = Some(candidate),
             _ => {}
         }
@@ -1,13 +1,13 @@
 //! Common metaheuristic algorithm conditions, e.g. used as termination criteria.
 
-use std::ops::Sub;
+use std::{ops::Sub, time::Duration};
 
 use better_any::{Tid, TidAble};
 use derivative::Derivative;
 use derive_more::{Deref, DerefMut};
 use dyn_clone::DynClone;
 use erased_serde::Serialize as DynSerialize;
-use eyre::ensure;
+use eyre::{ensure, WrapErr};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use trait_set::trait_set;
@@ -17,7 +17,7 @@ use crate::{
     conditions::Condition,
     lens::{AnyLens, Lens, LensRef, ValueOf},
     problems::KnownOptimumProblem,
-    state::common::{Evaluations, Iterations, Progress},
+    state::common::{Evaluations, Iterations, Progress, RunClock},
     CustomState, Problem, State,
 };
 
@@ -184,6 +184,172 @@ where
     }
 }
 
+/// Converts a time limit in `seconds` into a [`Duration`], rejecting limits that are not positive.
+fn duration_from_secs(seconds: f64) -> ExecResult<Duration> {
+    ensure!(
+        seconds > 0.,
+        "the time limit must be greater than 0, but was {seconds}"
+    );
+    Duration::try_from_secs_f64(seconds).wrap_err("invalid time limit")
+}
+
+/// Evaluates to `true` if the wall-clock time elapsed since the start of the run is less than `limit`.
+///
+/// The time is measured by the [`RunClock`], which is started by [`Configuration::optimize`].
+/// If the state doesn't contain a clock yet, it is started when initializing the condition.
+///
+/// The condition inserts and updates `Progress<TimeLimit>`, which is at most `1`.
+///
+/// [`Configuration::optimize`]: crate::Configuration::optimize
+///
+/// # Errors
+///
+/// Returns an `Err` on construction if the `limit` is not positive.
+///
+/// # Examples
+///
+/// Looping for at most 10 seconds:
+///
+/// ```
+/// use mahf::{conditions::TimeLimit, Configuration};
+/// # use mahf::Problem;
+///
+/// # fn example<P: Problem>() -> mahf::ExecResult<Configuration<P>> {
+/// # Ok(
+/// Configuration::builder()
+///     .while_(TimeLimit::seconds(10.)?, |builder| {
+///         /* main loop */
+///         # builder
+///     })
+///     .build()
+/// # )
+/// # }
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TimeLimit {
+    /// The wall-clock time budget.
+    pub limit: Duration,
+}
+
+impl TimeLimit {
+    /// Constructs a new `TimeLimit` with the given `limit`.
+    pub fn from_params(limit: Duration) -> ExecResult<Self> {
+        ensure!(!limit.is_zero(), "the time limit must be greater than 0");
+        Ok(Self { limit })
+    }
+
+    /// Constructs a new `TimeLimit` with the given `limit`.
+    pub fn new<P>(limit: Duration) -> ExecResult<Box<dyn Condition<P>>>
+    where
+        P: Problem,
+    {
+        Ok(Box::new(Self::from_params(limit)?))
+    }
+
+    /// Constructs a new `TimeLimit` with a limit of `seconds`.
+    pub fn seconds<P>(seconds: f64) -> ExecResult<Box<dyn Condition<P>>>
+    where
+        P: Problem,
+    {
+        Self::new(duration_from_secs(seconds)?)
+    }
+}
+
+impl<P> Condition<P> for TimeLimit
+where
+    P: Problem,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.contains::<RunClock>() {
+            state.insert(RunClock::start());
+        }
+        state.insert(Progress::<Self>::default());
+        Ok(())
+    }
+
+    fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let elapsed = state.borrow::<RunClock>().elapsed();
+        state.set_value::<Progress<Self>>(
+            (elapsed.as_secs_f64() / self.limit.as_secs_f64()).min(1.),
+        );
+
+        Ok(elapsed < self.limit)
+    }
+}
+
+/// Evaluates to `true` if the CPU time consumed by the process since the start of the run
+/// is less than `limit`.
+///
+/// The time is measured by the [`RunClock`], which is started by [`Configuration::optimize`].
+/// If the state doesn't contain a clock yet, it is started when initializing the condition.
+///
+/// Note that the CPU time includes the time spent by all threads of the process,
+/// e.g. when evaluating in parallel.
+///
+/// The condition inserts and updates `Progress<CpuTimeLimit>`, which is at most `1`.
+///
+/// [`Configuration::optimize`]: crate::Configuration::optimize
+///
+/// # Errors
+///
+/// Returns an `Err` on construction if the `limit` is not positive.
+/// Initializing the condition fails if the CPU time can't be measured on the current platform.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CpuTimeLimit {
+    /// The CPU time budget.
+    pub limit: Duration,
+}
+
+impl CpuTimeLimit {
+    /// Constructs a new `CpuTimeLimit` with the given `limit`.
+    pub fn from_params(limit: Duration) -> ExecResult<Self> {
+        ensure!(!limit.is_zero(), "the time limit must be greater than 0");
+        Ok(Self { limit })
+    }
+
+    /// Constructs a new `CpuTimeLimit` with the given `limit`.
+    pub fn new<P>(limit: Duration) -> ExecResult<Box<dyn Condition<P>>>
+    where
+        P: Problem,
+    {
+        Ok(Box::new(Self::from_params(limit)?))
+    }
+
+    /// Constructs a new `CpuTimeLimit` with a limit of `seconds`.
+    pub fn seconds<P>(seconds: f64) -> ExecResult<Box<dyn Condition<P>>>
+    where
+        P: Problem,
+    {
+        Self::new(duration_from_secs(seconds)?)
+    }
+}
+
+impl<P> Condition<P> for CpuTimeLimit
+where
+    P: Problem,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        if !state.contains::<RunClock>() {
+            state.insert(RunClock::start());
+        }
+        ensure!(
+            state.borrow::<RunClock>().cpu_elapsed().is_some(),
+            "the CPU time can't be measured on this platform"
+        );
+        state.insert(Progress::<Self>::default());
+        Ok(())
+    }
+
+    fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let elapsed = state.borrow::<RunClock>().cpu_elapsed().unwrap_or_default();
+        state.set_value::<Progress<Self>>(
+            (elapsed.as_secs_f64() / self.limit.as_secs_f64()).min(1.),
+        );
+
+        Ok(elapsed < self.limit)
+    }
+}
+
 /// Evaluates to `true` if `lens` evaluates to a value `v` such that `v % n == 0`.
 ///
 /// The condition is most commonly used as a trigger for logging.
@@ -571,3 +737,61 @@ where
         Ok(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::testing::SingleObjectiveTestProblem;
+
+    #[test]
+    fn time_limits_must_be_positive() {
+        type P = SingleObjectiveTestProblem;
+        for seconds in [0., -1., f64::NAN, f64::INFINITY] {
+            assert!(TimeLimit::seconds::<P>(seconds).is_err());
+            assert!(CpuTimeLimit::seconds::<P>(seconds).is_err());
+        }
+        assert!(TimeLimit::from_params(Duration::ZERO).is_err());
+        assert!(CpuTimeLimit::from_params(Duration::ZERO).is_err());
+        assert!(TimeLimit::seconds::<P>(0.5).is_ok());
+    }
+
+    #[test]
+    fn time_limit_terminates_after_limit() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = State::new();
+        let condition = TimeLimit::from_params(Duration::from_millis(50)).unwrap();
+        condition.init(&problem, &mut state).unwrap();
+
+        assert!(condition.evaluate(&problem, &mut state).unwrap());
+        let progress = state.get_value::<Progress<TimeLimit>>();
+        assert!((0.0..1.).contains(&progress));
+
+        thread::sleep(Duration::from_millis(100));
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+        assert_eq!(state.get_value::<Progress<TimeLimit>>(), 1.);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cpu_time_limit_terminates_after_limit() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = State::new();
+        let condition = CpuTimeLimit::from_params(Duration::from_millis(5)).unwrap();
+        condition.init(&problem, &mut state).unwrap();
+
+        // Sleeping doesn't consume CPU time, so the limit has to be exhausted by busy waiting.
+        let clock = state.borrow::<RunClock>().clone();
+        while clock.cpu_elapsed().unwrap() < Duration::from_millis(10) {
+            std::hint::black_box((0..1000).sum::<u64>());
+        }
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+        assert_eq!(state.get_value::<Progress<CpuTimeLimit>>(), 1.);
+
+        let condition = CpuTimeLimit::seconds(3600.).unwrap();
+        condition.init(&problem, &mut state).unwrap();
+        assert!(condition.evaluate(&problem, &mut state).unwrap());
+        assert!(state.get_value::<Progress<CpuTimeLimit>>() < 1.);
+    }
+}
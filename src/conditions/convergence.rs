@@ -0,0 +1,498 @@
+//! Conditions detecting stagnation and convergence of the search, e.g. used as termination criteria.
+//!
+//! All conditions in this module evaluate to `true` if the search stagnated or converged,
+//! so they have to be inverted with `!` when used as loop condition.
+//!
+//! # Examples
+//!
+//! Looping for at most 1000 iterations, or until the best objective value didn't improve by
+//! more than `1e-8` for 50 iterations:
+//!
+//! ```
+//! use mahf::{
+//!     conditions::{LessThanN, Stagnation},
+//!     Configuration,
+//! };
+//! # use mahf::{ExecResult, SingleObjectiveProblem};
+//!
+//! # fn example<P: SingleObjectiveProblem>() -> ExecResult<Configuration<P>> {
+//! # Ok(
+//! Configuration::builder()
+//!     .while_(
+//!         LessThanN::iterations(1_000) & !Stagnation::new(50, 1e-8)?,
+//!         |builder| {
+//!             /* main loop */
+//!             # builder
+//!         },
+//!     )
+//!     .build()
+//! # )
+//! # }
+//! ```
+
+use std::{collections::VecDeque, marker::PhantomData};
+
+use better_any::{Tid, TidAble};
+use derivative::Derivative;
+use eyre::ensure;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    component::{AnyComponent, ExecResult},
+    components::measures::{diversity::Diversity, stepsize::StepSize},
+    conditions::Condition,
+    identifier::{Global, Identifier, PhantomId},
+    problems::SingleObjectiveProblem,
+    state::StateReq,
+    utils::SerializablePhantom,
+    CustomState, Problem, State,
+};
+
+/// The best objective values of the last evaluations of [`Stagnation<I>`].
+#[derive(Tid)]
+struct BestObjectiveHistory<I: Identifier + 'static> {
+    values: VecDeque<f64>,
+    marker: PhantomData<I>,
+}
+
+impl<I: Identifier> Default for BestObjectiveHistory<I> {
+    fn default() -> Self {
+        Self {
+            values: VecDeque::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<I: Identifier> CustomState<'_> for BestObjectiveHistory<I> {}
+
+/// Evaluates to `true` if the objective value of the [`BestIndividual`] didn't improve by more
+/// than `epsilon` over the last `window` evaluations of the condition.
+///
+/// When used as loop condition, `window` therefore corresponds to the number of iterations.
+/// The condition evaluates to `false` until `window` evaluations have been observed.
+///
+/// The history of objective values is stored in the [`State`] per identifier `I`,
+/// i.e. multiple `Stagnation` conditions within the same state require different identifiers.
+///
+/// [`BestIndividual`]: crate::state::common::BestIndividual
+#[derive(Clone, Serialize)]
+pub struct Stagnation<I: Identifier = Global> {
+    /// The number of evaluations considered.
+    pub window: u32,
+    /// The minimal improvement within the window.
+    pub epsilon: f64,
+    id: PhantomId<I>,
+}
+
+impl<I: Identifier> Stagnation<I> {
+    /// Creates a new `Stagnation` with the given `window` and `epsilon`.
+    pub fn from_params(window: u32, epsilon: f64) -> ExecResult<Self> {
+        ensure!(window > 0, "window must be greater than 0");
+        ensure!(epsilon >= 0., "epsilon must not be negative");
+        Ok(Self {
+            window,
+            epsilon,
+            id: PhantomId::default(),
+        })
+    }
+
+    /// Creates a new `Stagnation` with the given `window` and `epsilon` and identifier `I`.
+    pub fn new_with_id<P>(window: u32, epsilon: f64) -> ExecResult<Box<dyn Condition<P>>>
+    where
+        P: SingleObjectiveProblem,
+    {
+        Ok(Box::new(Self::from_params(window, epsilon)?))
+    }
+}
+
+impl Stagnation<Global> {
+    /// Creates a new `Stagnation` with the given `window` and `epsilon`.
+    pub fn new<P>(window: u32, epsilon: f64) -> ExecResult<Box<dyn Condition<P>>>
+    where
+        P: SingleObjectiveProblem,
+    {
+        Self::new_with_id(window, epsilon)
+    }
+}
+
+impl<P, I> Condition<P> for Stagnation<I>
+where
+    P: SingleObjectiveProblem,
+    I: Identifier,
+{
+    fn init(&self, _problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(BestObjectiveHistory::<I>::default());
+        Ok(())
+    }
+
+    fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let Some(best) = state.best_objective_value() else {
+            return Ok(false);
+        };
+
+        let mut history = state.borrow_mut::<BestObjectiveHistory<I>>();
+        let values = &mut history.values;
+        values.push_back(best.value());
+        if values.len() > self.window as usize + 1 {
+            values.pop_front();
+        }
+
+        let stagnated = values.len() > self.window as usize
+            && values.front().unwrap() - values.back().unwrap() <= self.epsilon;
+        Ok(stagnated)
+    }
+}
+
+/// Evaluates to `true` if the range of objective values in the current population,
+/// i.e. the difference between the worst and best objective value, is below `threshold`.
+///
+/// The condition evaluates to `false` for an empty population.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ObjectiveRangeBelow {
+    /// The range of objective values considered converged.
+    pub threshold: f64,
+}
+
+impl ObjectiveRangeBelow {
+    /// Creates a new `ObjectiveRangeBelow` with the given `threshold`.
+    pub fn from_params(threshold: f64) -> Self {
+        Self { threshold }
+    }
+
+    /// Creates a new `ObjectiveRangeBelow` with the given `threshold`.
+    pub fn new<P>(threshold: f64) -> Box<dyn Condition<P>>
+    where
+        P: SingleObjectiveProblem,
+    {
+        Box::new(Self::from_params(threshold))
+    }
+}
+
+impl<P> Condition<P> for ObjectiveRangeBelow
+where
+    P: SingleObjectiveProblem,
+{
+    fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let populations = state.populations();
+        let population = populations.current();
+
+        if population.is_empty() {
+            return Ok(false);
+        }
+
+        let objectives = population.iter().map(|i| i.objective().value());
+        let (min, max) = objectives.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), o| {
+            (min.min(o), max.max(o))
+        });
+        Ok(max - min < self.threshold)
+    }
+}
+
+/// Evaluates to `true` if the standard deviation of objective values in the current population
+/// is below `threshold`.
+///
+/// The condition evaluates to `false` for an empty population.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ObjectiveStdDevBelow {
+    /// The standard deviation of objective values considered converged.
+    pub threshold: f64,
+}
+
+impl ObjectiveStdDevBelow {
+    /// Creates a new `ObjectiveStdDevBelow` with the given `threshold`.
+    pub fn from_params(threshold: f64) -> Self {
+        Self { threshold }
+    }
+
+    /// Creates a new `ObjectiveStdDevBelow` with the given `threshold`.
+    pub fn new<P>(threshold: f64) -> Box<dyn Condition<P>>
+    where
+        P: SingleObjectiveProblem,
+    {
+        Box::new(Self::from_params(threshold))
+    }
+}
+
+impl<P> Condition<P> for ObjectiveStdDevBelow
+where
+    P: SingleObjectiveProblem,
+{
+    fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let populations = state.populations();
+        let population = populations.current();
+
+        if population.is_empty() {
+            return Ok(false);
+        }
+
+        let n = population.len() as f64;
+        let mean = population
+            .iter()
+            .map(|i| i.objective().value())
+            .sum::<f64>()
+            / n;
+        let variance = population
+            .iter()
+            .map(|i| (i.objective().value() - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        Ok(variance.sqrt() < self.threshold)
+    }
+}
+
+/// Evaluates to `true` if the normalized diversity of the population measured by the
+/// [`DiversityMeasure`] `I` is below `threshold`.
+///
+/// The condition requires the [`Diversity<I>`] state, i.e. the measure `I` has to be executed
+/// as component before evaluating the condition.
+/// As the diversity is normalized by the maximal yet encountered diversity,
+/// `threshold` should be between 0 and 1.
+/// The condition evaluates to `false` as long as no positive diversity was measured,
+/// i.e. also before the first measurement.
+///
+/// [`DiversityMeasure`]: crate::components::measures::diversity::DiversityMeasure
+///
+/// # Examples
+///
+/// Looping until the dimension-wise diversity dropped below 1% of its maximum:
+///
+/// ```
+/// use mahf::{
+///     components::measures::diversity::DimensionWiseDiversity, conditions::DiversityBelow,
+///     Configuration,
+/// };
+/// # use mahf::problems::VectorProblem;
+///
+/// # fn example<P: VectorProblem<Element = f64>>() -> Configuration<P> {
+/// Configuration::builder()
+///     .do_(DimensionWiseDiversity::new())
+///     .while_(!DiversityBelow::<DimensionWiseDiversity>::new(0.01), |builder| {
+///         /* main loop */
+///         # builder
+///         .do_(DimensionWiseDiversity::new())
+///     })
+///     .build()
+/// # }
+/// ```
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct DiversityBelow<I> {
+    /// The normalized diversity considered converged.
+    pub threshold: f64,
+    marker: SerializablePhantom<I>,
+}
+
+impl<I: AnyComponent + 'static> DiversityBelow<I> {
+    /// Creates a new `DiversityBelow` with the given `threshold`.
+    pub fn from_params(threshold: f64) -> Self {
+        Self {
+            threshold,
+            marker: SerializablePhantom::default(),
+        }
+    }
+
+    /// Creates a new `DiversityBelow` with the given `threshold`.
+    pub fn new<P>(threshold: f64) -> Box<dyn Condition<P>>
+    where
+        P: Problem,
+    {
+        Box::new(Self::from_params(threshold))
+    }
+}
+
+impl<P, I> Condition<P> for DiversityBelow<I>
+where
+    P: Problem,
+    I: AnyComponent + 'static,
+{
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, Diversity<I>>()?;
+        Ok(())
+    }
+
+    fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let diversity = state.borrow::<Diversity<I>>();
+        Ok(diversity.max_diversity > 0. && diversity.diversity < self.threshold)
+    }
+}
+
+/// Evaluates to `true` if the mean step size measured by the [`StepSizeMeasure`] `I`
+/// is below `threshold`.
+///
+/// The condition requires the [`StepSize<I>`] state, i.e. the measure `I` has to be executed
+/// as component before evaluating the condition.
+/// The condition evaluates to `false` as long as no step size was measured, i.e. before the
+/// measure could compare two populations.
+///
+/// [`StepSizeMeasure`]: crate::components::measures::stepsize::StepSizeMeasure
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = ""))]
+pub struct StepSizeBelow<I> {
+    /// The mean step size considered collapsed.
+    pub threshold: f64,
+    marker: SerializablePhantom<I>,
+}
+
+impl<I: AnyComponent + 'static> StepSizeBelow<I> {
+    /// Creates a new `StepSizeBelow` with the given `threshold`.
+    pub fn from_params(threshold: f64) -> Self {
+        Self {
+            threshold,
+            marker: SerializablePhantom::default(),
+        }
+    }
+
+    /// Creates a new `StepSizeBelow` with the given `threshold`.
+    pub fn new<P>(threshold: f64) -> Box<dyn Condition<P>>
+    where
+        P: Problem,
+    {
+        Box::new(Self::from_params(threshold))
+    }
+}
+
+impl<P, I> Condition<P> for StepSizeBelow<I>
+where
+    P: Problem,
+    I: AnyComponent + 'static,
+{
+    fn require(&self, _problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        state_req.require::<Self, StepSize<I>>()?;
+        Ok(())
+    }
+
+    fn evaluate(&self, _problem: &P, state: &mut State<P>) -> ExecResult<bool> {
+        let step_size = state.borrow::<StepSize<I>>();
+        Ok(!step_size.all_steps.is_empty() && step_size.step_size < self.threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::measures::{diversity::DimensionWiseDiversity, stepsize::EuclideanStepSize},
+        identifier::{A, B},
+        state::common::{BestIndividual, Populations},
+        testing::*,
+    };
+
+    fn state_with_best(objective: f64) -> State<'static, SingleObjectiveTestProblem> {
+        let mut state = State::new();
+        state.insert(BestIndividual::<SingleObjectiveTestProblem>::default());
+        set_best(&mut state, objective);
+        state
+    }
+
+    fn set_best(state: &mut State<SingleObjectiveTestProblem>, objective: f64) {
+        state
+            .borrow_mut::<BestIndividual<SingleObjectiveTestProblem>>()
+            .update(&single_test_individual(objective));
+    }
+
+    #[test]
+    fn stagnation_detects_missing_improvement() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = state_with_best(5.);
+        let stagnation = Stagnation::<Global>::from_params(2, 0.5).unwrap();
+        stagnation.init(&problem, &mut state).unwrap();
+
+        let mut results = Vec::new();
+        for objective in [5., 4., 3.8, 3.6, 3.6] {
+            set_best(&mut state, objective);
+            results.push(stagnation.evaluate(&problem, &mut state).unwrap());
+        }
+        assert_eq!(results, [false, false, false, true, true]);
+    }
+
+    #[test]
+    fn stagnation_histories_are_separated_by_identifier() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = state_with_best(1.);
+        let first = Stagnation::<A>::from_params(2, 0.).unwrap();
+        let second = Stagnation::<B>::from_params(2, 0.).unwrap();
+        first.init(&problem, &mut state).unwrap();
+        second.init(&problem, &mut state).unwrap();
+
+        for _ in 0..3 {
+            first.evaluate(&problem, &mut state).unwrap();
+        }
+        assert!(first.evaluate(&problem, &mut state).unwrap());
+        assert!(!second.evaluate(&problem, &mut state).unwrap());
+    }
+
+    #[test]
+    fn diversity_below_waits_for_measurement() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = State::new();
+        state.insert(Diversity::<DimensionWiseDiversity>::new());
+        let condition = DiversityBelow::<DimensionWiseDiversity>::from_params(0.1);
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+
+        state
+            .borrow_mut::<Diversity<DimensionWiseDiversity>>()
+            .update(2.);
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+
+        state
+            .borrow_mut::<Diversity<DimensionWiseDiversity>>()
+            .update(0.1);
+        assert!(condition.evaluate(&problem, &mut state).unwrap());
+    }
+
+    fn state_with_population(objectives: &[f64]) -> State<'static, SingleObjectiveTestProblem> {
+        let mut state = State::new();
+        state.insert(Populations::<SingleObjectiveTestProblem>::new());
+        state
+            .populations_mut()
+            .push(single_test_population(objectives));
+        state
+    }
+
+    #[test]
+    fn objective_range_below_compares_range() {
+        let problem = SingleObjectiveTestProblem::new();
+        let condition = ObjectiveRangeBelow::from_params(1.);
+        let mut state = state_with_population(&[]);
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+        let mut state = state_with_population(&[2., 2.5, 2.9]);
+        assert!(condition.evaluate(&problem, &mut state).unwrap());
+        let mut state = state_with_population(&[2., 2.5, 3.]);
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+    }
+
+    #[test]
+    fn objective_std_dev_below_compares_std_dev() {
+        let problem = SingleObjectiveTestProblem::new();
+        let condition = ObjectiveStdDevBelow::from_params(1.);
+        let mut state = state_with_population(&[]);
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+        // The population standard deviation is 0.9 and 1 respectively.
+        let mut state = state_with_population(&[1.1, 2.9]);
+        assert!(condition.evaluate(&problem, &mut state).unwrap());
+        let mut state = state_with_population(&[1., 3.]);
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+    }
+
+    #[test]
+    fn step_size_below_waits_for_measurement() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = State::new();
+        state.insert(StepSize::<EuclideanStepSize>::new());
+        let condition = StepSizeBelow::<EuclideanStepSize>::from_params(0.1);
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+
+        state
+            .borrow_mut::<StepSize<EuclideanStepSize>>()
+            .update((vec![1., 2.], vec![]));
+        assert!(!condition.evaluate(&problem, &mut state).unwrap());
+
+        state
+            .borrow_mut::<StepSize<EuclideanStepSize>>()
+            .update((vec![0.01, 0.05], vec![]));
+        assert!(condition.evaluate(&problem, &mut state).unwrap());
+    }
+}
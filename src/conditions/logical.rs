@@ -5,7 +5,13 @@ use std::ops;
 use derivative::Derivative;
 use serde::Serialize;
 
-use crate::{component::ExecResult, conditions::Condition, state::StateReq, Problem, State};
+use crate::{
+    component::ExecResult,
+    conditions::Condition,
+    configuration::tree::{self, Edge, EdgeMut},
+    state::StateReq,
+    Problem, State,
+};
 
 /// Boolean `AND` operator (`&`) for [`Condition`]s.
 ///
@@ -46,17 +52,11 @@ impl<P: Problem> And<P> {
 
 impl<P: Problem> Condition<P> for And<P> {
     fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
-        for condition in self.0.iter() {
-            condition.init(problem, state)?;
-        }
-        Ok(())
+        tree::init_children(self.children(), problem, state)
     }
 
     fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
-        for condition in &self.0 {
-            condition.require(problem, state_req)?;
-        }
-        Ok(())
+        tree::require_children(self.children(), problem, state_req)
     }
 
     fn evaluate(&self, problem: &P, state: &mut State<P>) -> ExecResult<bool> {
@@ -67,6 +67,22 @@ impl<P: Problem> Condition<P> for And<P> {
             .collect();
         Ok(evaluations?.into_iter().all(|x| x))
     }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, condition)| Edge::condition(format!("And[{i}]"), condition.as_ref()))
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        self.0
+            .iter_mut()
+            .enumerate()
+            .map(|(i, condition)| EdgeMut::condition(format!("And[{i}]"), condition))
+            .collect()
+    }
 }
 
 impl<P: Problem> ops::BitAnd for Box<dyn Condition<P>> {
@@ -116,17 +132,11 @@ impl<P: Problem> Or<P> {
 
 impl<P: Problem> Condition<P> for Or<P> {
     fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
-        for condition in self.0.iter() {
-            condition.init(problem, state)?;
-        }
-        Ok(())
+        tree::init_children(self.children(), problem, state)
     }
 
     fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
-        for condition in &self.0 {
-            condition.require(problem, state_req)?;
-        }
-        Ok(())
+        tree::require_children(self.children(), problem, state_req)
     }
 
     fn evaluate(&self, problem: &P, state: &mut State<P>) -> ExecResult<bool> {
@@ -137,6 +147,22 @@ impl<P: Problem> Condition<P> for Or<P> {
             .collect();
         Ok(evaluations?.into_iter().any(|x| x))
     }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, condition)| Edge::condition(format!("Or[{i}]"), condition.as_ref()))
+            .collect()
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        self.0
+            .iter_mut()
+            .enumerate()
+            .map(|(i, condition)| EdgeMut::condition(format!("Or[{i}]"), condition))
+            .collect()
+    }
 }
 
 impl<P: Problem> ops::BitOr for Box<dyn Condition<P>> {
@@ -183,18 +209,24 @@ impl<P: Problem> Not<P> {
 
 impl<P: Problem> Condition<P> for Not<P> {
     fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
-        self.0.init(problem, state)?;
-        Ok(())
+        tree::init_children(self.children(), problem, state)
     }
 
     fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
-        self.0.require(problem, state_req)?;
-        Ok(())
+        tree::require_children(self.children(), problem, state_req)
     }
 
     fn evaluate(&self, problem: &P, state: &mut State<P>) -> ExecResult<bool> {
         Ok(!self.0.evaluate(problem, state)?)
     }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        vec![Edge::condition("Not", self.0.as_ref())]
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        vec![EdgeMut::condition("Not", &mut self.0)]
+    }
 }
 
 impl<P: Problem> ops::Not for Box<dyn Condition<P>> {
@@ -6,15 +6,22 @@
 
 use crate::{
     component::{AnyComponent, ExecResult},
+    configuration::tree::{Edge, EdgeMut},
     state::StateReq,
     Problem, State,
 };
 
 pub mod common;
+pub mod convergence;
 pub mod cro;
 pub mod logical;
 
-pub use common::{ChangeOf, EqualToN, EveryN, LessThanN, OptimumReached, RandomChance};
+pub use common::{
+    ChangeOf, CpuTimeLimit, EqualToN, EveryN, LessThanN, OptimumReached, RandomChance, TimeLimit,
+};
+pub use convergence::{
+    DiversityBelow, ObjectiveRangeBelow, ObjectiveStdDevBelow, Stagnation, StepSizeBelow,
+};
 pub use logical::{And, Not, Or};
 
 /// Trait to represent a condition *component* for loops or branches.
@@ -48,6 +55,29 @@ pub trait Condition<P: Problem>: AnyComponent {
 
     /// Evaluates the condition, performing the actual logic.
     fn evaluate(&self, problem: &P, state: &mut State<P>) -> ExecResult<bool>;
+
+    /// Returns the edges to the child conditions of the condition, e.g. the operands of an [`And`].
+    ///
+    /// Only conditions containing other conditions need to implement this method.
+    /// See the [`tree`] module for more details.
+    ///
+    /// [`tree`]: crate::configuration::tree
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        Vec::new()
+    }
+
+    /// Returns the edges to the mutable child conditions of the condition.
+    ///
+    /// This is the mutable twin of [`children`](Self::children), and has to return the same
+    /// children with the same labels in the same order.
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        Vec::new()
+    }
+
+    /// Returns the full name of the type of the condition.
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 erased_serde::serialize_trait_object!(<P: Problem> Condition<P>);
@@ -8,7 +8,6 @@ use crate::{
     component::ExecResult,
     components::{evaluation, utils::debug, Block, Branch, Component, Loop, Scope},
     conditions::Condition,
-    identifier,
     identifier::Identifier,
     logging,
     problems::{Evaluate, MultiObjectiveProblem, SingleObjectiveProblem},
@@ -16,6 +15,16 @@ use crate::{
     Problem, State,
 };
 
+pub mod flowchart;
+pub mod profiling;
+pub mod tree;
+pub mod validation;
+
+use flowchart::{Flowchart, FlowchartOptions};
+use profiling::{Profiler, ProfilingOptions};
+use tree::{ComponentPath, Node};
+use validation::{Validation, ValidationReport};
+
 /// A (meta)heuristic configuration.
 ///
 /// A grouping of components is called a metaheuristic configuration, and the `Configuration` struct
@@ -125,6 +134,105 @@ impl<P: Problem> Configuration<P> {
         Self::builder().do_(self.0)
     }
 
+    /// Calls the `visitor` on every [`Component`] and [`Condition`] of the `Configuration`
+    /// in pre-order, together with its [`ComponentPath`].
+    ///
+    /// See the [`tree`] module for more details.
+    ///
+    /// # Examples
+    ///
+    /// Counting the components of a configuration:
+    ///
+    /// ```
+    /// # use mahf::{Configuration, Problem};
+    /// use mahf::configuration::tree::NodeKind;
+    ///
+    /// # fn example<P: Problem>(config: Configuration<P>) -> usize {
+    /// let mut components = 0;
+    /// config.walk(|_, node| {
+    ///     if node.kind() == NodeKind::Component {
+    ///         components += 1;
+    ///     }
+    /// });
+    /// # components
+    /// # }
+    /// ```
+    pub fn walk<'a>(&'a self, visitor: impl FnMut(&ComponentPath, Node<'a, P>)) {
+        Node::Component(self.heuristic()).walk(&ComponentPath::default(), visitor)
+    }
+
+    /// Replaces every [`Component`] matched by `predicate` with the result of `replacement`,
+    /// which is called with the matched component.
+    ///
+    /// Returns the number of replaced components.
+    /// Neither the replacements nor the children of matched components are visited.
+    pub fn replace_where(
+        &mut self,
+        mut predicate: impl FnMut(&ComponentPath, &dyn Component<P>) -> bool,
+        mut replacement: impl FnMut(&dyn Component<P>) -> Box<dyn Component<P>>,
+    ) -> usize {
+        tree::replace(
+            &mut self.0,
+            &mut ComponentPath::default(),
+            &mut predicate,
+            &mut replacement,
+        )
+    }
+
+    /// Replaces every [`Component`] of type `T` with the result of `replacement`,
+    /// which is called with the matched component.
+    ///
+    /// Returns the number of replaced components.
+    ///
+    /// # Examples
+    ///
+    /// Creating an ablation variant without mutation:
+    ///
+    /// ```
+    /// # use mahf::{Configuration, problems::LimitedVectorProblem};
+    /// use mahf::components::{mutation::NormalMutation, utils::Noop};
+    ///
+    /// # fn example<P: LimitedVectorProblem<Element = f64>>(mut config: Configuration<P>) {
+    /// config.replace_all::<NormalMutation>(|_| Noop::new());
+    /// # }
+    /// ```
+    pub fn replace_all<T: Component<P>>(
+        &mut self,
+        replacement: impl FnMut(&dyn Component<P>) -> Box<dyn Component<P>>,
+    ) -> usize {
+        let name = std::any::type_name::<T>();
+        self.replace_where(|_, component| component.type_name() == name, replacement)
+    }
+
+    /// Inserts a clone of `component` after every [`Component`] matched by `predicate`.
+    ///
+    /// Returns the number of insertions.
+    /// Matched components are wrapped in a [`Block`] together with the inserted component,
+    /// which therefore changes their [`ComponentPath`].
+    pub fn insert_after_where(
+        &mut self,
+        mut predicate: impl FnMut(&ComponentPath, &dyn Component<P>) -> bool,
+        component: Box<dyn Component<P>>,
+    ) -> usize {
+        tree::insert_after(
+            &mut self.0,
+            &mut ComponentPath::default(),
+            &mut predicate,
+            component.as_ref(),
+        )
+    }
+
+    /// Inserts a clone of `component` after every [`Component`] of type `T`.
+    ///
+    /// Returns the number of insertions.
+    /// See [`insert_after_where`] for more details.
+    ///
+    /// [`insert_after_where`]: Self::insert_after_where
+    pub fn insert_after<T: Component<P>>(&mut self, component: Box<dyn Component<P>>) -> usize {
+        let name = std::any::type_name::<T>();
+        self.insert_after_where(|_, matched| matched.type_name() == name, component)
+    }
+
     /// Serializes the `Configuration` into the file at `path` using [`ron`].
     ///
     /// # Examples
@@ -150,6 +258,71 @@ impl<P: Problem> Configuration<P> {
         .wrap_err("failed to serialize configuration")
     }
 
+    /// Instruments every component of the `Configuration` for profiling.
+    ///
+    /// The measurements are collected in the [`Profile`] state, which is reported
+    /// after execution as specified by the `options`.
+    /// The component tree, including the [`ComponentPath`]s, is unaffected.
+    ///
+    /// See the [`profiling`] module for more details.
+    ///
+    /// [`Profile`]: profiling::Profile
+    ///
+    /// # Examples
+    ///
+    /// Finding the component which dominates the runtime:
+    ///
+    /// ```
+    /// # use mahf::{problems::{Evaluate, ObjectiveFunction}, ExecResult};
+    /// use mahf::{
+    ///     configuration::profiling::{Profile, ProfilingOptions},
+    ///     Configuration,
+    /// };
+    ///
+    /// # fn example<P: ObjectiveFunction>(config: Configuration<P>, problem: P, evaluator: impl Evaluate<Problem = P>) -> ExecResult<()> {
+    /// let state = config
+    ///     .profiled(ProfilingOptions::default())
+    ///     .optimize(&problem, evaluator)?;
+    /// println!("{}", state.borrow::<Profile>());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn profiled(self, options: ProfilingOptions) -> Self {
+        let mut heuristic = self.0;
+        profiling::instrument(&mut heuristic, &mut ComponentPath::default());
+        Self(Profiler::new(options, heuristic))
+    }
+
+    /// Renders the `Configuration` as flowchart in the DOT language of [Graphviz].
+    ///
+    /// See the [`flowchart`] module for details on the rendering.
+    ///
+    /// [Graphviz]: https://graphviz.org/
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mahf::Problem;
+    /// use mahf::{configuration::flowchart::FlowchartOptions, Configuration};
+    ///
+    /// # fn example<P: Problem>(config: Configuration<P>) {
+    /// let dot = config.to_dot(FlowchartOptions::default());
+    /// println!("{dot}");
+    /// # }
+    /// ```
+    pub fn to_dot(&self, options: FlowchartOptions) -> String {
+        Flowchart::new(self, options).to_dot()
+    }
+
+    /// Renders the `Configuration` as [Mermaid] flowchart.
+    ///
+    /// See the [`flowchart`] module for details on the rendering.
+    ///
+    /// [Mermaid]: https://mermaid.js.org/
+    pub fn to_mermaid(&self, options: FlowchartOptions) -> String {
+        Flowchart::new(self, options).to_mermaid()
+    }
+
     /// Runs the `Configuration` on the `problem` using a given [`State`].
     ///
     /// Note that the caller is responsible for initializing `state` properly.
@@ -165,19 +338,22 @@ impl<P: Problem> Configuration<P> {
     ///
     /// # Initialization
     ///
-    /// The state is pre-initialized with [`Populations`] and [`Log`].
+    /// The state is pre-initialized with [`Populations`] and [`Log`], and the [`RunClock`]
+    /// is started.
     ///
     /// The random generator defaults to a randomly seeded RNG ([`Random::default`]).
     ///
-    /// The `evaluator` is inserted wrapped inside an [`Evaluator`] with the [`Global`] identifier.
+    /// The `evaluator` is initialized and inserted wrapped inside an [`Evaluator`] with the
+    /// [`Global`] identifier, see [`State::insert_evaluator`].
     ///
     /// For initializing the state with custom state, e.g. a fixed random seed,
     /// see [`optimize_with`].
     ///
     /// [`Populations`]: common::Populations
     /// [`Log`]: logging::Log
+    /// [`RunClock`]: common::RunClock
     /// [`Evaluator`]: common::Evaluator
-    /// [`Global`]: identifier::Global
+    /// [`Global`]: crate::identifier::Global
     /// [`optimize_with`]: Self::optimize_with
     ///
     ///
@@ -205,7 +381,8 @@ impl<P: Problem> Configuration<P> {
         state.insert(logging::Log::new());
         state.insert(Random::default());
         state.insert(common::Populations::<P>::new());
-        state.insert(common::Evaluator::<P, identifier::Global>::new(evaluator));
+        state.insert_evaluator(evaluator);
+        state.insert(common::RunClock::start());
 
         self.run(problem, &mut state)?;
 
@@ -222,11 +399,13 @@ impl<P: Problem> Configuration<P> {
     ///
     /// If no random generator is inserted in `init_state`, it will default
     /// to a randomly seeded RNG ([Random::default]).
+    /// Similarly, the [`RunClock`] is started after `init_state` unless inserted there.
     ///
     /// Note that the evaluator has to be inserted **manually** into the [`State`], using e.g. `{`[`State::insert_evaluator`], [`State::insert_evaluator_as`]`}`.
     ///
     /// [`Populations`]: common::Populations
     /// [`Log`]: logging::Log
+    /// [`RunClock`]: common::RunClock
     /// [`optimize_with`]: Self::optimize_with
     ///
     /// # Examples
@@ -262,10 +441,91 @@ impl<P: Problem> Configuration<P> {
             state.insert(Random::default());
         }
 
+        if !state.contains::<common::RunClock>() {
+            state.insert(common::RunClock::start());
+        }
+
         self.run(problem, &mut state)?;
 
         Ok(state)
     }
+
+    /// Validates the `Configuration` on the given `problem` without executing it, returning
+    /// a report of all missing or conflicting custom state in the whole component tree.
+    ///
+    /// The state is initialized as in [`optimize`], with a placeholder evaluator, after which
+    /// the `{init, require}` methods are called on the configuration.
+    /// Errors and missing state don't abort the validation, but are collected in the report
+    /// together with the path of the offending component.
+    ///
+    /// For validating a configuration which requires custom state, see [`validate_with`].
+    ///
+    /// See the [`validation`] module for more details.
+    ///
+    /// [`optimize`]: Self::optimize
+    /// [`validate_with`]: Self::validate_with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use mahf::problems::ObjectiveFunction;
+    /// use mahf::Configuration;
+    ///
+    /// # fn example<P: ObjectiveFunction>(problem: P) {
+    /// let config = Configuration::builder()
+    ///     /* configuration definition */
+    ///     .build();
+    /// let report = config.validate(&problem);
+    /// assert!(report.is_valid(), "{report}");
+    /// # }
+    /// ```
+    pub fn validate(&self, problem: &P) -> ValidationReport {
+        self.validate_with(problem, |state| {
+            state.insert_evaluator(validation::Placeholder::new());
+            Ok(())
+        })
+    }
+
+    /// Validates the `Configuration` on the given `problem` without executing it, initializing
+    /// the [`State`] beforehand with a custom function.
+    ///
+    /// The state is initialized as in [`optimize_with`], so the evaluator has to be inserted
+    /// **manually**, if required.
+    /// Note that the evaluator is never called.
+    ///
+    /// See [`validate`] for more details.
+    ///
+    /// [`optimize_with`]: Self::optimize_with
+    /// [`validate`]: Self::validate
+    pub fn validate_with<'a>(
+        &self,
+        problem: &P,
+        init_state: impl FnOnce(&mut State<'a, P>) -> ExecResult<()>,
+    ) -> ValidationReport {
+        let mut state = State::new();
+
+        state.insert(Validation::default());
+        state.insert(logging::Log::new());
+        state.insert(common::Populations::<P>::new());
+
+        let result = init_state(&mut state);
+        state.borrow_mut::<Validation>().record(result);
+
+        if !state.contains::<Random>() {
+            state.insert(Random::default());
+        }
+
+        if !state.contains::<common::RunClock>() {
+            state.insert(common::RunClock::start());
+        }
+
+        let result = self.0.init(problem, &mut state);
+        state.borrow_mut::<Validation>().record(result);
+        let result = self.0.require(problem, &state.requirements());
+        state.borrow_mut::<Validation>().record(result);
+
+        state.take::<Validation>().into_report()
+    }
 }
 
 impl<P: Problem> From<Box<dyn Component<P>>> for Configuration<P> {
@@ -588,7 +848,7 @@ impl<P: Problem> ConfigurationBuilder<P> {
     /// [`Individual`]: crate::Individual
     /// [current population]: common::Populations::current
     /// [`Evaluator`]: common::Evaluator
-    /// [`Global`]: identifier::Global
+    /// [`Global`]: crate::identifier::Global
     /// [`PopulationEvaluator`]: evaluation::PopulationEvaluator
     ///
     /// # Examples
@@ -617,7 +877,7 @@ impl<P: Problem> ConfigurationBuilder<P> {
     /// [current population]: common::Populations::current
     /// [`Evaluator`]: common::Evaluator
     /// [`PopulationEvaluator`]: evaluation::PopulationEvaluator
-    /// [`Global`]: identifier::Global
+    /// [`Global`]: crate::identifier::Global
     ///
     /// # Examples
     ///
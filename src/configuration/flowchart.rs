@@ -0,0 +1,504 @@
+//! Rendering of configurations as flowcharts.
+//!
+//! [`Configuration::to_dot`] and [`Configuration::to_mermaid`] render the component tree
+//! as [Graphviz] and [Mermaid] flowcharts, respectively:
+//! - [`Block`]s are rendered as sequences of their components.
+//! - [`Loop`]s are rendered as a junction, with a back-edge from the end of the body,
+//!   labeled with the serialized condition.
+//! - [`Branch`]es are rendered as diamonds labeled with the serialized condition,
+//!   with `true` and `false` edges.
+//! - [`Scope`]s are rendered as subgraphs.
+//! - Other components with children (see the [`tree`] module) are rendered as a single node,
+//!   with their child components as subgraphs labeled with the edge label.
+//!
+//! All other components are rendered as boxes, labeled with their type name, or with their
+//! serialized parameters if enabled by [`FlowchartOptions::parameters`].
+//!
+//! [Graphviz]: https://graphviz.org/
+//! [Mermaid]: https://mermaid.js.org/
+//! [`Configuration::to_dot`]: crate::Configuration::to_dot
+//! [`Configuration::to_mermaid`]: crate::Configuration::to_mermaid
+//! [`Block`]: crate::components::Block
+//! [`Loop`]: crate::components::Loop
+//! [`Branch`]: crate::components::Branch
+//! [`Scope`]: crate::components::Scope
+//! [`tree`]: crate::configuration::tree
+//!
+//! # Examples
+//!
+//! Writing the flowchart of a configuration to a file:
+//!
+//! ```no_run
+//! # use mahf::{ExecResult, Problem};
+//! use mahf::{configuration::flowchart::FlowchartOptions, Configuration};
+//!
+//! # fn example<P: Problem>(config: Configuration<P>) -> ExecResult<()> {
+//! let options = FlowchartOptions { parameters: true };
+//! std::fs::write("configuration.dot", config.to_dot(options))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{any::type_name, fmt::Write};
+
+use crate::{
+    components::{Block, Branch, Loop, Scope},
+    conditions::{And, Not, Or},
+    configuration::tree::{short_type_name, Edge, Node},
+    Configuration, Problem,
+};
+
+/// Options for rendering flowcharts.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FlowchartOptions {
+    /// Whether components are labeled with their serialized parameters instead of only
+    /// their type name.
+    pub parameters: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Shape {
+    Terminal,
+    Process,
+    Decision,
+    Junction,
+}
+
+struct FlowNode {
+    label: String,
+    shape: Shape,
+}
+
+struct FlowEdge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+    back: bool,
+}
+
+#[derive(Default)]
+struct Cluster {
+    label: String,
+    nodes: Vec<usize>,
+    clusters: Vec<Cluster>,
+}
+
+/// The open edges leaving a part of the flowchart, with their optional labels.
+type Exits = Vec<(usize, Option<String>)>;
+
+/// A flowchart of a [`Configuration`], independent of the output format.
+pub(crate) struct Flowchart {
+    nodes: Vec<FlowNode>,
+    edges: Vec<FlowEdge>,
+    root: Cluster,
+}
+
+impl Flowchart {
+    /// Builds the flowchart of the `config`.
+    pub(crate) fn new<P: Problem>(config: &Configuration<P>, options: FlowchartOptions) -> Self {
+        let mut builder = Builder {
+            options,
+            chart: Flowchart {
+                nodes: Vec::new(),
+                edges: Vec::new(),
+                root: Cluster::default(),
+            },
+            clusters: vec![Cluster::default()],
+        };
+
+        let start = builder.node("Start".to_string(), Shape::Terminal);
+        let exits = builder.component(Node::Component(config.heuristic()), vec![(start, None)]);
+        let end = builder.node("End".to_string(), Shape::Terminal);
+        builder.connect(exits, end, None, false);
+
+        let mut chart = builder.chart;
+        chart.root = builder.clusters.pop().unwrap();
+        chart
+    }
+
+    /// Renders the flowchart in the DOT language of Graphviz.
+    pub(crate) fn to_dot(&self) -> String {
+        fn escape(label: &str) -> String {
+            label
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+        }
+
+        fn write_cluster(
+            chart: &Flowchart,
+            cluster: &Cluster,
+            indent: usize,
+            count: &mut usize,
+            out: &mut String,
+        ) {
+            let pad = "    ".repeat(indent);
+            for &id in &cluster.nodes {
+                let node = &chart.nodes[id];
+                let shape = match node.shape {
+                    Shape::Terminal => "oval",
+                    Shape::Process => "box",
+                    Shape::Decision => "diamond",
+                    Shape::Junction => "point",
+                };
+                let _ = writeln!(
+                    out,
+                    "{pad}n{id} [label=\"{}\", shape={shape}];",
+                    escape(&node.label)
+                );
+            }
+            for inner in &cluster.clusters {
+                let _ = writeln!(out, "{pad}subgraph cluster_{count} {{");
+                let _ = writeln!(out, "{pad}    label=\"{}\";", escape(&inner.label));
+                *count += 1;
+                write_cluster(chart, inner, indent + 1, count, out);
+                let _ = writeln!(out, "{pad}}}");
+            }
+        }
+
+        let mut out = String::from("digraph configuration {\n");
+        write_cluster(self, &self.root, 1, &mut 0, &mut out);
+        for edge in &self.edges {
+            let mut attributes = Vec::new();
+            if let Some(label) = &edge.label {
+                attributes.push(format!("label=\"{}\"", escape(label)));
+            }
+            if edge.back {
+                attributes.push("style=dashed".to_string());
+            }
+            let attributes = if attributes.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attributes.join(", "))
+            };
+            let _ = writeln!(out, "    n{} -> n{}{attributes};", edge.from, edge.to);
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Renders the flowchart as Mermaid flowchart.
+    pub(crate) fn to_mermaid(&self) -> String {
+        fn escape(label: &str) -> String {
+            label.replace('"', "#quot;").replace('\n', "<br>")
+        }
+
+        fn write_cluster(
+            chart: &Flowchart,
+            cluster: &Cluster,
+            indent: usize,
+            count: &mut usize,
+            out: &mut String,
+        ) {
+            let pad = "    ".repeat(indent);
+            for &id in &cluster.nodes {
+                let node = &chart.nodes[id];
+                let label = escape(&node.label);
+                let _ = match node.shape {
+                    Shape::Terminal => writeln!(out, "{pad}n{id}([\"{label}\"])"),
+                    Shape::Process => writeln!(out, "{pad}n{id}[\"{label}\"]"),
+                    Shape::Decision => writeln!(out, "{pad}n{id}{{\"{label}\"}}"),
+                    Shape::Junction => writeln!(out, "{pad}n{id}((\" \"))"),
+                };
+            }
+            for inner in &cluster.clusters {
+                let _ = writeln!(out, "{pad}subgraph c{count} [\"{}\"]", escape(&inner.label));
+                *count += 1;
+                write_cluster(chart, inner, indent + 1, count, out);
+                let _ = writeln!(out, "{pad}end");
+            }
+        }
+
+        let mut out = String::from("flowchart TD\n");
+        write_cluster(self, &self.root, 1, &mut 0, &mut out);
+        for edge in &self.edges {
+            let arrow = if edge.back { "-.->" } else { "-->" };
+            let label = edge
+                .label
+                .as_ref()
+                .map(|label| format!("|\"{}\"|", escape(label)))
+                .unwrap_or_default();
+            let _ = writeln!(out, "    n{} {arrow}{label} n{}", edge.from, edge.to);
+        }
+        out
+    }
+}
+
+struct Builder {
+    options: FlowchartOptions,
+    chart: Flowchart,
+    /// The stack of currently open clusters, starting with the root.
+    clusters: Vec<Cluster>,
+}
+
+impl Builder {
+    fn node(&mut self, label: String, shape: Shape) -> usize {
+        let id = self.chart.nodes.len();
+        self.chart.nodes.push(FlowNode { label, shape });
+        self.clusters.last_mut().unwrap().nodes.push(id);
+        id
+    }
+
+    /// Connects all `exits` to the node `to`, combining the labels of the exits with `label`.
+    fn connect(&mut self, exits: Exits, to: usize, label: Option<&str>, back: bool) {
+        for (from, exit_label) in exits {
+            let label = match (exit_label, label) {
+                (Some(exit_label), Some(label)) => Some(format!("{exit_label} / {label}")),
+                (exit_label, label) => exit_label.or(label.map(str::to_string)),
+            };
+            self.chart.edges.push(FlowEdge {
+                from,
+                to,
+                label,
+                back,
+            });
+        }
+    }
+
+    fn within<T>(&mut self, label: String, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.clusters.push(Cluster {
+            label,
+            ..Cluster::default()
+        });
+        let result = f(self);
+        let cluster = self.clusters.pop().unwrap();
+        self.clusters.last_mut().unwrap().clusters.push(cluster);
+        result
+    }
+
+    /// Adds the component `node` to the flowchart, entered from `entry`, and returns its exits.
+    fn component<P: Problem>(&mut self, node: Node<'_, P>, entry: Exits) -> Exits {
+        let name = node.type_name();
+        let children = node.children();
+
+        if name == type_name::<Block<P>>() {
+            children
+                .into_iter()
+                .fold(entry, |exits, child| self.component(child.node, exits))
+        } else if name == type_name::<Loop<P>>() {
+            let [condition, body] = <[Edge<P>; 2]>::try_from(children).ok().unwrap();
+            let junction = self.node(String::new(), Shape::Junction);
+            self.connect(entry, junction, None, false);
+            let exits = self.component(body.node, vec![(junction, None)]);
+            let label = format!("while {}", condition_label(condition.node));
+            self.connect(exits, junction, Some(&label), true);
+            vec![(junction, None)]
+        } else if name == type_name::<Branch<P>>() {
+            let mut children = children.into_iter();
+            let condition = children.next().unwrap();
+            let decision = self.node(condition_label(condition.node), Shape::Decision);
+            self.connect(entry, decision, None, false);
+            let if_body = children.next().unwrap();
+            let mut exits = self.component(if_body.node, vec![(decision, Some("true".into()))]);
+            match children.next() {
+                Some(else_body) => exits
+                    .extend(self.component(else_body.node, vec![(decision, Some("false".into()))])),
+                None => exits.push((decision, Some("false".into()))),
+            }
+            exits
+        } else if name == type_name::<Scope<P>>() {
+            let body = children.into_iter().next().unwrap();
+            self.within("Scope".to_string(), |builder| {
+                builder.component(body.node, entry)
+            })
+        } else {
+            let mut label = self.component_label(node);
+            let mut components = Vec::new();
+            for child in children {
+                match child.node {
+                    Node::Condition(_) => {
+                        let _ = write!(label, "\n{}: {}", child.label, condition_label(child.node));
+                    }
+                    Node::Component(_) => components.push(child),
+                }
+            }
+
+            let id = self.node(label, Shape::Process);
+            self.connect(entry, id, None, false);
+            for child in components {
+                let entry = vec![(id, Some(child.label.clone()))];
+                self.within(child.label, |builder| builder.component(child.node, entry));
+            }
+            vec![(id, None)]
+        }
+    }
+
+    fn component_label<P: Problem>(&self, node: Node<'_, P>) -> String {
+        if self.options.parameters {
+            serialized(node)
+        } else {
+            short_type_name(node.type_name())
+        }
+    }
+}
+
+/// Returns the label of a condition, combining [`And`], [`Or`], and [`Not`] using operators.
+fn condition_label<P: Problem>(node: Node<'_, P>) -> String {
+    let name = node.type_name();
+    let children = node.children();
+
+    let operand = |child: &Edge<P>| {
+        let label = condition_label(child.node);
+        let name = child.node.type_name();
+        if name == type_name::<And<P>>() || name == type_name::<Or<P>>() {
+            format!("({label})")
+        } else {
+            label
+        }
+    };
+
+    if name == type_name::<And<P>>() {
+        children.iter().map(operand).collect::<Vec<_>>().join(" & ")
+    } else if name == type_name::<Or<P>>() {
+        children.iter().map(operand).collect::<Vec<_>>().join(" | ")
+    } else if name == type_name::<Not<P>>() {
+        format!("!{}", operand(&children[0]))
+    } else {
+        serialized(node)
+    }
+}
+
+/// Serializes the node into compact [`ron`], falling back to the short type name.
+fn serialized<P: Problem>(node: Node<'_, P>) -> String {
+    let config = ron::ser::PrettyConfig::new()
+        .struct_names(true)
+        .new_line(String::new())
+        .indentor(String::new());
+    let serialized = match node {
+        Node::Component(component) => ron::ser::to_string_pretty(component, config),
+        Node::Condition(condition) => ron::ser::to_string_pretty(condition, config),
+    };
+    match serialized {
+        Ok(serialized) => compact(&serialized),
+        Err(_) => short_type_name(node.type_name()),
+    }
+}
+
+/// Formats single-line [`ron`] for labels, separating items by `", "`, removing trailing commas
+/// and the `r#` prefix of raw identifiers, and shortening type names (see [`short_type_name`]).
+///
+/// String and character literals are kept as they are.
+fn compact(ron: &str) -> String {
+    let mut compact = String::with_capacity(ron.len());
+    // The text since the last literal, whose type names are shortened.
+    let mut code = String::new();
+    let mut chars = ron.chars().peekable();
+    let mut previous = None;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                compact.push_str(&short_type_name(&std::mem::take(&mut code)));
+                compact.push(c);
+                while let Some(next) = chars.next() {
+                    compact.push(next);
+                    if next == '\\' {
+                        compact.extend(chars.next());
+                    } else if next == c {
+                        break;
+                    }
+                }
+            }
+            ',' => {
+                if !matches!(chars.peek(), Some(')' | ']' | '}')) {
+                    code.push_str(", ");
+                }
+            }
+            'r' if chars.peek() == Some(&'#')
+                && !previous.is_some_and(|p: char| p.is_alphanumeric() || p == '_') =>
+            {
+                chars.next();
+            }
+            c => code.push(c),
+        }
+        previous = Some(c);
+    }
+    compact.push_str(&short_type_name(&code));
+    compact
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+    use crate::{
+        components::{utils::Noop, Component},
+        conditions::{EveryN, LessThanN},
+        testing::SingleObjectiveTestProblem,
+        ExecResult, State,
+    };
+
+    fn config() -> Configuration<SingleObjectiveTestProblem> {
+        Configuration::builder()
+            .while_(LessThanN::iterations(10), |builder| {
+                builder
+                    .if_(EveryN::iterations(2) | !EveryN::iterations(3), |builder| {
+                        builder.do_(Noop::new())
+                    })
+                    .scope_(|builder| builder.do_(Noop::new()))
+            })
+            .build()
+    }
+
+    #[test]
+    fn condition_labels_combine_operators() {
+        let condition =
+            LessThanN::iterations(10) & (EveryN::iterations(2) | !EveryN::iterations(3));
+        let label =
+            condition_label::<SingleObjectiveTestProblem>(Node::Condition(condition.as_ref()));
+        assert_eq!(
+            label,
+            "LessThanN(n: 10, lens: ValueOf(Iterations)) & (EveryN(n: 2, lens: ValueOf(Iterations)) | !EveryN(n: 3, lens: ValueOf(Iterations)))"
+        );
+    }
+
+    #[test]
+    fn renders_control_flow() {
+        let chart = Flowchart::new(&config(), FlowchartOptions::default());
+
+        let dot = chart.to_dot();
+        assert!(dot.starts_with("digraph configuration {"));
+        assert_eq!(dot.matches("shape=diamond").count(), 1);
+        assert_eq!(dot.matches("shape=point").count(), 1);
+        assert_eq!(dot.matches("subgraph cluster_").count(), 1);
+        assert_eq!(dot.matches("style=dashed").count(), 1);
+        assert!(dot.contains("label=\"while LessThanN(n: 10, lens: ValueOf(Iterations))\""));
+        assert!(dot.contains("label=\"true\""));
+        assert!(dot.contains("label=\"false\""));
+
+        let mermaid = chart.to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD"));
+        assert_eq!(mermaid.matches("subgraph").count(), 1);
+        assert_eq!(mermaid.matches("-.->").count(), 1);
+    }
+
+    #[test]
+    fn serialized_labels_keep_strings() {
+        #[derive(Clone, Serialize)]
+        struct Named {
+            name: String,
+            r#type: Vec<char>,
+        }
+
+        impl Component<SingleObjectiveTestProblem> for Named {
+            fn execute(
+                &self,
+                _problem: &SingleObjectiveTestProblem,
+                _state: &mut State<SingleObjectiveTestProblem>,
+            ) -> ExecResult<()> {
+                Ok(())
+            }
+        }
+
+        let named = Named {
+            name: "a::b,c,) r#\"d\"".to_string(),
+            r#type: vec![',', 'r'],
+        };
+        let label = serialized::<SingleObjectiveTestProblem>(Node::Component(&named));
+        assert_eq!(
+            label,
+            r#"Named(name: "a::b,c,) r#\"d\"", type: [',', 'r'])"#
+        );
+    }
+}
@@ -0,0 +1,538 @@
+//! Per-component profiling and execution tracing.
+//!
+//! [`Configuration::profiled`] wraps every component of the component tree in a [`Profiled`]
+//! component, which measures the wall time and the number of [`Evaluations`] of every call
+//! of `execute`.
+//! The measurements are collected in the [`Profile`] state, keyed by the [`ComponentPath`] of
+//! the component, and can be displayed as summary table or written as [Chrome trace]
+//! at the end of the optimization, as specified by the [`ProfilingOptions`].
+//!
+//! Note that measurements are inclusive, i.e. the time and evaluations of a component include
+//! the ones of its children, e.g. of the body of a [`Loop`].
+//! Components executed on independent states, e.g. the branches of a [`ParallelBlock`],
+//! are not recorded.
+//!
+//! [`Configuration::profiled`]: crate::Configuration::profiled
+//! [`Evaluations`]: common::Evaluations
+//! [Chrome trace]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+//! [`Loop`]: crate::components::Loop
+//! [`ParallelBlock`]: crate::components::ParallelBlock
+//!
+//! # Examples
+//!
+//! Profiling a configuration, logging the summary table and writing a trace:
+//!
+//! ```
+//! # use mahf::{problems::{Evaluate, ObjectiveFunction}, ExecResult};
+//! use mahf::{configuration::profiling::ProfilingOptions, Configuration};
+//!
+//! # fn example<P: ObjectiveFunction>(config: Configuration<P>, problem: P, evaluator: impl Evaluate<Problem = P>) -> ExecResult<()> {
+//! let options = ProfilingOptions {
+//!     summary: true,
+//!     trace: Some("trace.json".into()),
+//! };
+//! let state = config.profiled(options).optimize(&problem, evaluator)?;
+//! # Ok(())
+//! # }
+//! ```
+
+#![allow(clippy::new_ret_no_self)]
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::BufWriter,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use better_any::{Tid, TidAble};
+use derivative::Derivative;
+use eyre::{ContextCompat, WrapErr};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    component::ExecResult,
+    components::{Block, Component},
+    configuration::tree::{short_type_name, ComponentPath, Edge, EdgeMut, NodeMut},
+    state::{common, StateReq},
+    CustomState, Problem, State,
+};
+
+/// Options for profiling configurations with [`Configuration::profiled`].
+///
+/// [`Configuration::profiled`]: crate::Configuration::profiled
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ProfilingOptions {
+    /// Whether the summary table of the [`Profile`] is logged after execution.
+    ///
+    /// The table is logged with level `info` using the [`log`] facade, so a logger
+    /// implementation has to be installed to see it.
+    pub summary: bool,
+    /// The file the [Chrome trace] is written to after execution, if any.
+    ///
+    /// Note that every call of every component is recorded if tracing is enabled.
+    ///
+    /// [Chrome trace]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+    pub trace: Option<PathBuf>,
+}
+
+/// The measurements of a single component.
+#[derive(Clone, Debug, Serialize)]
+pub struct ComponentProfile {
+    /// The path of the component in the component tree.
+    pub path: ComponentPath,
+    /// The type name of the component without module paths.
+    pub component: String,
+    /// The number of calls of `execute`.
+    pub calls: u32,
+    /// The total wall time of all calls.
+    pub total: Duration,
+    /// The total number of evaluations performed during all calls.
+    pub evaluations: u32,
+}
+
+impl ComponentProfile {
+    /// Returns the mean wall time per call.
+    pub fn mean(&self) -> Duration {
+        self.total / self.calls.max(1)
+    }
+}
+
+/// A single call of a component.
+struct TraceEvent {
+    component: usize,
+    start: Duration,
+    duration: Duration,
+    evaluations: u32,
+}
+
+/// The measurements of all components, collected by [`Profiled`] components.
+///
+/// The `Display` implementation renders a summary table, sorted by total wall time.
+#[derive(Tid)]
+pub struct Profile {
+    start: Instant,
+    components: Vec<ComponentProfile>,
+    indices: HashMap<ComponentPath, usize>,
+    trace: Option<Vec<TraceEvent>>,
+}
+
+impl CustomState<'_> for Profile {}
+
+impl Profile {
+    /// Creates a new `Profile`, which records every call for tracing if `trace` is `true`.
+    pub fn new(trace: bool) -> Self {
+        Self {
+            start: Instant::now(),
+            components: Vec::new(),
+            indices: HashMap::new(),
+            trace: trace.then(Vec::new),
+        }
+    }
+
+    /// Returns the measurements of all components in the order of their first call.
+    pub fn components(&self) -> &[ComponentProfile] {
+        &self.components
+    }
+
+    /// Returns the measurements of the component at `path`, if it was called.
+    pub fn get(&self, path: &ComponentPath) -> Option<&ComponentProfile> {
+        self.indices.get(path).map(|&i| &self.components[i])
+    }
+
+    fn record(
+        &mut self,
+        path: &ComponentPath,
+        component: &'static str,
+        start: Instant,
+        duration: Duration,
+        evaluations: u32,
+    ) {
+        let index = *self.indices.entry(path.clone()).or_insert_with(|| {
+            self.components.push(ComponentProfile {
+                path: path.clone(),
+                component: short_type_name(component),
+                calls: 0,
+                total: Duration::ZERO,
+                evaluations: 0,
+            });
+            self.components.len() - 1
+        });
+
+        let profile = &mut self.components[index];
+        profile.calls += 1;
+        profile.total += duration;
+        profile.evaluations += evaluations;
+
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEvent {
+                component: index,
+                start: start.saturating_duration_since(self.start),
+                duration,
+                evaluations,
+            });
+        }
+    }
+
+    /// Writes all recorded calls as [Chrome trace] JSON to the file at `path`, which can be
+    /// viewed with e.g. `chrome://tracing` or [Perfetto].
+    ///
+    /// Fails if the `Profile` was created without tracing.
+    ///
+    /// [Chrome trace]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+    /// [Perfetto]: https://ui.perfetto.dev/
+    pub fn write_trace(&self, path: impl AsRef<std::path::Path>) -> ExecResult<()> {
+        let trace = self
+            .trace
+            .as_ref()
+            .wrap_err("the profile was created without tracing")?;
+
+        let events: Vec<_> = trace
+            .iter()
+            .map(|event| {
+                let component = &self.components[event.component];
+                json!({
+                    "name": component.component,
+                    "cat": "component",
+                    "ph": "X",
+                    "ts": event.start.as_secs_f64() * 1e6,
+                    "dur": event.duration.as_secs_f64() * 1e6,
+                    "pid": 0,
+                    "tid": 0,
+                    "args": {
+                        "path": component.path.to_string(),
+                        "evaluations": event.evaluations,
+                    },
+                })
+            })
+            .collect();
+
+        let file = File::create(path).wrap_err("failed to create trace file")?;
+        serde_json::to_writer(
+            BufWriter::new(file),
+            &json!({ "traceEvents": events, "displayTimeUnit": "ms" }),
+        )
+        .wrap_err("failed to write trace")
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut components: Vec<_> = self.components.iter().collect();
+        components.sort_by_key(|c| Reverse(c.total));
+
+        let paths: Vec<_> = components.iter().map(|c| c.path.to_string()).collect();
+        let path_width = paths.iter().map(String::len).max().unwrap_or(0).max(4);
+        let name_width = components
+            .iter()
+            .map(|c| c.component.len())
+            .max()
+            .unwrap_or(0)
+            .max(9);
+
+        write!(
+            f,
+            "{:path_width$}  {:name_width$}  {:>8}  {:>12}  {:>12}  {:>11}",
+            "Path", "Component", "Calls", "Total [ms]", "Mean [ms]", "Evaluations"
+        )?;
+        for (component, path) in components.iter().zip(&paths) {
+            write!(
+                f,
+                "\n{:path_width$}  {:name_width$}  {:>8}  {:>12.3}  {:>12.3}  {:>11}",
+                path,
+                component.component,
+                component.calls,
+                component.total.as_secs_f64() * 1e3,
+                component.mean().as_secs_f64() * 1e3,
+                component.evaluations,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Measures the calls of `execute` of the `inner` component, recording them in the [`Profile`].
+///
+/// The component is transparent with respect to the component tree, i.e. it reports the
+/// type name and children of the `inner` component, and is serialized as the `inner` component.
+///
+/// It is usually only created implicitly by [`Configuration::profiled`].
+///
+/// [`Configuration::profiled`]: crate::Configuration::profiled
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[serde(transparent)]
+#[derivative(Clone(bound = ""))]
+pub struct Profiled<P: Problem> {
+    #[serde(skip)]
+    path: ComponentPath,
+    inner: Box<dyn Component<P>>,
+}
+
+impl<P: Problem> Profiled<P> {
+    /// Creates a new `Profiled` wrapping the `inner` component at `path`.
+    pub fn from_params(path: ComponentPath, inner: Box<dyn Component<P>>) -> Self {
+        Self { path, inner }
+    }
+
+    /// Creates a new `Profiled` wrapping the `inner` component at `path`.
+    pub fn new(path: ComponentPath, inner: Box<dyn Component<P>>) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(path, inner))
+    }
+}
+
+impl<P: Problem> Component<P> for Profiled<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        self.inner.init(problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        self.inner.require(problem, state_req)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        let evaluations = state
+            .try_get_value::<common::Evaluations>()
+            .unwrap_or_default();
+        let start = Instant::now();
+
+        let result = self.inner.execute(problem, state);
+
+        let duration = start.elapsed();
+        let evaluations = state
+            .try_get_value::<common::Evaluations>()
+            .unwrap_or_default()
+            .saturating_sub(evaluations);
+        if state.contains::<Profile>() {
+            state.borrow_mut::<Profile>().record(
+                &self.path,
+                self.inner.type_name(),
+                start,
+                duration,
+                evaluations,
+            );
+        }
+
+        result
+    }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        self.inner.children()
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        self.inner.children_mut()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.inner.type_name()
+    }
+}
+
+/// Inserts the [`Profile`] state and reports it after execution as specified by the `options`.
+///
+/// Like [`Profiled`], the component is transparent with respect to the component tree.
+///
+/// It is usually only created implicitly by [`Configuration::profiled`].
+///
+/// [`Configuration::profiled`]: crate::Configuration::profiled
+#[derive(Serialize, Derivative)]
+#[serde(bound = "")]
+#[serde(transparent)]
+#[derivative(Clone(bound = ""))]
+pub struct Profiler<P: Problem> {
+    #[serde(skip)]
+    options: ProfilingOptions,
+    inner: Box<dyn Component<P>>,
+}
+
+impl<P: Problem> Profiler<P> {
+    /// Creates a new `Profiler` for the `inner` component.
+    pub fn from_params(options: ProfilingOptions, inner: Box<dyn Component<P>>) -> Self {
+        Self { options, inner }
+    }
+
+    /// Creates a new `Profiler` for the `inner` component.
+    pub fn new(options: ProfilingOptions, inner: Box<dyn Component<P>>) -> Box<dyn Component<P>> {
+        Box::new(Self::from_params(options, inner))
+    }
+}
+
+impl<P: Problem> Component<P> for Profiler<P> {
+    fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        state.insert(Profile::new(self.options.trace.is_some()));
+        self.inner.init(problem, state)
+    }
+
+    fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        self.inner.require(problem, state_req)
+    }
+
+    fn execute(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        self.inner.execute(problem, state)?;
+
+        let profile = state.borrow::<Profile>();
+        if self.options.summary {
+            log::info!("component profile:\n{profile}");
+        }
+        if let Some(path) = &self.options.trace {
+            profile.write_trace(path)?;
+        }
+        Ok(())
+    }
+
+    fn children(&self) -> Vec<Edge<'_, P>> {
+        self.inner.children()
+    }
+
+    fn children_mut(&mut self) -> Vec<EdgeMut<'_, P>> {
+        self.inner.children_mut()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.inner.type_name()
+    }
+}
+
+/// Wraps every component in the tree rooted at `slot` in a [`Profiled`] component.
+pub(crate) fn instrument<P: Problem>(slot: &mut Box<dyn Component<P>>, path: &mut ComponentPath) {
+    for EdgeMut { label, node } in slot.children_mut() {
+        if let NodeMut::Component(child) = node {
+            path.push(label);
+            instrument(child, path);
+            path.pop();
+        }
+    }
+
+    let inner = std::mem::replace(slot, Block::new([]));
+    *slot = Profiled::new(path.clone(), inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::utils::Noop, conditions::LessThanN, testing::SingleObjectiveTestProblem,
+        Configuration,
+    };
+
+    fn path(segments: &[&str]) -> ComponentPath {
+        let mut path = ComponentPath::default();
+        for segment in segments {
+            path.push(segment.to_string());
+        }
+        path
+    }
+
+    #[test]
+    fn records_calls_per_component() {
+        let config = Configuration::<SingleObjectiveTestProblem>::builder()
+            .do_(Noop::new())
+            .while_(LessThanN::iterations(5), |builder| {
+                builder.do_(Noop::new()).do_(Noop::new())
+            })
+            .build()
+            .profiled(ProfilingOptions::default());
+
+        let state = config
+            .optimize_with(&SingleObjectiveTestProblem::new(), |_| Ok(()))
+            .unwrap();
+        let profile = state.borrow::<Profile>();
+
+        assert_eq!(profile.components().len(), 6);
+        assert_eq!(profile.get(&ComponentPath::default()).unwrap().calls, 1);
+        assert_eq!(profile.get(&path(&["Block[0]"])).unwrap().calls, 1);
+        assert_eq!(profile.get(&path(&["Block[1]"])).unwrap().calls, 1);
+
+        let body = profile.get(&path(&["Block[1]", "Loop.do"])).unwrap();
+        assert_eq!(body.component, "Block<TestProblem<SingleObjective>>");
+        assert_eq!(body.calls, 5);
+        let noop = profile
+            .get(&path(&["Block[1]", "Loop.do", "Block[1]"]))
+            .unwrap();
+        assert_eq!(noop.component, "Noop");
+        assert_eq!(noop.calls, 5);
+    }
+
+    #[test]
+    fn profiled_configurations_keep_their_tree() {
+        let config = || {
+            Configuration::<SingleObjectiveTestProblem>::builder()
+                .while_(LessThanN::iterations(5), |builder| builder.do_(Noop::new()))
+                .build()
+        };
+
+        let mut paths = Vec::new();
+        config().walk(|path, node| paths.push((path.clone(), node.type_name())));
+        let mut profiled_paths = Vec::new();
+        config()
+            .profiled(ProfilingOptions::default())
+            .walk(|path, node| profiled_paths.push((path.clone(), node.type_name())));
+
+        assert_eq!(paths, profiled_paths);
+    }
+
+    #[test]
+    fn writes_trace_of_all_calls() {
+        let file = std::env::temp_dir().join(format!("mahf-trace-{}.json", std::process::id()));
+        let config = Configuration::<SingleObjectiveTestProblem>::builder()
+            .while_(LessThanN::iterations(3), |builder| builder.do_(Noop::new()))
+            .build()
+            .profiled(ProfilingOptions {
+                summary: true,
+                trace: Some(file.clone()),
+            });
+        let state = config
+            .optimize_with(&SingleObjectiveTestProblem::new(), |_| Ok(()))
+            .unwrap();
+
+        let trace: serde_json::Value = serde_json::from_reader(File::open(&file).unwrap()).unwrap();
+        std::fs::remove_file(&file).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let calls: u32 = state
+            .borrow::<Profile>()
+            .components()
+            .iter()
+            .map(|c| c.calls)
+            .sum();
+        assert_eq!(events.len(), calls as usize);
+        assert!(events.iter().all(|event| event["ph"] == "X"));
+        let noops = events
+            .iter()
+            .filter(|event| event["name"] == "Noop")
+            .count();
+        assert_eq!(noops, 3);
+    }
+
+    #[test]
+    fn trace_requires_tracing() {
+        let profile = Profile::new(false);
+        let file = std::env::temp_dir().join(format!("mahf-no-trace-{}.json", std::process::id()));
+        assert!(profile.write_trace(&file).is_err());
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn summary_table_is_sorted_by_total_time() {
+        let mut profile = Profile::new(false);
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        profile.record(&path(&["x"]), "mahf::Fast", start, ms(1), 3);
+        profile.record(&path(&["y"]), "mahf::Slow", start, ms(2), 0);
+        profile.record(&path(&["y"]), "mahf::Slow", start, ms(4), 0);
+
+        let table = profile.to_string();
+        let rows: Vec<Vec<_>> = table
+            .lines()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][0], "Path");
+        assert_eq!(rows[1], ["y", "Slow", "2", "6.000", "3.000", "0"]);
+        assert_eq!(rows[2], ["x", "Fast", "1", "1.000", "1.000", "3"]);
+    }
+}
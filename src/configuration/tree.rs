@@ -0,0 +1,393 @@
+//! Introspection and rewriting of the component tree of configurations.
+//!
+//! A [`Configuration`] is a tree of [`Component`]s and [`Condition`]s, where control flow
+//! components like [`Block`], [`Loop`], or [`Branch`] contain their children.
+//! Every component exposes its children through [`Component::children`] and
+//! [`Component::children_mut`] (analogous for conditions), which allows traversing the tree
+//! using [`Configuration::walk`] without knowing the concrete types of the components.
+//!
+//! Children are connected by labeled edges, where the label is the segment of the
+//! [`ComponentPath`] of the child, e.g. `Loop.do` for the body of a [`Loop`].
+//! The paths therefore match the ones reported by [`Configuration::validate`].
+//!
+//! Building on this, [`Configuration::replace_all`] and [`Configuration::insert_after`]
+//! rewrite the tree, e.g. for building ablation variants of a configuration programmatically.
+//!
+//! # Implementing children
+//!
+//! Components and conditions containing other components or conditions should implement
+//! `children` and `children_mut`, returning the same children with the same labels in the
+//! same order.
+//! Otherwise, they are treated as leaves of the tree.
+//!
+//! To report the same paths during validation, `init` and `require` should propagate to the
+//! children using [`init_children`] and [`require_children`], which derive the path segments
+//! from the labels of the edges.
+//!
+//! [`Configuration`]: crate::Configuration
+//! [`Configuration::walk`]: crate::Configuration::walk
+//! [`Configuration::validate`]: crate::Configuration::validate
+//! [`Configuration::replace_all`]: crate::Configuration::replace_all
+//! [`Configuration::insert_after`]: crate::Configuration::insert_after
+//! [`Block`]: crate::components::Block
+//! [`Loop`]: crate::components::Loop
+//! [`Branch`]: crate::components::Branch
+//!
+//! # Examples
+//!
+//! Printing the paths and types of all components and conditions:
+//!
+//! ```
+//! # use mahf::{Configuration, Problem};
+//! # fn example<P: Problem>(config: Configuration<P>) {
+//! config.walk(|path, node| println!("{path}: {} ({:?})", node.type_name(), node.kind()));
+//! # }
+//! ```
+
+use serde::Serialize;
+
+pub use super::validation::ComponentPath;
+use crate::{
+    component::ExecResult,
+    components::Block,
+    conditions::Condition,
+    state::{State, StateReq},
+    Component, Problem,
+};
+
+/// The kind of a [`Node`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum NodeKind {
+    Component,
+    Condition,
+}
+
+/// A node of the component tree, i.e. a [`Component`] or a [`Condition`].
+pub enum Node<'a, P: Problem> {
+    Component(&'a dyn Component<P>),
+    Condition(&'a dyn Condition<P>),
+}
+
+impl<P: Problem> Clone for Node<'_, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: Problem> Copy for Node<'_, P> {}
+
+impl<'a, P: Problem> Node<'a, P> {
+    /// Returns the kind of the node.
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Node::Component(_) => NodeKind::Component,
+            Node::Condition(_) => NodeKind::Condition,
+        }
+    }
+
+    /// Returns the full name of the type of the node, e.g. `mahf::components::control_flow::Loop<...>`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Node::Component(component) => component.type_name(),
+            Node::Condition(condition) => condition.type_name(),
+        }
+    }
+
+    /// Returns the serialized parameters of the node.
+    ///
+    /// Note that the parameters of nodes with children also contain the serialized children.
+    pub fn parameters(&self) -> ExecResult<serde_json::Value> {
+        let value = match self {
+            Node::Component(component) => serde_json::to_value(component)?,
+            Node::Condition(condition) => serde_json::to_value(condition)?,
+        };
+        Ok(value)
+    }
+
+    /// Calls `init` on the component or condition.
+    pub fn init(&self, problem: &P, state: &mut State<P>) -> ExecResult<()> {
+        match self {
+            Node::Component(component) => component.init(problem, state),
+            Node::Condition(condition) => condition.init(problem, state),
+        }
+    }
+
+    /// Calls `require` on the component or condition.
+    pub fn require(&self, problem: &P, state_req: &StateReq<P>) -> ExecResult<()> {
+        match self {
+            Node::Component(component) => component.require(problem, state_req),
+            Node::Condition(condition) => condition.require(problem, state_req),
+        }
+    }
+
+    /// Returns the edges to the children of the node.
+    pub fn children(&self) -> Vec<Edge<'a, P>> {
+        match self {
+            Node::Component(component) => component.children(),
+            Node::Condition(condition) => condition.children(),
+        }
+    }
+
+    /// Calls the `visitor` on this node and all its descendants in pre-order,
+    /// i.e. every node is visited before its children.
+    ///
+    /// The `path` is the path of this node.
+    pub fn walk(&self, path: &ComponentPath, mut visitor: impl FnMut(&ComponentPath, Node<'a, P>)) {
+        fn walk<'a, P: Problem>(
+            node: Node<'a, P>,
+            path: &mut ComponentPath,
+            visitor: &mut impl FnMut(&ComponentPath, Node<'a, P>),
+        ) {
+            visitor(path, node);
+            for Edge { label, node } in node.children() {
+                path.push(label);
+                walk(node, path, visitor);
+                path.pop();
+            }
+        }
+
+        walk(*self, &mut path.clone(), &mut visitor);
+    }
+}
+
+/// A labeled edge to a child [`Node`].
+pub struct Edge<'a, P: Problem> {
+    /// The segment of the [`ComponentPath`] of the child, e.g. `Loop.do`.
+    pub label: String,
+    pub node: Node<'a, P>,
+}
+
+impl<'a, P: Problem> Edge<'a, P> {
+    /// Creates an edge to a child component.
+    pub fn component(label: impl Into<String>, component: &'a dyn Component<P>) -> Self {
+        Self {
+            label: label.into(),
+            node: Node::Component(component),
+        }
+    }
+
+    /// Creates an edge to a child condition.
+    pub fn condition(label: impl Into<String>, condition: &'a dyn Condition<P>) -> Self {
+        Self {
+            label: label.into(),
+            node: Node::Condition(condition),
+        }
+    }
+}
+
+/// Calls `init` on all `children` in order, each within the path segment of its label.
+///
+/// See the [module documentation](self) for details.
+pub fn init_children<P: Problem>(
+    children: Vec<Edge<'_, P>>,
+    problem: &P,
+    state: &mut State<P>,
+) -> ExecResult<()> {
+    for Edge { label, node } in children {
+        state.within(label, |state| node.init(problem, state))?;
+    }
+    Ok(())
+}
+
+/// Calls `require` on all `children` in order, each within the path segment of its label.
+///
+/// See the [module documentation](self) for details.
+pub fn require_children<P: Problem>(
+    children: Vec<Edge<'_, P>>,
+    problem: &P,
+    state_req: &StateReq<P>,
+) -> ExecResult<()> {
+    for Edge { label, node } in children {
+        state_req.within(label, || node.require(problem, state_req))?;
+    }
+    Ok(())
+}
+
+/// A mutable node of the component tree, which allows replacing the node.
+pub enum NodeMut<'a, P: Problem> {
+    Component(&'a mut Box<dyn Component<P>>),
+    Condition(&'a mut Box<dyn Condition<P>>),
+}
+
+/// A labeled edge to a mutable child [`NodeMut`].
+pub struct EdgeMut<'a, P: Problem> {
+    /// The segment of the [`ComponentPath`] of the child, e.g. `Loop.do`.
+    pub label: String,
+    pub node: NodeMut<'a, P>,
+}
+
+impl<'a, P: Problem> EdgeMut<'a, P> {
+    /// Creates an edge to a mutable child component.
+    pub fn component(label: impl Into<String>, component: &'a mut Box<dyn Component<P>>) -> Self {
+        Self {
+            label: label.into(),
+            node: NodeMut::Component(component),
+        }
+    }
+
+    /// Creates an edge to a mutable child condition.
+    pub fn condition(label: impl Into<String>, condition: &'a mut Box<dyn Condition<P>>) -> Self {
+        Self {
+            label: label.into(),
+            node: NodeMut::Condition(condition),
+        }
+    }
+}
+
+/// Replaces the components matched by `predicate` in the tree rooted at `slot` with the result
+/// of `replacement`, and returns the number of replaced components.
+///
+/// Replacements are not visited again, and neither are the children of replaced components.
+pub(crate) fn replace<P: Problem>(
+    slot: &mut Box<dyn Component<P>>,
+    path: &mut ComponentPath,
+    predicate: &mut impl FnMut(&ComponentPath, &dyn Component<P>) -> bool,
+    replacement: &mut impl FnMut(&dyn Component<P>) -> Box<dyn Component<P>>,
+) -> usize {
+    if predicate(path, slot.as_ref()) {
+        *slot = replacement(slot.as_ref());
+        return 1;
+    }
+
+    let mut replaced = 0;
+    for EdgeMut { label, node } in slot.children_mut() {
+        if let NodeMut::Component(child) = node {
+            path.push(label);
+            replaced += replace(child, path, predicate, replacement);
+            path.pop();
+        }
+    }
+    replaced
+}
+
+/// Inserts a clone of `component` after every component matched by `predicate` in the tree
+/// rooted at `slot`, and returns the number of insertions.
+///
+/// Matched components are wrapped in a [`Block`] together with the inserted component.
+pub(crate) fn insert_after<P: Problem>(
+    slot: &mut Box<dyn Component<P>>,
+    path: &mut ComponentPath,
+    predicate: &mut impl FnMut(&ComponentPath, &dyn Component<P>) -> bool,
+    component: &(dyn Component<P> + 'static),
+) -> usize {
+    let mut inserted = 0;
+    for EdgeMut { label, node } in slot.children_mut() {
+        if let NodeMut::Component(child) = node {
+            path.push(label);
+            inserted += insert_after(child, path, predicate, component);
+            path.pop();
+        }
+    }
+
+    if predicate(path, slot.as_ref()) {
+        let matched = std::mem::replace(slot, Block::new([]));
+        *slot = Block::new([matched, dyn_clone::clone_box(component)]);
+        inserted += 1;
+    }
+    inserted
+}
+
+/// Removes the module paths from all type names in `name`,
+/// e.g. `mahf::Loop<mahf::Problem>` becomes `Loop<Problem>`.
+pub(crate) fn short_type_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            short.push_str(segment.rsplit("::").next().unwrap());
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short.push_str(segment.rsplit("::").next().unwrap());
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::{
+            evaluation::PopulationEvaluator,
+            utils::{populations::ClearPopulation, Noop},
+            Loop,
+        },
+        conditions::{EveryN, LessThanN},
+        testing::SingleObjectiveTestProblem,
+        Configuration,
+    };
+
+    type P = SingleObjectiveTestProblem;
+
+    fn config() -> Configuration<P> {
+        Configuration::builder()
+            .evaluate()
+            .while_(
+                LessThanN::iterations(10) & !EveryN::iterations(3),
+                |builder| builder.evaluate().update_best_individual(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn walk_visits_all_nodes_in_pre_order() {
+        let mut nodes = Vec::new();
+        config().walk(|path, node| nodes.push((path.to_string(), node.kind())));
+
+        let expected = [
+            ("<root>", NodeKind::Component),
+            ("Block[0]", NodeKind::Component),
+            ("Block[1]", NodeKind::Component),
+            ("Block[1] > Loop.while", NodeKind::Condition),
+            ("Block[1] > Loop.while > And[0]", NodeKind::Condition),
+            ("Block[1] > Loop.while > And[1]", NodeKind::Condition),
+            ("Block[1] > Loop.while > And[1] > Not", NodeKind::Condition),
+            ("Block[1] > Loop.do", NodeKind::Component),
+            ("Block[1] > Loop.do > Block[0]", NodeKind::Component),
+            ("Block[1] > Loop.do > Block[1]", NodeKind::Component),
+        ];
+        let expected: Vec<_> = expected.iter().map(|(p, k)| (p.to_string(), *k)).collect();
+        assert_eq!(nodes, expected);
+    }
+
+    #[test]
+    fn replace_all_replaces_every_match() {
+        let mut config = config();
+        let replaced = config.replace_all::<PopulationEvaluator>(|_| Noop::new());
+        assert_eq!(replaced, 2);
+
+        let mut evaluators = 0;
+        let mut noops = 0;
+        config.walk(|_, node| {
+            evaluators +=
+                usize::from(node.type_name() == std::any::type_name::<PopulationEvaluator>());
+            noops += usize::from(node.type_name() == std::any::type_name::<Noop>());
+        });
+        assert_eq!((evaluators, noops), (0, 2));
+    }
+
+    #[test]
+    fn insert_after_wraps_matches() {
+        let mut config = config();
+        let inserted = config.insert_after::<Loop<P>>(ClearPopulation::new());
+        assert_eq!(inserted, 1);
+
+        let mut paths = Vec::new();
+        config.walk(|path, node| {
+            if node.kind() == NodeKind::Component && path.segments().len() == 2 {
+                paths.push(path.to_string());
+            }
+        });
+        assert_eq!(paths, ["Block[1] > Block[0]", "Block[1] > Block[1]"]);
+    }
+
+    #[test]
+    fn short_type_name_removes_paths() {
+        assert_eq!(
+            short_type_name("a::Loop<b::c::Problem, (d::A, [e::B; 2])>"),
+            "Loop<Problem, (A, [B; 2])>"
+        );
+    }
+}
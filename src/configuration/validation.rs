@@ -0,0 +1,452 @@
+//! Validation of configurations before execution.
+//!
+//! [`Configuration::validate`] initializes the [`State`] like [`Configuration::optimize`] would,
+//! but instead of failing at the first missing custom state, it collects all issues of the
+//! whole component tree into a [`ValidationReport`].
+//!
+//! Every issue contains the [`ComponentPath`] of the offending component, e.g.
+//! `Block[2] > Loop.do > Block[4]`, which denotes the fifth component in the body of the loop
+//! which is the third component of the configuration.
+//!
+//! # Component paths
+//!
+//! Components containing other components report the paths of their children by wrapping the
+//! calls of `init` and `require` using [`State::within`] and [`StateReq::within`], respectively.
+//! Outside of validation, these methods simply call the wrapped function.
+//! The segments are usually derived from the labels of the [`tree`] using [`init_children`]
+//! and [`require_children`], so that validation reports the same paths as the tree.
+//!
+//! Note that components whose children are executed on independent states at runtime,
+//! e.g. [`Scope`] or [`ParallelBlock`], validate their children on a simulated inner state.
+//!
+//! [`Configuration::validate`]: crate::Configuration::validate
+//! [`Configuration::optimize`]: crate::Configuration::optimize
+//! [`StateReq::within`]: crate::state::StateReq::within
+//! [`tree`]: super::tree
+//! [`init_children`]: super::tree::init_children
+//! [`require_children`]: super::tree::require_children
+//! [`Scope`]: crate::components::Scope
+//! [`ParallelBlock`]: crate::components::ParallelBlock
+//!
+//! # Examples
+//!
+//! Checking a configuration in a test:
+//!
+//! ```
+//! # use mahf::{Configuration, Problem};
+//! # fn example<P: Problem>(config: Configuration<P>, problem: P) {
+//! let report = config.validate(&problem);
+//! assert!(report.is_valid(), "{report}");
+//! # }
+//! ```
+
+use std::{fmt, marker::PhantomData};
+
+use better_any::{Tid, TidAble};
+use serde::{Serialize, Serializer};
+
+use crate::{
+    component::ExecResult, configuration::tree::Edge, problems::Evaluate, CustomState, Individual,
+    Problem, State,
+};
+
+/// The path of a component within the component tree of a [`Configuration`].
+///
+/// Each segment is either `Type[i]`, denoting the `i`-th element of a list of components,
+/// e.g. of a [`Block`], or `Type.field`, denoting a single child component, e.g. the body
+/// of a [`Loop`] as `Loop.do`.
+///
+/// [`Configuration`]: crate::Configuration
+/// [`Block`]: crate::components::Block
+/// [`Loop`]: crate::components::Loop
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ComponentPath(Vec<String>);
+
+impl ComponentPath {
+    /// Returns the segments of the path, starting at the root.
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Returns `true` if the path denotes the root component.
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, segment: String) {
+        self.0.push(segment);
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.0.pop();
+    }
+}
+
+impl fmt::Display for ComponentPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            write!(f, "<root>")
+        } else {
+            write!(f, "{}", self.0.join(" > "))
+        }
+    }
+}
+
+impl Serialize for ComponentPath {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+/// An issue found by [`Configuration::validate`].
+///
+/// [`Configuration::validate`]: crate::Configuration::validate
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind")]
+pub enum Issue {
+    /// Custom `state` required by the component `required_by` is missing.
+    MissingState {
+        path: ComponentPath,
+        state: &'static str,
+        required_by: &'static str,
+    },
+    /// Custom `state` maintained by the component `claimed_by` is also maintained by an
+    /// enclosing component at `outer`, which means that they overwrite each other's values.
+    ///
+    /// This is e.g. the case for nested [`Loop`]s without a [`Scope`] in between.
+    ///
+    /// [`Loop`]: crate::components::Loop
+    /// [`Scope`]: crate::components::Scope
+    ConflictingState {
+        path: ComponentPath,
+        state: &'static str,
+        claimed_by: &'static str,
+        outer: ComponentPath,
+    },
+    /// Initializing or checking the requirements of the component failed with an error.
+    Failed {
+        path: ComponentPath,
+        message: String,
+    },
+}
+
+impl Issue {
+    /// Returns the path of the offending component.
+    pub fn path(&self) -> &ComponentPath {
+        match self {
+            Issue::MissingState { path, .. } => path,
+            Issue::ConflictingState { path, .. } => path,
+            Issue::Failed { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::MissingState {
+                path,
+                state,
+                required_by,
+            } => write!(
+                f,
+                "{path}: `{state}` is missing, but it is a requirement of `{required_by}`"
+            ),
+            Issue::ConflictingState {
+                path,
+                state,
+                claimed_by,
+                outer,
+            } => write!(
+                f,
+                "{path}: `{state}` is maintained by `{claimed_by}`, but also by the enclosing component at {outer}"
+            ),
+            Issue::Failed { path, message } => write!(f, "{path}: {message}"),
+        }
+    }
+}
+
+/// The result of [`Configuration::validate`], containing all issues found.
+///
+/// The report can be displayed or serialized, e.g. for checking experiment configurations in CI.
+///
+/// [`Configuration::validate`]: crate::Configuration::validate
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ValidationReport {
+    issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no issues were found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns all issues in the order they were found.
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "no issues found");
+        }
+        write!(f, "{} issue(s) found:", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n- {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Custom state collecting the issues during validation.
+///
+/// Its presence in the [`State`] enables the validation mode.
+#[derive(Default, Tid)]
+pub(crate) struct Validation {
+    path: Vec<String>,
+    /// The custom state maintained by the components on the current path.
+    claims: Vec<(&'static str, &'static str, ComponentPath)>,
+    issues: Vec<Issue>,
+}
+
+impl CustomState<'_> for Validation {}
+
+impl Validation {
+    fn path(&self) -> ComponentPath {
+        ComponentPath(self.path.clone())
+    }
+
+    pub(crate) fn enter(&mut self, segment: String) {
+        self.path.push(segment);
+    }
+
+    /// Leaves the current component, recording the `result` of its `init` or `require`.
+    pub(crate) fn leave(&mut self, result: ExecResult<()>) {
+        self.record(result);
+        self.path.pop();
+        let depth = self.path.len();
+        self.claims.retain(|(_, _, path)| path.0.len() <= depth);
+    }
+
+    pub(crate) fn record(&mut self, result: ExecResult<()>) {
+        if let Err(error) = result {
+            let path = self.path();
+            self.issues.push(Issue::Failed {
+                path,
+                message: format!("{error:#}"),
+            });
+        }
+    }
+
+    pub(crate) fn missing(&mut self, state: &'static str, required_by: &'static str) {
+        let path = self.path();
+        self.issues.push(Issue::MissingState {
+            path,
+            state,
+            required_by,
+        });
+    }
+
+    pub(crate) fn claim(&mut self, state: &'static str, claimed_by: &'static str) {
+        let path = self.path();
+        if let Some((_, _, outer)) = self.claims.iter().find(|(s, _, _)| *s == state) {
+            self.issues.push(Issue::ConflictingState {
+                path: path.clone(),
+                state,
+                claimed_by,
+                outer: outer.clone(),
+            });
+        }
+        self.claims.push((state, claimed_by, path));
+    }
+
+    pub(crate) fn into_report(self) -> ValidationReport {
+        ValidationReport {
+            issues: self.issues,
+        }
+    }
+}
+
+/// Returns `true` if the `state` is being validated.
+pub(crate) fn is_validating<P>(state: &State<P>) -> bool {
+    state.contains::<Validation>()
+}
+
+/// Calls `f` without the claims of enclosing components, e.g. for a nested [`State`].
+pub(crate) fn isolated<P>(
+    state: &mut State<P>,
+    f: impl FnOnce(&mut State<P>) -> ExecResult<()>,
+) -> ExecResult<()> {
+    let claims = std::mem::take(&mut state.borrow_mut::<Validation>().claims);
+    let result = f(state);
+    state.borrow_mut::<Validation>().claims = claims;
+    result
+}
+
+/// Validates the `children` of a component executing them on an independent [`State`] at
+/// runtime, on a simulated inner state initialized by `state_init`.
+///
+/// Every child is initialized and checked for its requirements within the path segment
+/// of its label.
+/// Outside of validation, this does nothing.
+pub(crate) fn validate_isolated<P: Problem>(
+    state: &mut State<P>,
+    problem: &P,
+    children: Vec<Edge<'_, P>>,
+    state_init: impl FnOnce(&mut State<P>) -> ExecResult<()>,
+) -> ExecResult<()> {
+    if !is_validating(state) {
+        return Ok(());
+    }
+    state.with_inner_state(|state| {
+        state_init(state)?;
+        isolated(state, |state| {
+            for Edge { label, node } in children {
+                state.within(label, |state| {
+                    node.init(problem, state)?;
+                    node.require(problem, &state.requirements())
+                })?;
+            }
+            Ok(())
+        })
+    })?;
+    Ok(())
+}
+
+/// An evaluator standing in for the actual evaluator during validation.
+///
+/// Configurations are never executed during validation, so it is never called.
+pub(crate) struct Placeholder<P>(PhantomData<fn() -> P>);
+
+impl<P> Placeholder<P> {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P: Problem> Evaluate for Placeholder<P> {
+    type Problem = P;
+
+    fn evaluate(
+        &mut self,
+        _problem: &Self::Problem,
+        _state: &mut State<Self::Problem>,
+        _individuals: &mut [Individual<Self::Problem>],
+    ) {
+        unreachable!("configurations are not executed during validation")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::{
+            control_flow::{BranchInput, ParallelBlock},
+            islands::IslandModel,
+            measures::diversity::DimensionWiseDiversity,
+            utils::Noop,
+        },
+        conditions::{DiversityBelow, LessThanN},
+        testing::SingleObjectiveTestProblem,
+        Configuration,
+    };
+
+    fn path(segments: &[&str]) -> ComponentPath {
+        ComponentPath(segments.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn valid_configuration_has_no_issues() {
+        let config = Configuration::<SingleObjectiveTestProblem>::builder()
+            .evaluate()
+            .while_(LessThanN::iterations(10), |builder| {
+                builder.evaluate().update_best_individual()
+            })
+            .build();
+
+        let report = config.validate(&SingleObjectiveTestProblem::new());
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn reports_all_issues_with_paths() {
+        let config = Configuration::<SingleObjectiveTestProblem>::builder()
+            .evaluate()
+            .while_(LessThanN::iterations(10), |builder| {
+                builder
+                    .if_(
+                        DiversityBelow::<DimensionWiseDiversity>::new(0.1),
+                        |builder| builder,
+                    )
+                    .while_(LessThanN::iterations(2), |builder| builder)
+            })
+            .build();
+
+        let report = config.validate(&SingleObjectiveTestProblem::new());
+        let issues = report.issues();
+        assert_eq!(issues.len(), 2, "{report}");
+
+        assert!(matches!(&issues[0], Issue::MissingState { .. }));
+        assert_eq!(
+            issues[0].path(),
+            &path(&["Block[1]", "Loop.do", "Block[0]", "Branch.condition"])
+        );
+        assert!(matches!(
+            &issues[1],
+            Issue::ConflictingState { path: p, outer, .. }
+                if *p == path(&["Block[1]", "Loop.do", "Block[1]"]) && *outer == path(&["Block[1]"])
+        ));
+    }
+
+    #[test]
+    fn isolated_children_are_validated_with_tree_paths() {
+        let invalid = || {
+            Configuration::<SingleObjectiveTestProblem>::builder()
+                .if_(
+                    DiversityBelow::<DimensionWiseDiversity>::new(0.1),
+                    |builder| builder,
+                )
+                .build_component()
+        };
+        let config = Configuration::builder()
+            .do_(
+                ParallelBlock::new(BranchInput::Cloned, |_| Ok(()), [Noop::new(), invalid()])
+                    .unwrap(),
+            )
+            .do_(IslandModel::new(2, invalid()).unwrap())
+            .build();
+
+        let report = config.validate(&SingleObjectiveTestProblem::new());
+        let issues = report.issues();
+        assert_eq!(issues.len(), 2, "{report}");
+        assert_eq!(
+            issues[0].path(),
+            &path(&[
+                "Block[0]",
+                "ParallelBlock[1]",
+                "Block[0]",
+                "Branch.condition"
+            ])
+        );
+        assert_eq!(
+            issues[1].path(),
+            &path(&[
+                "Block[1]",
+                "IslandModel.body",
+                "Block[0]",
+                "Branch.condition"
+            ])
+        );
+
+        let mut paths = Vec::new();
+        config.walk(|path, _| paths.push(path.clone()));
+        assert!(issues.iter().all(|issue| paths.contains(issue.path())));
+    }
+}
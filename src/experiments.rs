@@ -1,15 +1,30 @@
 //! Utilities for performing experiments.
+//!
+//! [`par_experiment`] executes a single configuration on single-objective problems,
+//! while the [`Experiment`] builder allows for more complex experiments, e.g. comparing
+//! multiple configurations.
 
-use std::{fmt::Debug, fs, path::Path, sync::Arc};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs,
+    io::Write,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
+use eyre::{ensure, eyre, WrapErr};
 use indicatif::{ParallelProgressIterator, ProgressBar, ProgressStyle};
 use itertools::Itertools;
-use rayon::iter::{ParallelBridge, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     problems::{KnownOptimumProblem, SingleObjectiveProblem},
     state::random::Random,
-    Configuration, ExecResult, State,
+    Configuration, ExecResult, Problem, State,
 };
 
 /// Execute the `config` on all `problems` `runs` times in parallel.
@@ -31,6 +46,8 @@ use crate::{
 ///
 /// [`Log`]: crate::logging::Log
 /// [`Log::to_cbor`]: crate::logging::Log::to_cbor
+///
+/// See [`Experiment`] for more complex experiments.
 pub fn par_experiment<P>(
     config: &Configuration<P>,
     setup: impl Fn(&mut State<P>) -> ExecResult<()> + Send + Sync,
@@ -51,8 +68,7 @@ where
     let config_log_file = data_dir.join("configuration.ron");
     config.to_ron(config_log_file)?;
 
-    let bar = ProgressBar::new(runs).with_message("Performing Experiment.");
-    bar.set_style(ProgressStyle::with_template("{percent}% |{bar:40.white/green}| {pos:>7}/{len:7} [{elapsed_precise}<{eta_precise}, {per_sec}] {msg}").unwrap());
+    let bar = progress_bar(runs);
 
     (0..runs)
         .cartesian_product(problems)
@@ -74,3 +90,603 @@ where
     println!("All runs finished.");
     Ok(())
 }
+
+fn progress_bar(len: u64) -> ProgressBar {
+    let bar = ProgressBar::new(len).with_message("Performing Experiment.");
+    bar.set_style(ProgressStyle::with_template("{percent}% |{bar:40.white/green}| {pos:>7}/{len:7} [{elapsed_precise}<{eta_precise}, {per_sec}] {msg}").unwrap());
+    bar
+}
+
+/// Replaces all characters of `name` which are not safe in file names by `_`.
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// The status of a single run of an [`Experiment`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    /// The run finished successfully, and its log was written.
+    Completed,
+    /// The run failed with an error or panicked.
+    Failed,
+}
+
+/// The description of a single run of an [`Experiment`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// The name of the configuration.
+    pub configuration: String,
+    /// The name of the problem instance.
+    pub problem: String,
+    /// The number of the run, starting at `0`.
+    pub run: u64,
+    /// The seed of the [`Random`] generator.
+    pub seed: u64,
+    /// The path of the [`Log`] of the run, relative to the experiment folder.
+    ///
+    /// [`Log`]: crate::logging::Log
+    pub log: PathBuf,
+    pub status: RunStatus,
+    /// The error message of a failed run.
+    pub error: Option<String>,
+    /// The wall time of the run in seconds.
+    pub seconds: Option<f64>,
+}
+
+/// The manifest of an [`Experiment`], describing all runs.
+///
+/// The manifest is written to `manifest.json` in the experiment folder, and updated
+/// whenever a run finishes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub runs: Vec<RunRecord>,
+}
+
+impl Manifest {
+    /// The name of the manifest file in the experiment folder.
+    pub const FILE_NAME: &'static str = "manifest.json";
+
+    /// Reads the manifest from the experiment `folder`.
+    pub fn read(folder: impl AsRef<Path>) -> ExecResult<Self> {
+        let file = fs::File::open(folder.as_ref().join(Self::FILE_NAME))
+            .wrap_err("failed to open manifest")?;
+        serde_json::from_reader(std::io::BufReader::new(file)).wrap_err("failed to read manifest")
+    }
+
+    /// Reads the manifest from the experiment `folder`, or returns an empty manifest if
+    /// the folder doesn't contain one.
+    fn read_or_default(folder: &Path) -> ExecResult<Self> {
+        if folder.join(Self::FILE_NAME).exists() {
+            Self::read(folder)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Writes the manifest into the experiment `folder`.
+    ///
+    /// The manifest is written atomically, i.e. an existing manifest is only replaced
+    /// after the new one was written completely.
+    pub fn write(&self, folder: impl AsRef<Path>) -> ExecResult<()> {
+        let path = folder.as_ref().join(Self::FILE_NAME);
+        let tmp_path = path.with_extension("json.tmp");
+        let file = fs::File::create(&tmp_path).wrap_err("failed to create manifest")?;
+        let mut writer = std::io::BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, self).wrap_err("failed to write manifest")?;
+        writer.flush().wrap_err("failed to write manifest")?;
+        drop(writer);
+        fs::rename(&tmp_path, &path).wrap_err("failed to move manifest")
+    }
+
+    /// Returns the runs with the given `status`.
+    pub fn with_status(&self, status: RunStatus) -> impl Iterator<Item = &RunRecord> {
+        self.runs.iter().filter(move |run| run.status == status)
+    }
+}
+
+type SetupFn<P> = dyn Fn(&mut State<P>) -> ExecResult<()> + Send + Sync;
+type SeedFn = dyn Fn(u64) -> u64 + Send + Sync;
+
+/// An experiment executing multiple named configurations on multiple problem instances
+/// for multiple runs, i.e. seeds.
+///
+/// Contrary to [`par_experiment`], any [`Problem`] is supported, including multi-objective ones.
+///
+/// # Runs
+///
+/// Every configuration is executed on every problem instance for every run.
+/// The [`Random`] seed of a run is determined by the seed schedule, which defaults to the
+/// number of the run, and can be customized using [`seeds`] or [`seed_schedule`].
+///
+/// Runs are executed in parallel using [`rayon`], where the number of threads can be
+/// bounded using [`threads`].
+/// Each run is isolated, i.e. errors and panics of one run are recorded in the [`Manifest`]
+/// and don't abort the other runs.
+///
+/// [`seeds`]: Experiment::seeds
+/// [`seed_schedule`]: Experiment::seed_schedule
+/// [`threads`]: Experiment::threads
+///
+/// # Setup
+///
+/// The `setup` function can be used to initialize the [`State`] before each run,
+/// e.g. to insert the evaluator or configure the [`Log`].
+///
+/// [`Log`]: crate::logging::Log
+///
+/// # Saving the results
+///
+/// The results are saved in the experiment folder using the following layout:
+/// - `{configuration}/configuration.ron`: The serialized configuration.
+/// - `{configuration}/{problem}_{run}.cbor`: The [`Log`] of each run.
+/// - `manifest.json`: The [`Manifest`] describing all finished runs, which is updated
+///   whenever a run finishes.
+///
+/// Names are sanitized to be valid file names.
+///
+/// If [`skip_existing`] is enabled, completed runs of a previous execution of the experiment
+/// are not executed again, which allows resuming an interrupted experiment.
+/// A run counts as completed if the existing manifest contains a completed record with the
+/// same seed, and its log still exists.
+/// The records of these runs are carried over into the new manifest unchanged.
+/// Logs are written atomically, so only logs of finished runs exist.
+///
+/// [`skip_existing`]: Experiment::skip_existing
+///
+/// # Examples
+///
+/// Comparing two configurations on multiple problem instances with 30 runs each:
+///
+/// ```no_run
+/// # use mahf::{problems::ObjectiveFunction, Configuration, ExecResult};
+/// use mahf::{experiments::Experiment, problems::Sequential};
+///
+/// # fn example<P: ObjectiveFunction + Send + Sync>(ga: Configuration<P>, pso: Configuration<P>, problems: Vec<P>) -> ExecResult<()> {
+/// let manifest = Experiment::new("data/comparison")
+///     .configuration("GA", ga)
+///     .configuration("PSO", pso)
+///     .problems(problems)
+///     .runs(30)
+///     .setup(|state| {
+///         state.insert_evaluator(Sequential::new());
+///         Ok(())
+///     })
+///     .skip_existing(true)
+///     .threads(8)
+///     .run()?;
+/// println!("{} runs failed", manifest.with_status(mahf::experiments::RunStatus::Failed).count());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Experiment<P: Problem> {
+    folder: PathBuf,
+    configurations: Vec<(String, Configuration<P>)>,
+    problems: Vec<P>,
+    runs: u64,
+    seeds: Option<Vec<u64>>,
+    seed_schedule: Box<SeedFn>,
+    setup: Box<SetupFn<P>>,
+    skip_existing: bool,
+    threads: Option<usize>,
+}
+
+impl<P> Experiment<P>
+where
+    P: Problem + Send + Sync,
+{
+    /// Creates a new `Experiment` saving its results into `folder`.
+    pub fn new(folder: impl AsRef<Path>) -> Self {
+        Self {
+            folder: folder.as_ref().to_path_buf(),
+            configurations: Vec::new(),
+            problems: Vec::new(),
+            runs: 1,
+            seeds: None,
+            seed_schedule: Box::new(|run| run),
+            setup: Box::new(|_| Ok(())),
+            skip_existing: false,
+            threads: None,
+        }
+    }
+
+    /// Adds the `config` with the given `name`.
+    pub fn configuration(mut self, name: impl Into<String>, config: Configuration<P>) -> Self {
+        self.configurations.push((name.into(), config));
+        self
+    }
+
+    /// Adds the `problem` instance.
+    ///
+    /// The instance is identified by its [`Problem::name`].
+    pub fn problem(mut self, problem: P) -> Self {
+        self.problems.push(problem);
+        self
+    }
+
+    /// Adds multiple `problems` instances.
+    pub fn problems(mut self, problems: impl IntoIterator<Item = P>) -> Self {
+        self.problems.extend(problems);
+        self
+    }
+
+    /// Sets the number of runs per configuration and problem instance.
+    ///
+    /// If the seeds are set explicitly using [`seeds`], `runs` must not exceed their number.
+    ///
+    /// [`seeds`]: Experiment::seeds
+    pub fn runs(mut self, runs: u64) -> Self {
+        self.runs = runs;
+        self
+    }
+
+    /// Sets the seeds of the runs explicitly, which also sets the number of runs.
+    pub fn seeds(mut self, seeds: impl IntoIterator<Item = u64>) -> Self {
+        let seeds: Vec<_> = seeds.into_iter().collect();
+        self.runs = seeds.len() as u64;
+        self.seeds = Some(seeds);
+        self
+    }
+
+    /// Sets the seed schedule, mapping the number of each run to its seed.
+    ///
+    /// This replaces any seeds set explicitly using [`seeds`].
+    ///
+    /// [`seeds`]: Experiment::seeds
+    pub fn seed_schedule(mut self, schedule: impl Fn(u64) -> u64 + Send + Sync + 'static) -> Self {
+        self.seeds = None;
+        self.seed_schedule = Box::new(schedule);
+        self
+    }
+
+    /// Sets the function initializing the [`State`] before each run.
+    ///
+    /// The [`Random`] generator is already inserted when `setup` is called.
+    pub fn setup(
+        mut self,
+        setup: impl Fn(&mut State<P>) -> ExecResult<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.setup = Box::new(setup);
+        self
+    }
+
+    /// Sets whether runs which were already completed with the same seed are skipped.
+    ///
+    /// See the [type documentation](Experiment) for more information.
+    pub fn skip_existing(mut self, skip_existing: bool) -> Self {
+        self.skip_existing = skip_existing;
+        self
+    }
+
+    /// Bounds the number of runs executed in parallel.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Returns the seed of the `run`.
+    fn seed(&self, run: u64) -> u64 {
+        match &self.seeds {
+            Some(seeds) => seeds[run as usize],
+            None => (self.seed_schedule)(run),
+        }
+    }
+
+    /// Executes all runs, and returns the [`Manifest`] describing them.
+    ///
+    /// Fails only if the experiment itself is invalid, e.g. if names are not unique,
+    /// or if the results can't be saved, but not if single runs fail.
+    pub fn run(&self) -> ExecResult<Manifest> {
+        ensure!(
+            !self.configurations.is_empty(),
+            "at least one configuration is required"
+        );
+        ensure!(
+            !self.problems.is_empty(),
+            "at least one problem is required"
+        );
+        ensure!(self.runs > 0, "at least one run is required");
+        if let Some(seeds) = &self.seeds {
+            ensure!(
+                self.runs <= seeds.len() as u64,
+                "{} runs require at least as many seeds, but only {} were given",
+                self.runs,
+                seeds.len()
+            );
+        }
+
+        let configurations: Vec<_> = self
+            .configurations
+            .iter()
+            .map(|(name, config)| (file_name(name), config))
+            .collect();
+        let problems: Vec<_> = self
+            .problems
+            .iter()
+            .map(|problem| (file_name(problem.name()), problem))
+            .collect();
+        ensure!(
+            configurations.iter().map(|(name, _)| name).all_unique(),
+            "the names of the configurations must be unique"
+        );
+        ensure!(
+            problems.iter().map(|(name, _)| name).all_unique(),
+            "the names of the problems must be unique"
+        );
+
+        for (name, config) in &configurations {
+            let folder = self.folder.join(name);
+            fs::create_dir_all(&folder).wrap_err("failed to create experiment folder")?;
+            config.to_ron(folder.join("configuration.ron"))?;
+        }
+
+        let previous = if self.skip_existing {
+            Manifest::read_or_default(&self.folder)?
+        } else {
+            Manifest::default()
+        };
+        let mut completed: HashMap<_, _> = previous
+            .runs
+            .into_iter()
+            .filter(|record| record.status == RunStatus::Completed)
+            .filter(|record| self.folder.join(&record.log).exists())
+            .map(|record| (record.log.clone(), record))
+            .collect();
+
+        let runs: Vec<_> = configurations
+            .iter()
+            .cartesian_product(&problems)
+            .cartesian_product(0..self.runs)
+            .map(|(((config_name, config), (problem_name, problem)), run)| {
+                let record = RunRecord {
+                    configuration: config_name.clone(),
+                    problem: problem_name.clone(),
+                    run,
+                    seed: self.seed(run),
+                    log: Path::new(config_name).join(format!("{problem_name}_{run}.cbor")),
+                    status: RunStatus::Completed,
+                    error: None,
+                    seconds: None,
+                };
+                (record, *config, *problem)
+            })
+            .collect();
+
+        // Carry over the records of runs completed with the same seed, and execute all others.
+        let mut records = Vec::with_capacity(runs.len());
+        let mut pending = Vec::new();
+        for (i, (record, config, problem)) in runs.into_iter().enumerate() {
+            match completed.remove(&record.log) {
+                Some(previous) if previous.seed == record.seed => records.push(Some(previous)),
+                _ => {
+                    records.push(None);
+                    pending.push((i, record, config, problem));
+                }
+            }
+        }
+        let records = Mutex::new(records);
+
+        let bar = progress_bar(pending.len() as u64);
+        let execute = || {
+            pending
+                .into_par_iter()
+                .progress_with(bar)
+                .map(|(i, record, config, problem)| {
+                    let record = self.execute(record, config, problem);
+                    let mut records = records.lock().unwrap();
+                    records[i] = Some(record);
+                    Manifest {
+                        runs: records.iter().flatten().cloned().collect(),
+                    }
+                    .write(&self.folder)
+                })
+                .collect::<ExecResult<()>>()
+        };
+        match self.threads {
+            Some(threads) => rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .wrap_err("failed to create thread pool")?
+                .install(execute),
+            None => execute(),
+        }?;
+
+        let runs = records
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect();
+        let manifest = Manifest { runs };
+        manifest.write(&self.folder)?;
+
+        println!(
+            "All runs finished: {} completed, {} failed.",
+            manifest.with_status(RunStatus::Completed).count(),
+            manifest.with_status(RunStatus::Failed).count(),
+        );
+        Ok(manifest)
+    }
+
+    /// Executes a single run, catching errors and panics.
+    fn execute(&self, mut record: RunRecord, config: &Configuration<P>, problem: &P) -> RunRecord {
+        let log_file = self.folder.join(&record.log);
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| -> ExecResult<()> {
+            // Remove stale logs, e.g. of a run with another seed, which would otherwise remain
+            // if this run fails.
+            if log_file.exists() {
+                fs::remove_file(&log_file).wrap_err("failed to remove stale log")?;
+            }
+            let state = config.optimize_with(problem, |state| {
+                state.insert(Random::new(record.seed));
+                (self.setup)(state)
+            })?;
+            // Write to a temporary file first, so only logs of finished runs exist.
+            let tmp_file = log_file.with_extension("cbor.tmp");
+            state.log().to_cbor(&tmp_file)?;
+            fs::rename(&tmp_file, &log_file).wrap_err("failed to move log")?;
+            Ok(())
+        }))
+        .unwrap_or_else(|panic| {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            Err(eyre!("the run panicked: {message}"))
+        });
+
+        record.seconds = Some(start.elapsed().as_secs_f64());
+        if let Err(error) = result {
+            record.status = RunStatus::Failed;
+            record.error = Some(format!("{error:#}"));
+        }
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{components::utils::Noop, testing::SingleObjectiveTestProblem};
+
+    type P = SingleObjectiveTestProblem;
+
+    fn test_folder(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("mahf-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        folder
+    }
+
+    /// Returns a setup function counting the executed runs.
+    fn counting_setup(count: &Arc<AtomicUsize>) -> impl Fn(&mut State<P>) -> ExecResult<()> {
+        let count = count.clone();
+        move |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn experiment_isolates_failures_and_resumes() {
+        let folder = test_folder("experiment");
+        let executed = Arc::new(AtomicUsize::new(0));
+        let experiment = || {
+            Experiment::new(&folder)
+                .configuration("noop", Configuration::new(Noop::new()))
+                // Fails because no evaluator is inserted.
+                .configuration("failing", Configuration::builder().evaluate().build())
+                .problem(SingleObjectiveTestProblem::new())
+                .seeds([3, 5])
+                .setup(counting_setup(&executed))
+                .skip_existing(true)
+                .threads(2)
+        };
+
+        let first = experiment().run().unwrap();
+        let statuses: Vec<_> = first
+            .runs
+            .iter()
+            .map(|run| (run.configuration.as_str(), run.seed, run.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("noop", 3, RunStatus::Completed),
+                ("noop", 5, RunStatus::Completed),
+                ("failing", 3, RunStatus::Failed),
+                ("failing", 5, RunStatus::Failed),
+            ]
+        );
+        assert!(first.runs[2].error.is_some());
+        assert!(folder.join(&first.runs[0].log).exists());
+        assert!(!folder.join(&first.runs[2].log).exists());
+        assert_eq!(executed.load(Ordering::SeqCst), 4);
+
+        // Only the failed runs are executed again, and the completed ones are carried over.
+        let second = experiment().run().unwrap();
+        assert_eq!(executed.load(Ordering::SeqCst), 6);
+        assert_eq!(second.with_status(RunStatus::Completed).count(), 2);
+        assert_eq!(second.with_status(RunStatus::Failed).count(), 2);
+        assert_eq!(second.runs[0].seconds, first.runs[0].seconds);
+        assert_eq!(second.runs[1].seconds, first.runs[1].seconds);
+        assert_eq!(Manifest::read(&folder).unwrap().runs.len(), 4);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn experiment_reruns_runs_with_other_seeds() {
+        let folder = test_folder("reseeded");
+        let executed = Arc::new(AtomicUsize::new(0));
+        let experiment = |seeds: [u64; 2]| {
+            Experiment::new(&folder)
+                .configuration("noop", Configuration::new(Noop::new()))
+                .problem(SingleObjectiveTestProblem::new())
+                .seeds(seeds)
+                .setup(counting_setup(&executed))
+                .skip_existing(true)
+                .run()
+                .unwrap()
+        };
+
+        let first = experiment([3, 5]);
+        let second = experiment([4, 5]);
+        assert_eq!(executed.load(Ordering::SeqCst), 3);
+        assert_eq!(second.runs[0].seed, 4);
+        assert_eq!(second.runs[1].seconds, first.runs[1].seconds);
+        assert_eq!(second.with_status(RunStatus::Completed).count(), 2);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn experiment_writes_manifest_after_every_run() {
+        let folder = test_folder("manifest");
+        let setup_folder = folder.clone();
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let setup_finished = finished.clone();
+        Experiment::new(&folder)
+            .configuration("noop", Configuration::new(Noop::new()))
+            .problem(SingleObjectiveTestProblem::new())
+            .runs(3)
+            .setup(move |_| {
+                let runs = Manifest::read(&setup_folder).map_or(0, |manifest| manifest.runs.len());
+                setup_finished.lock().unwrap().push(runs);
+                Ok(())
+            })
+            .threads(1)
+            .run()
+            .unwrap();
+
+        let mut finished = finished.lock().unwrap().clone();
+        finished.sort();
+        assert_eq!(finished, [0, 1, 2]);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn experiment_rejects_more_runs_than_seeds() {
+        let folder = test_folder("seeds");
+        let result = Experiment::new(&folder)
+            .configuration("noop", Configuration::new(Noop::new()))
+            .problem(SingleObjectiveTestProblem::new())
+            .seeds([3, 5])
+            .runs(10)
+            .run();
+        assert!(result.is_err());
+        assert!(!folder.exists());
+    }
+}
@@ -0,0 +1,367 @@
+//! Estimation of Distribution Algorithms (EDAs).
+//!
+//! # References
+//!
+//! \[1\] Heinz Mühlenbein and Gerhard Paaß. 1996.
+//! From recombination of genes to the estimation of distributions I. Binary parameters.
+//! In Parallel Problem Solving from Nature — PPSN IV, 178–187.
+//! DOI:<https://doi.org/10.1007/3-540-61723-X_982>
+//!
+//! \[2\] Shumeet Baluja. 1994.
+//! Population-Based Incremental Learning: A Method for Integrating Genetic Search Based Function
+//! Optimization and Competitive Learning. Technical Report CMU-CS-94-163.
+//!
+//! \[3\] Georges R. Harik, Fernando G. Lobo, and David E. Goldberg. 1999.
+//! The compact genetic algorithm.
+//! IEEE Transactions on Evolutionary Computation 3, 4 (1999), 287–297.
+//! DOI:<https://doi.org/10.1109/4235.797971>
+//!
+//! \[4\] Shumeet Baluja and Scott Davies. 1997.
+//! Using Optimal Dependency-Trees for Combinatorial Optimization: Learning the Structure of the
+//! Search Space. In Proceedings of the 14th International Conference on Machine Learning, 30–38.
+
+use eyre::WrapErr;
+
+use crate::{
+    component::ExecResult,
+    components::{generative::eda, initialization, replacement, selection},
+    configuration::Configuration,
+    identifier::{Global, Identifier},
+    logging::Logger,
+    problems::{LimitedVectorProblem, SingleObjectiveProblem, VectorProblem},
+    Component, Condition,
+};
+
+/// Parameters for [`umda`].
+#[derive(Clone, Copy, Debug)]
+pub struct UMDAParameters {
+    pub population_size: u32,
+    pub num_selected: u32,
+    pub margin: f64,
+}
+
+/// Univariate Marginal Distribution Algorithm (UMDA) on bitstrings \[1\].
+///
+/// Uses the [`eda()`] component internally.
+pub fn umda<P>(
+    params: UMDAParameters,
+    condition: Box<dyn Condition<P>>,
+) -> ExecResult<Configuration<P>>
+where
+    P: SingleObjectiveProblem + VectorProblem<Element = bool>,
+{
+    let UMDAParameters {
+        population_size,
+        num_selected,
+        margin,
+    } = params;
+
+    let sampling = eda::UnivariateSampling::new(population_size);
+
+    Ok(Configuration::builder()
+        .do_(initialization::Empty::new())
+        .do_(sampling.clone())
+        .evaluate()
+        .update_best_individual()
+        .do_(eda::<P, Global>(
+            Parameters {
+                selection: selection::Truncation::new(num_selected),
+                update: eda::UnivariateUpdate::new(1., margin)
+                    .wrap_err("failed to construct the univariate update")?,
+                sampling,
+                replacement: replacement::Generational::new(population_size),
+            },
+            condition,
+        ))
+        .build())
+}
+
+/// Parameters for [`pbil`].
+#[derive(Clone, Copy, Debug)]
+pub struct PBILParameters {
+    pub population_size: u32,
+    pub num_selected: u32,
+    pub learning_rate: f64,
+    pub margin: f64,
+}
+
+/// Population-Based Incremental Learning (PBIL) on bitstrings \[2\].
+///
+/// Uses the [`eda()`] component internally.
+pub fn pbil<P>(
+    params: PBILParameters,
+    condition: Box<dyn Condition<P>>,
+) -> ExecResult<Configuration<P>>
+where
+    P: SingleObjectiveProblem + VectorProblem<Element = bool>,
+{
+    let PBILParameters {
+        population_size,
+        num_selected,
+        learning_rate,
+        margin,
+    } = params;
+
+    let sampling = eda::UnivariateSampling::new(population_size);
+
+    Ok(Configuration::builder()
+        .do_(initialization::Empty::new())
+        .do_(sampling.clone())
+        .evaluate()
+        .update_best_individual()
+        .do_(eda::<P, Global>(
+            Parameters {
+                selection: selection::Truncation::new(num_selected),
+                update: eda::UnivariateUpdate::new(learning_rate, margin)
+                    .wrap_err("failed to construct the univariate update")?,
+                sampling,
+                replacement: replacement::Generational::new(population_size),
+            },
+            condition,
+        ))
+        .build())
+}
+
+/// Parameters for [`cga`].
+#[derive(Clone, Copy, Debug)]
+pub struct CGAParameters {
+    pub virtual_population_size: u32,
+}
+
+/// Compact Genetic Algorithm (cGA) on bitstrings \[3\].
+///
+/// Samples two individuals per iteration.
+///
+/// Uses the [`eda()`] component internally.
+pub fn cga<P>(
+    params: CGAParameters,
+    condition: Box<dyn Condition<P>>,
+) -> ExecResult<Configuration<P>>
+where
+    P: SingleObjectiveProblem + VectorProblem<Element = bool>,
+{
+    let CGAParameters {
+        virtual_population_size,
+    } = params;
+
+    let sampling = eda::UnivariateSampling::new(2);
+
+    Ok(Configuration::builder()
+        .do_(initialization::Empty::new())
+        .do_(sampling.clone())
+        .evaluate()
+        .update_best_individual()
+        .do_(eda::<P, Global>(
+            Parameters {
+                selection: selection::All::new(),
+                update: eda::CompactUpdate::new(virtual_population_size)
+                    .wrap_err("failed to construct the compact update")?,
+                sampling,
+                replacement: replacement::Generational::new(2),
+            },
+            condition,
+        ))
+        .build())
+}
+
+/// Parameters for [`chow_liu_eda`].
+#[derive(Clone, Copy, Debug)]
+pub struct ChowLiuParameters {
+    pub population_size: u32,
+    pub num_selected: u32,
+    pub prior: f64,
+}
+
+/// An EDA learning tree-structured dependencies between bits with the Chow–Liu algorithm,
+/// also known as COMIT or dependency-tree EDA \[4\].
+///
+/// Uses the [`eda()`] component internally.
+pub fn chow_liu_eda<P>(
+    params: ChowLiuParameters,
+    condition: Box<dyn Condition<P>>,
+) -> ExecResult<Configuration<P>>
+where
+    P: SingleObjectiveProblem + VectorProblem<Element = bool>,
+{
+    let ChowLiuParameters {
+        population_size,
+        num_selected,
+        prior,
+    } = params;
+
+    let sampling = eda::DependencyTreeSampling::new(population_size);
+
+    Ok(Configuration::builder()
+        .do_(initialization::Empty::new())
+        .do_(sampling.clone())
+        .evaluate()
+        .update_best_individual()
+        .do_(eda::<P, Global>(
+            Parameters {
+                selection: selection::Truncation::new(num_selected),
+                update: eda::ChowLiuUpdate::new(prior)
+                    .wrap_err("failed to construct the Chow–Liu update")?,
+                sampling,
+                replacement: replacement::Generational::new(population_size),
+            },
+            condition,
+        ))
+        .build())
+}
+
+/// Parameters for [`real_umda`].
+#[derive(Clone, Copy, Debug)]
+pub struct RealUMDAParameters {
+    pub population_size: u32,
+    pub num_selected: u32,
+    pub min_std_dev: f64,
+}
+
+/// An example continuous UMDA operating on a real search space.
+///
+/// Uses the [`eda()`] component internally.
+pub fn real_umda<P>(
+    params: RealUMDAParameters,
+    condition: Box<dyn Condition<P>>,
+) -> ExecResult<Configuration<P>>
+where
+    P: SingleObjectiveProblem + LimitedVectorProblem<Element = f64>,
+{
+    let RealUMDAParameters {
+        population_size,
+        num_selected,
+        min_std_dev,
+    } = params;
+
+    let sampling = eda::GaussianSampling::new(population_size);
+
+    Ok(Configuration::builder()
+        .do_(initialization::Empty::new())
+        .do_(sampling.clone())
+        .evaluate()
+        .update_best_individual()
+        .do_(eda::<P, Global>(
+            Parameters {
+                selection: selection::Truncation::new(num_selected),
+                update: eda::GaussianUpdate::new(1., min_std_dev)
+                    .wrap_err("failed to construct the gaussian update")?,
+                sampling,
+                replacement: replacement::Generational::new(population_size),
+            },
+            condition,
+        ))
+        .build())
+}
+
+/// Basic building blocks of [`eda()`].
+pub struct Parameters<P> {
+    /// Selects the individuals the model is learned from.
+    pub selection: Box<dyn Component<P>>,
+    /// Learns the model from the selected individuals.
+    pub update: Box<dyn Component<P>>,
+    /// Replaces the selected individuals with samples from the model.
+    pub sampling: Box<dyn Component<P>>,
+    pub replacement: Box<dyn Component<P>>,
+}
+
+/// A generic single-objective Estimation of Distribution Algorithm (EDA) template.
+pub fn eda<P, I>(params: Parameters<P>, condition: Box<dyn Condition<P>>) -> Box<dyn Component<P>>
+where
+    P: SingleObjectiveProblem,
+    I: Identifier,
+{
+    let Parameters {
+        selection,
+        update,
+        sampling,
+        replacement,
+    } = params;
+
+    Configuration::builder()
+        .while_(condition, |builder| {
+            builder
+                .do_(selection)
+                .do_(update)
+                .do_(sampling)
+                .evaluate_with::<I>()
+                .update_best_individual()
+                .do_(replacement)
+                .do_(Logger::new())
+        })
+        .build_component()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        conditions::LessThanN,
+        problems::{ObjectiveFunction, Sequential},
+        testing::*,
+    };
+
+    /// Runs the `config` for five iterations and checks the number of evaluations.
+    fn smoke_test<P>(config: Configuration<P>, problem: &P, evaluations: u32)
+    where
+        P: SingleObjectiveProblem + ObjectiveFunction,
+    {
+        let state = config.optimize(problem, Sequential::new()).unwrap();
+        assert_eq!(state.iterations(), 5);
+        assert_eq!(state.evaluations(), evaluations);
+        assert!(state.best_objective_value().is_some());
+    }
+
+    #[test]
+    fn umda_runs() {
+        let params = UMDAParameters {
+            population_size: 10,
+            num_selected: 5,
+            margin: 0.05,
+        };
+        let config = umda(params, LessThanN::iterations(5)).unwrap();
+        smoke_test(config, &BitstringTestProblem(8), 60);
+    }
+
+    #[test]
+    fn pbil_runs() {
+        let params = PBILParameters {
+            population_size: 10,
+            num_selected: 2,
+            learning_rate: 0.1,
+            margin: 0.05,
+        };
+        let config = pbil(params, LessThanN::iterations(5)).unwrap();
+        smoke_test(config, &BitstringTestProblem(8), 60);
+    }
+
+    #[test]
+    fn cga_runs() {
+        let params = CGAParameters {
+            virtual_population_size: 20,
+        };
+        let config = cga(params, LessThanN::iterations(5)).unwrap();
+        smoke_test(config, &BitstringTestProblem(8), 12);
+    }
+
+    #[test]
+    fn chow_liu_eda_runs() {
+        let params = ChowLiuParameters {
+            population_size: 10,
+            num_selected: 5,
+            prior: 1.,
+        };
+        let config = chow_liu_eda(params, LessThanN::iterations(5)).unwrap();
+        smoke_test(config, &BitstringTestProblem(8), 60);
+    }
+
+    #[test]
+    fn real_umda_runs() {
+        let params = RealUMDAParameters {
+            population_size: 10,
+            num_selected: 5,
+            min_std_dev: 0.01,
+        };
+        let config = real_umda(params, LessThanN::iterations(5)).unwrap();
+        smoke_test(config, &RealTestProblem(3), 60);
+    }
+}
@@ -122,6 +122,53 @@ where
         .build())
 }
 
+/// Parameters for [`integer_ga`].
+#[derive(Clone, Copy, Debug)]
+pub struct IntegerProblemParameters {
+    pub population_size: u32,
+    pub tournament_size: u32,
+    pub pm: f64,
+    pub deviation: f64,
+    pub pc: f64,
+}
+
+/// An example single-objective GA operating on a bounded integer search space.
+///
+/// Uses the [`ga`] component internally.
+pub fn integer_ga<P>(
+    params: IntegerProblemParameters,
+    condition: Box<dyn Condition<P>>,
+) -> ExecResult<Configuration<P>>
+where
+    P: SingleObjectiveProblem + LimitedVectorProblem<Element = i64>,
+{
+    let IntegerProblemParameters {
+        population_size,
+        tournament_size,
+        pm,
+        deviation,
+        pc,
+    } = params;
+
+    Ok(Configuration::builder()
+        .do_(initialization::RandomSpread::new(population_size))
+        .evaluate()
+        .update_best_individual()
+        .do_(ga::<P, Global>(
+            Parameters {
+                selection: selection::Tournament::new(population_size, tournament_size),
+                crossover: recombination::UniformCrossover::new_insert_both(pc),
+                pm,
+                mutation: mutation::DiscreteGaussianMutation::new_dev(deviation),
+                constraints: boundary::IntegerSaturation::new(),
+                archive: None,
+                replacement: replacement::Generational::new(population_size),
+            },
+            condition,
+        ))
+        .build())
+}
+
 /// Basic building blocks of [`ga`].
 pub struct Parameters<P> {
     pub selection: Box<dyn Component<P>>,
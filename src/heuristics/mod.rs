@@ -11,6 +11,7 @@ pub mod aco;
 pub mod bh;
 pub mod cro;
 pub mod de;
+pub mod eda;
 pub mod es;
 pub mod fa;
 pub mod ga;
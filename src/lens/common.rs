@@ -16,7 +16,7 @@ use crate::{
     lens::{AnyLens, Lens, LensMap, LensMut, LensRef},
     logging::extractor::{EntryExtractor, EntryName},
     problems::SingleObjectiveProblem,
-    state::common::{BestIndividual, Populations},
+    state::common::{BestIndividual, Populations, RunClock},
     utils::SerializablePhantom,
     CustomState, Problem, SingleObjective, State,
 };
@@ -231,6 +231,80 @@ impl<P: Problem> LensMap for PopulationSizeLens<P> {
     }
 }
 
+/// Lens for extracting the wall-clock time elapsed since the start of the run in seconds.
+///
+/// The time is measured by the [`RunClock`].
+#[derive(Clone, Default, Serialize)]
+pub struct ElapsedTimeLens;
+
+impl ElapsedTimeLens {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Constructs the lens for logging entries.
+    pub fn entry<P: Problem>() -> Box<dyn EntryExtractor<P>> {
+        Box::new(Self)
+    }
+}
+
+impl AnyLens for ElapsedTimeLens {
+    type Target = f64;
+}
+
+impl EntryName for ElapsedTimeLens {
+    fn entry_name() -> &'static str {
+        "ElapsedTime"
+    }
+}
+
+impl LensMap for ElapsedTimeLens {
+    type Source = RunClock;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        source.elapsed().as_secs_f64()
+    }
+}
+
+/// Lens for extracting the CPU time consumed by the process since the start of the run in seconds.
+///
+/// The time is measured by the [`RunClock`].
+#[derive(Clone, Default, Serialize)]
+pub struct CpuTimeLens;
+
+impl CpuTimeLens {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Constructs the lens for logging entries.
+    pub fn entry<P: Problem>() -> Box<dyn EntryExtractor<P>> {
+        Box::new(Self)
+    }
+}
+
+impl AnyLens for CpuTimeLens {
+    type Target = f64;
+}
+
+impl EntryName for CpuTimeLens {
+    fn entry_name() -> &'static str {
+        "CpuTime"
+    }
+}
+
+impl<P: Problem> Lens<P> for CpuTimeLens {
+    fn get(&self, _problem: &P, state: &State<P>) -> ExecResult<Self::Target> {
+        state
+            .try_borrow::<RunClock>()?
+            .cpu_elapsed()
+            .map(|elapsed| elapsed.as_secs_f64())
+            .ok_or_else(|| eyre!("the CPU time can't be measured on this platform"))
+    }
+}
+
 /// Lens for extracting the solution of the [`BestIndividual`].
 #[derive(Serialize, Derivative)]
 #[serde(bound = "")]
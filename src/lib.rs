@@ -153,6 +153,7 @@ pub use rand_distr;
 #[doc(hidden)]
 pub use serde;
 
+pub mod analysis;
 pub mod component;
 pub mod components;
 pub mod conditions;
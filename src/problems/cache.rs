@@ -0,0 +1,382 @@
+//! Caching of objective values for deterministic objective functions.
+//!
+//! Especially on discrete search spaces, identical solutions are generated and evaluated
+//! repeatedly.
+//! The [`Cached`] evaluator wraps another [`Evaluate`] implementation, and only evaluates
+//! solutions whose objective value is not already stored in a bounded [`LruCache`].
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
+
+use better_any::{Tid, TidAble};
+use serde::Serialize;
+
+use crate::{
+    lens::{AnyLens, LensMap},
+    logging::extractor::{EntryExtractor, EntryName},
+    problems::Evaluate,
+    CustomState, Individual, Problem, State,
+};
+
+/// A bounded cache evicting the least recently used (LRU) entry first.
+#[derive(Clone)]
+pub struct LruCache<K, V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<K, (V, u64)>,
+    order: BTreeMap<u64, K>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> LruCache<K, V> {
+    /// Creates an empty cache holding at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the value for `key`, marking it as most recently used.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let (value, used) = self.entries.get_mut(key)?;
+        let k = self.order.remove(used).unwrap();
+        *used = self.tick;
+        self.order.insert(self.tick, k);
+        Some(value.clone())
+    }
+
+    /// Inserts the `value` for `key`, evicting the least recently used entry if necessary.
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.remove(&key) {
+            self.order.remove(&used);
+        } else if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (value, self.tick));
+    }
+}
+
+/// The number of cache hits and misses of a [`Cached`] evaluator.
+#[derive(Clone, Default, Serialize, Tid)]
+pub struct CacheStatistics {
+    /// The number of solutions whose objective value was taken from the cache.
+    pub hits: u32,
+    /// The number of solutions which had to be evaluated.
+    pub misses: u32,
+}
+
+impl CacheStatistics {
+    /// Returns the fraction of cache hits, or `0` if nothing was evaluated yet.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+impl CustomState<'_> for CacheStatistics {}
+
+/// An evaluator caching the objective values of the `inner` evaluator.
+///
+/// Only solutions not found in the cache are passed to the `inner` evaluator, where identical
+/// solutions within the same call are only evaluated once.
+/// Therefore, the objective function must be deterministic.
+///
+/// If `count_hits` is `false`, cache hits don't count towards the [`Evaluations`].
+///
+/// The number of hits and misses is stored in the [`CacheStatistics`], which are inserted into
+/// the [`State`] when the evaluator is inserted using [`State::insert_evaluator`].
+///
+/// [`Evaluations`]: crate::state::common::Evaluations
+///
+/// # Examples
+///
+/// Caching up to 10000 objective values of a sequential evaluator:
+///
+/// ```
+/// # use std::hash::Hash;
+/// # use mahf::{problems::ObjectiveFunction, Configuration, ExecResult, Random};
+/// use mahf::problems::{cache::Cached, Sequential};
+///
+/// # fn example<P: ObjectiveFunction>(config: Configuration<P>, problem: P) -> ExecResult<()>
+/// # where P::Encoding: Hash + Eq {
+/// let state = config.optimize_with(&problem, |state| {
+///     state.insert_evaluator(Cached::new(Sequential::new(), 10_000, false));
+///     Ok(())
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Cached<E: Evaluate> {
+    inner: E,
+    cache: LruCache<<E::Problem as Problem>::Encoding, <E::Problem as Problem>::Objective>,
+    count_hits: bool,
+    last_misses: usize,
+}
+
+impl<E> Cached<E>
+where
+    E: Evaluate,
+    <E::Problem as Problem>::Encoding: Hash + Eq,
+{
+    /// Wraps the `inner` evaluator with a cache of some `capacity`.
+    pub fn new(inner: E, capacity: usize, count_hits: bool) -> Self {
+        Self {
+            inner,
+            cache: LruCache::new(capacity),
+            count_hits,
+            last_misses: 0,
+        }
+    }
+
+    /// Returns the cache.
+    pub fn cache(
+        &self,
+    ) -> &LruCache<<E::Problem as Problem>::Encoding, <E::Problem as Problem>::Objective> {
+        &self.cache
+    }
+}
+
+impl<E> Evaluate for Cached<E>
+where
+    E: Evaluate,
+    <E::Problem as Problem>::Encoding: Hash + Eq,
+{
+    type Problem = E::Problem;
+
+    fn evaluate(
+        &mut self,
+        problem: &Self::Problem,
+        state: &mut State<Self::Problem>,
+        individuals: &mut [Individual<Self::Problem>],
+    ) {
+        // The cached objective values, or the index of the solution to evaluate, where duplicates
+        // within this call are only evaluated once.
+        let mut unique = HashMap::new();
+        let mut misses = Vec::new();
+        let lookups: Vec<_> = individuals
+            .iter()
+            .map(|individual| {
+                let solution = individual.solution();
+                self.cache.get(solution).ok_or_else(|| {
+                    *unique.entry(solution).or_insert_with(|| {
+                        misses.push(Individual::new_unevaluated(solution.clone()));
+                        misses.len() - 1
+                    })
+                })
+            })
+            .collect();
+
+        self.inner.evaluate(problem, state, &mut misses);
+        for individual in &misses {
+            self.cache.insert(
+                individual.solution().clone(),
+                individual.objective().clone(),
+            );
+        }
+
+        for (individual, lookup) in individuals.iter_mut().zip(lookups) {
+            let objective = match lookup {
+                Ok(objective) => objective,
+                Err(index) => misses[index].objective().clone(),
+            };
+            individual.set_objective(objective);
+        }
+
+        self.last_misses = misses.len();
+        // The statistics are missing if the evaluator was not initialized.
+        let mut statistics = state.entry::<CacheStatistics>().or_default();
+        statistics.misses += misses.len() as u32;
+        statistics.hits += (individuals.len() - misses.len()) as u32;
+    }
+
+    fn counted_evaluations(&self, n: usize) -> usize {
+        self.inner
+            .counted_evaluations(if self.count_hits { n } else { self.last_misses })
+    }
+
+    fn init(&mut self, state: &mut State<Self::Problem>) {
+        self.inner.init(state);
+        state.insert(CacheStatistics::default());
+    }
+}
+
+/// Lens for the number of cache hits stored in [`CacheStatistics`].
+#[derive(Default, Clone, Serialize)]
+pub struct CacheHitsLens;
+
+impl CacheHitsLens {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Constructs the lens for logging.
+    pub fn entry<P: Problem>() -> Box<dyn EntryExtractor<P>> {
+        Box::new(Self)
+    }
+}
+
+impl AnyLens for CacheHitsLens {
+    type Target = u32;
+}
+
+impl EntryName for CacheHitsLens {
+    fn entry_name() -> &'static str {
+        "Cache hits"
+    }
+}
+
+impl LensMap for CacheHitsLens {
+    type Source = CacheStatistics;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        source.hits
+    }
+}
+
+/// Lens for the number of cache misses stored in [`CacheStatistics`].
+#[derive(Default, Clone, Serialize)]
+pub struct CacheMissesLens;
+
+impl CacheMissesLens {
+    /// Constructs the lens.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Constructs the lens for logging.
+    pub fn entry<P: Problem>() -> Box<dyn EntryExtractor<P>> {
+        Box::new(Self)
+    }
+}
+
+impl AnyLens for CacheMissesLens {
+    type Target = u32;
+}
+
+impl EntryName for CacheMissesLens {
+    fn entry_name() -> &'static str {
+        "Cache misses"
+    }
+}
+
+impl LensMap for CacheMissesLens {
+    type Source = CacheStatistics;
+
+    fn map(&self, source: &Self::Source) -> Self::Target {
+        source.misses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{single_test_population, SingleObjectiveTestProblem};
+
+    /// Evaluates every solution as `1`, and counts every evaluation twice.
+    struct Double;
+
+    impl Evaluate for Double {
+        type Problem = SingleObjectiveTestProblem;
+
+        fn evaluate(
+            &mut self,
+            _problem: &Self::Problem,
+            _state: &mut State<Self::Problem>,
+            individuals: &mut [Individual<Self::Problem>],
+        ) {
+            for individual in individuals {
+                individual.set_objective(1.0.try_into().unwrap());
+            }
+        }
+
+        fn counted_evaluations(&self, n: usize) -> usize {
+            2 * n
+        }
+    }
+
+    #[test]
+    fn statistics_are_inserted_with_evaluator() {
+        let mut state = State::<SingleObjectiveTestProblem>::new();
+        state.insert_evaluator(Cached::new(Double, 10, false));
+        assert!(state.contains::<CacheStatistics>());
+    }
+
+    #[test]
+    fn optimize_initializes_the_evaluator() {
+        let config = crate::Configuration::builder().build();
+        let state = config
+            .optimize(
+                &SingleObjectiveTestProblem::new(),
+                Cached::new(Double, 10, false),
+            )
+            .unwrap();
+        assert!(state.contains::<CacheStatistics>());
+    }
+
+    #[test]
+    fn counted_evaluations_are_forwarded() {
+        let problem = SingleObjectiveTestProblem::new();
+        let mut state = State::new();
+        let mut population = single_test_population(&[0., 0., 0.]);
+
+        let mut cached = Cached::new(Double, 10, false);
+        cached.init(&mut state);
+        cached.evaluate(&problem, &mut state, &mut population);
+        assert_eq!(cached.counted_evaluations(3), 2);
+        cached.evaluate(&problem, &mut state, &mut population);
+        assert_eq!(cached.counted_evaluations(3), 0);
+
+        let statistics = state.borrow::<CacheStatistics>().clone();
+        assert_eq!(statistics.misses, 1);
+        assert_eq!(statistics.hits, 5);
+
+        let mut cached = Cached::new(Double, 10, true);
+        cached.evaluate(&problem, &mut state, &mut population);
+        assert_eq!(cached.counted_evaluations(3), 6);
+    }
+
+    #[test]
+    fn lru_cache_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, 'a');
+        cache.insert(2, 'b');
+        assert_eq!(cache.get(&1), Some('a'));
+        cache.insert(3, 'c');
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some('a'));
+        assert_eq!(cache.get(&3), Some('c'));
+        assert_eq!(cache.len(), 2);
+    }
+}
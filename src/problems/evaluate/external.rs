@@ -0,0 +1,494 @@
+//! Evaluate [`Individual`]s using objective functions implemented by external processes.
+//!
+//! The [`ExternalProcess`] evaluator spawns local subprocesses (e.g. Python scripts or
+//! compiled simulators), and exchanges batches of solutions and objective values with them
+//! over stdin/stdout using line-delimited JSON.
+//!
+//! # Protocol
+//!
+//! For every batch, a single line containing a request is written to the stdin of the process:
+//!
+//! ```json
+//! {"solutions": [<solution>, ...]}
+//! ```
+//!
+//! where every solution is serialized using its [`Serialize`] implementation,
+//! e.g. a `Vec<f64>` as an array of numbers.
+//!
+//! The process is expected to answer with a single line on its stdout, containing either the
+//! objective values of all solutions in the same order, or an error message:
+//!
+//! ```json
+//! {"objectives": [<objective>, ...]}
+//! {"error": "<message>"}
+//! ```
+//!
+//! where an objective is a number for [`SingleObjective`]s, and an array of numbers for
+//! [`MultiObjective`]s.
+//! The stderr of the process is inherited, and can therefore be used for debugging output.
+//!
+//! A reference implementation of the sphere function can be found in
+//! `scripts/external_sphere.py`.
+
+use std::{
+    ffi::OsString,
+    io::{self, BufRead, BufReader, Write},
+    marker::PhantomData,
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    problems::{objective::IllegalObjective, Evaluate, MultiObjective, Objective},
+    Individual, Problem, SingleObjective, State,
+};
+
+/// Trait for objective types which can be received from an [`ExternalProcess`].
+pub trait ExternalObjective: Objective {
+    /// The raw value sent by the process.
+    type Value: DeserializeOwned;
+
+    /// Converts the raw `value` into an objective value.
+    fn from_value(value: Self::Value) -> Result<Self, IllegalObjective>;
+}
+
+impl ExternalObjective for SingleObjective {
+    type Value = f64;
+
+    fn from_value(value: Self::Value) -> Result<Self, IllegalObjective> {
+        value.try_into()
+    }
+}
+
+impl ExternalObjective for MultiObjective {
+    type Value = Vec<f64>;
+
+    fn from_value(value: Self::Value) -> Result<Self, IllegalObjective> {
+        value.try_into()
+    }
+}
+
+/// Error type for failed evaluations of an [`ExternalProcess`].
+#[derive(Debug, Error)]
+pub enum ExternalProcessError {
+    /// The process could not be spawned.
+    #[error("failed to spawn the process: {0}")]
+    Spawn(io::Error),
+    /// The process exited or closed its stdin/stdout.
+    #[error("the process crashed: {0}")]
+    Crashed(String),
+    /// The process did not respond in time.
+    #[error("the process did not respond within {0:?}")]
+    Timeout(Duration),
+    /// The process sent a response which violates the protocol.
+    #[error("invalid response: {0}")]
+    Protocol(String),
+    /// The process reported an error.
+    #[error("the process reported an error: {0}")]
+    Remote(String),
+}
+
+impl ExternalProcessError {
+    /// Returns `true` if restarting the process may resolve the error.
+    pub fn is_recoverable(&self) -> bool {
+        matches!(self, Self::Crashed(_) | Self::Timeout(_))
+    }
+}
+
+#[derive(Serialize)]
+struct Request<'a, E> {
+    solutions: &'a [&'a E],
+}
+
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct Response<V: DeserializeOwned> {
+    objectives: Option<Vec<V>>,
+    error: Option<String>,
+}
+
+/// A running subprocess with a thread reading its stdout line by line.
+struct Worker {
+    child: Child,
+    stdin: ChildStdin,
+    lines: mpsc::Receiver<io::Result<String>>,
+}
+
+impl Worker {
+    fn spawn(program: &OsString, args: &[OsString]) -> Result<Self, ExternalProcessError> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(ExternalProcessError::Spawn)?;
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (sender, lines) = mpsc::channel();
+        // The thread stops when the process closes its stdout or the worker is dropped.
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    /// Returns a description of why the process stopped.
+    fn exit_reason(&mut self) -> String {
+        match self.child.try_wait() {
+            Ok(Some(status)) => format!("exited with {status}"),
+            _ => "closed its stdout".to_string(),
+        }
+    }
+
+    fn request(
+        &mut self,
+        request: &str,
+        timeout: Option<Duration>,
+    ) -> Result<String, ExternalProcessError> {
+        let written = self
+            .stdin
+            .write_all(request.as_bytes())
+            .and_then(|_| self.stdin.write_all(b"\n"))
+            .and_then(|_| self.stdin.flush());
+        if let Err(error) = written {
+            return Err(ExternalProcessError::Crashed(format!(
+                "failed to write request: {error}"
+            )));
+        }
+
+        let line = match timeout {
+            Some(timeout) => self.lines.recv_timeout(timeout).map_err(|e| match e {
+                mpsc::RecvTimeoutError::Timeout => ExternalProcessError::Timeout(timeout),
+                mpsc::RecvTimeoutError::Disconnected => {
+                    ExternalProcessError::Crashed(self.exit_reason())
+                }
+            })?,
+            None => self
+                .lines
+                .recv()
+                .map_err(|_| ExternalProcessError::Crashed(self.exit_reason()))?,
+        };
+        line.map_err(|error| ExternalProcessError::Crashed(format!("failed to read: {error}")))
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// An evaluator sending solutions to external processes for evaluation.
+///
+/// See the [module documentation](self) for the protocol.
+///
+/// The processes are spawned from `program` with `args` on the first evaluation.
+/// Every batch of individuals is split evenly between the `workers` processes,
+/// which evaluate their parts in parallel.
+///
+/// If a process crashes or does not respond within the `timeout`, it is restarted, and the
+/// request is retried up to `max_retries` times.
+///
+/// # Panics
+///
+/// As [`Evaluate`] can't fail, evaluating panics if a process can't be spawned, violates the
+/// protocol, reports an error, or still fails after `max_retries` restarts.
+///
+/// # Examples
+///
+/// Evaluating on 4 Python processes, allowing 10 seconds per batch:
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use serde::Serialize;
+/// # use mahf::{Configuration, ExecResult, SingleObjectiveProblem};
+/// use mahf::problems::evaluate::ExternalProcess;
+///
+/// # fn example<P: SingleObjectiveProblem>(config: Configuration<P>, problem: P) -> ExecResult<()>
+/// # where P::Encoding: Serialize {
+/// let mut evaluator = ExternalProcess::new("python3", ["scripts/external_sphere.py"]);
+/// evaluator.workers = 4;
+/// evaluator.timeout = Some(Duration::from_secs(10));
+///
+/// let state = config.optimize_with(&problem, |state| {
+///     state.insert_evaluator(evaluator);
+///     Ok(())
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ExternalProcess<P: Problem> {
+    program: OsString,
+    args: Vec<OsString>,
+    /// The number of processes.
+    pub workers: usize,
+    /// The maximal time to wait for a response, or `None` to wait indefinitely.
+    pub timeout: Option<Duration>,
+    /// The maximal number of restarts per request.
+    pub max_retries: u32,
+    processes: Vec<Option<Worker>>,
+    restarts: u32,
+    marker: PhantomData<fn() -> P>,
+}
+
+impl<P> ExternalProcess<P>
+where
+    P: Problem,
+    P::Encoding: Serialize,
+    P::Objective: ExternalObjective,
+{
+    /// Creates an evaluator running `program` with `args` on a single process, without timeout
+    /// and with up to 3 retries.
+    pub fn new<S, I, A>(program: S, args: I) -> Self
+    where
+        S: Into<OsString>,
+        I: IntoIterator<Item = A>,
+        A: Into<OsString>,
+    {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            workers: 1,
+            timeout: None,
+            max_retries: 3,
+            processes: Vec::new(),
+            restarts: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the total number of process restarts.
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+}
+
+/// The settings for (re)starting processes and sending requests, shared between threads.
+struct Launcher<'a, P> {
+    program: &'a OsString,
+    args: &'a [OsString],
+    timeout: Option<Duration>,
+    max_retries: u32,
+    marker: PhantomData<fn() -> P>,
+}
+
+impl<P> Launcher<'_, P>
+where
+    P: Problem,
+    P::Encoding: Serialize,
+    P::Objective: ExternalObjective,
+{
+    /// Sends the `request` for `n` solutions to the process in `slot`, (re)starting it
+    /// if necessary.
+    ///
+    /// Returns the objective values and the number of restarts.
+    fn evaluate_on(
+        &self,
+        slot: &mut Option<Worker>,
+        request: &str,
+        n: usize,
+    ) -> (Result<Vec<P::Objective>, ExternalProcessError>, u32) {
+        let mut restarts = 0;
+        loop {
+            let result = self.request_on(slot, request, n);
+            match result {
+                Err(error) if error.is_recoverable() && restarts < self.max_retries => {
+                    // Dropping the worker kills the process.
+                    *slot = None;
+                    restarts += 1;
+                }
+                result => return (result, restarts),
+            }
+        }
+    }
+
+    fn request_on(
+        &self,
+        slot: &mut Option<Worker>,
+        request: &str,
+        n: usize,
+    ) -> Result<Vec<P::Objective>, ExternalProcessError> {
+        if slot.is_none() {
+            *slot = Some(Worker::spawn(self.program, self.args)?);
+        }
+        let line = slot.as_mut().unwrap().request(request, self.timeout)?;
+
+        let response: Response<<P::Objective as ExternalObjective>::Value> =
+            serde_json::from_str(&line)
+                .map_err(|e| ExternalProcessError::Protocol(format!("{e}: {line}")))?;
+        if let Some(error) = response.error {
+            return Err(ExternalProcessError::Remote(error));
+        }
+        let objectives = response.objectives.ok_or_else(|| {
+            ExternalProcessError::Protocol("missing `objectives` or `error`".to_string())
+        })?;
+        if objectives.len() != n {
+            return Err(ExternalProcessError::Protocol(format!(
+                "expected {n} objective values, but got {}",
+                objectives.len()
+            )));
+        }
+        objectives
+            .into_iter()
+            .map(|value| {
+                P::Objective::from_value(value)
+                    .map_err(|e| ExternalProcessError::Protocol(e.to_string()))
+            })
+            .collect()
+    }
+}
+
+impl<P> Evaluate for ExternalProcess<P>
+where
+    P: Problem,
+    P::Encoding: Serialize,
+    P::Objective: ExternalObjective,
+{
+    type Problem = P;
+
+    fn evaluate(
+        &mut self,
+        _problem: &Self::Problem,
+        _state: &mut State<Self::Problem>,
+        individuals: &mut [Individual<Self::Problem>],
+    ) {
+        if individuals.is_empty() {
+            return;
+        }
+        let workers = self.workers.max(1);
+        self.processes.resize_with(workers, || None);
+
+        // Requests are serialized upfront, so solutions are not shared between threads.
+        let solutions: Vec<_> = individuals.iter().map(Individual::solution).collect();
+        let requests: Vec<_> = solutions
+            .chunks(solutions.len().div_ceil(workers))
+            .map(|solutions| {
+                let request = serde_json::to_string(&Request { solutions })
+                    .expect("failed to serialize solutions");
+                (request, solutions.len())
+            })
+            .collect();
+
+        let launcher = Launcher::<P> {
+            program: &self.program,
+            args: &self.args,
+            timeout: self.timeout,
+            max_retries: self.max_retries,
+            marker: PhantomData,
+        };
+        let launcher = &launcher;
+        let results: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .processes
+                .iter_mut()
+                .zip(&requests)
+                .map(|(slot, (request, n))| {
+                    scope.spawn(move || launcher.evaluate_on(slot, request, *n))
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let mut objectives = Vec::with_capacity(individuals.len());
+        for (result, restarts) in results {
+            self.restarts += restarts;
+            match result {
+                Ok(values) => objectives.extend(values),
+                Err(error) => panic!("external evaluation failed: {error}"),
+            }
+        }
+        for (individual, objective) in individuals.iter_mut().zip(objectives) {
+            individual.set_objective(objective);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sphere;
+
+    impl Problem for Sphere {
+        type Encoding = Vec<f64>;
+        type Objective = SingleObjective;
+
+        fn name(&self) -> &str {
+            "Sphere"
+        }
+    }
+
+    /// Returns the evaluator for the reference script.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `python3` is not available, as the reference script requires it.
+    fn reference_script(args: &[&str]) -> ExternalProcess<Sphere> {
+        let available = Command::new("python3")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        assert!(
+            available,
+            "the external evaluator tests require `python3` to run the reference script"
+        );
+        let script = concat!(env!("CARGO_MANIFEST_DIR"), "/scripts/external_sphere.py");
+        ExternalProcess::new(
+            "python3",
+            std::iter::once(script).chain(args.iter().copied()),
+        )
+    }
+
+    fn evaluate(evaluator: &mut ExternalProcess<Sphere>, solutions: &[Vec<f64>]) -> Vec<f64> {
+        let mut individuals: Vec<_> = solutions
+            .iter()
+            .cloned()
+            .map(Individual::new_unevaluated)
+            .collect();
+        evaluator.evaluate(&Sphere, &mut State::new(), &mut individuals);
+        individuals.iter().map(|i| i.objective().value()).collect()
+    }
+
+    #[test]
+    fn evaluates_batches_on_multiple_processes() {
+        let mut evaluator = reference_script(&[]);
+        evaluator.workers = 2;
+        let solutions = [vec![1., 2.], vec![0.5], vec![3.], vec![]];
+        assert_eq!(evaluate(&mut evaluator, &solutions), [5., 0.25, 9., 0.]);
+        assert_eq!(evaluator.restarts(), 0);
+    }
+
+    #[test]
+    fn restarts_crashed_processes() {
+        let mut evaluator = reference_script(&["--crash-after", "1"]);
+        assert_eq!(evaluate(&mut evaluator, &[vec![1.]]), [1.]);
+        assert_eq!(evaluate(&mut evaluator, &[vec![2.]]), [4.]);
+        assert_eq!(evaluator.restarts(), 1);
+    }
+
+    #[test]
+    fn restarts_unresponsive_processes() {
+        let mut evaluator = reference_script(&["--hang-after", "1"]);
+        evaluator.timeout = Some(Duration::from_millis(500));
+        assert_eq!(evaluate(&mut evaluator, &[vec![1.]]), [1.]);
+        assert_eq!(evaluate(&mut evaluator, &[vec![2.]]), [4.]);
+        assert_eq!(evaluator.restarts(), 1);
+    }
+}
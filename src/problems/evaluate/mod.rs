@@ -1,6 +1,10 @@
 //! Evaluate [`Individual`]s according to some objective function.
 
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
 
 use better_any::{Tid, TidAble};
 use derivative::Derivative;
@@ -8,6 +12,10 @@ use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
 
 use crate::{CustomState, Individual, Problem, State};
 
+pub mod external;
+
+pub use external::{ExternalObjective, ExternalProcess, ExternalProcessError};
+
 /// Trait for evaluating individuals, i.e. evaluate their solutions to an optimization problem.
 ///
 /// Implement [`ObjectiveFunction`] instead if the objective function does not require `&mut self`
@@ -126,6 +134,24 @@ pub trait Evaluate: Send {
         state: &mut State<Self::Problem>,
         individuals: &mut [Individual<Self::Problem>],
     );
+
+    /// Returns how many of the `n` individuals passed to the last call of [`evaluate`]
+    /// count towards the [`Evaluations`].
+    ///
+    /// Defaults to `n`, i.e. every individual counts as one evaluation.
+    ///
+    /// [`evaluate`]: Evaluate::evaluate
+    /// [`Evaluations`]: crate::state::common::Evaluations
+    fn counted_evaluations(&self, n: usize) -> usize {
+        n
+    }
+
+    /// Initializes any state the evaluator maintains, e.g. statistics.
+    ///
+    /// Called when the evaluator is inserted into the [`State`] using
+    /// [`State::insert_evaluator`], and defaults to doing nothing.
+    #[allow(unused_variables)]
+    fn init(&mut self, state: &mut State<Self::Problem>) {}
 }
 
 /// Trait for a non-mutable objective function of an optimization problem.
@@ -319,6 +345,107 @@ where
 
 impl<P: ObjectiveFunction> CustomState<'_> for Parallel<P> {}
 
+/// A pool of worker threads which evaluate individuals asynchronously.
+///
+/// Individuals are [`submit`]ted together with an identifier, and returned by [`receive`]
+/// as soon as their evaluation completed, i.e. not necessarily in the order of submission.
+/// This avoids idle workers when evaluation times differ between individuals.
+///
+/// The workers are spawned in a [`thread::Scope`], and stop as soon as the pool is dropped
+/// and they finished their current evaluation.
+///
+/// Every worker creates its own evaluator using the `evaluator` factory, and evaluates
+/// individuals one at a time on a worker-local [`State`].
+/// This means that any state the evaluator keeps, e.g. a cache, is not shared between workers.
+///
+/// [`submit`]: WorkerPool::submit
+/// [`receive`]: WorkerPool::receive
+///
+/// # Examples
+///
+/// ```
+/// # use mahf::{Individual, problems::{Evaluate, ObjectiveFunction}};
+/// use mahf::problems::evaluate::{Sequential, WorkerPool};
+///
+/// # fn example<P: ObjectiveFunction + Sync>(problem: &P, solutions: Vec<P::Encoding>) {
+/// let evaluator = || -> Box<dyn Evaluate<Problem = P>> { Box::new(Sequential::new()) };
+/// std::thread::scope(|scope| {
+///     let pool = WorkerPool::spawn(scope, problem, 4, &evaluator);
+///     let n = solutions.len();
+///     for (id, solution) in solutions.into_iter().enumerate() {
+///         pool.submit(id, Individual::new_unevaluated(solution));
+///     }
+///     for _ in 0..n {
+///         let (id, individual, evaluations) = pool.receive().unwrap();
+///         // Do something with the evaluated individual.
+///     }
+/// });
+/// # }
+/// ```
+pub struct WorkerPool<P: Problem> {
+    jobs: mpsc::Sender<(usize, Individual<P>)>,
+    results: mpsc::Receiver<(usize, Individual<P>, usize)>,
+}
+
+impl<P> WorkerPool<P>
+where
+    P: Problem + Sync,
+{
+    /// Spawns `workers` worker threads within the `scope`, each evaluating individuals
+    /// with an evaluator created by `evaluator`.
+    pub fn spawn<'scope, 'env, F>(
+        scope: &'scope thread::Scope<'scope, 'env>,
+        problem: &'scope P,
+        workers: usize,
+        evaluator: &'scope F,
+    ) -> Self
+    where
+        F: Fn() -> Box<dyn Evaluate<Problem = P>> + Sync + ?Sized,
+    {
+        let (jobs, job_receiver) = mpsc::channel::<(usize, Individual<P>)>();
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        for _ in 0..workers {
+            let job_receiver = Arc::clone(&job_receiver);
+            let result_sender = result_sender.clone();
+            scope.spawn(move || {
+                let mut evaluator = evaluator();
+                let mut state = State::new();
+                evaluator.init(&mut state);
+                loop {
+                    // The lock is released before evaluating.
+                    let job = job_receiver.lock().unwrap().recv();
+                    let Ok((id, mut individual)) = job else {
+                        break;
+                    };
+                    evaluator.evaluate(problem, &mut state, std::slice::from_mut(&mut individual));
+                    let evaluations = evaluator.counted_evaluations(1);
+                    if result_sender.send((id, individual, evaluations)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Self { jobs, results }
+    }
+
+    /// Submits the `individual` with some `id` for evaluation.
+    pub fn submit(&self, id: usize, individual: Individual<P>) {
+        // Sending only fails if all workers stopped, in which case `receive` returns `None`.
+        let _ = self.jobs.send((id, individual));
+    }
+
+    /// Blocks until the next evaluation completes and returns the evaluated individual with
+    /// its `id` and the number of evaluations it counts as (see [`Evaluate::counted_evaluations`]).
+    ///
+    /// Returns `None` if all workers stopped.
+    pub fn receive(&self) -> Option<(usize, Individual<P>, usize)> {
+        self.results.recv().ok()
+    }
+}
+
 impl<P> Default for Box<dyn Evaluate<Problem = P>>
 where
     P: ObjectiveFunction,
@@ -0,0 +1,487 @@
+//! Grammatical evolution (GE), i.e. evolving programs by mapping integer genomes through a grammar.
+//!
+//! A [`Grammar`] in Backus-Naur form (BNF) maps a genome of integer codons to a phenotype string
+//! by always expanding the leftmost non-terminal, where each codon chooses one of the
+//! productions of the non-terminal.
+//! The [`GrammaticalEvolution`] problem evaluates genomes by applying a user-defined objective
+//! function to their phenotype.
+//!
+//! # References
+//!
+//! \[1\] Michael O'Neill and Conor Ryan. 2001.
+//! Grammatical Evolution.
+//! IEEE Transactions on Evolutionary Computation 5, 4 (2001), 349–358.
+//! DOI:<https://doi.org/10.1109/4235.942529>
+
+use std::{
+    collections::HashMap,
+    fs,
+    ops::{Range, RangeInclusive},
+    path::Path,
+};
+
+use eyre::{bail, ensure, eyre, WrapErr};
+use rand::Rng;
+use serde::Serialize;
+
+use crate::{
+    component::ExecResult,
+    problems::{
+        GrammarProblem, LimitedVectorProblem, ObjectiveFunction, VariableLengthProblem,
+        VectorProblem,
+    },
+    Problem, SingleObjective,
+};
+
+/// A symbol of a production.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum Symbol {
+    /// A terminal, which is copied to the phenotype.
+    Terminal(String),
+    /// The index of the [`Rule`] of a non-terminal.
+    NonTerminal(usize),
+}
+
+/// The productions of a non-terminal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Rule {
+    /// The name of the non-terminal, without angle brackets.
+    pub name: String,
+    /// The alternative productions of the non-terminal.
+    pub productions: Vec<Vec<Symbol>>,
+}
+
+/// The result of mapping a genome through a [`Grammar`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    /// The derived phenotype.
+    pub phenotype: String,
+    /// The number of consumed codons, which exceeds the length of the genome if it was wrapped.
+    pub codons: usize,
+}
+
+/// A context-free grammar in Backus-Naur form.
+///
+/// # Examples
+///
+/// ```
+/// use mahf::problems::grammar::Grammar;
+///
+/// # fn example() -> mahf::ExecResult<()> {
+/// let grammar = Grammar::parse(
+///     "<expr> ::= <expr> <op> <expr> | x | 1
+///      <op>   ::= + | *",
+/// )?;
+/// let mapping = grammar.map(&[0, 1, 1, 2], 0).unwrap();
+/// assert_eq!(mapping.phenotype, "x*1");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct Grammar {
+    rules: Vec<Rule>,
+}
+
+impl Grammar {
+    /// Parses a grammar in BNF, where the first rule is the start symbol.
+    ///
+    /// Every rule has the form `<name> ::= production | production | ...`, and may be
+    /// continued on the following lines.
+    /// Within productions, non-terminals are enclosed in angle brackets, and all other text is
+    /// a terminal.
+    /// Unquoted whitespace only separates symbols, while text in single or double quotes is
+    /// copied verbatim, e.g. to include whitespace, `|` or `<` in terminals.
+    /// Empty lines and lines starting with `#` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the grammar is malformed, refers to undefined non-terminals, or
+    /// contains non-terminals which can't derive a string of terminals.
+    pub fn parse(source: &str) -> ExecResult<Self> {
+        // The names and right-hand sides of all rules.
+        let mut definitions: Vec<(String, String)> = Vec::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once("::=") {
+                Some((name, rhs)) => {
+                    let name = name.trim();
+                    let name = name
+                        .strip_prefix('<')
+                        .and_then(|name| name.strip_suffix('>'))
+                        .ok_or_else(|| eyre!("invalid non-terminal {name} in line {}", i + 1))?;
+                    definitions.push((name.to_string(), rhs.to_string()));
+                }
+                None => {
+                    let (_, rhs) = definitions
+                        .last_mut()
+                        .ok_or_else(|| eyre!("line {} doesn't belong to a rule", i + 1))?;
+                    rhs.push('\n');
+                    rhs.push_str(line);
+                }
+            }
+        }
+        ensure!(!definitions.is_empty(), "the grammar contains no rules");
+
+        // Rules defined multiple times are merged.
+        let mut indices = HashMap::new();
+        let mut rules = Vec::new();
+        for (name, _) in &definitions {
+            indices.entry(name.clone()).or_insert_with(|| {
+                rules.push(Rule {
+                    name: name.clone(),
+                    productions: Vec::new(),
+                });
+                rules.len() - 1
+            });
+        }
+        for (name, rhs) in &definitions {
+            let productions = Self::parse_productions(rhs, &indices)
+                .wrap_err_with(|| format!("invalid rule <{name}>"))?;
+            rules[indices[name]].productions.extend(productions);
+        }
+
+        let grammar = Self { rules };
+        grammar.validate()?;
+        Ok(grammar)
+    }
+
+    /// Reads a grammar from the file at `path`, see [`Grammar::parse`].
+    pub fn read(path: impl AsRef<Path>) -> ExecResult<Self> {
+        let path = path.as_ref();
+        let source =
+            fs::read_to_string(path).wrap_err_with(|| format!("failed to read {path:?}"))?;
+        Self::parse(&source)
+    }
+
+    fn parse_productions(
+        rhs: &str,
+        indices: &HashMap<String, usize>,
+    ) -> ExecResult<Vec<Vec<Symbol>>> {
+        let mut productions = Vec::new();
+        let mut production = Vec::new();
+        let mut terminal = String::new();
+
+        fn flush(terminal: &mut String, production: &mut Vec<Symbol>) {
+            if !terminal.is_empty() {
+                production.push(Symbol::Terminal(std::mem::take(terminal)));
+            }
+        }
+
+        let mut chars = rhs.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '<' => {
+                    flush(&mut terminal, &mut production);
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for next in chars.by_ref() {
+                        if next == '>' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(next);
+                    }
+                    ensure!(closed, "unterminated non-terminal <{name}");
+                    let index = indices
+                        .get(&name)
+                        .ok_or_else(|| eyre!("undefined non-terminal <{name}>"))?;
+                    production.push(Symbol::NonTerminal(*index));
+                }
+                '"' | '\'' => {
+                    let mut closed = false;
+                    for next in chars.by_ref() {
+                        if next == c {
+                            closed = true;
+                            break;
+                        }
+                        terminal.push(next);
+                    }
+                    ensure!(closed, "unterminated quote");
+                }
+                '|' => {
+                    flush(&mut terminal, &mut production);
+                    productions.push(std::mem::take(&mut production));
+                }
+                c if c.is_whitespace() => flush(&mut terminal, &mut production),
+                c => terminal.push(c),
+            }
+        }
+        flush(&mut terminal, &mut production);
+        productions.push(production);
+        Ok(productions)
+    }
+
+    /// Ensures that every non-terminal can derive a string of terminals,
+    /// which guarantees that mapping terminates.
+    fn validate(&self) -> ExecResult<()> {
+        let mut terminating = vec![false; self.rules.len()];
+        loop {
+            let mut changed = false;
+            for (i, rule) in self.rules.iter().enumerate() {
+                if terminating[i] {
+                    continue;
+                }
+                let terminates = rule.productions.iter().any(|production| {
+                    production.iter().all(|symbol| match symbol {
+                        Symbol::Terminal(_) => true,
+                        Symbol::NonTerminal(j) => terminating[*j],
+                    })
+                });
+                if terminates {
+                    terminating[i] = true;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        if let Some(i) = terminating.iter().position(|&t| !t) {
+            bail!(
+                "the non-terminal <{}> can't derive a string of terminals",
+                self.rules[i].name
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the start rule.
+    pub fn start(&self) -> &Rule {
+        &self.rules[0]
+    }
+
+    /// Returns all rules.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Maps the `genome` to a phenotype, reusing it from the start up to `max_wraps` times
+    /// if all codons are consumed.
+    ///
+    /// A codon is only consumed for non-terminals with more than one production, where the
+    /// production is chosen by the codon modulo the number of productions.
+    ///
+    /// Returns `None` if the genome is invalid, i.e. if the derivation is still incomplete after
+    /// all wraps.
+    pub fn map(&self, genome: &[i64], max_wraps: usize) -> Option<Mapping> {
+        let mut phenotype = String::new();
+        let mut codons = 0;
+        let mut stack = vec![Symbol::NonTerminal(0)];
+
+        while let Some(symbol) = stack.pop() {
+            let rule = match symbol {
+                Symbol::Terminal(terminal) => {
+                    phenotype.push_str(&terminal);
+                    continue;
+                }
+                Symbol::NonTerminal(index) => &self.rules[index],
+            };
+            let choice = if rule.productions.len() == 1 {
+                0
+            } else {
+                if genome.is_empty() || codons >= genome.len() * (max_wraps + 1) {
+                    return None;
+                }
+                let codon = genome[codons % genome.len()];
+                codons += 1;
+                codon.rem_euclid(rule.productions.len() as i64) as usize
+            };
+            stack.extend(rule.productions[choice].iter().rev().cloned());
+        }
+
+        Some(Mapping { phenotype, codons })
+    }
+}
+
+/// A problem mapping integer genomes through a [`Grammar`] before evaluating the phenotype
+/// with a user-defined objective function, which is minimized.
+///
+/// Invalid genomes, i.e. genomes which don't map to a complete phenotype, receive the worst
+/// possible objective value.
+///
+/// # Examples
+///
+/// Finding an expression evaluating to 10:
+///
+/// ```
+/// use mahf::problems::grammar::{Grammar, GrammaticalEvolution};
+///
+/// # fn eval(expression: &str) -> f64 { unimplemented!() }
+/// # fn example() -> mahf::ExecResult<()> {
+/// let grammar = Grammar::parse(
+///     "<expr> ::= <expr> <op> <expr> | <digit>
+///      <op>   ::= + | - | *
+///      <digit> ::= 1 | 2 | 3",
+/// )?;
+/// let mut problem = GrammaticalEvolution::new(grammar, |phenotype| (eval(phenotype) - 10.).abs());
+/// problem.genome_length = 50;
+/// # Ok(())
+/// # }
+/// ```
+pub struct GrammaticalEvolution<F> {
+    grammar: Grammar,
+    objective: F,
+    /// The length of the genomes created by initialization.
+    pub genome_length: usize,
+    /// The maximal length of genomes, see [`VariableLengthProblem::lengths`].
+    pub max_genome_length: usize,
+    /// The range of codon values.
+    pub codons: Range<i64>,
+    /// The maximal number of wraps during mapping.
+    pub max_wraps: usize,
+}
+
+impl<F> GrammaticalEvolution<F>
+where
+    F: Fn(&str) -> f64 + 'static,
+{
+    /// Creates the problem with genomes of length 100 (at most 1000), codons in `[0, 256)`,
+    /// and at most 2 wraps.
+    pub fn new(grammar: Grammar, objective: F) -> Self {
+        Self {
+            grammar,
+            objective,
+            genome_length: 100,
+            max_genome_length: 1000,
+            codons: 0..256,
+            max_wraps: 2,
+        }
+    }
+
+    /// Returns the phenotype of the `genome`, or `None` if the genome is invalid.
+    pub fn phenotype(&self, genome: &[i64]) -> Option<String> {
+        self.grammar
+            .map(genome, self.max_wraps)
+            .map(|mapping| mapping.phenotype)
+    }
+}
+
+impl<F> Problem for GrammaticalEvolution<F>
+where
+    F: Fn(&str) -> f64 + 'static,
+{
+    type Encoding = Vec<i64>;
+    type Objective = SingleObjective;
+
+    fn name(&self) -> &str {
+        "GrammaticalEvolution"
+    }
+}
+
+impl<F> VectorProblem for GrammaticalEvolution<F>
+where
+    F: Fn(&str) -> f64 + 'static,
+{
+    type Element = i64;
+
+    fn dimension(&self) -> usize {
+        self.genome_length
+    }
+}
+
+impl<F> LimitedVectorProblem for GrammaticalEvolution<F>
+where
+    F: Fn(&str) -> f64 + 'static,
+{
+    fn domain(&self) -> Vec<Range<Self::Element>> {
+        vec![self.codons.clone(); self.genome_length]
+    }
+}
+
+impl<F> VariableLengthProblem for GrammaticalEvolution<F>
+where
+    F: Fn(&str) -> f64 + 'static,
+{
+    fn lengths(&self) -> RangeInclusive<usize> {
+        1..=self.max_genome_length
+    }
+
+    fn random_element(&self, rng: &mut impl Rng) -> Self::Element {
+        rng.gen_range(self.codons.clone())
+    }
+}
+
+impl<F> GrammarProblem for GrammaticalEvolution<F>
+where
+    F: Fn(&str) -> f64 + 'static,
+{
+    fn grammar(&self) -> &Grammar {
+        &self.grammar
+    }
+
+    fn codons(&self) -> Range<i64> {
+        self.codons.clone()
+    }
+
+    fn max_wraps(&self) -> usize {
+        self.max_wraps
+    }
+}
+
+impl<F> ObjectiveFunction for GrammaticalEvolution<F>
+where
+    F: Fn(&str) -> f64 + 'static,
+{
+    fn objective(&self, solution: &Self::Encoding) -> Self::Objective {
+        let value = self
+            .phenotype(solution)
+            .map_or(f64::INFINITY, |phenotype| (self.objective)(&phenotype));
+        // NaN is treated as the worst possible objective value.
+        if value.is_nan() { f64::INFINITY } else { value }
+            .try_into()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    const GRAMMAR: &str = "
+        # Arithmetic expressions.
+        <expr> ::= <expr> <op> <expr>
+                 | '(' <expr> ')'
+                 | <var>
+        <op>   ::= '+' | ' * '
+        <var>  ::= x";
+
+    #[test_case(&[2], 0, Some("x"); "single codon")]
+    #[test_case(&[0, 2, 1, 2], 0, Some("x * x"); "binary operator")]
+    #[test_case(&[1, 2], 0, Some("(x)"); "parentheses")]
+    #[test_case(&[0, 2], 1, Some("x+x"); "wrapping")]
+    #[test_case(&[0, 2], 0, None; "incomplete")]
+    #[test_case(&[], 2, None; "empty")]
+    fn maps_genomes_to_phenotypes(genome: &[i64], max_wraps: usize, expected: Option<&str>) {
+        let grammar = Grammar::parse(GRAMMAR).unwrap();
+        let mapping = grammar.map(genome, max_wraps);
+        assert_eq!(mapping.map(|m| m.phenotype).as_deref(), expected);
+    }
+
+    #[test_case("<a> ::= <b>"; "undefined non-terminal")]
+    #[test_case("<a> ::= <a> x"; "non-terminating")]
+    #[test_case("| x"; "missing rule")]
+    #[test_case("<a> ::= x | <a"; "unterminated non-terminal")]
+    #[test_case("<a> ::= 'x"; "unterminated quote")]
+    fn rejects_invalid_grammars(source: &str) {
+        assert!(Grammar::parse(source).is_err());
+    }
+
+    #[test]
+    fn grammatical_evolution_is_a_variable_length_problem() {
+        let grammar = Grammar::parse(GRAMMAR).unwrap();
+        let mut problem = GrammaticalEvolution::new(grammar, |phenotype| phenotype.len() as f64);
+        problem.codons = 5..7;
+        problem.max_genome_length = 8;
+
+        assert_eq!(problem.lengths(), 1..=8);
+        let mut rng = crate::Random::new(0);
+        assert!((0..100).all(|_| (5..7).contains(&problem.random_element(&mut rng))));
+        assert_eq!(problem.objective(&vec![2]).value(), 1.);
+        assert_eq!(problem.objective(&vec![0]).value(), f64::INFINITY);
+    }
+}
@@ -0,0 +1,142 @@
+//! The 0-1 knapsack problem, i.e. choosing a subset of items with maximal value
+//! whose total weight doesn't exceed the capacity of the knapsack.
+
+use std::collections::BTreeSet;
+
+use eyre::ensure;
+
+use crate::{
+    component::ExecResult,
+    problems::{ObjectiveFunction, SubsetProblem},
+    Problem, SingleObjective,
+};
+
+/// The 0-1 knapsack problem.
+///
+/// As optimizing means minimizing, the objective value of a feasible subset is its negated
+/// total value.
+/// Infeasible subsets are penalized with their excess weight, which makes every feasible subset
+/// better than every infeasible one.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeSet;
+///
+/// use mahf::problems::{knapsack::Knapsack, ObjectiveFunction};
+///
+/// # fn example() -> mahf::ExecResult<()> {
+/// let problem = Knapsack::new(vec![2., 3., 4.], vec![3., 4., 6.], 5.)?;
+/// assert_eq!(problem.objective(&BTreeSet::from([0, 1])).value(), -7.);
+/// assert_eq!(problem.objective(&BTreeSet::from([1, 2])).value(), 2.);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Knapsack {
+    weights: Vec<f64>,
+    values: Vec<f64>,
+    capacity: f64,
+}
+
+impl Knapsack {
+    /// Creates a knapsack problem with the `weights` and `values` of all items.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the number of weights and values differ, or if any weight, value,
+    /// or the capacity is negative or not finite.
+    pub fn new(weights: Vec<f64>, values: Vec<f64>, capacity: f64) -> ExecResult<Self> {
+        ensure!(
+            weights.len() == values.len(),
+            "the number of weights and values must be equal"
+        );
+        ensure!(
+            weights.iter().all(|&w| w.is_finite() && w >= 0.),
+            "weights must be finite and not negative"
+        );
+        ensure!(
+            values.iter().all(|&v| v.is_finite() && v >= 0.),
+            "values must be finite and not negative"
+        );
+        ensure!(
+            capacity.is_finite() && capacity >= 0.,
+            "the capacity must be finite and not negative"
+        );
+        Ok(Self {
+            weights,
+            values,
+            capacity,
+        })
+    }
+
+    /// Returns the weights of all items.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Returns the values of all items.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the capacity of the knapsack.
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+}
+
+impl Problem for Knapsack {
+    type Encoding = BTreeSet<usize>;
+    type Objective = SingleObjective;
+
+    fn name(&self) -> &str {
+        "Knapsack"
+    }
+}
+
+impl SubsetProblem for Knapsack {
+    fn items(&self) -> usize {
+        self.weights.len()
+    }
+}
+
+impl ObjectiveFunction for Knapsack {
+    fn objective(&self, solution: &Self::Encoding) -> Self::Objective {
+        let weight: f64 = solution.iter().map(|&i| self.weights[i]).sum();
+        let value: f64 = solution.iter().map(|&i| self.values[i]).sum();
+        if weight <= self.capacity {
+            -value
+        } else {
+            weight - self.capacity
+        }
+        .try_into()
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test]
+    fn objective_penalizes_excess_weight() {
+        let problem = Knapsack::new(vec![2., 3., 4.], vec![3., 4., 6.], 5.).unwrap();
+        assert_eq!(problem.objective(&BTreeSet::new()).value(), 0.);
+        assert_eq!(problem.objective(&BTreeSet::from([0, 1])).value(), -7.);
+        assert_eq!(problem.objective(&BTreeSet::from([0, 1, 2])).value(), 4.);
+    }
+
+    #[test_case(vec![1., 2.], vec![1.], 1.; "when lengths differ")]
+    #[test_case(vec![1., -2.], vec![1., 2.], 1.; "when weight is negative")]
+    #[test_case(vec![1., f64::NAN], vec![1., 2.], 1.; "when weight is nan")]
+    #[test_case(vec![1., 2.], vec![-1., 2.], 1.; "when value is negative")]
+    #[test_case(vec![1., 2.], vec![1., f64::INFINITY], 1.; "when value is infinite")]
+    #[test_case(vec![1., 2.], vec![1., 2.], -1.; "when capacity is negative")]
+    #[test_case(vec![1., 2.], vec![1., 2.], f64::NAN; "when capacity is nan")]
+    fn new_rejects_invalid_items(weights: Vec<f64>, values: Vec<f64>, capacity: f64) {
+        assert!(Knapsack::new(weights, values, capacity).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Mixed-variable encodings with continuous, integer, and categorical variables.
+//!
+//! A solution to a [`MixedVectorProblem`] is a vector of [`MixedValue`]s, where the type and
+//! bounds of every dimension are described by a [`MixedVariable`].
+//!
+//! Variables with an empty domain can't be sampled, and are rejected by the mixed operators
+//! on initialization, see [`validate_variables`].
+//!
+//! [`MixedVectorProblem`]: crate::problems::MixedVectorProblem
+
+use std::ops::{Range, RangeInclusive};
+
+use eyre::{ensure, WrapErr};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::ExecResult;
+
+/// The type and bounds of a single variable of a [`MixedVectorProblem`].
+///
+/// [`MixedVectorProblem`]: crate::problems::MixedVectorProblem
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MixedVariable {
+    /// A real-valued variable within the range.
+    Continuous(Range<f64>),
+    /// An integer variable within the (inclusive) range.
+    Integer(RangeInclusive<i64>),
+    /// A categorical variable with the given number of unordered categories.
+    Categorical(usize),
+}
+
+impl MixedVariable {
+    /// Creates a real-valued variable within the `range`, which must not be empty.
+    pub fn continuous(range: Range<f64>) -> ExecResult<Self> {
+        let variable = Self::Continuous(range);
+        variable.validate()?;
+        Ok(variable)
+    }
+
+    /// Creates an integer variable within the inclusive `range`, which must not be empty.
+    pub fn integer(range: RangeInclusive<i64>) -> ExecResult<Self> {
+        let variable = Self::Integer(range);
+        variable.validate()?;
+        Ok(variable)
+    }
+
+    /// Creates a categorical variable with `n` categories, where `n` must be greater than 0.
+    pub fn categorical(n: usize) -> ExecResult<Self> {
+        let variable = Self::Categorical(n);
+        variable.validate()?;
+        Ok(variable)
+    }
+
+    /// Returns an `Err` if the domain of the variable is empty.
+    pub fn validate(&self) -> ExecResult<()> {
+        match self {
+            Self::Continuous(range) => ensure!(
+                range.start < range.end,
+                "the range {range:?} of a continuous variable must not be empty"
+            ),
+            Self::Integer(range) => ensure!(
+                !range.is_empty(),
+                "the range {range:?} of an integer variable must not be empty"
+            ),
+            Self::Categorical(n) => ensure!(
+                *n > 0,
+                "a categorical variable must have at least one category"
+            ),
+        }
+        Ok(())
+    }
+
+    /// Samples a value uniformly from the domain of the variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the domain is empty, see [`validate`].
+    ///
+    /// [`validate`]: MixedVariable::validate
+    pub fn sample(&self, rng: &mut impl Rng) -> MixedValue {
+        match self {
+            Self::Continuous(range) => MixedValue::Continuous(rng.gen_range(range.clone())),
+            Self::Integer(range) => MixedValue::Integer(rng.gen_range(range.clone())),
+            Self::Categorical(n) => MixedValue::Categorical(rng.gen_range(0..*n)),
+        }
+    }
+
+    /// Returns `true` if the `value` has the type of the variable and lies within its bounds.
+    pub fn contains(&self, value: &MixedValue) -> bool {
+        match (self, value) {
+            (Self::Continuous(range), MixedValue::Continuous(x)) => {
+                range.start <= *x && *x <= range.end
+            }
+            (Self::Integer(range), MixedValue::Integer(x)) => range.contains(x),
+            (Self::Categorical(n), MixedValue::Categorical(x)) => x < n,
+            _ => false,
+        }
+    }
+
+    /// Converts the `value` to the type of the variable and clamps it to its bounds.
+    ///
+    /// Real values are rounded to the nearest integer or category.
+    pub fn clamp(&self, value: MixedValue) -> MixedValue {
+        match self {
+            Self::Continuous(range) => {
+                MixedValue::Continuous(value.to_f64().clamp(range.start, range.end))
+            }
+            Self::Integer(range) => MixedValue::Integer(
+                (value.to_f64().round() as i64).clamp(*range.start(), *range.end()),
+            ),
+            Self::Categorical(n) => MixedValue::Categorical(
+                (value.to_f64().round().max(0.) as usize).min(n.saturating_sub(1)),
+            ),
+        }
+    }
+}
+
+/// Returns an `Err` if the domain of any of the `variables` is empty.
+pub fn validate_variables(variables: &[MixedVariable]) -> ExecResult<()> {
+    for (i, variable) in variables.iter().enumerate() {
+        variable
+            .validate()
+            .wrap_err_with(|| format!("invalid variable {i}"))?;
+    }
+    Ok(())
+}
+
+/// The value of a single variable of a [`MixedVectorProblem`].
+///
+/// [`MixedVectorProblem`]: crate::problems::MixedVectorProblem
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MixedValue {
+    /// The value of a [`MixedVariable::Continuous`] variable.
+    Continuous(f64),
+    /// The value of a [`MixedVariable::Integer`] variable.
+    Integer(i64),
+    /// The index of the category of a [`MixedVariable::Categorical`] variable.
+    Categorical(usize),
+}
+
+impl MixedValue {
+    /// Returns the value as `f64`, where categories are represented by their index.
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Self::Continuous(x) => x,
+            Self::Integer(x) => x as f64,
+            Self::Categorical(x) => x as f64,
+        }
+    }
+
+    /// Returns the real value, or `None` if the value is not continuous.
+    pub fn as_continuous(self) -> Option<f64> {
+        match self {
+            Self::Continuous(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer value, or `None` if the value is not an integer.
+    pub fn as_integer(self) -> Option<i64> {
+        match self {
+            Self::Integer(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Returns the category index, or `None` if the value is not categorical.
+    pub fn as_categorical(self) -> Option<usize> {
+        match self {
+            Self::Categorical(x) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(MixedVariable::Continuous(-1.0..1.0), MixedValue::Continuous(2.5), MixedValue::Continuous(1.0); "continuous")]
+    #[test_case(MixedVariable::Integer(0..=10), MixedValue::Continuous(3.6), MixedValue::Integer(4); "integer from real")]
+    #[test_case(MixedVariable::Integer(0..=10), MixedValue::Integer(-3), MixedValue::Integer(0); "integer")]
+    #[test_case(MixedVariable::Categorical(3), MixedValue::Categorical(7), MixedValue::Categorical(2); "categorical")]
+    fn clamp_converts_into_domain(
+        variable: MixedVariable,
+        value: MixedValue,
+        expected: MixedValue,
+    ) {
+        let clamped = variable.clamp(value);
+        assert_eq!(clamped, expected);
+        assert!(variable.contains(&clamped));
+    }
+
+    #[test_case(MixedVariable::continuous(1.0..1.0); "continuous")]
+    #[test_case(MixedVariable::continuous(f64::NAN..1.0); "continuous nan")]
+    #[test_case(MixedVariable::integer(RangeInclusive::new(3, 2)); "integer")]
+    #[test_case(MixedVariable::categorical(0); "categorical")]
+    fn empty_domains_are_rejected(variable: ExecResult<MixedVariable>) {
+        assert!(variable.is_err());
+    }
+
+    #[test]
+    fn invalid_variables_are_reported() {
+        let valid = vec![MixedVariable::Integer(0..=0), MixedVariable::Categorical(1)];
+        assert!(validate_variables(&valid).is_ok());
+
+        let invalid = vec![MixedVariable::Integer(0..=0), MixedVariable::Categorical(0)];
+        let err = validate_variables(&invalid).unwrap_err();
+        assert!(err.to_string().contains("variable 1"));
+    }
+}
@@ -45,19 +45,33 @@
 //! You can similarly define own traits based on [`Problem`] to allow your custom components
 //! to access any problem-specific information.
 
-use std::ops::Range;
+use std::{
+    collections::BTreeSet,
+    ops::{Range, RangeInclusive},
+};
 
+use rand::Rng;
 use trait_set::trait_set;
 
+pub mod cache;
 pub mod encoding;
 pub mod evaluate;
+pub mod grammar;
 pub mod individual;
+pub mod knapsack;
+pub mod mixed;
+pub mod noise;
 pub mod objective;
+pub mod symbolic_regression;
+pub mod tree;
 
 pub use encoding::AnyEncoding;
-pub use evaluate::{Evaluate, ObjectiveFunction, Parallel, Sequential};
+pub use evaluate::{Evaluate, ObjectiveFunction, Parallel, Sequential, WorkerPool};
+pub use grammar::Grammar;
 pub use individual::Individual;
+pub use mixed::{MixedValue, MixedVariable};
 pub use objective::{MultiObjective, Objective, SingleObjective};
+pub use tree::{PrimitiveSet, Tree};
 
 /// An optimization (minimization) problem.
 ///
@@ -226,6 +240,110 @@ pub trait LimitedVectorProblem: VectorProblem {
     fn domain(&self) -> Vec<Range<Self::Element>>;
 }
 
+/// A vector-based optimization problem with heterogeneous variables.
+///
+/// Every dimension is either continuous, integer or categorical, as described by the
+/// [`MixedVariable`] of the dimension, and solutions are encoded as vectors of [`MixedValue`]s.
+///
+/// # Examples
+///
+/// A problem with a real-valued, an integer, and a categorical variable:
+///
+/// ```
+/// use mahf::{
+///     problems::{MixedValue, MixedVariable, MixedVectorProblem, VectorProblem},
+///     Problem, SingleObjective,
+/// };
+///
+/// pub struct Example;
+///
+/// impl Problem for Example {
+///     type Encoding = Vec<MixedValue>;
+///     type Objective = SingleObjective;
+///
+///     fn name(&self) -> &str {
+///         "Example"
+///     }
+/// }
+///
+/// impl VectorProblem for Example {
+///     type Element = MixedValue;
+///
+///     fn dimension(&self) -> usize {
+///         3
+///     }
+/// }
+///
+/// impl MixedVectorProblem for Example {
+///     fn variables(&self) -> Vec<MixedVariable> {
+///         vec![
+///             MixedVariable::Continuous(-1.0..1.0),
+///             MixedVariable::Integer(0..=10),
+///             MixedVariable::Categorical(4),
+///         ]
+///     }
+/// }
+/// ```
+pub trait MixedVectorProblem: VectorProblem<Element = MixedValue> {
+    /// Returns the type and bounds of every variable, i.e. [`VectorProblem::dimension`]
+    /// many variables.
+    fn variables(&self) -> Vec<MixedVariable>;
+}
+
+/// An optimization problem whose solutions are programs encoded as [`Tree`]s,
+/// e.g. for genetic programming.
+///
+/// See the [`tree`] module for more information.
+pub trait TreeProblem: Problem<Encoding = Tree> {
+    /// Returns the functions and terminals trees are built from.
+    fn primitives(&self) -> &PrimitiveSet;
+
+    /// Returns the maximum depth of trees, where a single terminal has depth 0.
+    fn max_depth(&self) -> usize;
+}
+
+/// An optimization problem whose solutions are vectors of varying length.
+///
+/// [`VectorProblem::dimension`] is the typical length of solutions, e.g. of the optimum,
+/// while solutions may have any length within [`VariableLengthProblem::lengths`].
+pub trait VariableLengthProblem: VectorProblem {
+    /// Returns the allowed lengths of solutions.
+    fn lengths(&self) -> RangeInclusive<usize>;
+
+    /// Samples a random element, e.g. for inserting it into a solution.
+    fn random_element(&self, rng: &mut impl Rng) -> Self::Element;
+}
+
+/// An optimization problem whose solutions are subsets of the items `0..items()`,
+/// e.g. feature selection or knapsack problems.
+pub trait SubsetProblem: Problem<Encoding = BTreeSet<usize>> {
+    /// Returns the number of items.
+    fn items(&self) -> usize;
+
+    /// Returns the allowed sizes of subsets, which are unrestricted by default.
+    fn sizes(&self) -> RangeInclusive<usize> {
+        0..=self.items()
+    }
+}
+
+/// An optimization problem whose solutions are integer genomes mapped through a [`Grammar`],
+/// i.e. grammatical evolution.
+///
+/// Genomes may have any length, where [`VectorProblem::dimension`] is the length of newly
+/// created genomes.
+///
+/// See the [`grammar`] module for more information.
+pub trait GrammarProblem: LimitedVectorProblem<Element = i64> {
+    /// Returns the grammar genomes are mapped through.
+    fn grammar(&self) -> &Grammar;
+
+    /// Returns the range of codon values.
+    fn codons(&self) -> Range<i64>;
+
+    /// Returns the maximal number of times a genome is reused from the start during mapping.
+    fn max_wraps(&self) -> usize;
+}
+
 /// A single-objective optimization problem with a known optimum value.
 ///
 /// # Examples
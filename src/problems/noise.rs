@@ -0,0 +1,338 @@
+//! Repeated sampling of noisy objective functions.
+//!
+//! On noisy problems, a single evaluation is only a sample of the (unknown) expected objective
+//! value of a solution, and comparing individuals by single samples favors lucky ones.
+//! The [`Resampling`] evaluator evaluates every solution multiple times, and stores the
+//! aggregated [`Samples`] per solution in a [`SampleArchive`], which can then be used by
+//! noise-aware components (see [`components::noise`]).
+//!
+//! [`components::noise`]: crate::components::noise
+
+use std::collections::VecDeque;
+
+use better_any::{Tid, TidAble};
+use eyre::ensure;
+use serde::Serialize;
+
+use crate::{
+    problems::{Evaluate, SingleObjectiveProblem},
+    CustomState, ExecResult, Individual, Problem, SingleObjective, State,
+};
+
+/// Running statistics of the objective value samples of a single solution.
+///
+/// The mean and variance are updated incrementally using Welford's algorithm.
+#[derive(Clone, Copy, Debug, Default, Serialize, PartialEq)]
+pub struct Samples {
+    n: u32,
+    mean: f64,
+    m2: f64,
+}
+
+impl Samples {
+    /// Creates empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sample `value`.
+    pub fn push(&mut self, value: f64) {
+        self.n += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.n as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Returns the number of samples.
+    pub fn len(&self) -> u32 {
+        self.n
+    }
+
+    /// Returns `true` if there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Returns the sample mean.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the (unbiased) sample variance, or `None` if there are less than two samples.
+    pub fn variance(&self) -> Option<f64> {
+        (self.n >= 2).then(|| self.m2 / (self.n - 1) as f64)
+    }
+
+    /// Returns the standard error of the mean, or `None` if there are less than two samples.
+    pub fn std_error(&self) -> Option<f64> {
+        self.variance().map(|v| (v / self.n as f64).sqrt())
+    }
+
+    /// Returns the confidence interval `mean ± z * std_error` of the mean.
+    ///
+    /// With less than two samples, the interval is infinitely wide.
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        match self.std_error() {
+            Some(se) => (self.mean - z * se, self.mean + z * se),
+            None => (f64::NEG_INFINITY, f64::INFINITY),
+        }
+    }
+
+    /// Returns the mean as objective value, or `None` if the mean is not a legal objective value.
+    pub fn objective(&self) -> Option<SingleObjective> {
+        if self.is_empty() {
+            None
+        } else {
+            self.mean.try_into().ok()
+        }
+    }
+}
+
+/// Archive of the [`Samples`] of recently evaluated solutions.
+///
+/// Solutions are looked up by equality, and at most `capacity` solutions are stored,
+/// where the oldest solution is removed first.
+#[derive(Tid)]
+pub struct SampleArchive<P: Problem + 'static> {
+    capacity: usize,
+    entries: VecDeque<(P::Encoding, Samples)>,
+}
+
+impl<P: Problem> SampleArchive<P> {
+    /// Creates an empty archive storing at most `capacity` solutions.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Returns the maximal number of stored solutions.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of stored solutions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the archive is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the samples of the `solution`, if present.
+    pub fn get(&self, solution: &P::Encoding) -> Option<&Samples> {
+        self.entries
+            .iter()
+            .find(|(s, _)| s == solution)
+            .map(|(_, samples)| samples)
+    }
+
+    /// Adds the sample `value` of the `solution`, returning its updated samples.
+    pub fn record(&mut self, solution: &P::Encoding, value: f64) -> Samples {
+        let samples =
+            if let Some((_, samples)) = self.entries.iter_mut().find(|(s, _)| s == solution) {
+                samples
+            } else {
+                if self.entries.len() >= self.capacity {
+                    self.entries.pop_front();
+                }
+                self.entries.push_back((solution.clone(), Samples::new()));
+                &mut self.entries.back_mut().unwrap().1
+            };
+        samples.push(value);
+        *samples
+    }
+
+    /// Returns an iterator over all stored solutions and their samples.
+    pub fn iter(&self) -> impl Iterator<Item = &(P::Encoding, Samples)> {
+        self.entries.iter()
+    }
+}
+
+impl<P: Problem> Default for SampleArchive<P> {
+    /// Creates an empty archive storing at most 1000 solutions.
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl<P: Problem> CustomState<'_> for SampleArchive<P> {}
+
+/// An evaluator evaluating every solution `samples` times using the `inner` evaluator.
+///
+/// The samples are accumulated in the [`SampleArchive`], which is inserted into the [`State`]
+/// together with the evaluator, and the objective value of an individual is set to the mean of all
+/// samples of its solution so far, i.e. also including samples from previous evaluations.
+///
+/// Every sample counts towards the [`Evaluations`], as counted by the `inner` evaluator.
+///
+/// [`Evaluations`]: crate::state::common::Evaluations
+///
+/// # Examples
+///
+/// Averaging over 5 samples per evaluation, remembering the samples of up to 1000 solutions:
+///
+/// ```
+/// # use mahf::{problems::ObjectiveFunction, Configuration, ExecResult, SingleObjectiveProblem};
+/// use mahf::problems::{noise::Resampling, Sequential};
+///
+/// # fn example<P: SingleObjectiveProblem + ObjectiveFunction>(config: Configuration<P>, problem: P) -> ExecResult<()> {
+/// let state = config.optimize_with(&problem, |state| {
+///     state.insert_evaluator(Resampling::from_params(Sequential::new(), 5, 1000)?);
+///     Ok(())
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Resampling<E: Evaluate> {
+    inner: E,
+    samples: usize,
+    capacity: usize,
+}
+
+impl<E> Resampling<E>
+where
+    E: Evaluate,
+    E::Problem: SingleObjectiveProblem,
+{
+    /// Wraps the `inner` evaluator, evaluating every solution `samples` times and storing the
+    /// samples of up to `capacity` solutions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if `samples` or `capacity` is 0.
+    pub fn from_params(inner: E, samples: usize, capacity: usize) -> ExecResult<Self> {
+        ensure!(samples > 0, "`samples` must be greater than 0");
+        ensure!(capacity > 0, "`capacity` must be greater than 0");
+        Ok(Self {
+            inner,
+            samples,
+            capacity,
+        })
+    }
+}
+
+impl<E> Evaluate for Resampling<E>
+where
+    E: Evaluate,
+    E::Problem: SingleObjectiveProblem,
+{
+    type Problem = E::Problem;
+
+    fn evaluate(
+        &mut self,
+        problem: &Self::Problem,
+        state: &mut State<Self::Problem>,
+        individuals: &mut [Individual<Self::Problem>],
+    ) {
+        let mut copies: Vec<_> = individuals
+            .iter()
+            .flat_map(|i| std::iter::repeat_n(i.solution(), self.samples))
+            .map(|solution| Individual::new_unevaluated(solution.clone()))
+            .collect();
+        self.inner.evaluate(problem, state, &mut copies);
+
+        // The archive is inserted on `init`, but may have been removed since.
+        let capacity = self.capacity;
+        let mut archive = state
+            .entry::<SampleArchive<Self::Problem>>()
+            .or_insert_with(|| SampleArchive::new(capacity));
+
+        for (individual, copies) in individuals.iter_mut().zip(copies.chunks(self.samples)) {
+            let mut samples = Samples::new();
+            for copy in copies {
+                samples = archive.record(copy.solution(), copy.objective().value());
+            }
+            // Fall back to the last sample if the mean is not a legal objective value.
+            let objective = samples
+                .objective()
+                .unwrap_or_else(|| *copies.last().unwrap().objective());
+            individual.set_objective(objective);
+        }
+    }
+
+    fn counted_evaluations(&self, n: usize) -> usize {
+        self.inner.counted_evaluations(n * self.samples)
+    }
+
+    fn init(&mut self, state: &mut State<Self::Problem>) {
+        self.inner.init(state);
+        state.insert(SampleArchive::<Self::Problem>::new(self.capacity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+
+    use super::*;
+    use crate::{problems::Sequential, testing::*};
+
+    #[test]
+    fn samples_match_batch_statistics() {
+        let values = [1., 4., 2., 8., 5.];
+        let mut samples = Samples::new();
+        for value in values {
+            samples.push(value);
+        }
+        let mean = values.iter().sum::<f64>() / 5.;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / 4.;
+
+        assert_eq!(samples.len(), 5);
+        assert_float_eq!(samples.mean(), mean, abs <= 1e-12);
+        assert_float_eq!(samples.variance().unwrap(), variance, abs <= 1e-12);
+    }
+
+    #[test]
+    fn archive_evicts_oldest_solution() {
+        let mut archive = SampleArchive::<IntegerTestProblem>::new(2);
+        archive.record(&vec![1], 1.);
+        archive.record(&vec![2], 2.);
+        archive.record(&vec![1], 3.);
+        archive.record(&vec![3], 4.);
+
+        assert_eq!(archive.len(), 2);
+        assert!(archive.get(&vec![1]).is_none());
+        assert_eq!(archive.get(&vec![2]).unwrap().len(), 1);
+        assert_eq!(archive.get(&vec![3]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn optimize_inserts_archive_with_capacity() {
+        let config = crate::Configuration::builder().build();
+        let resampling = Resampling::from_params(Sequential::new(), 3, 7).unwrap();
+        let state = config
+            .optimize(&IntegerTestProblem(vec![0..10; 2]), resampling)
+            .unwrap();
+        let archive = state.borrow::<SampleArchive<IntegerTestProblem>>();
+        assert_eq!(archive.capacity(), 7);
+    }
+
+    #[test]
+    fn resampling_rejects_invalid_params() {
+        let inner = || Sequential::<IntegerTestProblem>::new();
+        assert!(Resampling::from_params(inner(), 0, 10).is_err());
+        assert!(Resampling::from_params(inner(), 3, 0).is_err());
+        assert!(Resampling::from_params(inner(), 3, 10).is_ok());
+    }
+
+    #[test]
+    fn resampling_inserts_archive_and_counts_every_sample() {
+        let problem = IntegerTestProblem(vec![0..10; 2]);
+        let mut state = integer_test_state(Vec::new());
+        let mut resampling = Resampling::from_params(Sequential::new(), 3, 10).unwrap();
+        resampling.init(&mut state);
+        assert!(state.contains::<SampleArchive<IntegerTestProblem>>());
+        assert_eq!(resampling.counted_evaluations(2), 6);
+
+        let mut individuals = vec![Individual::new_unevaluated(vec![1, 2])];
+        resampling.evaluate(&problem, &mut state, &mut individuals);
+        assert_eq!(individuals[0].objective().value(), 3.);
+
+        let archive = state.borrow::<SampleArchive<IntegerTestProblem>>();
+        assert_eq!(archive.get(&vec![1, 2]).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,298 @@
+//! Symbolic regression, i.e. finding a mathematical expression fitting a dataset.
+//!
+//! The [`SymbolicRegression`] problem evaluates [`Tree`]s built from arithmetic functions,
+//! the input variables of a [`Dataset`], and ephemeral random constants by their mean squared
+//! error on the dataset.
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use eyre::{ensure, WrapErr};
+
+use crate::{
+    component::ExecResult,
+    problems::{
+        tree::{Primitive, PrimitiveSet, Tree, ValueType},
+        ObjectiveFunction, TreeProblem,
+    },
+    Problem, SingleObjective,
+};
+
+/// A regression dataset of input rows and target values.
+#[derive(Clone, Debug)]
+pub struct Dataset {
+    /// The names of the input variables.
+    pub names: Vec<String>,
+    /// The input variables of every sample.
+    pub inputs: Vec<Vec<f64>>,
+    /// The target value of every sample.
+    pub targets: Vec<f64>,
+}
+
+impl Dataset {
+    /// Reads a dataset from comma-separated values, where the last column is the target.
+    ///
+    /// If the first line can't be parsed as numbers, it is used as the header containing the
+    /// variable names, which are `x0`, `x1`, ... otherwise.
+    /// Empty lines are skipped.
+    pub fn from_csv(reader: impl BufRead) -> ExecResult<Self> {
+        let mut names = None;
+        let mut inputs = Vec::new();
+        let mut targets = Vec::new();
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.wrap_err("failed to read line")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<_> = line.split(',').map(str::trim).collect();
+            let values: Result<Vec<f64>, _> = fields.iter().map(|f| f.parse()).collect();
+            let mut values = match values {
+                Ok(values) => values,
+                Err(_) if i == 0 => {
+                    names = Some(
+                        fields[..fields.len() - 1]
+                            .iter()
+                            .map(|f| f.to_string())
+                            .collect(),
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e).wrap_err(format!("invalid number in line {}", i + 1)),
+            };
+            ensure!(
+                values.len() >= 2,
+                "line {} has less than two columns",
+                i + 1
+            );
+            targets.push(values.pop().unwrap());
+            inputs.push(values);
+        }
+
+        ensure!(!inputs.is_empty(), "the dataset contains no samples");
+        let dimension = inputs[0].len();
+        ensure!(
+            inputs.iter().all(|row| row.len() == dimension),
+            "all rows must have the same number of columns"
+        );
+        let names: Vec<String> =
+            names.unwrap_or_else(|| (0..dimension).map(|i| format!("x{i}")).collect());
+        ensure!(
+            names.len() == dimension,
+            "the header must have as many columns as the rows"
+        );
+
+        Ok(Self {
+            names,
+            inputs,
+            targets,
+        })
+    }
+
+    /// Reads a dataset from the CSV file at `path`, see [`Dataset::from_csv`].
+    pub fn read_csv(path: impl AsRef<Path>) -> ExecResult<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).wrap_err_with(|| format!("failed to open {path:?}"))?;
+        Self::from_csv(BufReader::new(file))
+    }
+
+    /// Returns the number of input variables.
+    pub fn dimension(&self) -> usize {
+        self.names.len()
+    }
+}
+
+/// The operation of a primitive of [`SymbolicRegression`].
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    /// Protected division, returning 1 for divisors close to 0.
+    Div,
+    Sin,
+    Cos,
+    Variable(usize),
+    Constant,
+}
+
+/// Symbolic regression on a [`Dataset`], minimizing the mean squared error of the predictions.
+///
+/// Trees are built from the functions `+`, `-`, `*`, protected `/` (returning 1 for divisors
+/// close to 0), `sin` and `cos`, the input variables, and ephemeral random constants in `[-1, 1)`.
+///
+/// # Examples
+///
+/// ```
+/// use mahf::problems::symbolic_regression::{Dataset, SymbolicRegression};
+///
+/// # fn example() -> mahf::ExecResult<()> {
+/// let csv = "x,y\n0,1\n1,3\n2,5\n";
+/// let problem = SymbolicRegression::new(Dataset::from_csv(csv.as_bytes())?, 8);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SymbolicRegression {
+    dataset: Dataset,
+    primitives: PrimitiveSet,
+    operations: Vec<Operation>,
+    max_depth: usize,
+}
+
+impl SymbolicRegression {
+    /// The value type of all primitives.
+    pub const REAL: ValueType = ValueType("f64");
+
+    /// Creates the problem on the `dataset` with a maximum tree depth of `max_depth`.
+    pub fn new(dataset: Dataset, max_depth: usize) -> Self {
+        let real = Self::REAL;
+        let mut primitives = PrimitiveSet::new(real);
+        let mut operations = Vec::new();
+
+        let functions = [
+            ("+", 2, Operation::Add),
+            ("-", 2, Operation::Sub),
+            ("*", 2, Operation::Mul),
+            ("/", 2, Operation::Div),
+            ("sin", 1, Operation::Sin),
+            ("cos", 1, Operation::Cos),
+        ];
+        for (name, arity, operation) in functions {
+            primitives.push(Primitive::function(name, vec![real; arity], real));
+            operations.push(operation);
+        }
+        for (i, name) in dataset.names.iter().enumerate() {
+            primitives.push(Primitive::terminal(name.clone(), real));
+            operations.push(Operation::Variable(i));
+        }
+        primitives.push(Primitive::constant("c", real, -1.0..1.0));
+        operations.push(Operation::Constant);
+
+        Self {
+            dataset,
+            primitives,
+            operations,
+            max_depth,
+        }
+    }
+
+    /// Returns the dataset.
+    pub fn dataset(&self) -> &Dataset {
+        &self.dataset
+    }
+
+    /// Returns the prediction of the `tree` for the `inputs`.
+    pub fn predict(&self, tree: &Tree, inputs: &[f64]) -> f64 {
+        self.predict_at(tree, 0, inputs).0
+    }
+
+    /// Evaluates the subtree at `index`, returning its value and the index after the subtree.
+    fn predict_at(&self, tree: &Tree, index: usize, inputs: &[f64]) -> (f64, usize) {
+        let node = &tree.nodes()[index];
+        let operation = self.operations[node.primitive];
+        let mut next = index + 1;
+        let mut arg = || {
+            let (value, end) = self.predict_at(tree, next, inputs);
+            next = end;
+            value
+        };
+        let value = match operation {
+            Operation::Add => arg() + arg(),
+            Operation::Sub => arg() - arg(),
+            Operation::Mul => arg() * arg(),
+            Operation::Div => {
+                let (a, b) = (arg(), arg());
+                if b.abs() < 1e-9 {
+                    1.
+                } else {
+                    a / b
+                }
+            }
+            Operation::Sin => arg().sin(),
+            Operation::Cos => arg().cos(),
+            Operation::Variable(i) => inputs[i],
+            Operation::Constant => node.value,
+        };
+        (value, next)
+    }
+}
+
+impl Problem for SymbolicRegression {
+    type Encoding = Tree;
+    type Objective = SingleObjective;
+
+    fn name(&self) -> &str {
+        "SymbolicRegression"
+    }
+}
+
+impl TreeProblem for SymbolicRegression {
+    fn primitives(&self) -> &PrimitiveSet {
+        &self.primitives
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+}
+
+impl ObjectiveFunction for SymbolicRegression {
+    fn objective(&self, solution: &Self::Encoding) -> Self::Objective {
+        let Dataset {
+            inputs, targets, ..
+        } = &self.dataset;
+        let mse = inputs
+            .iter()
+            .zip(targets)
+            .map(|(x, y)| (self.predict(solution, x) - y).powi(2))
+            .sum::<f64>()
+            / targets.len() as f64;
+        // Non-finite errors, e.g. from overflows, are the worst possible objective value.
+        if mse.is_finite() { mse } else { f64::INFINITY }
+            .try_into()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use float_eq::assert_float_eq;
+
+    use super::*;
+    use crate::problems::tree::Node;
+
+    #[test]
+    fn evaluates_trees_on_csv_dataset() {
+        let csv = "x,y\n0,1\n1,3\n\n2,5\n";
+        let problem = SymbolicRegression::new(Dataset::from_csv(csv.as_bytes()).unwrap(), 4);
+        assert_eq!(problem.dataset().names, ["x"]);
+        assert_eq!(problem.dataset().targets, [1., 3., 5.]);
+
+        let set = problem.primitives();
+        let index = |name: &str| {
+            set.primitives()
+                .iter()
+                .position(|p| p.name == name)
+                .unwrap()
+        };
+        let node = |name: &str, value: f64| Node {
+            primitive: index(name),
+            arity: set.get(index(name)).arity(),
+            value,
+        };
+
+        // (+ (* c x) c) with c = 2 and c = 1, i.e. y = 2x + 1.
+        let tree = Tree::from_nodes(vec![
+            node("+", 0.),
+            node("*", 0.),
+            node("c", 2.),
+            node("x", 0.),
+            node("c", 1.),
+        ]);
+        assert_eq!(tree.format(set), "(+ (* 2 x) 1)");
+        assert_float_eq!(problem.objective(&tree).value(), 0., abs <= 1e-12);
+    }
+}
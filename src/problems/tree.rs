@@ -0,0 +1,369 @@
+//! Tree-based program encodings for genetic programming (GP).
+//!
+//! A [`Tree`] is a program composed of [`Primitive`]s from a [`PrimitiveSet`], i.e. functions
+//! (inner nodes) and terminals (leaves).
+//! Every primitive has an output [`ValueType`] and a [`ValueType`] for each of its inputs,
+//! and a tree is only valid if the output type of every node matches the corresponding input
+//! type of its parent (strongly typed GP).
+//!
+//! # References
+//!
+//! \[1\] John R. Koza. 1992.
+//! Genetic Programming: On the Programming of Computers by Means of Natural Selection.
+//! MIT Press, Cambridge, MA, USA.
+//!
+//! \[2\] David J. Montana. 1995.
+//! Strongly Typed Genetic Programming.
+//! Evolutionary Computation 3, 2 (1995), 199–230.
+//! DOI:<https://doi.org/10.1162/evco.1995.3.2.199>
+
+use std::ops::Range;
+
+use eyre::ensure;
+use rand::Rng;
+use serde::Serialize;
+
+use crate::component::ExecResult;
+
+/// The type of the value a [`Primitive`] takes as input or returns, e.g. `ValueType("f64")`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct ValueType(pub &'static str);
+
+/// A function or terminal of a [`PrimitiveSet`].
+#[derive(Clone, Debug, Serialize)]
+pub struct Primitive {
+    /// The name of the primitive.
+    pub name: String,
+    /// The type of the value the primitive returns.
+    pub output: ValueType,
+    /// The types of the inputs of the primitive, which are empty for terminals.
+    pub inputs: Vec<ValueType>,
+    /// The range of an ephemeral random constant, which is sampled when the terminal is created.
+    pub constant: Option<Range<f64>>,
+}
+
+impl Primitive {
+    /// Creates a function with `inputs`.
+    pub fn function(name: impl Into<String>, inputs: Vec<ValueType>, output: ValueType) -> Self {
+        Self {
+            name: name.into(),
+            output,
+            inputs,
+            constant: None,
+        }
+    }
+
+    /// Creates a terminal, e.g. an input variable.
+    pub fn terminal(name: impl Into<String>, output: ValueType) -> Self {
+        Self {
+            name: name.into(),
+            output,
+            inputs: Vec::new(),
+            constant: None,
+        }
+    }
+
+    /// Creates an ephemeral random constant, which is sampled uniformly from the `range`.
+    pub fn constant(name: impl Into<String>, output: ValueType, range: Range<f64>) -> Self {
+        Self {
+            name: name.into(),
+            output,
+            inputs: Vec::new(),
+            constant: Some(range),
+        }
+    }
+
+    /// Returns the number of inputs.
+    pub fn arity(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns `true` if the primitive is a terminal.
+    pub fn is_terminal(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+/// A set of typed functions and terminals from which [`Tree`]s are built.
+#[derive(Clone, Debug, Serialize)]
+pub struct PrimitiveSet {
+    /// The type returned by complete programs, i.e. the root of a tree.
+    pub root: ValueType,
+    primitives: Vec<Primitive>,
+}
+
+impl PrimitiveSet {
+    /// Creates an empty set for programs returning `root`.
+    pub fn new(root: ValueType) -> Self {
+        Self {
+            root,
+            primitives: Vec::new(),
+        }
+    }
+
+    /// Adds the `primitive` and returns its index.
+    pub fn push(&mut self, primitive: Primitive) -> usize {
+        self.primitives.push(primitive);
+        self.primitives.len() - 1
+    }
+
+    /// Returns the primitive with `index`.
+    pub fn get(&self, index: usize) -> &Primitive {
+        &self.primitives[index]
+    }
+
+    /// Returns all primitives.
+    pub fn primitives(&self) -> &[Primitive] {
+        &self.primitives
+    }
+
+    /// Returns the indices of all functions returning `output`.
+    pub fn functions(&self, output: ValueType) -> Vec<usize> {
+        self.indices(|p| !p.is_terminal() && p.output == output)
+    }
+
+    /// Returns the indices of all terminals returning `output`.
+    pub fn terminals(&self, output: ValueType) -> Vec<usize> {
+        self.indices(|p| p.is_terminal() && p.output == output)
+    }
+
+    fn indices(&self, f: impl Fn(&Primitive) -> bool) -> Vec<usize> {
+        (0..self.primitives.len())
+            .filter(|&i| f(&self.primitives[i]))
+            .collect()
+    }
+
+    /// Checks that every type required by the root or any function can be produced by a
+    /// terminal, which guarantees that trees of any depth limit can be generated.
+    pub fn validate(&self) -> ExecResult<()> {
+        let required = std::iter::once(self.root).chain(
+            self.primitives
+                .iter()
+                .flat_map(|p| p.inputs.iter().copied()),
+        );
+        for ty in required {
+            ensure!(
+                !self.terminals(ty).is_empty(),
+                "no terminal returns the type {ty:?}"
+            );
+        }
+        Ok(())
+    }
+
+    /// Creates a node of the primitive with `index`, sampling its value if it is a constant.
+    pub fn node(&self, index: usize, rng: &mut impl Rng) -> Node {
+        let primitive = self.get(index);
+        let value = primitive
+            .constant
+            .as_ref()
+            .map_or(0., |range| rng.gen_range(range.clone()));
+        Node {
+            primitive: index,
+            arity: primitive.arity(),
+            value,
+        }
+    }
+
+    /// Generates a random tree of type `output` using the grow (`full = false`)
+    /// or full (`full = true`) method with the given maximum depth.
+    ///
+    /// The full method only uses functions above `max_depth`, while the grow method chooses
+    /// uniformly from all primitives of the required type.
+    /// Functions are used in place of missing terminals and vice versa.
+    pub fn generate(
+        &self,
+        output: ValueType,
+        max_depth: usize,
+        full: bool,
+        rng: &mut impl Rng,
+    ) -> Tree {
+        let mut nodes = Vec::new();
+        self.generate_into(output, max_depth, full, rng, &mut nodes);
+        Tree { nodes }
+    }
+
+    fn generate_into(
+        &self,
+        output: ValueType,
+        depth: usize,
+        full: bool,
+        rng: &mut impl Rng,
+        nodes: &mut Vec<Node>,
+    ) {
+        let functions = self.functions(output);
+        let terminals = self.terminals(output);
+
+        let use_terminal = if depth == 0 || functions.is_empty() {
+            !terminals.is_empty()
+        } else if full || terminals.is_empty() {
+            false
+        } else {
+            rng.gen_range(0..functions.len() + terminals.len()) >= functions.len()
+        };
+
+        let candidates = if use_terminal { &terminals } else { &functions };
+        let index = candidates[rng.gen_range(0..candidates.len())];
+        nodes.push(self.node(index, rng));
+        for &input in &self.get(index).inputs {
+            self.generate_into(input, depth.saturating_sub(1), full, rng, nodes);
+        }
+    }
+}
+
+/// A node of a [`Tree`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Node {
+    /// The index of the primitive in the [`PrimitiveSet`].
+    pub primitive: usize,
+    /// The number of children.
+    pub arity: usize,
+    /// The value of an ephemeral random constant, and `0` otherwise.
+    pub value: f64,
+}
+
+/// A program tree, stored as a sequence of [`Node`]s in prefix order.
+///
+/// Storing the tree in prefix order makes every subtree a contiguous slice of nodes,
+/// see [`Tree::subtree`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    /// Creates a tree from `nodes` in prefix order.
+    pub fn from_nodes(nodes: Vec<Node>) -> Self {
+        debug_assert_eq!(
+            Self::end_of(&nodes, 0),
+            nodes.len(),
+            "nodes are not a single tree"
+        );
+        Self { nodes }
+    }
+
+    /// Returns the nodes in prefix order.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Returns the nodes in prefix order mutably.
+    ///
+    /// Note that changing the arity of nodes invalidates the tree.
+    pub fn nodes_mut(&mut self) -> &mut [Node] {
+        &mut self.nodes
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the tree has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn end_of(nodes: &[Node], start: usize) -> usize {
+        let mut open = 1;
+        let mut end = start;
+        while open > 0 {
+            open = open + nodes[end].arity - 1;
+            end += 1;
+        }
+        end
+    }
+
+    /// Returns the range of nodes of the subtree rooted at the node with `index`.
+    pub fn subtree(&self, index: usize) -> Range<usize> {
+        index..Self::end_of(&self.nodes, index)
+    }
+
+    /// Returns the depth of the tree, where a single terminal has depth 0.
+    pub fn depth(&self) -> usize {
+        let mut max_depth = 0;
+        // The number of remaining children of all ancestors of the current node.
+        let mut open: Vec<usize> = Vec::new();
+        for node in &self.nodes {
+            max_depth = max_depth.max(open.len());
+            if let Some(remaining) = open.last_mut() {
+                *remaining -= 1;
+            }
+            if node.arity > 0 {
+                open.push(node.arity);
+            } else {
+                while open.last() == Some(&0) {
+                    open.pop();
+                }
+            }
+        }
+        max_depth
+    }
+
+    /// Replaces the subtree rooted at the node with `index` with `subtree`.
+    pub fn replace_subtree(&mut self, index: usize, subtree: &[Node]) {
+        let range = self.subtree(index);
+        self.nodes.splice(range, subtree.iter().cloned());
+    }
+
+    /// Formats the tree as s-expression using the names of the primitives in `set`.
+    pub fn format(&self, set: &PrimitiveSet) -> String {
+        let mut out = String::new();
+        self.format_into(set, 0, &mut out);
+        out
+    }
+
+    fn format_into(&self, set: &PrimitiveSet, index: usize, out: &mut String) -> usize {
+        let node = &self.nodes[index];
+        let primitive = set.get(node.primitive);
+        if primitive.constant.is_some() {
+            out.push_str(&node.value.to_string());
+            return index + 1;
+        }
+        if node.arity == 0 {
+            out.push_str(&primitive.name);
+            return index + 1;
+        }
+        out.push('(');
+        out.push_str(&primitive.name);
+        let mut next = index + 1;
+        for _ in 0..node.arity {
+            out.push(' ');
+            next = self.format_into(set, next, out);
+        }
+        out.push(')');
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(primitive: usize, arity: usize) -> Node {
+        Node {
+            primitive,
+            arity,
+            value: 0.,
+        }
+    }
+
+    #[test]
+    fn subtrees_and_depth_follow_prefix_order() {
+        // (+ (* x x) x)
+        let mut tree = Tree::from_nodes(vec![
+            node(0, 2),
+            node(1, 2),
+            node(2, 0),
+            node(2, 0),
+            node(2, 0),
+        ]);
+        assert_eq!(tree.subtree(0), 0..5);
+        assert_eq!(tree.subtree(1), 1..4);
+        assert_eq!(tree.subtree(4), 4..5);
+        assert_eq!(tree.depth(), 2);
+
+        tree.replace_subtree(1, &[node(2, 0)]);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(tree.depth(), 1);
+    }
+}
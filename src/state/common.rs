@@ -2,11 +2,16 @@
 //!
 //! This module contains custom state used by almost all metaheuristics and components, for example:
 //! - the number of [`Iterations`] and objective function [`Evaluations`] performed,
+//! - the time elapsed since the start of the run, measured by the [`RunClock`],
 //! - the [`BestIndividual`] yet found,
 //! - the current approximation of the [`ParetoFront`], or
 //! - storing [`Populations`] of [`Individual`]s.
 
-use std::{marker::PhantomData, ops::Deref};
+use std::{
+    marker::PhantomData,
+    ops::Deref,
+    time::{Duration, Instant},
+};
 
 use better_any::{Tid, TidAble};
 use derive_more::{Deref, DerefMut};
@@ -142,8 +147,11 @@ impl CustomState<'_> for Iterations {}
 /// # Usages
 ///
 /// The [`LessThanN<T>`] condition automatically inserts and updates `Progress<T>`.
+/// The same holds for the [`TimeLimit`] and [`CpuTimeLimit`] conditions.
 ///
 /// [`LessThanN<T>`]: crate::conditions::LessThanN
+/// [`TimeLimit`]: crate::conditions::TimeLimit
+/// [`CpuTimeLimit`]: crate::conditions::CpuTimeLimit
 #[derive(Clone, Deref, DerefMut, Tid)]
 pub struct Progress<T: 'static>(
     #[deref]
@@ -172,6 +180,92 @@ impl<T> Serialize for Progress<T> {
 
 impl<T> CustomState<'_> for Progress<T> {}
 
+/// The clock measuring the wall-clock and CPU time elapsed since the start of the run.
+///
+/// # Usages
+///
+/// This state is automatically inserted by [`Configuration::optimize`] and
+/// [`Configuration::optimize_with`], and used by the [`TimeLimit`] and [`CpuTimeLimit`] conditions.
+///
+/// The elapsed time can be logged using the [`ElapsedTimeLens`] and [`CpuTimeLens`].
+///
+/// Note that the CPU time is measured for the whole process, i.e. it includes the time spent
+/// by all threads, and is only available on Unix platforms.
+///
+/// [`Configuration::optimize`]: crate::Configuration::optimize
+/// [`Configuration::optimize_with`]: crate::Configuration::optimize_with
+/// [`TimeLimit`]: crate::conditions::TimeLimit
+/// [`CpuTimeLimit`]: crate::conditions::CpuTimeLimit
+/// [`ElapsedTimeLens`]: crate::lens::common::ElapsedTimeLens
+/// [`CpuTimeLens`]: crate::lens::common::CpuTimeLens
+///
+/// # Examples
+///
+/// ```
+/// # use mahf::Problem;
+/// use mahf::{state::common::RunClock, State};
+///
+/// # pub fn example<P: Problem>() {
+/// let mut state: State<P> = State::new();
+/// state.insert(RunClock::start()); // Automatically done by `Configuration::optimize`.
+///
+/// let seconds = state.borrow::<RunClock>().elapsed().as_secs_f64();
+/// # }
+/// ```
+#[derive(Clone, Debug, Tid)]
+pub struct RunClock {
+    start: Instant,
+    cpu_start: Option<Duration>,
+}
+
+impl RunClock {
+    /// Starts a new clock.
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            cpu_start: process_cpu_time(),
+        }
+    }
+
+    /// Returns the wall-clock time elapsed since the clock was started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the CPU time consumed by the process since the clock was started,
+    /// or `None` if it can't be measured on this platform.
+    pub fn cpu_elapsed(&self) -> Option<Duration> {
+        let start = self.cpu_start?;
+        process_cpu_time().map(|now| now.saturating_sub(start))
+    }
+}
+
+impl Default for RunClock {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+impl CustomState<'_> for RunClock {}
+
+/// Returns the CPU time consumed by the current process.
+#[cfg(unix)]
+fn process_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid pointer to a `timespec` for the duration of the call.
+    let result = unsafe { libc::clock_gettime(libc::CLOCK_PROCESS_CPUTIME_ID, &mut time) };
+    (result == 0).then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+/// Returns the CPU time consumed by the current process.
+#[cfg(not(unix))]
+fn process_cpu_time() -> Option<Duration> {
+    None
+}
+
 /// The best individual yet found.
 ///
 /// Note that this state is only possible for [`SingleObjectiveProblem`]s.
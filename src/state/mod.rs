@@ -2,6 +2,7 @@
 
 use std::{
     cell::{Ref, RefMut},
+    fmt::Display,
     marker::PhantomData,
     ops::Deref,
 };
@@ -11,6 +12,7 @@ use derive_more::{Deref, DerefMut};
 
 use crate::{
     component::ExecResult,
+    configuration::validation::Validation,
     identifier,
     identifier::Identifier,
     logging,
@@ -61,13 +63,51 @@ impl<'a, P> State<'a, P> {
         StateReq::new(self)
     }
 
+    /// Calls `f`, which initializes the child component at `segment` in the component tree.
+    ///
+    /// During [`Configuration::validate`], this records any error returned by `f` with the
+    /// path of the child component, and returns `Ok`.
+    /// Otherwise, this method simply calls `f`.
+    ///
+    /// See the [`validation`] module for more details.
+    ///
+    /// [`Configuration::validate`]: crate::Configuration::validate
+    /// [`validation`]: crate::configuration::validation
+    pub fn within(
+        &mut self,
+        segment: impl Display,
+        f: impl FnOnce(&mut Self) -> ExecResult<()>,
+    ) -> ExecResult<()> {
+        if !self.contains::<Validation>() {
+            return f(self);
+        }
+
+        self.borrow_mut::<Validation>().enter(segment.to_string());
+        let result = f(self);
+        self.borrow_mut::<Validation>().leave(result);
+        Ok(())
+    }
+
     /// Calls `f` with a child state, which is split off and returned afterwards.
     pub fn with_inner_state<F>(&mut self, f: F) -> ExecResult<Self>
+    where
+        F: FnOnce(&mut Self) -> ExecResult<()>,
+    {
+        self.with_existing_inner_state(Self::new(), f)
+    }
+
+    /// Calls `f` with `inner` as child state, which is split off and returned afterwards.
+    ///
+    /// This allows to preserve a child state across multiple calls,
+    /// e.g. a state returned by [`with_inner_state`].
+    ///
+    /// [`with_inner_state`]: Self::with_inner_state
+    pub fn with_existing_inner_state<F>(&mut self, inner: Self, f: F) -> ExecResult<Self>
     where
         F: FnOnce(&mut Self) -> ExecResult<()>,
     {
         let registry = std::mem::take(&mut self.registry);
-        let mut state = registry.into_child().into();
+        let mut state = registry.into_child_with(inner.registry).into();
         f(&mut state)?;
         let (registry, child) = StateRegistry::from(state).into_parent();
         self.registry = registry.unwrap();
@@ -184,21 +224,25 @@ where
 }
 
 impl<'a, P: Problem> State<'a, P> {
-    /// Inserts the `evaluator` wrapped in an [`Evaluator`] using the [`Global`] identifier.
+    /// Inserts the `evaluator` wrapped in an [`Evaluator`] using the [`Global`] identifier,
+    /// after initializing it with [`Evaluate::init`].
     ///
     /// [`Evaluator`]: common::Evaluator
     /// [`Global`]: identifier::Global
-    pub fn insert_evaluator(&mut self, evaluator: impl Evaluate<Problem = P> + 'a) {
+    pub fn insert_evaluator(&mut self, mut evaluator: impl Evaluate<Problem = P> + 'a) {
+        evaluator.init(self);
         self.insert(common::Evaluator::<P, identifier::Global>::new(evaluator));
     }
 
-    /// Inserts the `evaluator` wrapped in an [`Evaluator`] using the identifier `I`.
+    /// Inserts the `evaluator` wrapped in an [`Evaluator`] using the identifier `I`,
+    /// after initializing it with [`Evaluate::init`].
     ///
     /// [`Evaluator`]: common::Evaluator
     pub fn insert_evaluator_as<I: Identifier>(
         &mut self,
-        evaluator: impl Evaluate<Problem = P> + 'a,
+        mut evaluator: impl Evaluate<Problem = P> + 'a,
     ) {
+        evaluator.init(self);
         self.insert(common::Evaluator::<P, I>::new(evaluator));
     }
 }
@@ -188,7 +188,7 @@ impl Random {
     ///
     /// assert_eq!(numbers1, numbers2);
     /// ```
-    pub fn iter_children(&mut self) -> RandomIter {
+    pub fn iter_children(&mut self) -> RandomIter<'_> {
         RandomIter { rng: self }
     }
 }
@@ -81,6 +81,19 @@ impl<'a> StateRegistry<'a> {
         }
     }
 
+    /// Pushes `child` on the stack and returns it, taking ownership of the old registry.
+    ///
+    /// This allows to reattach a registry previously split off using [`into_parent`].
+    /// Note that any parent registries of `child` are discarded.
+    ///
+    /// [`into_parent`]: Self::into_parent
+    pub fn into_child_with(self, child: Self) -> Self {
+        Self {
+            parent: Some(Box::new(self)),
+            map: child.map,
+        }
+    }
+
     /// Pops the current registry from the stack and returns it along with the parent registry.
     pub fn into_parent(self) -> (Option<Self>, Self) {
         (self.parent.map(|parent| *parent), self.map.into())
@@ -614,7 +627,7 @@ impl<'a> StateRegistry<'a> {
     /// assert_eq!(*registry.borrow_value::<A>(), 10);
     /// ```
     #[track_caller]
-    pub fn borrow_value<T>(&self) -> Ref<T::Target>
+    pub fn borrow_value<T>(&self) -> Ref<'_, T::Target>
     where
         T: CustomState<'a> + Deref,
         T::Target: Sized,
@@ -649,7 +662,7 @@ impl<'a> StateRegistry<'a> {
     /// drop(mut_a);
     /// assert!(registry.try_borrow_value::<A>().is_ok());
     /// ```
-    pub fn try_borrow_value<T>(&self) -> StateResult<Ref<T::Target>>
+    pub fn try_borrow_value<T>(&self) -> StateResult<Ref<'_, T::Target>>
     where
         T: CustomState<'a> + Deref,
         T::Target: Sized,
@@ -685,7 +698,7 @@ impl<'a> StateRegistry<'a> {
     /// assert_eq!(registry.get_value::<A>(), 11);
     /// ```
     #[track_caller]
-    pub fn borrow_value_mut<T>(&self) -> RefMut<T::Target>
+    pub fn borrow_value_mut<T>(&self) -> RefMut<'_, T::Target>
     where
         T: CustomState<'a> + DerefMut,
         T::Target: Sized,
@@ -726,7 +739,7 @@ impl<'a> StateRegistry<'a> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn try_borrow_value_mut<T>(&self) -> StateResult<RefMut<T::Target>>
+    pub fn try_borrow_value_mut<T>(&self) -> StateResult<RefMut<'_, T::Target>>
     where
         T: CustomState<'a> + DerefMut,
         T::Target: Sized,
@@ -1,6 +1,11 @@
 //! State requirements.
 
-use crate::{state::StateResult, CustomState, State, StateError};
+use std::{any::type_name, fmt::Display};
+
+use crate::{
+    component::ExecResult, configuration::validation::Validation, state::StateResult, CustomState,
+    State, StateError,
+};
 
 /// Helper struct to check if specific custom state is present in the state.
 ///
@@ -49,13 +54,66 @@ impl<'a, 'b, P> StateReq<'a, 'b, P> {
     /// #    }
     /// }
     /// ```
+    ///
+    /// During [`Configuration::validate`], missing state is recorded instead of returning an `Err`.
+    ///
+    /// [`Configuration::validate`]: crate::Configuration::validate
     pub fn require<Source, T>(&self) -> StateResult<()>
     where
         T: CustomState<'b>,
     {
-        self.0
-            .contains::<T>()
-            .then_some(())
-            .ok_or_else(StateError::required_missing::<Source, T>)
+        if self.0.contains::<T>() {
+            Ok(())
+        } else if self.0.contains::<Validation>() {
+            self.0
+                .borrow_mut::<Validation>()
+                .missing(type_name::<T>(), type_name::<Source>());
+            Ok(())
+        } else {
+            Err(StateError::required_missing::<Source, T>())
+        }
+    }
+
+    /// Declares that `T` is maintained by `Source`, e.g. the [`Iterations`] by a [`Loop`].
+    ///
+    /// This allows [`Configuration::validate`] to report enclosing components which maintain
+    /// the same state, and would therefore overwrite each other's values.
+    /// Outside of validation, this method does nothing.
+    ///
+    /// [`Iterations`]: crate::state::common::Iterations
+    /// [`Loop`]: crate::components::Loop
+    /// [`Configuration::validate`]: crate::Configuration::validate
+    pub fn claim<Source, T>(&self) {
+        if self.0.contains::<Validation>() {
+            self.0
+                .borrow_mut::<Validation>()
+                .claim(type_name::<T>(), type_name::<Source>());
+        }
+    }
+
+    /// Calls `f`, which checks the requirements of the child component at `segment`
+    /// in the component tree.
+    ///
+    /// During [`Configuration::validate`], this records any error returned by `f` with the
+    /// path of the child component, and returns `Ok`.
+    /// Otherwise, this method simply calls `f`.
+    ///
+    /// See the [`validation`] module for more details.
+    ///
+    /// [`Configuration::validate`]: crate::Configuration::validate
+    /// [`validation`]: crate::configuration::validation
+    pub fn within(
+        &self,
+        segment: impl Display,
+        f: impl FnOnce() -> ExecResult<()>,
+    ) -> ExecResult<()> {
+        if !self.0.contains::<Validation>() {
+            return f();
+        }
+
+        self.0.borrow_mut::<Validation>().enter(segment.to_string());
+        let result = f();
+        self.0.borrow_mut::<Validation>().leave(result);
+        Ok(())
     }
 }
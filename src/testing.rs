@@ -2,11 +2,23 @@
 
 #![allow(dead_code)]
 
-use std::{any::type_name, marker::PhantomData};
+use std::{
+    any::type_name,
+    collections::BTreeSet,
+    marker::PhantomData,
+    ops::{Range, RangeInclusive},
+};
 
 use float_eq::assert_float_eq;
+use rand::Rng;
 
-use crate::{Individual, MultiObjective, Objective, Problem, SingleObjective};
+use crate::{
+    problems::{
+        LimitedVectorProblem, ObjectiveFunction, SubsetProblem, VariableLengthProblem,
+        VectorProblem,
+    },
+    Individual, MultiObjective, Objective, Problem, SingleObjective, State,
+};
 
 pub struct TestProblem<O: Objective>(PhantomData<O>);
 
@@ -34,6 +46,183 @@ where
 pub type SingleObjectiveTestProblem = TestProblem<SingleObjective>;
 pub type MultiObjectiveTestProblem = TestProblem<MultiObjective>;
 
+/// An integer vector problem with the given `domain`, used to test integer operators.
+pub struct IntegerTestProblem(pub Vec<Range<i64>>);
+
+impl Problem for IntegerTestProblem {
+    type Encoding = Vec<i64>;
+    type Objective = SingleObjective;
+
+    fn name(&self) -> &str {
+        "IntegerTestProblem"
+    }
+}
+
+impl VectorProblem for IntegerTestProblem {
+    type Element = i64;
+
+    fn dimension(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl LimitedVectorProblem for IntegerTestProblem {
+    fn domain(&self) -> Vec<Range<i64>> {
+        self.0.clone()
+    }
+}
+
+impl ObjectiveFunction for IntegerTestProblem {
+    fn objective(&self, solution: &Self::Encoding) -> Self::Objective {
+        (solution.iter().sum::<i64>() as f64).try_into().unwrap()
+    }
+}
+
+/// A bitstring problem of the given `dimension` with the number of unset bits as objective,
+/// used to test bitstring operators.
+pub struct BitstringTestProblem(pub usize);
+
+impl Problem for BitstringTestProblem {
+    type Encoding = Vec<bool>;
+    type Objective = SingleObjective;
+
+    fn name(&self) -> &str {
+        "BitstringTestProblem"
+    }
+}
+
+impl VectorProblem for BitstringTestProblem {
+    type Element = bool;
+
+    fn dimension(&self) -> usize {
+        self.0
+    }
+}
+
+impl ObjectiveFunction for BitstringTestProblem {
+    fn objective(&self, solution: &Self::Encoding) -> Self::Objective {
+        (solution.iter().filter(|&&bit| !bit).count() as f64)
+            .try_into()
+            .unwrap()
+    }
+}
+
+/// A real-valued vector problem of the given dimension on `[-1, 1]` with the sphere function
+/// as objective, used to test real-valued components.
+pub struct RealTestProblem(pub usize);
+
+impl Problem for RealTestProblem {
+    type Encoding = Vec<f64>;
+    type Objective = SingleObjective;
+
+    fn name(&self) -> &str {
+        "RealTestProblem"
+    }
+}
+
+impl VectorProblem for RealTestProblem {
+    type Element = f64;
+
+    fn dimension(&self) -> usize {
+        self.0
+    }
+}
+
+impl LimitedVectorProblem for RealTestProblem {
+    fn domain(&self) -> Vec<Range<f64>> {
+        vec![-1.0..1.0; self.0]
+    }
+}
+
+impl ObjectiveFunction for RealTestProblem {
+    fn objective(&self, solution: &Self::Encoding) -> Self::Objective {
+        solution
+            .iter()
+            .map(|x| x * x)
+            .sum::<f64>()
+            .try_into()
+            .unwrap()
+    }
+}
+
+/// An integer vector problem whose solutions may have any of the given `lengths`,
+/// used to test variable-length operators.
+///
+/// Random elements are sampled from `[0, 10)`.
+pub struct VariableLengthTestProblem(pub RangeInclusive<usize>);
+
+impl Problem for VariableLengthTestProblem {
+    type Encoding = Vec<i64>;
+    type Objective = SingleObjective;
+
+    fn name(&self) -> &str {
+        "VariableLengthTestProblem"
+    }
+}
+
+impl VectorProblem for VariableLengthTestProblem {
+    type Element = i64;
+
+    fn dimension(&self) -> usize {
+        *self.0.start()
+    }
+}
+
+impl VariableLengthProblem for VariableLengthTestProblem {
+    fn lengths(&self) -> RangeInclusive<usize> {
+        self.0.clone()
+    }
+
+    fn random_element(&self, rng: &mut impl Rng) -> Self::Element {
+        rng.gen_range(0..10)
+    }
+}
+
+/// A subset problem with the given number of items and allowed `sizes` of subsets,
+/// used to test subset operators.
+pub struct SubsetTestProblem(pub usize, pub RangeInclusive<usize>);
+
+impl Problem for SubsetTestProblem {
+    type Encoding = BTreeSet<usize>;
+    type Objective = SingleObjective;
+
+    fn name(&self) -> &str {
+        "SubsetTestProblem"
+    }
+}
+
+impl SubsetProblem for SubsetTestProblem {
+    fn items(&self) -> usize {
+        self.0
+    }
+
+    fn sizes(&self) -> RangeInclusive<usize> {
+        self.1.clone()
+    }
+}
+
+/// Creates a [`State`] with a fixed [`Random`] generator and the `solutions` as the
+/// current population.
+///
+/// [`Random`]: crate::Random
+pub fn test_state<P: Problem>(solutions: Vec<P::Encoding>) -> State<'static, P> {
+    let mut state = State::new();
+    state.insert(crate::Random::new(0));
+    state.insert(crate::state::common::Populations::<P>::new());
+    state.populations_mut().push(
+        solutions
+            .into_iter()
+            .map(Individual::new_unevaluated)
+            .collect(),
+    );
+    state
+}
+
+/// Creates a [`State`] for the [`IntegerTestProblem`], see [`test_state`].
+pub fn integer_test_state(solutions: Vec<Vec<i64>>) -> State<'static, IntegerTestProblem> {
+    test_state(solutions)
+}
+
 pub fn single_test_individual(objective: f64) -> Individual<SingleObjectiveTestProblem> {
     Individual::new_test_unit(objective.try_into().unwrap())
 }